@@ -3,6 +3,7 @@ use caldata::{
     generator::{Emitter, IcalCalendar},
     parser::{ContentLine, ICalProperty},
     property::IcalDTSTARTProperty,
+    synth,
     types::{CalDate, CalDateTime, PartialDate},
 };
 use criterion::{Criterion, criterion_group, criterion_main};
@@ -58,6 +59,10 @@ fn benchmark(c: &mut Criterion) {
     drop(group);
     let mut group = c.benchmark_group("comps_parse");
     group.bench_function("ics parse ical_everything.ics", |b| b.iter(parse_ical));
+    let synthetic = synth::ics(&synth::SynthOptions::new()).unwrap();
+    group.bench_function("ics parse synthetic calendar", |b| {
+        b.iter(|| IcalParser::from_slice(&synthetic).expect_one().unwrap())
+    });
 
     drop(group);
     let mut group = c.benchmark_group("comps_serialise");
@@ -65,22 +70,35 @@ fn benchmark(c: &mut Criterion) {
     group.bench_function("ics serialise ical_everything.ics", |b| {
         b.iter(|| cal.generate())
     });
-    // #[cfg(feature = "rkyv")]
-    // c.bench_function("rkyv serialise ical_everything.ics", |b| {
-    //     b.iter(|| rkyv::to_bytes::<rkyv::rancor::Error>(&cal).unwrap())
-    // });
+    #[cfg(feature = "rkyv")]
+    group.bench_function("rkyv serialise ical_everything.ics", |b| {
+        b.iter(|| rkyv::to_bytes::<rkyv::rancor::Error>(&cal).unwrap())
+    });
+    drop(group);
 
-    // let rkyv_bytes = include_bytes!("ical_everything.rkyv");
-    // #[cfg(feature = "rkyv")]
-    // c.bench_function("rkyv deserialise ical_everything.ics", |b| {
-    //     b.iter(|| {
-    //         use ical::parser::ical::component::ArchivedIcalCalendar;
-    //
-    //         let archived =
-    //             rkyv::access::<ArchivedIcalCalendar, rkyv::rancor::Error>(rkyv_bytes).unwrap();
-    //         rkyv::deserialize::<_, rkyv::rancor::Error>(archived).unwrap();
-    //     })
-    // });
+    #[cfg(feature = "rkyv")]
+    {
+        let mut group = c.benchmark_group("comps_deserialise");
+        let rkyv_bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&cal).unwrap();
+        group.bench_function("rkyv access ical_everything.ics", |b| {
+            b.iter(|| {
+                rkyv::access::<caldata::component::ArchivedIcalCalendar, rkyv::rancor::Error>(
+                    &rkyv_bytes,
+                )
+                .unwrap()
+            })
+        });
+        group.bench_function("rkyv deserialise ical_everything.ics", |b| {
+            b.iter(|| {
+                let archived = rkyv::access::<
+                    caldata::component::ArchivedIcalCalendar,
+                    rkyv::rancor::Error,
+                >(&rkyv_bytes)
+                .unwrap();
+                rkyv::deserialize::<IcalCalendar, rkyv::rancor::Error>(archived).unwrap()
+            })
+        });
+    }
 }
 
 criterion_group!(benches, benchmark);
@@ -1,11 +1,7 @@
-use std::fs::read_to_string;
-
 fn main() {
-    let buf = read_to_string("./tests/resources/ical_input.ics").unwrap();
-
-    let reader = caldata::IcalParser::from_slice(buf.as_bytes());
+    let calendars = caldata::fs::load_calendar("./tests/resources/ical_everything.ics").unwrap();
 
-    for line in reader {
-        println!("{:?}", line);
+    for calendar in calendars {
+        println!("{:?}", calendar);
     }
 }
@@ -1,7 +1,7 @@
 use std::fs::read_to_string;
 
 fn main() {
-    let buf = read_to_string("./tests/ressources/ical_input.ics").unwrap();
+    let buf = read_to_string("./tests/resources/ical_example_1.ics").unwrap();
 
     let reader = caldata::ContentLineParser::from_slice(buf.as_bytes());
 
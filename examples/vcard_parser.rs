@@ -1,11 +1,7 @@
-use std::fs::read_to_string;
-
 fn main() {
-    let buf = read_to_string("./tests/ressources/vcard_input.vcf").unwrap();
-
-    let reader = caldata::VcardParser::from_slice(buf.as_bytes());
+    let contacts = caldata::fs::load_contacts("./tests/resources/vcard_input.vcf").unwrap();
 
-    for line in reader {
-        println!("{:?}", line);
+    for contact in contacts {
+        println!("{:?}", contact);
     }
 }
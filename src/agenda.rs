@@ -0,0 +1,228 @@
+//! Agenda views grouping the occurrences of events by day
+use crate::{
+    component::{CalendarInnerData, IcalCalendarObject, IcalEvent},
+    types::{CalDateOrDateTime, Tz},
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// Floating and DATE values may refer to any timezone, so the expanded window is widened
+/// by the maximum UTC offset
+#[cfg(feature = "recurrence")]
+const FLOATING_PADDING: Duration = Duration::hours(14);
+
+/// A single instance of an event
+#[derive(Debug, Clone)]
+pub struct Occurrence<'a> {
+    /// The object the instance belongs to
+    pub object: &'a IcalCalendarObject,
+    /// The expanded instance with UTC or floating DTSTART
+    pub event: IcalEvent,
+    /// Wall-clock start in the agenda's timezone
+    pub start: NaiveDateTime,
+    /// Wall-clock end in the agenda's timezone, exclusive
+    pub end: NaiveDateTime,
+    /// Whether the instance is DATE-valued, all-day instances start and end at midnight
+    pub all_day: bool,
+}
+
+impl<'a> Occurrence<'a> {
+    pub(crate) fn new(object: &'a IcalCalendarObject, event: IcalEvent, tz: &Tz) -> Self {
+        let start = wall_clock(&event.dtstart.0, tz);
+        let end = match &event.dtend {
+            Some(dtend) => wall_clock(&dtend.0, tz),
+            None => start + length(&event),
+        };
+        Self {
+            object,
+            all_day: event.dtstart.0.is_date(),
+            event,
+            start,
+            end,
+        }
+    }
+
+    /// Days the instance is listed on.
+    /// Instances ending exactly at midnight aren't listed on the following day.
+    fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let first = self.start.date();
+        let last = if self.end > self.start {
+            (self.end - Duration::nanoseconds(1)).date()
+        } else {
+            first
+        };
+        first.iter_days().take_while(move |day| day <= &last)
+    }
+}
+
+/// Wall-clock time of a value in `tz`, floating values are taken as is
+pub(crate) fn wall_clock(value: &CalDateOrDateTime, tz: &Tz) -> NaiveDateTime {
+    match value {
+        CalDateOrDateTime::Date(date) => date.naive_date().and_time(NaiveTime::MIN),
+        CalDateOrDateTime::DateTime(datetime) if datetime.timezone().is_local() => {
+            datetime.0.naive_local()
+        }
+        CalDateOrDateTime::DateTime(datetime) => datetime.utc().with_timezone(tz).naive_local(),
+    }
+}
+
+fn length(event: &IcalEvent) -> Duration {
+    event.get_duration().unwrap_or_else(|| {
+        if event.dtstart.0.is_date() {
+            Duration::days(1)
+        } else {
+            Duration::zero()
+        }
+    })
+}
+
+/// Instances of `object` that may be listed on the days of `range`
+#[cfg(feature = "recurrence")]
+fn instances(object: &IcalCalendarObject, range: &RangeInclusive<NaiveDate>) -> Vec<IcalEvent> {
+    let CalendarInnerData::Event(main, overrides) = &object.inner else {
+        return vec![];
+    };
+    let longest = std::iter::once(main)
+        .chain(overrides)
+        .map(length)
+        .max()
+        .unwrap_or_default();
+    let start = range.start().and_time(NaiveTime::MIN).and_utc() - FLOATING_PADDING - longest;
+    let end = range.end().and_time(NaiveTime::MIN).and_utc() + Duration::days(1) + FLOATING_PADDING;
+    let mut instances = main.expand_recurrence(Some(start), Some(end), overrides);
+    if main.has_rruleset() {
+        // Overrides moved into the range from outside of the expanded window
+        for over in overrides {
+            let recurid = over.recurid.as_ref().map(|recurid| recurid.0.utc());
+            if !instances
+                .iter()
+                .any(|instance| instance.recurid.as_ref().map(|recurid| recurid.0.utc()) == recurid)
+            {
+                instances.push(over.clone().to_utc_or_local());
+            }
+        }
+    }
+    instances
+}
+
+/// Without the `recurrence` feature only the stored components are listed
+#[cfg(not(feature = "recurrence"))]
+fn instances(object: &IcalCalendarObject, _range: &RangeInclusive<NaiveDate>) -> Vec<IcalEvent> {
+    let CalendarInnerData::Event(main, overrides) = &object.inner else {
+        return vec![];
+    };
+    std::iter::once(main)
+        .chain(overrides)
+        .map(|event| event.clone().to_utc_or_local())
+        .collect()
+}
+
+/// Occurrences of `object` listed on at least one of the days of `range` in timezone `tz`
+pub(crate) fn occurrences<'a>(
+    object: &'a IcalCalendarObject,
+    range: &RangeInclusive<NaiveDate>,
+    tz: &Tz,
+) -> impl Iterator<Item = Occurrence<'a>> {
+    instances(object, range)
+        .into_iter()
+        .map(move |event| Occurrence::new(object, event, tz))
+        .filter(|occurrence| occurrence.days().any(|day| range.contains(&day)))
+}
+
+/// Groups the occurrences of events in `objects` by the days of `range` in timezone `tz`.
+///
+/// Instances spanning midnight are listed on every day they cover. Within a day all-day
+/// instances come first, followed by the others ordered by their start.
+/// Days without occurrences are skipped. To-dos and journals aren't listed.
+/// Like [`IcalEvent::expand_recurrence`] at most 2048 instances are generated per object,
+/// without the `recurrence` feature only the stored components are listed.
+pub fn agenda<'a>(
+    objects: impl IntoIterator<Item = &'a IcalCalendarObject>,
+    range: RangeInclusive<NaiveDate>,
+    tz: Tz,
+) -> impl Iterator<Item = (NaiveDate, Vec<Occurrence<'a>>)> {
+    let mut days: BTreeMap<NaiveDate, Vec<Occurrence<'a>>> = BTreeMap::new();
+    for object in objects {
+        for occurrence in occurrences(object, &range, &tz) {
+            for day in occurrence.days() {
+                if range.contains(&day) {
+                    days.entry(day).or_default().push(occurrence.clone());
+                }
+            }
+        }
+    }
+    days.into_iter().map(|(day, mut occurrences)| {
+        occurrences.sort_by(|a, b| {
+            (!a.all_day, a.start, a.end, a.event.get_uid()).cmp(&(
+                !b.all_day,
+                b.start,
+                b.end,
+                b.event.get_uid(),
+            ))
+        });
+        (day, occurrences)
+    })
+}
+
+// The tests depend on the expansion of recurrences
+#[cfg(all(test, feature = "recurrence"))]
+mod tests {
+    use super::agenda;
+    use crate::{IcalObjectParser, component::IcalCalendarObject, types::Tz};
+    use chrono::NaiveDate;
+
+    fn object(lines: &str) -> IcalCalendarObject {
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\n{lines}END:VCALENDAR\r\n"
+        );
+        IcalObjectParser::from_slice(ics.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_agenda() {
+        let objects = [
+            // 22:00-01:00 in Berlin
+            object(
+                "BEGIN:VEVENT\r\nUID:party\r\nDTSTAMP:20260101T000000Z\r\nDTSTART:20260320T210000Z\r\nDTEND:20260321T000000Z\r\nEND:VEVENT\r\n",
+            ),
+            object(
+                "BEGIN:VEVENT\r\nUID:holiday\r\nDTSTAMP:20260101T000000Z\r\nDTSTART;VALUE=DATE:20260320\r\nDTEND;VALUE=DATE:20260322\r\nEND:VEVENT\r\n",
+            ),
+            // Daily at 08:00 floating, the second instance is moved to the 25th
+            object(
+                "BEGIN:VEVENT\r\nUID:standup\r\nDTSTAMP:20260101T000000Z\r\nDTSTART:20260319T080000\r\nDURATION:PT15M\r\nRRULE:FREQ=DAILY;COUNT=3\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:standup\r\nDTSTAMP:20260101T000000Z\r\nRECURRENCE-ID:20260320T080000\r\nDTSTART:20260325T090000\r\nDURATION:PT15M\r\nEND:VEVENT\r\n",
+            ),
+            object(
+                "BEGIN:VTODO\r\nUID:todo\r\nDTSTAMP:20260101T000000Z\r\nDUE:20260320T100000Z\r\nEND:VTODO\r\n",
+            ),
+        ];
+        let days: Vec<String> = agenda(
+            &objects,
+            date("2026-03-20")..=date("2026-03-25"),
+            Tz::Olson(chrono_tz::Europe::Berlin),
+        )
+        .flat_map(|(day, occurrences)| {
+            occurrences.into_iter().map(move |occ| {
+                format!("{day}: {} {}..{}", occ.event.get_uid(), occ.start, occ.end)
+            })
+        })
+        .collect();
+        assert_eq!(
+            days,
+            [
+                "2026-03-20: holiday 2026-03-20 00:00:00..2026-03-22 00:00:00",
+                "2026-03-20: party 2026-03-20 22:00:00..2026-03-21 01:00:00",
+                "2026-03-21: holiday 2026-03-20 00:00:00..2026-03-22 00:00:00",
+                "2026-03-21: party 2026-03-20 22:00:00..2026-03-21 01:00:00",
+                "2026-03-21: standup 2026-03-21 08:00:00..2026-03-21 08:15:00",
+                "2026-03-25: standup 2026-03-25 09:00:00..2026-03-25 09:15:00",
+            ]
+        );
+    }
+}
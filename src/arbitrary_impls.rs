@@ -0,0 +1,301 @@
+//! [`Arbitrary`] implementations generating structured inputs for fuzzing.
+//!
+//! Generated values stay within the syntax of iCalendar,
+//! so fuzz targets exercise the semantics rather than only the lexer.
+use crate::{
+    component::{
+        CalendarInnerDataBuilder, IcalCalendarBuilder, IcalCalendarObjectBuilder, IcalTimeZone,
+    },
+    parser::{ContentLine, ContentLineParams},
+    rrule::{NWeekday, RRule, Unvalidated},
+    types::{CalDate, CalDateOrDateTime, CalDateTime, Tz},
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use chrono::{DateTime, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+
+const PROPERTY_NAMES: &[&str] = &[
+    "UID",
+    "DTSTAMP",
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "RECURRENCE-ID",
+    "RRULE",
+    "RDATE",
+    "EXDATE",
+    "SUMMARY",
+    "DESCRIPTION",
+    "SEQUENCE",
+    "ORGANIZER",
+    "ATTENDEE",
+    "TRIGGER",
+    "ACTION",
+    "TZID",
+    "TZOFFSETFROM",
+    "TZOFFSETTO",
+    "VERSION",
+    "PRODID",
+    "FN",
+    "BDAY",
+];
+
+const PARAM_NAMES: &[&str] = &["TZID", "VALUE", "RANGE", "RELATED", "CN", "PARTSTAT"];
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// A known name or an `X-` name
+fn name(u: &mut Unstructured, known: &[&str]) -> Result<String> {
+    if u.ratio(7, 8)? {
+        return Ok(u.choose(known)?.to_string());
+    }
+    let len = u.int_in_range(1..=12)?;
+    let mut name = "X-".to_owned();
+    for _ in 0..len {
+        name.push(*u.choose(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-")? as char);
+    }
+    Ok(name)
+}
+
+/// Text without control characters which can't be represented in content lines
+fn text(u: &mut Unstructured) -> Result<String> {
+    Ok(String::arbitrary(u)?
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect())
+}
+
+fn timezone(u: &mut Unstructured) -> Result<Tz> {
+    Ok(match u.int_in_range(0..=2)? {
+        0 => Tz::Local,
+        1 => Tz::UTC,
+        _ => Tz::Olson(*u.choose(&chrono_tz::TZ_VARIANTS)?),
+    })
+}
+
+fn nonzero_in_range(u: &mut Unstructured, max: i16) -> Result<i16> {
+    let value = u.int_in_range(1..=max)?;
+    Ok(if u.arbitrary()? { value } else { -value })
+}
+
+impl<'a> Arbitrary<'a> for ContentLineParams {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(0..=3)?;
+        let mut params = Vec::with_capacity(len);
+        for _ in 0..len {
+            let values_len = u.int_in_range(1..=2)?;
+            let values = (0..values_len)
+                .map(|_| Ok(text(u)?.replace('"', "")))
+                .collect::<Result<_>>()?;
+            params.push((name(u, PARAM_NAMES)?.into(), values));
+        }
+        Ok(Self(params))
+    }
+}
+
+impl<'a> Arbitrary<'a> for ContentLine {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let name = name(u, PROPERTY_NAMES)?;
+        let params = u.arbitrary()?;
+        // Mostly generate values that have a chance to be valid for the property
+        let value = match name.as_str() {
+            "DTSTAMP" | "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "RDATE" | "EXDATE"
+                if u.ratio(3, 4)? =>
+            {
+                CalDateOrDateTime::arbitrary(u)?.format()
+            }
+            "RRULE" if u.ratio(3, 4)? => RRule::arbitrary(u)?.to_string(),
+            _ => text(u)?,
+        };
+        Ok(Self {
+            name,
+            params,
+            value,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CalDateTime {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // Between 1900 and 2100
+        let timestamp = u.int_in_range(-2_208_988_800..=4_102_444_800)?;
+        let utc = DateTime::from_timestamp(timestamp, 0).expect("timestamp is in range");
+        Ok(match timezone(u)? {
+            Tz::Local => Self(
+                utc.naive_utc()
+                    .and_local_timezone(Tz::Local)
+                    .single()
+                    .expect("Local timezone has constant offset"),
+            ),
+            tz => Self(utc.with_timezone(&tz)),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for CalDate {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let epoch = NaiveDate::from_ymd_opt(1900, 1, 1).expect("valid date");
+        let date = epoch + chrono::Duration::days(u.int_in_range(0..=73_000)?);
+        Ok(Self(date, timezone(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for CalDateOrDateTime {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.ratio(3, 4)? {
+            Self::DateTime(u.arbitrary()?)
+        } else {
+            Self::Date(u.arbitrary()?)
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for NWeekday {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let weekday = *u.choose(&WEEKDAYS)?;
+        Ok(if u.arbitrary()? {
+            Self::Every(weekday)
+        } else {
+            Self::Nth(nonzero_in_range(u, 53)?, weekday)
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for RRule<Unvalidated> {
+    /// Rule parts are generated within their valid ranges, combinations might still be invalid
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        fn values<T>(
+            u: &mut Unstructured,
+            mut value: impl FnMut(&mut Unstructured) -> Result<T>,
+        ) -> Result<Vec<T>> {
+            let len = u.int_in_range(0..=3)?;
+            (0..len).map(|_| value(u)).collect()
+        }
+
+        let count = if u.ratio(1, 3)? {
+            Some(u.int_in_range(1..=100)?)
+        } else {
+            None
+        };
+        let until = if count.is_none() && u.ratio(1, 2)? {
+            Some(CalDateTime::arbitrary(u)?.0)
+        } else {
+            None
+        };
+        Ok(Self {
+            freq: u.arbitrary()?,
+            interval: u.int_in_range(1..=10)?,
+            count,
+            until,
+            week_start: *u.choose(&WEEKDAYS)?,
+            by_set_pos: values(u, |u| Ok(nonzero_in_range(u, 366)?.into()))?,
+            by_month: values(u, |u| u.int_in_range(1..=12))?,
+            by_month_day: values(u, |u| Ok(nonzero_in_range(u, 31)? as i8))?,
+            by_year_day: values(u, |u| nonzero_in_range(u, 366))?,
+            by_week_no: values(u, |u| Ok(nonzero_in_range(u, 53)? as i8))?,
+            by_weekday: values(u, |u| u.arbitrary())?,
+            by_hour: values(u, |u| u.int_in_range(0..=23))?,
+            by_minute: values(u, |u| u.int_in_range(0..=59))?,
+            by_second: values(u, |u| u.int_in_range(0..=59))?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Timezones known to the timezone database, keyed by their TZID
+fn vtimezones(u: &mut Unstructured) -> Result<BTreeMap<String, IcalTimeZone>> {
+    let len = u.int_in_range(0..=2)?;
+    let mut vtimezones = BTreeMap::new();
+    for _ in 0..len {
+        let tzid = u.choose(&chrono_tz::TZ_VARIANTS)?.name();
+        if let Some(vtimezone) = IcalTimeZone::from_tzid(tzid) {
+            vtimezones.insert(tzid.to_owned(), vtimezone.clone());
+        }
+    }
+    Ok(vtimezones)
+}
+
+impl<'a> Arbitrary<'a> for IcalCalendarBuilder {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            properties: u.arbitrary()?,
+            events: u.arbitrary()?,
+            alarms: u.arbitrary()?,
+            todos: u.arbitrary()?,
+            journals: u.arbitrary()?,
+            free_busys: u.arbitrary()?,
+            vtimezones: vtimezones(u)?,
+            timezones: Default::default(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for IcalCalendarObjectBuilder {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            properties: u.arbitrary()?,
+            inner: u.arbitrary::<Option<CalendarInnerDataBuilder>>()?,
+            vtimezones: vtimezones(u)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        component::{ComponentMut, IcalCalendarBuilder},
+        generator::Emitter,
+        parser::ContentLine,
+        rrule::{RRule, Unvalidated},
+        types::CalDateTime,
+    };
+    use arbitrary::{Arbitrary, Unstructured};
+
+    fn seeds() -> impl Iterator<Item = Vec<u8>> {
+        (0u32..64).map(|seed| {
+            (0..1024)
+                .map(|i: u32| (seed.wrapping_mul(2_654_435_761) ^ i.wrapping_mul(40_503)) as u8)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_generated_values_roundtrip() {
+        for data in seeds() {
+            let mut u = Unstructured::new(&data);
+            let datetime = CalDateTime::arbitrary(&mut u).unwrap();
+            let timezone = match datetime.timezone() {
+                crate::types::Tz::Olson(tz) => Some(tz),
+                crate::types::Tz::Local | crate::types::Tz::Fixed(_) => None,
+            };
+            assert_eq!(
+                CalDateTime::parse(&datetime.format(), timezone).unwrap(),
+                datetime
+            );
+
+            let rrule = RRule::<Unvalidated>::arbitrary(&mut u).unwrap();
+            assert!(RRule::from_str_lenient(&rrule.to_string()).is_ok());
+
+            let line = ContentLine::arbitrary(&mut u).unwrap();
+            assert!(!line.generate().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_builders_dont_panic() {
+        for data in seeds() {
+            let mut u = Unstructured::new(&data);
+            let builder = IcalCalendarBuilder::arbitrary(&mut u).unwrap();
+            let _ = builder.build(&Default::default(), None);
+        }
+    }
+}
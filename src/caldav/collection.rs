@@ -0,0 +1,242 @@
+//! A calendar collection holding calendar object resources
+use super::Snapshot;
+use crate::{
+    component::{IcalCalendar, IcalCalendarObject},
+    parser::ContentLine,
+};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CollectionError {
+    /// CALDAV:no-uid-conflict, another resource already holds an object with this UID
+    #[error("UID {uid} is already used by {href}")]
+    UidConflict { uid: String, href: String },
+    /// The object at `href` has a different UID than the object that should replace it
+    #[error("the object at {href} has the UID {uid}")]
+    UidChanged { href: String, uid: String },
+}
+
+/// Calendar objects by their href, no two objects in a collection share the same UID.
+///
+/// Objects are iterated in the order of their hrefs.
+#[derive(Debug, Clone, Default)]
+pub struct CalendarCollection {
+    objects: BTreeMap<String, IcalCalendarObject>,
+    /// The href of each UID
+    hrefs: BTreeMap<String, String>,
+}
+
+impl CalendarCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Stores `object` at `href`, returns the object it replaces.
+    ///
+    /// Fails if another href holds an object with the same UID or if the replaced object
+    /// has another UID.
+    pub fn insert(
+        &mut self,
+        href: impl Into<String>,
+        object: IcalCalendarObject,
+    ) -> Result<Option<IcalCalendarObject>, CollectionError> {
+        let href = href.into();
+        let uid = object.get_uid();
+        if let Some(other) = self.hrefs.get(uid)
+            && *other != href
+        {
+            return Err(CollectionError::UidConflict {
+                uid: uid.to_owned(),
+                href: other.clone(),
+            });
+        }
+        if let Some(old) = self.objects.get(&href)
+            && old.get_uid() != uid
+        {
+            return Err(CollectionError::UidChanged {
+                href,
+                uid: old.get_uid().to_owned(),
+            });
+        }
+        self.hrefs.insert(uid.to_owned(), href.clone());
+        Ok(self.objects.insert(href, object))
+    }
+
+    /// Stores `object` with its UID as href, replacing the object with the same UID.
+    ///
+    /// Fails if an object with another UID is stored at that href.
+    pub fn upsert(
+        &mut self,
+        object: IcalCalendarObject,
+    ) -> Result<Option<IcalCalendarObject>, CollectionError> {
+        let href = self
+            .hrefs
+            .get(object.get_uid())
+            .cloned()
+            .unwrap_or_else(|| object.get_uid().to_owned());
+        self.insert(href, object)
+    }
+
+    pub fn remove(&mut self, href: &str) -> Option<IcalCalendarObject> {
+        let object = self.objects.remove(href)?;
+        self.hrefs.remove(object.get_uid());
+        Some(object)
+    }
+
+    pub fn get(&self, href: &str) -> Option<&IcalCalendarObject> {
+        self.objects.get(href)
+    }
+
+    /// The href of the object with `uid`
+    pub fn href(&self, uid: &str) -> Option<&str> {
+        self.hrefs.get(uid).map(String::as_str)
+    }
+
+    pub fn get_by_uid(&self, uid: &str) -> Option<&IcalCalendarObject> {
+        self.objects.get(self.hrefs.get(uid)?)
+    }
+
+    /// The objects and their hrefs, ordered by href
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &IcalCalendarObject)> {
+        self.objects
+            .iter()
+            .map(|(href, object)| (href.as_str(), object))
+    }
+
+    /// The TZIDs referenced by any object
+    pub fn get_tzids(&self) -> HashSet<&str> {
+        self.objects
+            .values()
+            .flat_map(IcalCalendarObject::get_tzids)
+            .collect()
+    }
+
+    /// The state of the collection to detect changes, see [`Snapshot`]
+    pub fn snapshot(&self) -> Snapshot {
+        self.objects.values().collect()
+    }
+
+    /// All objects in a single calendar, e.g. to export the collection
+    pub fn to_calendar(
+        &self,
+        prodid: String,
+        additional_properties: Vec<ContentLine>,
+    ) -> IcalCalendar {
+        IcalCalendar::from_objects(
+            prodid,
+            self.objects.values().cloned().collect(),
+            additional_properties,
+        )
+    }
+}
+
+impl<'a> IntoIterator for &'a CalendarCollection {
+    type Item = (&'a String, &'a IcalCalendarObject);
+    type IntoIter = std::collections::btree_map::Iter<'a, String, IcalCalendarObject>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.objects.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalendarCollection, CollectionError};
+    use crate::{IcalObjectParser, component::IcalCalendarObject};
+
+    fn object(uid: &str, tzid: Option<&str>) -> IcalCalendarObject {
+        let (vtimezone, dtstart) = match tzid {
+            Some(tzid) => (
+                format!(
+                    "BEGIN:VTIMEZONE\r\nTZID:{tzid}\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\n\
+                     TZOFFSETFROM:+0100\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n"
+                ),
+                format!("DTSTART;TZID={tzid}:20260323T090000"),
+            ),
+            None => (String::new(), "DTSTART:20260323T080000Z".to_owned()),
+        };
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\n\
+             {vtimezone}BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20260101T120000Z\r\n{dtstart}\r\n\
+             END:VEVENT\r\nEND:VCALENDAR\r\n"
+        );
+        IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_collection() {
+        let mut collection = CalendarCollection::new();
+        assert!(
+            collection
+                .insert("b.ics", object("b", Some("Europe/Berlin")))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            collection
+                .insert("a.ics", object("a", None))
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            collection.insert("c.ics", object("a", None)).unwrap_err(),
+            CollectionError::UidConflict {
+                uid: "a".to_owned(),
+                href: "a.ics".to_owned()
+            }
+        );
+        assert_eq!(
+            collection.insert("a.ics", object("c", None)).unwrap_err(),
+            CollectionError::UidChanged {
+                href: "a.ics".to_owned(),
+                uid: "a".to_owned()
+            }
+        );
+        assert!(
+            collection
+                .insert("a.ics", object("a", None))
+                .unwrap()
+                .is_some()
+        );
+        assert!(collection.upsert(object("a", None)).unwrap().is_some());
+        assert!(collection.upsert(object("c", None)).unwrap().is_none());
+        assert!(collection.insert("d", object("e", None)).unwrap().is_none());
+        assert_eq!(
+            collection.upsert(object("d", None)).unwrap_err(),
+            CollectionError::UidChanged {
+                href: "d".to_owned(),
+                uid: "e".to_owned()
+            }
+        );
+        assert!(collection.remove("d").is_some());
+
+        assert_eq!(
+            collection.iter().map(|(href, _)| href).collect::<Vec<_>>(),
+            vec!["a.ics", "b.ics", "c"]
+        );
+        assert_eq!(collection.href("b"), Some("b.ics"));
+        assert_eq!(collection.get_by_uid("c").unwrap().get_uid(), "c");
+        assert_eq!(
+            collection.get_tzids().into_iter().collect::<Vec<_>>(),
+            vec!["Europe/Berlin"]
+        );
+        let calendar = collection.to_calendar("-//Example//EN".to_owned(), vec![]);
+        assert_eq!(calendar.events.len(), 3);
+        assert_eq!(calendar.vtimezones.len(), 1);
+
+        let snapshot = collection.snapshot();
+        assert!(collection.remove("b.ics").is_some());
+        assert!(collection.get_by_uid("b").is_none());
+        assert_eq!(snapshot.changes(&collection.snapshot()).removed, vec!["b"]);
+    }
+}
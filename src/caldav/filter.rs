@@ -0,0 +1,975 @@
+//! The CALDAV:filter of calendar-query REPORTs, RFC 4791, section 9.7
+#[cfg(feature = "recurrence")]
+use crate::{component::ExpansionRange, property::IcalRECURIDProperty};
+use crate::{
+    component::{CalendarInnerData, Component, IcalCalendarObject},
+    parser::{ContentLine, ParseProp},
+    types::{CalDateOrDateTime, Tz, parse_duration},
+};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+#[cfg(feature = "recurrence")]
+use std::cmp::Ordering;
+use std::{borrow::Cow, collections::HashMap};
+
+/// Upper bound of alarm repetitions that are evaluated
+const MAX_ALARM_REPEAT: i32 = 1000;
+
+/// A CALDAV:time-range, both bounds are optional and the range is half-open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
+        Self { start, end }
+    }
+
+    fn start_before(&self, datetime: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start < datetime)
+    }
+
+    fn start_before_or_at(&self, datetime: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| start <= datetime)
+    }
+
+    fn end_after(&self, datetime: DateTime<Utc>) -> bool {
+        self.end.is_none_or(|end| end > datetime)
+    }
+
+    fn end_after_or_at(&self, datetime: DateTime<Utc>) -> bool {
+        self.end.is_none_or(|end| end >= datetime)
+    }
+
+    /// Whether the instant `datetime` lies inside the range
+    fn contains(&self, datetime: DateTime<Utc>) -> bool {
+        self.start_before_or_at(datetime) && self.end_after(datetime)
+    }
+
+    /// Whether the period from `start` to `end` overlaps the range,
+    /// a period without length overlaps if its start lies inside the range
+    pub(super) fn overlaps(&self, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> bool {
+        match end {
+            Some(end) if end > start => self.start_before(end) && self.end_after(start),
+            _ => self.contains(start),
+        }
+    }
+}
+
+/// Collations for text matches, RFC 4790
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// `i;ascii-casemap`, compares ASCII letters case-insensitively
+    #[default]
+    AsciiCasemap,
+    /// `i;unicode-casemap`, compares all letters case-insensitively, RFC 5051.
+    ///
+    /// Letters are case folded by mapping them to upper and then to lower case,
+    /// so e.g. "ß" equals "SS" and "ς" equals "Σ".
+    /// Unlike RFC 5051 strings aren't decomposed, so precomposed characters
+    /// don't match their decomposed forms.
+    UnicodeCasemap,
+    /// `i;octet`, compares the octets
+    Octet,
+}
+
+impl Collation {
+    /// The collation with the registered name `name`, `None` if it's unsupported
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "i;ascii-casemap" => Some(Self::AsciiCasemap),
+            "i;unicode-casemap" => Some(Self::UnicodeCasemap),
+            "i;octet" => Some(Self::Octet),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AsciiCasemap => "i;ascii-casemap",
+            Self::UnicodeCasemap => "i;unicode-casemap",
+            Self::Octet => "i;octet",
+        }
+    }
+
+    /// The representation of `value` that is compared octet by octet
+    fn fold<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::AsciiCasemap => Cow::Owned(value.to_ascii_lowercase()),
+            Self::UnicodeCasemap => Cow::Owned(
+                value
+                    .chars()
+                    .flat_map(char::to_uppercase)
+                    .flat_map(char::to_lowercase)
+                    .collect(),
+            ),
+            Self::Octet => Cow::Borrowed(value),
+        }
+    }
+
+    /// Whether `value` matches `needle` in the way of `match_type`
+    pub fn matches(&self, value: &str, needle: &str, match_type: MatchType) -> bool {
+        let (value, needle) = (self.fold(value), self.fold(needle));
+        match match_type {
+            MatchType::Equals => value == needle,
+            MatchType::Contains => value.contains(needle.as_ref()),
+            MatchType::StartsWith => value.starts_with(needle.as_ref()),
+            MatchType::EndsWith => value.ends_with(needle.as_ref()),
+        }
+    }
+}
+
+/// How a text-match compares values, RFC 6352, section 10.5.4.
+/// CalDAV only knows [`MatchType::Contains`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchType {
+    Equals,
+    #[default]
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl MatchType {
+    /// The match type with the name `name`, `None` if it's unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equals" => Some(Self::Equals),
+            "contains" => Some(Self::Contains),
+            "starts-with" => Some(Self::StartsWith),
+            "ends-with" => Some(Self::EndsWith),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equals => "equals",
+            Self::Contains => "contains",
+            Self::StartsWith => "starts-with",
+            Self::EndsWith => "ends-with",
+        }
+    }
+}
+
+/// A CALDAV:text-match or CARDDAV:text-match, matches values containing `value`
+/// unless another `match_type` is given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub value: String,
+    pub collation: Collation,
+    pub match_type: MatchType,
+    /// Inverts the match, i.e. negate-condition="yes"
+    pub negate: bool,
+}
+
+impl TextMatch {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            collation: Collation::default(),
+            match_type: MatchType::default(),
+            negate: false,
+        }
+    }
+
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    pub fn with_match_type(mut self, match_type: MatchType) -> Self {
+        self.match_type = match_type;
+        self
+    }
+
+    pub fn negated(mut self) -> Self {
+        self.negate = true;
+        self
+    }
+
+    /// Whether `value` matches
+    pub fn matches(&self, value: &str) -> bool {
+        self.collation.matches(value, &self.value, self.match_type) != self.negate
+    }
+}
+
+/// A CALDAV:param-filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamFilter {
+    pub name: String,
+    /// Matches properties without the parameter
+    pub is_not_defined: bool,
+    pub text_match: Option<TextMatch>,
+}
+
+impl ParamFilter {
+    /// Matches properties with the parameter `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            is_not_defined: false,
+            text_match: None,
+        }
+    }
+
+    /// Matches properties without the parameter `name`
+    pub fn not_defined(name: impl Into<String>) -> Self {
+        Self {
+            is_not_defined: true,
+            ..Self::new(name)
+        }
+    }
+
+    pub fn with_text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    fn matches(&self, prop: &ContentLine) -> bool {
+        let values = prop
+            .params
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&self.name))
+            .map(|(_, values)| values);
+        match (values, &self.text_match) {
+            (None, _) => self.is_not_defined,
+            (Some(_), _) if self.is_not_defined => false,
+            (Some(_), None) => true,
+            (Some(values), Some(text_match)) => {
+                values.iter().any(|value| text_match.matches(value))
+            }
+        }
+    }
+}
+
+/// A CALDAV:prop-filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropFilter {
+    pub name: String,
+    /// Matches components without the property
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub text_match: Option<TextMatch>,
+    pub param_filters: Vec<ParamFilter>,
+}
+
+impl PropFilter {
+    /// Matches components with the property `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            is_not_defined: false,
+            time_range: None,
+            text_match: None,
+            param_filters: vec![],
+        }
+    }
+
+    /// Matches components without the property `name`
+    pub fn not_defined(name: impl Into<String>) -> Self {
+        Self {
+            is_not_defined: true,
+            ..Self::new(name)
+        }
+    }
+
+    pub fn with_time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    pub fn with_text_match(mut self, text_match: TextMatch) -> Self {
+        self.text_match = Some(text_match);
+        self
+    }
+
+    pub fn with_param_filter(mut self, param_filter: ParamFilter) -> Self {
+        self.param_filters.push(param_filter);
+        self
+    }
+
+    fn matches(&self, properties: &[ContentLine], context: &Context) -> bool {
+        let mut props = properties
+            .iter()
+            .filter(|prop| prop.name.eq_ignore_ascii_case(&self.name));
+        if self.is_not_defined {
+            return props.next().is_none();
+        }
+        props.any(|prop| {
+            self.time_range
+                .is_none_or(|range| context.property_in_range(prop, &range))
+                && self
+                    .text_match
+                    .as_ref()
+                    .is_none_or(|text_match| text_match.matches(&prop.value))
+                && self.param_filters.iter().all(|filter| filter.matches(prop))
+        })
+    }
+}
+
+/// A CALDAV:comp-filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompFilter {
+    pub name: String,
+    /// Matches if there's no such component
+    pub is_not_defined: bool,
+    pub time_range: Option<TimeRange>,
+    pub prop_filters: Vec<PropFilter>,
+    pub comp_filters: Vec<CompFilter>,
+}
+
+impl CompFilter {
+    /// Matches if there's a component named `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            is_not_defined: false,
+            time_range: None,
+            prop_filters: vec![],
+            comp_filters: vec![],
+        }
+    }
+
+    /// Matches if there's no component named `name`
+    pub fn not_defined(name: impl Into<String>) -> Self {
+        Self {
+            is_not_defined: true,
+            ..Self::new(name)
+        }
+    }
+
+    pub fn with_time_range(mut self, time_range: TimeRange) -> Self {
+        self.time_range = Some(time_range);
+        self
+    }
+
+    pub fn with_prop_filter(mut self, prop_filter: PropFilter) -> Self {
+        self.prop_filters.push(prop_filter);
+        self
+    }
+
+    pub fn with_comp_filter(mut self, comp_filter: CompFilter) -> Self {
+        self.comp_filters.push(comp_filter);
+        self
+    }
+
+    /// All time ranges of this filter and the nested filters
+    #[cfg(feature = "recurrence")]
+    fn time_ranges(&self) -> Vec<TimeRange> {
+        self.time_range
+            .into_iter()
+            .chain(
+                self.prop_filters
+                    .iter()
+                    .filter_map(|prop_filter| prop_filter.time_range),
+            )
+            .chain(self.comp_filters.iter().flat_map(CompFilter::time_ranges))
+            .collect()
+    }
+
+    fn matches(&self, nodes: &[Node], parent: Option<&Node>, context: &Context) -> bool {
+        let mut nodes = nodes
+            .iter()
+            .filter(|node| node.name.eq_ignore_ascii_case(&self.name));
+        if self.is_not_defined {
+            return nodes.next().is_none();
+        }
+        nodes.any(|node| {
+            self.time_range
+                .is_none_or(|range| context.component_in_range(node, parent, &range))
+                && self
+                    .prop_filters
+                    .iter()
+                    .all(|filter| filter.matches(node.properties, context))
+                && self
+                    .comp_filters
+                    .iter()
+                    .all(|filter| filter.matches(&node.children, Some(node), context))
+        })
+    }
+}
+
+/// A CALDAV:filter, its comp-filter refers to the VCALENDAR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub comp_filter: CompFilter,
+    /// Timezone of floating and DATE values, i.e. the CALDAV:calendar-timezone.
+    /// UTC if `None`.
+    pub timezone: Option<chrono_tz::Tz>,
+}
+
+impl Filter {
+    pub fn new(comp_filter: CompFilter) -> Self {
+        Self {
+            comp_filter,
+            timezone: None,
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// The range containing all time ranges of the filter, `None` if there's no time range
+    #[cfg(feature = "recurrence")]
+    fn time_range(&self) -> Option<TimeRange> {
+        self.comp_filter
+            .time_ranges()
+            .into_iter()
+            .reduce(|a, b| TimeRange {
+                start: a.start.zip(b.start).map(|(a, b)| a.min(b)),
+                end: a.end.zip(b.end).map(|(a, b)| a.max(b)),
+            })
+    }
+}
+
+/// A component the filter is evaluated on
+#[derive(Debug)]
+struct Node<'a> {
+    name: &'static str,
+    properties: &'a [ContentLine],
+    children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn new<C: Component>(component: &'a C) -> Self {
+        Self {
+            name: component.get_comp_name(),
+            properties: component.get_properties(),
+            children: vec![],
+        }
+    }
+
+    fn with_children<C: Component>(mut self, children: &'a [C]) -> Self {
+        self.children = children.iter().map(Node::new).collect();
+        self
+    }
+}
+
+/// The nodes of the components of `inner`, optionally without the main component
+fn inner_nodes(inner: &CalendarInnerData, with_main: bool) -> Vec<Node<'_>> {
+    let skip = usize::from(!with_main);
+    match inner {
+        CalendarInnerData::Event(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .skip(skip)
+            .map(|event| Node::new(event).with_children(event.get_alarms()))
+            .collect(),
+        CalendarInnerData::Todo(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .skip(skip)
+            .map(|todo| Node::new(todo).with_children(&todo.alarms))
+            .collect(),
+        CalendarInnerData::Journal(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .skip(skip)
+            .map(Node::new)
+            .collect(),
+    }
+}
+
+/// Whether both RECURRENCE-IDs refer to the same instance
+#[cfg(feature = "recurrence")]
+pub(super) fn same_instance(
+    a: Option<&IcalRECURIDProperty>,
+    b: Option<&IcalRECURIDProperty>,
+) -> bool {
+    a.zip(b)
+        .is_some_and(|(a, b)| a.0.cmp(&b.0) == Ordering::Equal)
+}
+
+/// The nodes of the instances `expanded` from `stored`.
+/// Expanded instances don't contain alarms, so they're taken from the stored components.
+#[cfg(feature = "recurrence")]
+fn expanded_nodes<'a>(
+    expanded: &'a CalendarInnerData,
+    stored: &'a CalendarInnerData,
+) -> Vec<Node<'a>> {
+    match (expanded, stored) {
+        (CalendarInnerData::Event(first, rest), CalendarInnerData::Event(main, overrides)) => {
+            std::iter::once(first)
+                .chain(rest)
+                .map(|instance| {
+                    let stored = overrides
+                        .iter()
+                        .find(|over| {
+                            same_instance(over.recurid.as_ref(), instance.recurid.as_ref())
+                        })
+                        .unwrap_or(main);
+                    Node::new(instance).with_children(stored.get_alarms())
+                })
+                .collect()
+        }
+        (CalendarInnerData::Todo(first, rest), CalendarInnerData::Todo(main, overrides)) => {
+            std::iter::once(first)
+                .chain(rest)
+                .map(|instance| {
+                    let stored = overrides
+                        .iter()
+                        .find(|over| {
+                            same_instance(over.recurid.as_ref(), instance.recurid.as_ref())
+                        })
+                        .unwrap_or(main);
+                    Node::new(instance).with_children(&stored.alarms)
+                })
+                .collect()
+        }
+        (expanded, _) => inner_nodes(expanded, true),
+    }
+}
+
+pub(super) struct Context<'a> {
+    pub(super) timezones: &'a HashMap<String, Option<Tz>>,
+    pub(super) floating: chrono_tz::Tz,
+}
+
+impl Context<'_> {
+    /// The range to expand `inner` in to find all instances relevant to `range`.
+    ///
+    /// Instances starting before the range may still overlap it and alarms may be
+    /// triggered long before their instance, so the range is extended
+    /// by the longest duration, trigger offset and the maximum UTC offset.
+    #[cfg(feature = "recurrence")]
+    pub(super) fn expansion_range(
+        &self,
+        inner: &CalendarInnerData,
+        range: &TimeRange,
+    ) -> ExpansionRange {
+        let nodes = inner_nodes(inner, true);
+        let padding = nodes
+            .iter()
+            .flat_map(|node| {
+                let length = self
+                    .bounds(node.properties)
+                    .and_then(|(start, end)| end.map(|end| end - start));
+                let offsets = node.children.iter().filter_map(|alarm| {
+                    let (start, _) = self.bounds(node.properties)?;
+                    self.alarm_triggers(alarm.properties, node.properties)
+                        .into_iter()
+                        .map(|trigger| (trigger - start).abs())
+                        .max()
+                });
+                length.into_iter().chain(offsets)
+            })
+            .max()
+            .unwrap_or_default()
+            + Duration::days(1);
+        let mut expansion = ExpansionRange::new();
+        if let Some(start) = range.start {
+            expansion = expansion.with_start(start - padding);
+        }
+        if let Some(end) = range.end {
+            expansion = expansion.with_end(end + padding);
+        }
+        expansion
+    }
+
+    fn floating(&self, naive: NaiveDateTime) -> DateTime<Utc> {
+        self.floating
+            .from_local_datetime(&naive)
+            .earliest()
+            .map_or_else(|| naive.and_utc(), |datetime| datetime.to_utc())
+    }
+
+    /// The instant of a DATE or DATE-TIME property and whether it's a DATE
+    pub(super) fn datetime(&self, prop: &ContentLine) -> Option<(DateTime<Utc>, bool)> {
+        let value: CalDateOrDateTime =
+            ParseProp::parse_prop(prop, Some(self.timezones), "DATE-TIME").ok()?;
+        let instant = match &value {
+            CalDateOrDateTime::Date(date) => {
+                self.floating(date.naive_date().and_time(Default::default()))
+            }
+            CalDateOrDateTime::DateTime(datetime) if datetime.timezone().is_local() => {
+                self.floating(datetime.0.naive_local())
+            }
+            CalDateOrDateTime::DateTime(datetime) => datetime.utc(),
+        };
+        Some((instant, value.is_date()))
+    }
+
+    fn get(&self, properties: &[ContentLine], name: &str) -> Option<(DateTime<Utc>, bool)> {
+        properties
+            .iter()
+            .find(|prop| prop.name == name)
+            .and_then(|prop| self.datetime(prop))
+    }
+
+    fn duration(properties: &[ContentLine]) -> Option<Duration> {
+        properties
+            .iter()
+            .find(|prop| prop.name == "DURATION")
+            .and_then(|prop| parse_duration(&prop.value).ok())
+    }
+
+    /// Start and end of an event, to-do or journal
+    fn bounds(&self, properties: &[ContentLine]) -> Option<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+        let (start, is_date) = self.get(properties, "DTSTART")?;
+        let end = self
+            .get(properties, "DTEND")
+            .or_else(|| self.get(properties, "DUE"))
+            .map(|(end, _)| end)
+            .or_else(|| Self::duration(properties).map(|duration| start + duration))
+            .or_else(|| is_date.then(|| start + Duration::days(1)));
+        Some((start, end))
+    }
+
+    pub(super) fn property_in_range(&self, prop: &ContentLine, range: &TimeRange) -> bool {
+        match self.datetime(prop) {
+            Some((start, true)) => range.overlaps(start, Some(start + Duration::days(1))),
+            Some((start, false)) => range.contains(start),
+            None => false,
+        }
+    }
+
+    /// Whether the event, to-do or journal `component` lies inside `range`
+    pub(super) fn in_range<C: Component>(&self, component: &C, range: &TimeRange) -> bool {
+        self.component_in_range(&Node::new(component), None, range)
+    }
+
+    /// Time-range semantics of RFC 4791, section 9.9
+    fn component_in_range(&self, node: &Node, parent: Option<&Node>, range: &TimeRange) -> bool {
+        let properties = node.properties;
+        match node.name {
+            "VEVENT" | "VJOURNAL" => self
+                .bounds(properties)
+                .is_some_and(|(start, end)| range.overlaps(start, end)),
+            "VTODO" => self.todo_in_range(properties, range),
+            "VALARM" => parent.is_some_and(|parent| {
+                self.alarm_triggers(properties, parent.properties)
+                    .into_iter()
+                    .any(|trigger| range.contains(trigger))
+            }),
+            "VFREEBUSY" => {
+                let start = self.get(properties, "DTSTART").map(|(start, _)| start);
+                let end = self.get(properties, "DTEND").map(|(end, _)| end);
+                start.is_some_and(|start| range.overlaps(start, end))
+            }
+            _ => false,
+        }
+    }
+
+    fn todo_in_range(&self, properties: &[ContentLine], range: &TimeRange) -> bool {
+        let start = self.get(properties, "DTSTART").map(|(start, _)| start);
+        let due = self.get(properties, "DUE").map(|(due, _)| due);
+        let duration = Self::duration(properties);
+        let completed = self.get(properties, "COMPLETED").map(|(at, _)| at);
+        let created = self.get(properties, "CREATED").map(|(at, _)| at);
+        match (start, due, duration, completed, created) {
+            (Some(start), _, Some(duration), _, _) => {
+                let end = start + duration;
+                range.start_before_or_at(end)
+                    && (range.end_after(start) || range.end_after_or_at(end))
+            }
+            (Some(start), Some(due), _, _, _) => {
+                (range.start_before(due) || range.start_before_or_at(start))
+                    && (range.end_after(start) || range.end_after_or_at(due))
+            }
+            (Some(start), None, None, _, _) => range.contains(start),
+            (None, Some(due), _, _, _) => range.start_before(due) && range.end_after_or_at(due),
+            (None, None, _, Some(completed), Some(created)) => {
+                (range.start_before_or_at(created) || range.start_before_or_at(completed))
+                    && (range.end_after_or_at(created) || range.end_after_or_at(completed))
+            }
+            (None, None, _, Some(completed), None) => {
+                range.start_before_or_at(completed) && range.end_after_or_at(completed)
+            }
+            (None, None, _, None, Some(created)) => range.end_after(created),
+            (None, None, _, None, None) => true,
+        }
+    }
+
+    /// All times an alarm of the component with `parent` properties is triggered
+    fn alarm_triggers(
+        &self,
+        properties: &[ContentLine],
+        parent: &[ContentLine],
+    ) -> Vec<DateTime<Utc>> {
+        let Some(trigger) = properties.iter().find(|prop| prop.name == "TRIGGER") else {
+            return vec![];
+        };
+        let first = if trigger.params.get_value_type() == Some("DATE-TIME") {
+            self.datetime(trigger).map(|(at, _)| at)
+        } else {
+            let offset = parse_duration(&trigger.value).ok();
+            let base = if trigger.params.get_param("RELATED") == Some("END") {
+                self.bounds(parent)
+                    .and_then(|(_, end)| end)
+                    .or_else(|| self.get(parent, "DUE").map(|(due, _)| due))
+            } else {
+                self.get(parent, "DTSTART").map(|(start, _)| start)
+            };
+            base.zip(offset).map(|(base, offset)| base + offset)
+        };
+        let Some(first) = first else {
+            return vec![];
+        };
+        let repeat = properties
+            .iter()
+            .find(|prop| prop.name == "REPEAT")
+            .and_then(|repeat| repeat.value.parse::<i32>().ok())
+            .unwrap_or_default()
+            .clamp(0, MAX_ALARM_REPEAT);
+        match Self::duration(properties) {
+            Some(interval) if repeat > 0 => {
+                (0..=repeat).map(|index| first + interval * index).collect()
+            }
+            _ => vec![first],
+        }
+    }
+}
+
+impl IcalCalendarObject {
+    /// Whether this object matches the CalDAV `filter`.
+    ///
+    /// Time ranges are evaluated on every instance of recurring events and to-dos,
+    /// following the semantics of RFC 4791, section 9.9.
+    /// Without the `recurrence` feature only the stored components are evaluated.
+    /// Values that can't be parsed never match a time range.
+    pub fn matches(&self, filter: &Filter) -> bool {
+        let context = Context {
+            timezones: &self.timezones,
+            floating: filter.timezone.unwrap_or(chrono_tz::UTC),
+        };
+
+        #[cfg(feature = "recurrence")]
+        let expanded = filter.time_range().and_then(|range| {
+            self.expand(&context.expansion_range(&self.inner, &range))
+                .ok()
+        });
+
+        let mut children: Vec<Node> = self
+            .vtimezones
+            .values()
+            .map(|vtimezone| Node::new(vtimezone).with_children(&vtimezone.transitions))
+            .collect();
+        #[cfg(feature = "recurrence")]
+        match &expanded {
+            Some(expansion) => {
+                if let Some(object) = &expansion.object {
+                    children.extend(expanded_nodes(&object.inner, &self.inner));
+                }
+                // Overrides may move instances into the range from far away
+                children.extend(inner_nodes(&self.inner, false));
+            }
+            None => children.extend(inner_nodes(&self.inner, true)),
+        }
+        // Without the recurrence feature only the stored components are evaluated
+        #[cfg(not(feature = "recurrence"))]
+        children.extend(inner_nodes(&self.inner, true));
+        let root = Node {
+            name: "VCALENDAR",
+            properties: &self.properties,
+            children,
+        };
+        filter
+            .comp_filter
+            .matches(std::slice::from_ref(&root), None, &context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Collation, CompFilter, Filter, MatchType, ParamFilter, PropFilter, TextMatch, TimeRange,
+    };
+    use crate::{IcalObjectParser, component::IcalCalendarObject};
+    use chrono::{DateTime, Utc};
+    use rstest::rstest;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+EXDATE:20260330T080000Z\r
+SUMMARY:Meeting\r
+ATTENDEE;PARTSTAT=ACCEPTED:mailto:john@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Meeting\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID:20260406T080000Z\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260410T120000Z\r
+DURATION:PT1H\r
+SUMMARY:Meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    const ALL_DAY: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:holiday@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;VALUE=DATE:20260406\r
+SUMMARY:Holiday\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse(input: &str) -> IcalCalendarObject {
+        IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn datetime(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().to_utc()
+    }
+
+    fn range(start: &str, end: &str) -> TimeRange {
+        TimeRange::new(Some(datetime(start)), Some(datetime(end)))
+    }
+
+    fn events(filter: CompFilter) -> Filter {
+        Filter::new(CompFilter::new("VCALENDAR").with_comp_filter(filter))
+    }
+
+    #[rstest]
+    // Overlaps the first instance
+    #[case("2026-03-23T08:30:00Z", "2026-03-23T08:45:00Z", true)]
+    // The range is exclusive at its start
+    #[case("2026-03-23T09:00:00Z", "2026-03-24T00:00:00Z", false)]
+    // Excluded instance
+    #[case("2026-03-30T07:00:00Z", "2026-03-30T10:00:00Z", false)]
+    // Moved instance
+    #[case("2026-04-06T07:00:00Z", "2026-04-06T10:00:00Z", false)]
+    #[case("2026-04-10T11:00:00Z", "2026-04-10T12:30:00Z", true)]
+    // Later instances are only expanded with the recurrence feature
+    #[cfg_attr(not(feature = "recurrence"), ignore)]
+    #[case("2026-04-13T08:59:00Z", "2026-04-13T10:00:00Z", true)]
+    #[case("2026-04-20T00:00:00Z", "2026-05-01T00:00:00Z", false)]
+    fn test_event_time_range(#[case] start: &str, #[case] end: &str, #[case] expected: bool) {
+        let object = parse(CALENDAR);
+        let filter = events(CompFilter::new("VEVENT").with_time_range(range(start, end)));
+        assert_eq!(object.matches(&filter), expected);
+    }
+
+    #[rstest]
+    #[case("2026-03-23T07:45:00Z", "2026-03-23T07:50:00Z", true)]
+    #[cfg_attr(not(feature = "recurrence"), ignore)]
+    #[case("2026-04-13T07:45:00Z", "2026-04-13T07:50:00Z", true)]
+    // The moved instance has no alarm
+    #[case("2026-04-10T11:40:00Z", "2026-04-10T11:50:00Z", false)]
+    #[case("2026-03-23T08:00:00Z", "2026-03-23T09:00:00Z", false)]
+    fn test_alarm_time_range(#[case] start: &str, #[case] end: &str, #[case] expected: bool) {
+        let object = parse(CALENDAR);
+        let filter = events(
+            CompFilter::new("VEVENT")
+                .with_comp_filter(CompFilter::new("VALARM").with_time_range(range(start, end))),
+        );
+        assert_eq!(object.matches(&filter), expected);
+    }
+
+    #[rstest]
+    #[case(PropFilter::new("SUMMARY").with_text_match(TextMatch::new("meet")), true)]
+    #[case(PropFilter::new("SUMMARY").with_text_match(TextMatch::new("meet").negated()), false)]
+    #[case(
+        PropFilter::new("SUMMARY")
+            .with_text_match(TextMatch::new("meet").with_collation(Collation::Octet)),
+        false
+    )]
+    #[case(PropFilter::not_defined("LOCATION"), true)]
+    #[case(PropFilter::not_defined("SUMMARY"), false)]
+    #[case(
+        PropFilter::new("ATTENDEE")
+            .with_param_filter(ParamFilter::new("PARTSTAT").with_text_match(TextMatch::new("accepted"))),
+        true
+    )]
+    #[case(PropFilter::new("ATTENDEE").with_param_filter(ParamFilter::not_defined("PARTSTAT")), false)]
+    #[case(
+        PropFilter::new("DTSTART").with_time_range(range("2026-03-23T00:00:00Z", "2026-03-24T00:00:00Z")),
+        true
+    )]
+    fn test_prop_filter(#[case] prop_filter: PropFilter, #[case] expected: bool) {
+        let object = parse(CALENDAR);
+        let filter = events(CompFilter::new("VEVENT").with_prop_filter(prop_filter));
+        assert_eq!(object.matches(&filter), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "Straße",
+        "STRASSE",
+        Collation::UnicodeCasemap,
+        MatchType::Equals,
+        true
+    )]
+    #[case("Straße", "STRASSE", Collation::AsciiCasemap, MatchType::Equals, false)]
+    #[case("ΟΔΟΣ", "οδος", Collation::UnicodeCasemap, MatchType::Equals, true)]
+    #[case("Ärger", "är", Collation::UnicodeCasemap, MatchType::StartsWith, true)]
+    #[case("Ärger", "är", Collation::AsciiCasemap, MatchType::StartsWith, false)]
+    #[case("Jane Doe", "doe", Collation::AsciiCasemap, MatchType::EndsWith, true)]
+    #[case(
+        "Jane Doe",
+        "jane",
+        Collation::AsciiCasemap,
+        MatchType::EndsWith,
+        false
+    )]
+    #[case("Jane Doe", "Doe", Collation::Octet, MatchType::Contains, true)]
+    #[case("Jane Doe", "doe", Collation::Octet, MatchType::Contains, false)]
+    fn test_text_match(
+        #[case] value: &str,
+        #[case] needle: &str,
+        #[case] collation: Collation,
+        #[case] match_type: MatchType,
+        #[case] expected: bool,
+    ) {
+        let text_match = TextMatch::new(needle)
+            .with_collation(collation)
+            .with_match_type(match_type);
+        assert_eq!(text_match.matches(value), expected);
+        assert_eq!(text_match.negated().matches(value), !expected);
+    }
+
+    #[test]
+    fn test_comp_filter() {
+        let object = parse(CALENDAR);
+        assert!(object.matches(&Filter::new(CompFilter::new("VCALENDAR"))));
+        assert!(!object.matches(&events(CompFilter::new("VTODO"))));
+        assert!(object.matches(&events(CompFilter::not_defined("VTODO"))));
+        assert!(!object.matches(&Filter::new(CompFilter::new("VTODO"))));
+    }
+
+    #[rstest]
+    #[case(None, "2026-04-05T23:00:00Z", "2026-04-06T00:00:00Z", false)]
+    #[case(None, "2026-04-06T23:00:00Z", "2026-04-07T00:00:00Z", true)]
+    // The day starts two hours earlier in Berlin
+    #[case(
+        Some(chrono_tz::Europe::Berlin),
+        "2026-04-05T23:00:00Z",
+        "2026-04-06T00:00:00Z",
+        true
+    )]
+    #[case(
+        Some(chrono_tz::Europe::Berlin),
+        "2026-04-06T23:00:00Z",
+        "2026-04-07T00:00:00Z",
+        false
+    )]
+    fn test_floating(
+        #[case] timezone: Option<chrono_tz::Tz>,
+        #[case] start: &str,
+        #[case] end: &str,
+        #[case] expected: bool,
+    ) {
+        let object = parse(ALL_DAY);
+        let mut filter = events(CompFilter::new("VEVENT").with_time_range(range(start, end)));
+        filter.timezone = timezone;
+        assert_eq!(object.matches(&filter), expected);
+    }
+}
@@ -0,0 +1,10 @@
+//! Building blocks for CalDAV servers as defined in RFC 4791
+mod collection;
+pub use collection::*;
+mod filter;
+pub use filter::*;
+mod partial;
+pub use partial::*;
+mod sync;
+pub use sync::*;
+mod transform;
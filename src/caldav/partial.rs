@@ -0,0 +1,349 @@
+//! Partial retrieval of calendar data, RFC 4791, section 9.6.1
+use crate::{
+    component::{CalendarInnerData, Component, IcalCalendar, IcalCalendarObject},
+    parser::ContentLine,
+    validation::required_properties,
+};
+use std::borrow::Cow;
+
+/// A CALDAV:prop of a CALDAV:comp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropSpec {
+    pub name: String,
+    /// Only return the property's name and parameters, i.e. novalue="yes"
+    pub novalue: bool,
+}
+
+/// The properties of a component that are returned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropSelection {
+    /// CALDAV:allprop
+    All,
+    /// A list of CALDAV:prop
+    Some(Vec<PropSpec>),
+}
+
+/// The sub-components of a component that are returned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompSelection {
+    /// CALDAV:allcomp
+    All,
+    /// A list of CALDAV:comp
+    Some(Vec<CompSpec>),
+}
+
+/// A CALDAV:comp, the requested part of a component named `name`.
+///
+/// Properties that are required by RFC 5545 and RECURRENCE-ID are always returned,
+/// so the result stays valid and instances can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompSpec {
+    pub name: String,
+    pub props: PropSelection,
+    pub comps: CompSelection,
+}
+
+impl CompSpec {
+    /// Only returns the required properties of the component
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            props: PropSelection::Some(vec![]),
+            comps: CompSelection::Some(vec![]),
+        }
+    }
+
+    /// Returns the whole component
+    pub fn all(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            props: PropSelection::All,
+            comps: CompSelection::All,
+        }
+    }
+
+    pub fn with_all_props(mut self) -> Self {
+        self.props = PropSelection::All;
+        self
+    }
+
+    pub fn with_prop(self, name: impl Into<String>) -> Self {
+        self.with_prop_spec(PropSpec {
+            name: name.into(),
+            novalue: false,
+        })
+    }
+
+    /// Returns the property `name` without its value
+    pub fn with_prop_novalue(self, name: impl Into<String>) -> Self {
+        self.with_prop_spec(PropSpec {
+            name: name.into(),
+            novalue: true,
+        })
+    }
+
+    fn with_prop_spec(mut self, spec: PropSpec) -> Self {
+        match &mut self.props {
+            PropSelection::All => {}
+            PropSelection::Some(props) => props.push(spec),
+        }
+        self
+    }
+
+    pub fn with_all_comps(mut self) -> Self {
+        self.comps = CompSelection::All;
+        self
+    }
+
+    pub fn with_comp(mut self, comp: CompSpec) -> Self {
+        match &mut self.comps {
+            CompSelection::All => {}
+            CompSelection::Some(comps) => comps.push(comp),
+        }
+        self
+    }
+
+    /// The spec of the sub-components named `name`, `None` if they aren't requested
+    fn comp(&self, name: &str) -> Option<Cow<'_, CompSpec>> {
+        match &self.comps {
+            CompSelection::All => Some(Cow::Owned(Self::all(name))),
+            CompSelection::Some(comps) => comps
+                .iter()
+                .find(|comp| comp.name.eq_ignore_ascii_case(name))
+                .map(Cow::Borrowed),
+        }
+    }
+
+    /// The requested properties of the component `component`
+    fn props(&self, component: &str, properties: &[ContentLine]) -> Vec<ContentLine> {
+        let PropSelection::Some(specs) = &self.props else {
+            return properties.to_vec();
+        };
+        let required = required_properties(component);
+        properties
+            .iter()
+            .filter_map(|prop| {
+                if prop.name == "RECURRENCE-ID"
+                    || required.iter().any(|(name, _)| *name == prop.name)
+                {
+                    return Some(prop.clone());
+                }
+                let spec = specs
+                    .iter()
+                    .find(|spec| spec.name.eq_ignore_ascii_case(&prop.name))?;
+                let mut prop = prop.clone();
+                if spec.novalue {
+                    prop.value.clear();
+                }
+                Some(prop)
+            })
+            .collect()
+    }
+
+    /// The requested properties of `component`
+    fn project<C: Component>(&self, component: &C) -> Vec<ContentLine> {
+        self.props(component.get_comp_name(), component.get_properties())
+    }
+}
+
+impl IcalCalendarObject {
+    /// The part of this object requested by `spec`, which refers to the VCALENDAR.
+    ///
+    /// Serialize the result with [`Emitter::generate`](crate::generator::Emitter::generate)
+    /// to honor a CALDAV:calendar-data element in a REPORT or PROPFIND.
+    /// The result is a [`IcalCalendar`] as it may not contain any component.
+    pub fn partial(&self, spec: &CompSpec) -> IcalCalendar {
+        let mut calendar = IcalCalendar {
+            properties: spec.project(self),
+            events: vec![],
+            alarms: vec![],
+            todos: vec![],
+            journals: vec![],
+            free_busys: vec![],
+            vtimezones: Default::default(),
+            timezones: self.timezones.clone(),
+        };
+        if let Some(spec) = spec.comp("VTIMEZONE") {
+            let standard = spec.comp("STANDARD");
+            let daylight = spec.comp("DAYLIGHT");
+            calendar.vtimezones = self
+                .vtimezones
+                .iter()
+                .map(|(tzid, vtimezone)| {
+                    let mut vtimezone = vtimezone.clone();
+                    vtimezone.properties = spec.project(&vtimezone);
+                    vtimezone.transitions.retain_mut(|transition| {
+                        let spec = match transition.get_comp_name() {
+                            "STANDARD" => &standard,
+                            _ => &daylight,
+                        };
+                        let Some(spec) = spec else {
+                            return false;
+                        };
+                        transition.properties = spec.project(transition);
+                        true
+                    });
+                    (tzid.clone(), vtimezone)
+                })
+                .collect();
+        }
+        match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                if let Some(spec) = spec.comp("VEVENT") {
+                    let alarms = spec.comp("VALARM");
+                    calendar.events = std::iter::once(main)
+                        .chain(overrides)
+                        .map(|event| {
+                            let mut event = event.clone();
+                            event.properties = spec.project(&event);
+                            match &alarms {
+                                Some(spec) => {
+                                    for alarm in &mut event.alarms {
+                                        alarm.properties = spec.project(alarm);
+                                    }
+                                }
+                                None => event.alarms.clear(),
+                            }
+                            event
+                        })
+                        .collect();
+                }
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                if let Some(spec) = spec.comp("VTODO") {
+                    let alarms = spec.comp("VALARM");
+                    calendar.todos = std::iter::once(main)
+                        .chain(overrides)
+                        .map(|todo| {
+                            let mut todo = todo.clone();
+                            todo.properties = spec.project(&todo);
+                            match &alarms {
+                                Some(spec) => {
+                                    for alarm in &mut todo.alarms {
+                                        alarm.properties = spec.project(alarm);
+                                    }
+                                }
+                                None => todo.alarms.clear(),
+                            }
+                            todo
+                        })
+                        .collect();
+                }
+            }
+            CalendarInnerData::Journal(main, overrides) => {
+                if let Some(spec) = spec.comp("VJOURNAL") {
+                    calendar.journals = std::iter::once(main)
+                        .chain(overrides)
+                        .map(|journal| {
+                            let mut journal = journal.clone();
+                            journal.properties = spec.project(&journal);
+                            journal
+                        })
+                        .collect();
+                }
+            }
+        }
+        calendar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompSpec;
+    use crate::{IcalObjectParser, component::IcalCalendarObject, generator::Emitter};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260323T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+SUMMARY:Meeting\r
+DESCRIPTION:Weekly meeting\r
+ATTENDEE;PARTSTAT=ACCEPTED:mailto:john@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Meeting\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID;TZID=Europe/Berlin:20260330T090000\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260330T100000\r
+DURATION:PT1H\r
+SUMMARY:Moved meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse() -> IcalCalendarObject {
+        IcalObjectParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_partial() {
+        let object = parse();
+        assert_eq!(
+            object.partial(&CompSpec::all("VCALENDAR")).generate(),
+            CALENDAR
+        );
+
+        let spec = CompSpec::new("VCALENDAR").with_comp(
+            CompSpec::new("VEVENT")
+                .with_prop("SUMMARY")
+                .with_prop_novalue("ATTENDEE"),
+        );
+        assert_eq!(
+            object.partial(&spec).generate(),
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260323T090000\r
+SUMMARY:Meeting\r
+ATTENDEE;PARTSTAT=ACCEPTED:\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID;TZID=Europe/Berlin:20260330T090000\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260330T100000\r
+SUMMARY:Moved meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+        );
+
+        let spec = CompSpec::new("VCALENDAR")
+            .with_comp(CompSpec::all("VTIMEZONE"))
+            .with_comp(CompSpec::new("VEVENT").with_comp(CompSpec::new("VALARM")));
+        let partial = object.partial(&spec);
+        assert_eq!(partial.vtimezones.len(), 1);
+        assert_eq!(partial.events.len(), 2);
+        assert_eq!(
+            partial.events[0].get_alarms()[0].generate(),
+            "BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\n"
+        );
+
+        let partial = object.partial(&CompSpec::new("VCALENDAR").with_comp(CompSpec::new("VTODO")));
+        assert!(partial.events.is_empty() && partial.vtimezones.is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Change detection between states of a collection, e.g. for sync-collection REPORTs (RFC 6578)
+use crate::component::{IcalCalendarObject, VcardContact};
+use std::collections::BTreeMap;
+
+/// The state of a collection, i.e. the content hash of every object by its UID.
+///
+/// Snapshots are cheap to keep, so one can be stored per sync token and compared
+/// to the current state of the collection with [`Snapshot::changes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    hashes: BTreeMap<String, u64>,
+}
+
+/// The changes between two snapshots, each sorted by UID
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Records the object with `uid` and content hash `hash`, replacing a previous one with the same UID
+    pub fn insert(&mut self, uid: impl Into<String>, hash: u64) {
+        self.hashes.insert(uid.into(), hash);
+    }
+
+    pub fn insert_object(&mut self, object: &IcalCalendarObject) {
+        self.insert(object.get_uid(), object.content_hash());
+    }
+
+    /// Records `contact`, contacts without UID are ignored
+    pub fn insert_contact(&mut self, contact: &VcardContact) {
+        if let Some(uid) = contact.get_uid() {
+            self.insert(uid, contact.content_hash());
+        }
+    }
+
+    pub fn remove(&mut self, uid: &str) -> Option<u64> {
+        self.hashes.remove(uid)
+    }
+
+    /// The content hash of the object with `uid`
+    pub fn get(&self, uid: &str) -> Option<u64> {
+        self.hashes.get(uid).copied()
+    }
+
+    /// The changes from this snapshot to the `newer` one
+    pub fn changes(&self, newer: &Self) -> Changes {
+        let mut changes = Changes::default();
+        for (uid, hash) in &newer.hashes {
+            match self.hashes.get(uid) {
+                None => changes.added.push(uid.clone()),
+                Some(old) if old != hash => changes.changed.push(uid.clone()),
+                Some(_) => {}
+            }
+        }
+        changes.removed = self
+            .hashes
+            .keys()
+            .filter(|uid| !newer.hashes.contains_key(*uid))
+            .cloned()
+            .collect();
+        changes
+    }
+}
+
+impl<'a> FromIterator<&'a IcalCalendarObject> for Snapshot {
+    fn from_iter<T: IntoIterator<Item = &'a IcalCalendarObject>>(iter: T) -> Self {
+        let mut snapshot = Self::new();
+        for object in iter {
+            snapshot.insert_object(object);
+        }
+        snapshot
+    }
+}
+
+impl<'a> FromIterator<&'a VcardContact> for Snapshot {
+    fn from_iter<T: IntoIterator<Item = &'a VcardContact>>(iter: T) -> Self {
+        let mut snapshot = Self::new();
+        for contact in iter {
+            snapshot.insert_contact(contact);
+        }
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Changes, Snapshot};
+    use crate::{IcalObjectParser, component::IcalCalendarObject};
+
+    fn object(uid: &str, summary: &str) -> IcalCalendarObject {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\n\
+             BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20260101T120000Z\r\n\
+             DTSTART:20260323T080000Z\r\nSUMMARY:{summary}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        );
+        IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_changes() {
+        let old: Snapshot = [object("a", "A"), object("b", "B"), object("c", "C")]
+            .iter()
+            .collect();
+        let new: Snapshot = [object("d", "D"), object("c", "C"), object("b", "Changed")]
+            .iter()
+            .collect();
+        assert!(old.changes(&old).is_empty());
+        assert_eq!(
+            old.changes(&new),
+            Changes {
+                added: vec!["d".to_owned()],
+                changed: vec!["b".to_owned()],
+                removed: vec!["a".to_owned()],
+            }
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! The CALDAV:expand, CALDAV:limit-recurrence-set and CALDAV:limit-freebusy-set transforms
+//! of calendar-data, RFC 4791, section 9.6
+#[cfg(feature = "recurrence")]
+use super::filter::same_instance;
+use super::filter::{Context, TimeRange};
+use crate::{
+    component::{CalendarInnerData, Component, IcalCalendarObject, IcalFreeBusy},
+    types::Period,
+};
+#[cfg(feature = "recurrence")]
+use crate::{
+    component::{IcalEvent, IcalJournal, IcalTodo},
+    parser::ParserError,
+};
+#[cfg(feature = "recurrence")]
+use std::collections::HashSet;
+
+impl Context<'_> {
+    /// Whether the override `over` is needed to describe the instances inside `range`:
+    /// It either overlaps the range, replaces an instance inside of it or
+    /// changes instances inside of it with RANGE=THISANDFUTURE.
+    fn keeps_override<C: Component>(&self, over: &C, range: &TimeRange) -> bool {
+        if self.in_range(over, range) {
+            return true;
+        }
+        let Some(recurid) = over.get_property("RECURRENCE-ID") else {
+            return false;
+        };
+        if recurid.params.get_param("RANGE") == Some("THISANDFUTURE") {
+            return self
+                .datetime(recurid)
+                .is_some_and(|(start, _)| range.end.is_none_or(|end| end > start));
+        }
+        self.property_in_range(recurid, range)
+    }
+}
+
+impl IcalCalendarObject {
+    /// Applies CALDAV:expand with `range`, see RFC 4791, section 9.6.5.
+    ///
+    /// Every instance overlapping `range` is returned as its own component with a
+    /// RECURRENCE-ID and without RRULE, RDATE, EXRULE and EXDATE. Instances keep the
+    /// alarms of their override or main component.
+    /// Floating and DATE values are evaluated in UTC.
+    /// Returns `None` if no instance overlaps the range.
+    #[cfg(feature = "recurrence")]
+    pub fn caldav_expand(&self, range: &TimeRange) -> Result<Option<Self>, ParserError> {
+        let context = Context {
+            timezones: &self.timezones,
+            floating: chrono_tz::UTC,
+        };
+        let expansion = self.expand(&context.expansion_range(&self.inner, range))?;
+        let expanded = expansion.object.as_ref().map(|object| &object.inner);
+
+        let inner = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let instances = match expanded {
+                    Some(CalendarInnerData::Event(first, rest)) => {
+                        std::iter::once(first).chain(rest).collect()
+                    }
+                    _ => vec![],
+                };
+                let mut events: Vec<IcalEvent> = instances
+                    .into_iter()
+                    .filter(|instance| context.in_range(*instance, range))
+                    .map(|instance| {
+                        let stored = overrides
+                            .iter()
+                            .find(|over| {
+                                same_instance(over.recurid.as_ref(), instance.recurid.as_ref())
+                            })
+                            .unwrap_or(main);
+                        let mut instance = instance.clone();
+                        instance.alarms.clone_from(&stored.alarms);
+                        instance
+                    })
+                    .collect();
+                // Overrides may move instances into the range from outside the expanded one
+                let moved: Vec<IcalEvent> = overrides
+                    .iter()
+                    .filter(|over| {
+                        context.in_range(*over, range)
+                            && !events.iter().any(|event| {
+                                same_instance(event.recurid.as_ref(), over.recurid.as_ref())
+                            })
+                    })
+                    .map(|over| over.clone().to_utc_or_local())
+                    .collect();
+                events.extend(moved);
+                if events.is_empty() {
+                    return Ok(None);
+                }
+                CalendarInnerData::from_events(events)?
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let instances = match expanded {
+                    Some(CalendarInnerData::Todo(first, rest)) => {
+                        std::iter::once(first).chain(rest).collect()
+                    }
+                    _ => vec![],
+                };
+                let mut todos: Vec<IcalTodo> = instances
+                    .into_iter()
+                    .filter(|instance| context.in_range(*instance, range))
+                    .map(|instance| {
+                        let stored = overrides
+                            .iter()
+                            .find(|over| {
+                                same_instance(over.recurid.as_ref(), instance.recurid.as_ref())
+                            })
+                            .unwrap_or(main);
+                        let mut instance = instance.clone();
+                        instance.alarms.clone_from(&stored.alarms);
+                        instance
+                    })
+                    .collect();
+                let moved: Vec<IcalTodo> = overrides
+                    .iter()
+                    .filter(|over| {
+                        context.in_range(*over, range)
+                            && !todos.iter().any(|todo| {
+                                same_instance(todo.recurid.as_ref(), over.recurid.as_ref())
+                            })
+                    })
+                    .map(|over| over.clone().to_utc_or_local())
+                    .collect();
+                todos.extend(moved);
+                if todos.is_empty() {
+                    return Ok(None);
+                }
+                CalendarInnerData::from_todos(todos)?
+            }
+            // Journals aren't expanded, see [`Self::expand`]
+            CalendarInnerData::Journal(main, overrides) => {
+                let journals: Vec<IcalJournal> = std::iter::once(main)
+                    .chain(overrides)
+                    .filter(|journal| context.in_range(*journal, range))
+                    .cloned()
+                    .collect();
+                if journals.is_empty() {
+                    return Ok(None);
+                }
+                CalendarInnerData::from_journals(journals)?
+            }
+        };
+
+        let tzids: HashSet<&str> = inner.get_tzids();
+        let mut timezones = self.timezones.clone();
+        let mut vtimezones = self.vtimezones.clone();
+        timezones.retain(|tzid, _| tzids.contains(tzid.as_str()));
+        vtimezones.retain(|tzid, _| tzids.contains(tzid.as_str()));
+        Ok(Some(Self {
+            properties: self.properties.clone(),
+            inner,
+            vtimezones,
+            timezones,
+        }))
+    }
+
+    /// Applies CALDAV:limit-recurrence-set with `range`, see RFC 4791, section 9.6.6.
+    ///
+    /// The main component is always returned, overrides only if they overlap the range,
+    /// replace an instance inside of it or change instances inside of it with RANGE=THISANDFUTURE.
+    /// Floating and DATE values are evaluated in UTC.
+    pub fn limit_recurrence_set(&self, range: &TimeRange) -> Self {
+        let context = Context {
+            timezones: &self.timezones,
+            floating: chrono_tz::UTC,
+        };
+        let inner = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => CalendarInnerData::Event(
+                main.clone(),
+                overrides
+                    .iter()
+                    .filter(|over| context.keeps_override(*over, range))
+                    .cloned()
+                    .collect(),
+            ),
+            CalendarInnerData::Todo(main, overrides) => CalendarInnerData::Todo(
+                main.clone(),
+                overrides
+                    .iter()
+                    .filter(|over| context.keeps_override(*over, range))
+                    .cloned()
+                    .collect(),
+            ),
+            CalendarInnerData::Journal(main, overrides) => CalendarInnerData::Journal(
+                main.clone(),
+                overrides
+                    .iter()
+                    .filter(|over| context.keeps_override(*over, range))
+                    .cloned()
+                    .collect(),
+            ),
+        };
+        Self {
+            properties: self.properties.clone(),
+            inner,
+            vtimezones: self.vtimezones.clone(),
+            timezones: self.timezones.clone(),
+        }
+    }
+}
+
+impl IcalFreeBusy {
+    /// Applies CALDAV:limit-freebusy-set with `range`, see RFC 4791, section 9.6.7.
+    ///
+    /// Periods of FREEBUSY properties are clipped to the range, periods outside of it and
+    /// FREEBUSY properties without any remaining period are removed.
+    /// Periods that can't be parsed are kept as they are.
+    pub fn limit_freebusy_set(&self, range: &TimeRange) -> Self {
+        let mut properties = Vec::with_capacity(self.properties.len());
+        for prop in &self.properties {
+            if prop.name != "FREEBUSY" {
+                properties.push(prop.clone());
+                continue;
+            }
+            let periods: Vec<String> = prop
+                .value
+                .split(',')
+                .filter_map(|value| {
+                    let Ok(period) = Period::parse(value, None) else {
+                        return Some(value.to_owned());
+                    };
+                    let (start, end) = (period.start().utc(), period.end().utc());
+                    if !range.overlaps(start, Some(end)) {
+                        return None;
+                    }
+                    let start = range
+                        .start
+                        .map_or(start, |range_start| start.max(range_start));
+                    let end = range.end.map_or(end, |range_end| end.min(range_end));
+                    Some(format!(
+                        "{}/{}",
+                        start.format("%Y%m%dT%H%M%SZ"),
+                        end.format("%Y%m%dT%H%M%SZ")
+                    ))
+                })
+                .collect();
+            if !periods.is_empty() {
+                let mut prop = prop.clone();
+                prop.value = periods.join(",");
+                properties.push(prop);
+            }
+        }
+        Self {
+            properties,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "recurrence")]
+    use crate::generator::Emitter;
+    use crate::{
+        IcalObjectParser, IcalParser,
+        caldav::TimeRange,
+        component::{CalendarInnerData, Component, IcalCalendarObject},
+    };
+    use chrono::DateTime;
+    use rstest::rstest;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+DTSTART:19700329T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260323T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+EXDATE;TZID=Europe/Berlin:20260330T090000\r
+SUMMARY:Meeting\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Meeting\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID;TZID=Europe/Berlin:20260406T090000\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260410T140000\r
+DURATION:PT1H\r
+SUMMARY:Moved meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse() -> IcalCalendarObject {
+        IcalObjectParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn range(start: &str, end: &str) -> TimeRange {
+        let datetime = |value| DateTime::parse_from_rfc3339(value).unwrap().to_utc();
+        TimeRange::new(Some(datetime(start)), Some(datetime(end)))
+    }
+
+    #[cfg(feature = "recurrence")]
+    fn events(object: &IcalCalendarObject) -> Vec<(&str, Option<&str>, usize)> {
+        let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+            panic!("expected events");
+        };
+        std::iter::once(main)
+            .chain(overrides)
+            .map(|event| {
+                (
+                    event.get_property("DTSTART").unwrap().value.as_str(),
+                    event
+                        .get_property("RECURRENCE-ID")
+                        .map(|recurid| recurid.value.as_str()),
+                    event.get_alarms().len(),
+                )
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[rstest]
+    #[case(
+        "2026-03-20T00:00:00Z",
+        "2026-04-20T00:00:00Z",
+        vec![
+            ("20260323T080000Z", Some("20260323T080000Z"), 1),
+            ("20260410T120000Z", Some("20260406T070000Z"), 0),
+            ("20260413T070000Z", Some("20260413T070000Z"), 1),
+        ]
+    )]
+    // Only the moved instance
+    #[case(
+        "2026-04-10T12:30:00Z",
+        "2026-04-10T13:00:00Z",
+        vec![("20260410T120000Z", Some("20260406T070000Z"), 0)]
+    )]
+    // Excluded and moved away instances
+    #[case("2026-03-30T00:00:00Z", "2026-04-07T00:00:00Z", vec![])]
+    fn test_caldav_expand(
+        #[case] start: &str,
+        #[case] end: &str,
+        #[case] expected: Vec<(&str, Option<&str>, usize)>,
+    ) {
+        let object = parse();
+        let expanded = object.caldav_expand(&range(start, end)).unwrap();
+        let Some(expanded) = expanded else {
+            assert!(expected.is_empty());
+            return;
+        };
+        assert_eq!(events(&expanded), expected);
+        assert!(expanded.get_tzids().is_empty());
+        let ics = expanded.generate();
+        assert!(!ics.contains("RRULE") && !ics.contains("EXDATE"));
+        assert!(!ics.contains("VTIMEZONE"));
+    }
+
+    #[rstest]
+    #[case("2026-03-23T00:00:00Z", "2026-03-24T00:00:00Z", false)]
+    // The override replaces an instance in the range
+    #[case("2026-04-06T00:00:00Z", "2026-04-07T00:00:00Z", true)]
+    // The override overlaps the range
+    #[case("2026-04-10T12:30:00Z", "2026-04-10T13:00:00Z", true)]
+    #[case("2026-04-13T00:00:00Z", "2026-04-14T00:00:00Z", false)]
+    fn test_limit_recurrence_set(#[case] start: &str, #[case] end: &str, #[case] expected: bool) {
+        let object = parse();
+        let limited = object.limit_recurrence_set(&range(start, end));
+        let CalendarInnerData::Event(main, overrides) = limited.get_inner() else {
+            panic!("expected events");
+        };
+        assert!(main.get_property("RRULE").is_some());
+        assert_eq!(overrides.len(), usize::from(expected));
+        assert_eq!(limited.vtimezones.len(), 1);
+    }
+
+    #[test]
+    fn test_limit_freebusy_set() {
+        let calendar = IcalParser::from_slice(
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VFREEBUSY\r
+UID:fb@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T000000Z\r
+DTEND:20260324T000000Z\r
+FREEBUSY:20260323T080000Z/20260323T100000Z,20260323T140000Z/PT1H\r
+FREEBUSY;FBTYPE=BUSY-TENTATIVE:20260323T180000Z/20260323T190000Z\r
+END:VFREEBUSY\r
+END:VCALENDAR\r
+"
+            .as_bytes(),
+        )
+        .expect_one()
+        .unwrap();
+        let freebusy = calendar.free_busys[0]
+            .limit_freebusy_set(&range("2026-03-23T09:00:00Z", "2026-03-23T18:00:00Z"));
+        let periods: Vec<_> = freebusy
+            .get_named_properties("FREEBUSY")
+            .map(|prop| prop.value.as_str())
+            .collect();
+        assert_eq!(
+            periods,
+            vec!["20260323T090000Z/20260323T100000Z,20260323T140000Z/20260323T150000Z"]
+        );
+        assert!(freebusy.get_property("DTSTART").is_some());
+    }
+}
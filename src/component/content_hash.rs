@@ -0,0 +1,245 @@
+//! Hashes of the canonical form of components, e.g. to derive ETags,
+//! and the equality of components consistent with them.
+use super::semantic_eq::{Canonical, canonical_as_written};
+use crate::component::{CalendarInnerData, IcalAlarm, IcalCalendarObject, IcalEvent, VcardContact};
+use std::hash::{Hash, Hasher};
+
+/// 64-bit FNV-1a.
+/// Unlike [`std::hash::DefaultHasher`] its output is specified,
+/// so it doesn't change between Rust versions or platforms.
+pub(crate) struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn hash(canonical: &Canonical) -> u64 {
+    let mut bytes = vec![];
+    canonical.write_bytes(&mut bytes);
+    let mut hasher = Fnv1a::default();
+    hasher.write(&bytes);
+    hasher.finish()
+}
+
+fn alarms(alarms: &[IcalAlarm]) -> Vec<Canonical> {
+    alarms
+        .iter()
+        .map(|alarm| canonical_as_written(alarm, vec![]))
+        .collect()
+}
+
+/// The canonical form of the content, values are taken as they are written
+trait ContentCanonical {
+    fn content_canonical(&self) -> Canonical;
+}
+
+impl ContentCanonical for IcalEvent {
+    fn content_canonical(&self) -> Canonical {
+        canonical_as_written(self, alarms(&self.alarms))
+    }
+}
+
+impl ContentCanonical for IcalCalendarObject {
+    fn content_canonical(&self) -> Canonical {
+        let mut children: Vec<Canonical> = self
+            .vtimezones
+            .values()
+            .map(|vtimezone| {
+                let transitions = vtimezone
+                    .transitions
+                    .iter()
+                    .map(|transition| canonical_as_written(transition, vec![]))
+                    .collect();
+                canonical_as_written(vtimezone, transitions)
+            })
+            .collect();
+        match &self.inner {
+            CalendarInnerData::Event(main, overrides) => children.extend(
+                std::iter::once(main)
+                    .chain(overrides)
+                    .map(ContentCanonical::content_canonical),
+            ),
+            CalendarInnerData::Todo(main, overrides) => children.extend(
+                std::iter::once(main)
+                    .chain(overrides)
+                    .map(|todo| canonical_as_written(todo, alarms(&todo.alarms))),
+            ),
+            CalendarInnerData::Journal(main, overrides) => children.extend(
+                std::iter::once(main)
+                    .chain(overrides)
+                    .map(|journal| canonical_as_written(journal, vec![])),
+            ),
+        }
+        canonical_as_written(self, children)
+    }
+}
+
+impl ContentCanonical for VcardContact {
+    fn content_canonical(&self) -> Canonical {
+        canonical_as_written(self, vec![])
+    }
+}
+
+impl IcalCalendarObject {
+    /// A hash of the content of this object, e.g. to derive an ETag or to cheaply detect changes.
+    ///
+    /// The hash is the same on every platform and with any features and doesn't depend on
+    /// the order of properties, parameters and components, VALUE parameters stating the default
+    /// value type, the order of RRULE parts or how lines were folded.
+    /// Unlike with [`SemanticEq`](super::SemanticEq), values are hashed as they are written,
+    /// so moving an event into another timezone changes the hash.
+    pub fn content_hash(&self) -> u64 {
+        hash(&self.content_canonical())
+    }
+}
+
+impl VcardContact {
+    /// A hash of the content of this contact, see [`IcalCalendarObject::content_hash`]
+    pub fn content_hash(&self) -> u64 {
+        hash(&self.content_canonical())
+    }
+}
+
+// Equality and hashing follow the content hash,
+// so components differing only in their representation are equal
+macro_rules! content_eq {
+    ($($component:ty),*) => {
+        $(
+            impl PartialEq for $component {
+                fn eq(&self, other: &Self) -> bool {
+                    self.content_canonical() == other.content_canonical()
+                }
+            }
+
+            impl Eq for $component {}
+
+            impl Hash for $component {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.content_canonical().hash(state);
+                }
+            }
+        )*
+    };
+}
+
+content_eq!(IcalEvent, IcalCalendarObject, VcardContact);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalObjectParser, VcardParser,
+        component::{CalendarInnerData, IcalCalendarObject},
+    };
+    use std::collections::HashSet;
+
+    fn parse(input: &str) -> IcalCalendarObject {
+        IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;VALUE=DATE-TIME:20260323T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4;BYDAY=MO\r
+SUMMARY:A meeting with a summary long enough to be folded by most clients\r
+ATTENDEE;ROLE=CHAIR;PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    const REORDERED: &str = "BEGIN:VCALENDAR\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:A meeting with a summary long enough to \r
+ be folded by most clients\r
+ATTENDEE;PARTSTAT=ACCEPTED;ROLE=CHAIR:mailto:jane@example.com\r
+RRULE:FREQ=WEEKLY;BYDAY=MO;COUNT=4\r
+DURATION:PT1H\r
+DTSTART:20260323T080000Z\r
+DTSTAMP:20260101T120000Z\r
+UID:meeting@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_content_hash() {
+        let hash = parse(EVENT).content_hash();
+        assert_eq!(hash, parse(REORDERED).content_hash());
+        assert_ne!(
+            hash,
+            parse(&EVENT.replace("SUMMARY:A meeting", "SUMMARY:A call")).content_hash()
+        );
+        assert_ne!(
+            hash,
+            parse(&EVENT.replace("PARTSTAT=ACCEPTED", "PARTSTAT=DECLINED")).content_hash()
+        );
+        // The hash must not change between releases as it would invalidate stored ETags
+        assert_eq!(hash, 1922716358651839444);
+
+        let contact = |input: &str| {
+            VcardParser::from_slice(input.as_bytes())
+                .expect_one()
+                .unwrap()
+                .content_hash()
+        };
+        assert_eq!(
+            contact("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\nEND:VCARD\r\n"),
+            contact("BEGIN:VCARD\r\nN:Doe;Jane;;;\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n")
+        );
+    }
+
+    #[test]
+    fn test_eq_hash() {
+        let objects: HashSet<IcalCalendarObject> = [
+            parse(EVENT),
+            parse(REORDERED),
+            parse(&EVENT.replace("SUMMARY:A meeting", "SUMMARY:A call")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(objects.len(), 2);
+
+        let event = |input: &str| match parse(input).get_inner() {
+            CalendarInnerData::Event(main, _) => main.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(event(EVENT), event(REORDERED));
+        assert_ne!(
+            event(EVENT),
+            event(&EVENT.replace("DURATION:PT1H", "DURATION:PT2H"))
+        );
+
+        let contact = |input: &str| {
+            VcardParser::from_slice(input.as_bytes())
+                .expect_one()
+                .unwrap()
+        };
+        assert_eq!(
+            contact("BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n"),
+            contact("BEGIN:VCARD\r\nFN:Jane Doe\r\nVERSION:4.0\r\nEND:VCARD\r\n")
+        );
+    }
+}
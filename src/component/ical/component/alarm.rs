@@ -3,17 +3,20 @@ use crate::parser::ParserOptions;
 use crate::{
     component::{Component, ComponentMut},
     parser::{ContentLine, ContentLineParser, ParserError},
+    types::Tz,
 };
 #[cfg(not(tarpaulin_include))]
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalAlarmBuilder {
     pub properties: Vec<ContentLine>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalAlarm {
     pub properties: Vec<ContentLine>,
 }
@@ -74,7 +77,7 @@ impl ComponentMut for IcalAlarmBuilder {
     fn build(
         self,
         _options: &ParserOptions,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalAlarm, ParserError> {
         Ok(IcalAlarm {
             properties: self.properties,
@@ -0,0 +1,344 @@
+use crate::{
+    component::{
+        Component, ComponentMut, IcalAlarmBuilder, IcalEvent, IcalEventBuilder, IcalJournalBuilder,
+        IcalTodoBuilder,
+    },
+    itip::same_address,
+    parser::ContentLine,
+    property::{CuType, PartStat, Role},
+    types::CalAddress,
+};
+
+fn partstat(attendee: &ContentLine) -> PartStat {
+    attendee
+        .params
+        .get_param(PartStat::PARAM)
+        .map_or(PartStat::NeedsAction, |value| {
+            value.parse().unwrap_or_else(|never| match never {})
+        })
+}
+
+/// An ATTENDEE property of a component that's being edited
+#[derive(Debug)]
+pub struct Attendee<'a> {
+    line: &'a mut ContentLine,
+}
+
+impl Attendee<'_> {
+    /// The CAL-ADDRESS of the attendee
+    pub fn address(&self) -> &str {
+        &self.line.value
+    }
+
+    /// The participation status, NEEDS-ACTION if there's none
+    pub fn partstat(&self) -> PartStat {
+        partstat(self.line)
+    }
+
+    /// The participation role, REQ-PARTICIPANT if there's none
+    pub fn role(&self) -> Role {
+        self.line
+            .params
+            .get_param(Role::PARAM)
+            .map_or(Role::ReqParticipant, |value| {
+                value.parse().unwrap_or_else(|never| match never {})
+            })
+    }
+
+    /// Whether a response is expected
+    pub fn rsvp(&self) -> bool {
+        self.line
+            .params
+            .get_param("RSVP")
+            .is_some_and(|rsvp| rsvp.eq_ignore_ascii_case("TRUE"))
+    }
+
+    /// Sets the participation status, any status other than NEEDS-ACTION answers the RSVP.
+    pub fn respond(&mut self, partstat: PartStat) {
+        if partstat != PartStat::NeedsAction {
+            self.line.params.remove("RSVP");
+        }
+        self.line
+            .params
+            .replace_param(PartStat::PARAM.to_owned(), partstat.to_string());
+    }
+
+    /// Resets the participation status to NEEDS-ACTION and asks for a response
+    pub fn request_response(&mut self) {
+        self.respond(PartStat::NeedsAction);
+        self.line
+            .params
+            .replace_param("RSVP".to_owned(), "TRUE".to_owned());
+    }
+
+    pub fn set_role(&mut self, role: Role) {
+        self.line
+            .params
+            .replace_param(Role::PARAM.to_owned(), role.to_string());
+    }
+
+    /// Sets a parameter like CN or CUTYPE
+    pub fn set_param(&mut self, name: &str, value: String) {
+        self.line.params.replace_param(name.to_owned(), value);
+    }
+
+    /// The attendees this attendee delegated to
+    pub fn delegated_to(&self) -> &[String] {
+        self.line.params.get_param_values("DELEGATED-TO")
+    }
+
+    /// The attendees that delegated to this attendee
+    pub fn delegated_from(&self) -> &[String] {
+        self.line.params.get_param_values("DELEGATED-FROM")
+    }
+
+    fn add_param_value(&mut self, name: &str, address: &str) {
+        let mut values = self.line.params.get_param_values(name).to_vec();
+        if !values.iter().any(|value| same_address(value, address)) {
+            values.push(address.to_owned());
+        }
+        self.line
+            .params
+            .replace_param_values(name.to_owned(), values);
+    }
+}
+
+/// Editing of the ATTENDEE properties of a component,
+/// attendees are matched by their CAL-ADDRESS ignoring case
+pub trait AttendeesMut: ComponentMut {
+    fn attendees_mut(&mut self) -> impl Iterator<Item = Attendee<'_>> {
+        self.get_properties_mut()
+            .iter_mut()
+            .filter(|prop| prop.name == "ATTENDEE")
+            .map(|line| Attendee { line })
+    }
+
+    fn attendee_mut(&mut self, address: &str) -> Option<Attendee<'_>> {
+        self.attendees_mut()
+            .find(|attendee| same_address(attendee.address(), address))
+    }
+
+    /// Adds an attendee with the role `role` that's asked for a response,
+    /// or returns the existing one with that address.
+    fn add_attendee(&mut self, address: &CalAddress, role: Role) -> Attendee<'_> {
+        let exists = self
+            .get_named_properties("ATTENDEE")
+            .any(|prop| same_address(&prop.value, address));
+        if !exists {
+            let mut line = ContentLine {
+                name: "ATTENDEE".to_owned(),
+                params: Default::default(),
+                value: address.to_string(),
+            };
+            line.params
+                .replace_param(Role::PARAM.to_owned(), role.to_string());
+            line.params.replace_param(
+                PartStat::PARAM.to_owned(),
+                PartStat::NeedsAction.to_string(),
+            );
+            line.params
+                .replace_param("RSVP".to_owned(), "TRUE".to_owned());
+            self.add_content_line(line);
+        }
+        self.attendee_mut(address)
+            .expect("attendee was added if it didn't exist")
+    }
+
+    /// Delegates the attendance of `delegator` to `delegate` as described in RFC 5546, section 4.2.5.
+    ///
+    /// The delegator gets PARTSTAT=DELEGATED and a DELEGATED-TO referring to the delegate.
+    /// The delegate is added with the role of the delegator and asked for a response
+    /// unless it's already an attendee, either way it gets a DELEGATED-FROM referring to the delegator.
+    /// Returns the delegate or `None` if `delegator` isn't an attendee.
+    fn delegate(&mut self, delegator: &str, delegate: &CalAddress) -> Option<Attendee<'_>> {
+        let mut attendee = self.attendee_mut(delegator)?;
+        let delegator = attendee.address().to_owned();
+        let role = attendee.role();
+        attendee.respond(PartStat::Delegated);
+        attendee.add_param_value("DELEGATED-TO", delegate);
+
+        let mut attendee = self.add_attendee(delegate, role);
+        attendee.add_param_value("DELEGATED-FROM", &delegator);
+        Some(attendee)
+    }
+
+    /// Removes the attendee with `address`, returns whether it existed
+    fn remove_attendee(&mut self, address: &str) -> bool {
+        let properties = self.get_properties_mut();
+        let len = properties.len();
+        properties.retain(|prop| !(prop.name == "ATTENDEE" && same_address(&prop.value, address)));
+        properties.len() != len
+    }
+}
+
+impl AttendeesMut for IcalEventBuilder {}
+impl AttendeesMut for IcalTodoBuilder {}
+impl AttendeesMut for IcalJournalBuilder {}
+impl AttendeesMut for IcalAlarmBuilder {}
+
+/// The ATTENDEE properties of an event grouped by their participation status.
+///
+/// Rooms and resources are listed separately from the participants, so the lengths of
+/// the lists can be shown as the number of people that accepted or declined.
+/// Attendees that delegated their attendance are only listed as delegated,
+/// their delegates are counted instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParticipationSummary<'a> {
+    pub accepted: Vec<&'a ContentLine>,
+    pub declined: Vec<&'a ContentLine>,
+    pub tentative: Vec<&'a ContentLine>,
+    /// Attendees that haven't responded yet
+    pub needs_action: Vec<&'a ContentLine>,
+    pub delegated: Vec<&'a ContentLine>,
+    /// Participants with a PARTSTAT that isn't valid for events
+    pub other: Vec<&'a ContentLine>,
+    /// Attendees with CUTYPE=ROOM or CUTYPE=RESOURCE, regardless of their status
+    pub resources: Vec<&'a ContentLine>,
+}
+
+impl ParticipationSummary<'_> {
+    /// The number of participants, excluding resources and attendees that delegated
+    pub fn participants(&self) -> usize {
+        self.accepted.len()
+            + self.declined.len()
+            + self.tentative.len()
+            + self.needs_action.len()
+            + self.other.len()
+    }
+
+    /// The number of participants that responded
+    pub fn responded(&self) -> usize {
+        self.accepted.len() + self.declined.len() + self.tentative.len()
+    }
+}
+
+impl IcalEvent {
+    /// Groups the attendees of this event by their participation status
+    pub fn participation_summary(&self) -> ParticipationSummary<'_> {
+        let mut summary = ParticipationSummary::default();
+        for attendee in self.get_named_properties("ATTENDEE") {
+            let cutype = attendee
+                .params
+                .get_param(CuType::PARAM)
+                .map(|cutype| cutype.parse().unwrap_or_else(|never| match never {}));
+            if matches!(cutype, Some(CuType::Room | CuType::Resource)) {
+                summary.resources.push(attendee);
+                continue;
+            }
+            match partstat(attendee) {
+                PartStat::Accepted => summary.accepted.push(attendee),
+                PartStat::Declined => summary.declined.push(attendee),
+                PartStat::Tentative => summary.tentative.push(attendee),
+                PartStat::NeedsAction => summary.needs_action.push(attendee),
+                PartStat::Delegated => summary.delegated.push(attendee),
+                _ => summary.other.push(attendee),
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AttendeesMut;
+    use crate::{
+        IcalObjectParser,
+        component::{CalendarInnerData, Component, IcalEventBuilder},
+        parser::ContentLine,
+        property::{PartStat, Role},
+    };
+
+    #[test]
+    fn test_attendees() {
+        let mut event = IcalEventBuilder::new();
+        let john = "mailto:john@example.com".parse().unwrap();
+        assert!(event.attendee_mut("mailto:john@example.com").is_none());
+        let attendee = event.add_attendee(&john, Role::OptParticipant);
+        assert!(attendee.rsvp());
+        assert_eq!(attendee.partstat(), PartStat::NeedsAction);
+        event.add_attendee(&john, Role::Chair);
+        assert_eq!(event.get_named_properties("ATTENDEE").count(), 1);
+
+        let mut attendee = event.attendee_mut("MAILTO:John@example.com").unwrap();
+        assert_eq!(attendee.role(), Role::OptParticipant);
+        attendee.respond(PartStat::Declined);
+        assert!(!attendee.rsvp());
+        assert_eq!(
+            event
+                .get_property("ATTENDEE")
+                .unwrap()
+                .params
+                .get_param("PARTSTAT"),
+            Some("DECLINED")
+        );
+
+        assert!(event.remove_attendee("mailto:JOHN@example.com"));
+        assert!(!event.remove_attendee("mailto:john@example.com"));
+    }
+
+    #[test]
+    fn test_participation_summary() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:summary@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+ATTENDEE;PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=DELEGATED;DELEGATED-TO=\"mailto:max@example.com\":mailto:john@example.com\r
+ATTENDEE;PARTSTAT=tentative;DELEGATED-FROM=\"mailto:john@example.com\":mailto:max@example.com\r
+ATTENDEE:mailto:eve@example.com\r
+ATTENDEE;CUTYPE=ROOM;PARTSTAT=ACCEPTED:mailto:room@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let CalendarInnerData::Event(event, _) = &object.inner else {
+            panic!();
+        };
+        let summary = event.participation_summary();
+        let addresses = |attendees: &[&ContentLine]| {
+            attendees
+                .iter()
+                .map(|attendee| attendee.value.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(addresses(&summary.accepted), ["mailto:jane@example.com"]);
+        assert_eq!(addresses(&summary.tentative), ["mailto:max@example.com"]);
+        assert_eq!(addresses(&summary.needs_action), ["mailto:eve@example.com"]);
+        assert_eq!(addresses(&summary.delegated), ["mailto:john@example.com"]);
+        assert_eq!(addresses(&summary.resources), ["mailto:room@example.com"]);
+        assert_eq!(summary.participants(), 3);
+        assert_eq!(summary.responded(), 2);
+    }
+
+    #[test]
+    fn test_delegate() {
+        let mut event = IcalEventBuilder::new();
+        let jane = "mailto:jane@example.com".parse().unwrap();
+        let john = "mailto:john@example.com".parse().unwrap();
+        assert!(event.delegate("mailto:jane@example.com", &john).is_none());
+        event.add_attendee(&jane, Role::Chair);
+
+        let delegate = event.delegate("MAILTO:jane@example.com", &john).unwrap();
+        assert_eq!(delegate.role(), Role::Chair);
+        assert!(delegate.rsvp());
+        assert_eq!(delegate.delegated_from(), ["mailto:jane@example.com"]);
+        // Delegating again doesn't duplicate the references
+        event.delegate("mailto:jane@example.com", &john).unwrap();
+        let delegator = event.attendee_mut("mailto:jane@example.com").unwrap();
+        assert_eq!(delegator.partstat(), PartStat::Delegated);
+        assert!(!delegator.rsvp());
+        assert_eq!(delegator.delegated_to(), ["mailto:john@example.com"]);
+        assert_eq!(
+            event
+                .attendee_mut("mailto:john@example.com")
+                .unwrap()
+                .delegated_from(),
+            ["mailto:jane@example.com"]
+        );
+    }
+}
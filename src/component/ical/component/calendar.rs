@@ -4,12 +4,14 @@ use crate::{
         CalendarInnerData, Component, ComponentMut, IcalAlarm, IcalAlarmBuilder,
         IcalCalendarObject, IcalEvent, IcalEventBuilder, IcalFreeBusy, IcalFreeBusyBuilder,
         IcalJournal, IcalJournalBuilder, IcalTimeZone, IcalTodo, IcalTodoBuilder, ParserError,
+        properties_to_utc,
     },
-    parser::{ContentLine, ParserOptions},
+    parser::{ContentLine, ParserOptions, quirks::localize_floating_components},
     property::{
         Calscale, GetProperty, IcalCALSCALEProperty, IcalPRODIDProperty, IcalVERSIONProperty,
         IcalVersion,
     },
+    types::Tz,
 };
 use std::{
     borrow::Cow,
@@ -17,6 +19,7 @@ use std::{
 };
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 /// An ICAL calendar.
 pub struct IcalCalendar<
     const VERIFIED: bool = true,
@@ -33,7 +36,8 @@ pub struct IcalCalendar<
     pub journals: Vec<J>,
     pub free_busys: Vec<F>,
     pub vtimezones: BTreeMap<String, IcalTimeZone>,
-    pub timezones: HashMap<String, Option<chrono_tz::Tz>>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = crate::component::rkyv_impls::TimezoneNames))]
+    pub timezones: HashMap<String, Option<Tz>>,
 }
 pub type IcalCalendarBuilder = IcalCalendar<
     false,
@@ -131,9 +135,9 @@ impl ComponentMut for IcalCalendarBuilder {
     }
 
     fn build(
-        self,
+        mut self,
         options: &ParserOptions,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self::Verified, ParserError> {
         let _version: IcalVERSIONProperty = self.safe_get_required(None)?;
         // This should technically be REQUIRED but Apple Calendar doesn't adhere to the spec. :(
@@ -141,11 +145,28 @@ impl ComponentMut for IcalCalendarBuilder {
         let _calscale: Option<IcalCALSCALEProperty> = self.safe_get_optional(None)?;
 
         #[allow(unused_mut)]
-        let mut timezones = HashMap::from_iter(
-            self.vtimezones
-                .iter()
-                .map(|(tzid, tz)| (tzid.to_owned(), tz.into())),
-        );
+        let mut timezones = HashMap::from_iter(self.vtimezones.iter().map(|(tzid, tz)| {
+            (
+                tzid.to_owned(),
+                options
+                    .tzid_cache
+                    .resolve_or_fixed(tz, &options.tzid_aliases),
+            )
+        }));
+
+        if let Some(tz) = options.default_timezone {
+            let localized = localize_floating_components(&mut self.events, tz)?
+                | localize_floating_components(&mut self.todos, tz)?
+                | localize_floating_components(&mut self.journals, tz)?;
+            if localized
+                && !timezones.contains_key(tz.name())
+                && let Some(ical_tz) = IcalTimeZone::from_tzid(tz.name())
+            {
+                timezones.insert(tz.name().to_owned(), Some(tz.into()));
+                self.vtimezones
+                    .insert(tz.name().to_owned(), ical_tz.clone());
+            }
+        }
 
         let used_tzids: HashSet<&str> = self
             .events
@@ -160,12 +181,11 @@ impl ComponentMut for IcalCalendarBuilder {
         let mut vtimezones = self.vtimezones;
         if options.rfc7809 {
             // Populate our map of chrono timezones with those we can populate ourselves
-            use std::str::FromStr;
             for tzid in used_tzids {
-                if let Ok(tz) = chrono_tz::Tz::from_str(tzid)
+                if let Some(tz) = options.tzid_cache.resolve_tzid(tzid)
                     && let Some(ical_tz) = IcalTimeZone::from_tzid(tzid)
                 {
-                    timezones.insert(tzid.to_owned(), Some(tz));
+                    timezones.insert(tzid.to_owned(), Some(tz.into()));
                     vtimezones.insert(tzid.to_owned(), ical_tz.clone());
                 }
             }
@@ -235,6 +255,97 @@ impl IcalCalendar {
         cal
     }
 
+    /// Converts every zoned DATE-TIME value to UTC and removes the VTIMEZONEs
+    /// that aren't referenced anymore.
+    ///
+    /// DATE and floating values are left untouched. Recurring components keep their
+    /// timezone: their instances are expanded in the timezone of DTSTART, so converting it
+    /// to UTC would move the instances after a daylight saving time switch.
+    pub fn normalize_to_utc(self) -> Result<Self, ParserError> {
+        let mut builder = self.mutable();
+        let timezones = std::mem::take(&mut builder.timezones);
+        for event in &mut builder.events {
+            properties_to_utc(&mut event.properties, &timezones)?;
+            for alarm in &mut event.alarms {
+                properties_to_utc(&mut alarm.properties, &timezones)?;
+            }
+        }
+        for todo in &mut builder.todos {
+            properties_to_utc(&mut todo.properties, &timezones)?;
+            for alarm in &mut todo.alarms {
+                properties_to_utc(&mut alarm.properties, &timezones)?;
+            }
+        }
+        for journal in &mut builder.journals {
+            properties_to_utc(&mut journal.properties, &timezones)?;
+        }
+        for free_busy in &mut builder.free_busys {
+            properties_to_utc(&mut free_busy.properties, &timezones)?;
+        }
+        for alarm in &mut builder.alarms {
+            properties_to_utc(&mut alarm.properties, &timezones)?;
+        }
+
+        let used_tzids: HashSet<String> = builder
+            .events
+            .iter()
+            .flat_map(|object| object.get_tzids())
+            .chain(builder.alarms.iter().flat_map(|object| object.get_tzids()))
+            .chain(builder.todos.iter().flat_map(|object| object.get_tzids()))
+            .chain(
+                builder
+                    .journals
+                    .iter()
+                    .flat_map(|object| object.get_tzids()),
+            )
+            .chain(
+                builder
+                    .free_busys
+                    .iter()
+                    .flat_map(|object| object.get_tzids()),
+            )
+            .map(ToOwned::to_owned)
+            .collect();
+        let mut vtimezones = builder.vtimezones;
+        vtimezones.retain(|tzid, _| used_tzids.contains(tzid));
+        let timezones: HashMap<_, _> = timezones
+            .into_iter()
+            .filter(|(tzid, _)| used_tzids.contains(tzid))
+            .collect();
+
+        let options = ParserOptions::reverify(&timezones);
+        Ok(IcalCalendar {
+            properties: builder.properties,
+            events: builder
+                .events
+                .into_iter()
+                .map(|builder| builder.build(&options, Some(&timezones)))
+                .collect::<Result<_, _>>()?,
+            alarms: builder
+                .alarms
+                .into_iter()
+                .map(|builder| builder.build(&options, Some(&timezones)))
+                .collect::<Result<_, _>>()?,
+            todos: builder
+                .todos
+                .into_iter()
+                .map(|builder| builder.build(&options, Some(&timezones)))
+                .collect::<Result<_, _>>()?,
+            journals: builder
+                .journals
+                .into_iter()
+                .map(|builder| builder.build(&options, Some(&timezones)))
+                .collect::<Result<_, _>>()?,
+            free_busys: builder
+                .free_busys
+                .into_iter()
+                .map(|builder| builder.build(&options, Some(&timezones)))
+                .collect::<Result<_, _>>()?,
+            vtimezones,
+            timezones,
+        })
+    }
+
     pub fn into_objects(self) -> Result<Vec<IcalCalendarObject>, ParserError> {
         let mut out = vec![];
 
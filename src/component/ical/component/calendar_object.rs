@@ -1,14 +1,24 @@
+#[cfg(feature = "recurrence")]
+use crate::component::expand_recurring;
 use crate::{
     ContentLineParser,
     component::{
         Component, ComponentMut, IcalCalendar, IcalEvent, IcalEventBuilder, IcalJournal,
-        IcalJournalBuilder, IcalTimeZone, IcalTodo, IcalTodoBuilder,
+        IcalJournalBuilder, IcalTimeZone, IcalTodo, IcalTodoBuilder, rezone_properties,
     },
     generator::Emitter,
-    parser::{ContentLine, ParserError, ParserOptions},
-    property::{GetProperty, IcalCALSCALEProperty, IcalPRODIDProperty, IcalVERSIONProperty},
-    types::CalDateTime,
+    parser::{
+        ContentLine, ICalProperty, ParserError, ParserOptions, quirks::localize_floating_components,
+    },
+    property::{
+        GetProperty, IcalCALSCALEProperty, IcalDTENDProperty, IcalDTSTARTProperty, IcalDUEProperty,
+        IcalEXDATEProperty, IcalPRODIDProperty, IcalRDATEProperty, IcalRECURIDProperty,
+        IcalVERSIONProperty,
+    },
+    types::{CalDate, CalDateOrDateTime, CalDateTime, CalDateTimeError, Tz},
 };
+use chrono::Duration;
+#[cfg(feature = "recurrence")]
 use chrono::{DateTime, Utc};
 use std::{
     borrow::Cow,
@@ -16,6 +26,7 @@ use std::{
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub enum CalendarInnerData {
     Event(IcalEvent, Vec<IcalEvent>),
     Todo(IcalTodo, Vec<IcalTodo>),
@@ -23,6 +34,7 @@ pub enum CalendarInnerData {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum CalendarInnerDataBuilder {
     Event(Vec<IcalEventBuilder>),
     Todo(Vec<IcalTodoBuilder>),
@@ -30,6 +42,15 @@ pub enum CalendarInnerDataBuilder {
 }
 
 impl CalendarInnerDataBuilder {
+    /// Binds floating DATE-TIME values to `tz`, returns whether any value was floating
+    pub(crate) fn localize_floating(&mut self, tz: chrono_tz::Tz) -> Result<bool, ParserError> {
+        match self {
+            Self::Event(events) => localize_floating_components(events, tz),
+            Self::Todo(todos) => localize_floating_components(todos, tz),
+            Self::Journal(journals) => localize_floating_components(journals, tz),
+        }
+    }
+
     pub fn get_tzids(&self) -> HashSet<&str> {
         match self {
             Self::Event(events) => events
@@ -216,11 +237,139 @@ impl CalendarInnerData {
     }
 }
 
+/// Moves all date properties describing when an object or its recurrence set occurs by `duration`.
+pub(super) fn shift_properties(
+    properties: &mut [ContentLine],
+    duration: Duration,
+    timezones: Option<&HashMap<String, Option<Tz>>>,
+) -> Result<(), ParserError> {
+    // UNTIL is in UTC but has to move like DTSTART in its timezone
+    let zone = properties
+        .iter()
+        .find(|prop| prop.name == "DTSTART")
+        .map(|prop| IcalDTSTARTProperty::parse_prop(prop, timezones))
+        .transpose()?
+        .map(|IcalDTSTARTProperty(dtstart, _)| dtstart.timezone());
+    for prop in properties.iter_mut() {
+        *prop = match prop.name.as_str() {
+            "DTSTART" => {
+                let IcalDTSTARTProperty(dt, params) = ICalProperty::parse_prop(prop, timezones)?;
+                IcalDTSTARTProperty(dt.shift(duration)?, params).into()
+            }
+            "DTEND" => {
+                let IcalDTENDProperty(dt, params) = ICalProperty::parse_prop(prop, timezones)?;
+                IcalDTENDProperty(dt.shift(duration)?, params).into()
+            }
+            "DUE" => {
+                let IcalDUEProperty(dt, params) = ICalProperty::parse_prop(prop, timezones)?;
+                IcalDUEProperty(dt.shift(duration)?, params).into()
+            }
+            "RECURRENCE-ID" => {
+                let IcalRECURIDProperty(dt, params, range) =
+                    ICalProperty::parse_prop(prop, timezones)?;
+                IcalRECURIDProperty(dt.shift(duration)?, params, range).into()
+            }
+            "RDATE" => {
+                let IcalRDATEProperty(dates, params) = ICalProperty::parse_prop(prop, timezones)?;
+                let dates = dates
+                    .into_iter()
+                    .map(|dt| dt.shift(duration))
+                    .collect::<Result<_, _>>()?;
+                IcalRDATEProperty(dates, params).into()
+            }
+            "EXDATE" => {
+                let IcalEXDATEProperty(dates, params) = ICalProperty::parse_prop(prop, timezones)?;
+                let dates = dates
+                    .into_iter()
+                    .map(|dt| dt.shift(duration))
+                    .collect::<Result<_, _>>()?;
+                IcalEXDATEProperty(dates, params).into()
+            }
+            "RRULE" | "EXRULE" => ContentLine {
+                value: shift_until(&prop.value, duration, zone)?,
+                ..prop.clone()
+            },
+            // Absolute alarm triggers
+            "TRIGGER" if prop.params.get_value_type() == Some("DATE-TIME") => {
+                let trigger = CalDateTime::parse_prop(prop, timezones)?.shift(duration)?;
+                ContentLine {
+                    value: trigger.format(),
+                    ..prop.clone()
+                }
+            }
+            _ => continue,
+        };
+    }
+    Ok(())
+}
+
+/// Moves the UNTIL part of a rule, keeping its value type and the other parts as written.
+/// A UTC UNTIL moves by the wall-clock time in `zone`, the timezone of DTSTART.
+fn shift_until(
+    rule: &str,
+    duration: Duration,
+    zone: Option<Tz>,
+) -> Result<String, CalDateTimeError> {
+    rule.split(';')
+        .map(|part| match part.split_once('=') {
+            Some((name, until)) if name.eq_ignore_ascii_case("UNTIL") => {
+                let until = if until.len() == 8 {
+                    CalDateOrDateTime::Date(CalDate::parse(until, None)?).shift(duration)?
+                } else {
+                    let until = CalDateTime::parse(until, None)?;
+                    CalDateOrDateTime::DateTime(match zone {
+                        Some(zone) if !until.timezone().is_local() => {
+                            let local = CalDateTime(until.0.with_timezone(&zone));
+                            local.shift(duration)?.utc().into()
+                        }
+                        _ => until.shift(duration)?,
+                    })
+                };
+                Ok(format!("{name}={}", until.format()))
+            }
+            _ => Ok(part.to_owned()),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|parts| parts.join(";"))
+}
+
 impl CalendarInnerDataBuilder {
+    /// Moves the whole series including its overrides by `duration`.
+    pub fn shift(
+        &mut self,
+        duration: Duration,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
+    ) -> Result<(), ParserError> {
+        match self {
+            Self::Event(events) => {
+                for event in events {
+                    shift_properties(&mut event.properties, duration, timezones)?;
+                    for alarm in &mut event.alarms {
+                        shift_properties(&mut alarm.properties, duration, timezones)?;
+                    }
+                }
+            }
+            Self::Todo(todos) => {
+                for todo in todos {
+                    shift_properties(&mut todo.properties, duration, timezones)?;
+                    for alarm in &mut todo.alarms {
+                        shift_properties(&mut alarm.properties, duration, timezones)?;
+                    }
+                }
+            }
+            Self::Journal(journals) => {
+                for journal in journals {
+                    shift_properties(&mut journal.properties, duration, timezones)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn build(
         self,
         options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<CalendarInnerData, ParserError> {
         match self {
             Self::Event(events) => {
@@ -249,12 +398,14 @@ impl CalendarInnerDataBuilder {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 /// An ICAL calendar object.
 pub struct IcalCalendarObject {
     pub properties: Vec<ContentLine>,
     pub(crate) inner: CalendarInnerData,
     pub(crate) vtimezones: BTreeMap<String, IcalTimeZone>,
-    pub(crate) timezones: HashMap<String, Option<chrono_tz::Tz>>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = crate::component::rkyv_impls::TimezoneNames))]
+    pub(crate) timezones: HashMap<String, Option<Tz>>,
 }
 
 impl IcalCalendarObject {
@@ -270,34 +421,132 @@ impl IcalCalendarObject {
         &self.vtimezones
     }
 
-    pub fn get_timezones(&self) -> &HashMap<String, Option<chrono_tz::Tz>> {
+    pub fn get_timezones(&self) -> &HashMap<String, Option<Tz>> {
         &self.timezones
     }
 
+    /// Expands the recurrence set into single instances starting within `start..=end`,
+    /// at most 2048 of them.
+    ///
+    /// If no instance lies within the range the object is returned unexpanded,
+    /// use [`Self::expand`] to tell such ranges apart.
+    #[cfg(feature = "recurrence")]
     pub fn expand_recurrence(
         &self,
         start: Option<DateTime<Utc>>,
         end: Option<DateTime<Utc>>,
     ) -> Cow<'_, Self> {
-        match &self.inner {
-            CalendarInnerData::Event(main, overrides) => {
-                let mut events = main.expand_recurrence(start, end, overrides);
-                let first = events.remove(0);
-                Cow::Owned(Self {
-                    properties: self.properties.clone(),
-                    inner: CalendarInnerData::Event(first, events),
-                    timezones: HashMap::new(),
-                    vtimezones: BTreeMap::new(),
-                })
-            }
-            _ => Cow::Borrowed(self),
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("expand_recurrence", uid = self.get_uid()).entered();
+        self.expand_instances(start, end)
+            .unwrap_or(Cow::Borrowed(self))
+    }
+
+    /// Like [`Self::expand_recurrence`] but `None` if no instance lies within the range
+    #[cfg(feature = "recurrence")]
+    pub(crate) fn expand_instances(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Option<Cow<'_, Self>> {
+        fn split<C>(instances: Vec<C>) -> Option<(C, Vec<C>)> {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(instances = instances.len(), "expanded recurrence");
+            let mut instances = instances.into_iter();
+            Some((instances.next()?, instances.collect()))
         }
+
+        let inner = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let (first, rest) = split(expand_recurring(main, start, end, overrides))?;
+                CalendarInnerData::Event(first, rest)
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let (first, rest) = split(expand_recurring(main, start, end, overrides))?;
+                CalendarInnerData::Todo(first, rest)
+            }
+            CalendarInnerData::Journal(..) => return Some(Cow::Borrowed(self)),
+        };
+        Some(Cow::Owned(Self {
+            properties: self.properties.clone(),
+            inner,
+            timezones: HashMap::new(),
+            vtimezones: BTreeMap::new(),
+        }))
     }
 
     pub fn get_tzids(&self) -> HashSet<&str> {
         self.inner.get_tzids()
     }
 
+    /// Moves the entire series by `duration`.
+    ///
+    /// This shifts DTSTART, DTEND, DUE, RDATE, EXDATE, absolute alarm triggers,
+    /// the RECURRENCE-ID of every override and the UNTIL of every RRULE/EXRULE.
+    /// Zoned values move by their wall-clock time, so a series moved across a DST change
+    /// keeps its local time of day like its recurrence instances do.
+    /// DATE values are only moved by the whole days contained in `duration`.
+    pub fn shift(self, duration: Duration) -> Result<Self, ParserError> {
+        let mut inner = self.inner.mutable();
+        inner.shift(duration, Some(&self.timezones))?;
+        let inner = inner.build(
+            &ParserOptions::reverify(&self.timezones),
+            Some(&self.timezones),
+        )?;
+        Ok(Self { inner, ..self })
+    }
+
+    /// Rewrites all zoned and UTC DATE-TIME values to local time in `tz` and adds its VTIMEZONE,
+    /// VTIMEZONEs that aren't referenced anymore are removed.
+    ///
+    /// DATE and floating values are left untouched, as are absolute alarm triggers
+    /// and timestamps like DTSTAMP which must be specified in UTC.
+    /// Recurring components keep their timezone: their instances are expanded in the timezone
+    /// of DTSTART, so they would move by an hour if `tz` switched to daylight saving time
+    /// at different dates.
+    pub fn in_timezone(self, tz: chrono_tz::Tz) -> Result<Self, ParserError> {
+        let vtimezone = IcalTimeZone::from_tzid(tz.name())
+            .ok_or_else(|| CalDateTimeError::InvalidTZID(tz.name().to_owned()))?;
+        let mut inner = self.inner.mutable();
+        match &mut inner {
+            CalendarInnerDataBuilder::Event(events) => {
+                for event in events {
+                    rezone_properties(&mut event.properties, &self.timezones, tz)?;
+                }
+            }
+            CalendarInnerDataBuilder::Todo(todos) => {
+                for todo in todos {
+                    rezone_properties(&mut todo.properties, &self.timezones, tz)?;
+                }
+            }
+            CalendarInnerDataBuilder::Journal(journals) => {
+                for journal in journals {
+                    rezone_properties(&mut journal.properties, &self.timezones, tz)?;
+                }
+            }
+        }
+
+        let mut timezones = self.timezones;
+        let mut vtimezones = self.vtimezones;
+        timezones.insert(tz.name().to_owned(), Some(tz.into()));
+        vtimezones.insert(tz.name().to_owned(), vtimezone.clone());
+        let tzids: HashSet<String> = inner
+            .get_tzids()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect();
+        timezones.retain(|tzid, _| tzids.contains(tzid));
+        vtimezones.retain(|tzid, _| tzids.contains(tzid));
+
+        let inner = inner.build(&ParserOptions::reverify(&timezones), Some(&timezones))?;
+        Ok(Self {
+            properties: self.properties,
+            inner,
+            vtimezones,
+            timezones,
+        })
+    }
+
     pub fn add_to_calendar(self, cal: &mut IcalCalendar) {
         match self.inner {
             CalendarInnerData::Event(main, overrides) => {
@@ -431,7 +680,7 @@ impl ComponentMut for IcalCalendarObjectBuilder {
     fn build(
         self,
         options: &ParserOptions,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self::Verified, ParserError> {
         let _version: IcalVERSIONProperty = self.safe_get_required(None)?;
         let _prodid: IcalPRODIDProperty = self.safe_get_required(None)?;
@@ -439,26 +688,39 @@ impl ComponentMut for IcalCalendarObjectBuilder {
 
         #[allow(unused_mut)]
         let mut vtimezones: BTreeMap<String, IcalTimeZone> = self.vtimezones;
-        let inner = self.inner.ok_or(ParserError::NotComplete)?;
+        let mut inner = self.inner.ok_or(ParserError::NotComplete)?;
 
         #[allow(unused_mut)]
-        let mut timezones = HashMap::from_iter(
-            vtimezones
-                .iter()
-                .map(|(name, value)| (name.clone(), value.into())),
-        );
+        let mut timezones = HashMap::from_iter(vtimezones.iter().map(|(name, value)| {
+            (
+                name.clone(),
+                options
+                    .tzid_cache
+                    .resolve_or_fixed(value, &options.tzid_aliases),
+            )
+        }));
+
+        let mut localized = false;
+        if let Some(tz) = options.default_timezone {
+            localized = inner.localize_floating(tz)?;
+            if localized {
+                timezones
+                    .entry(tz.name().to_owned())
+                    .or_insert(Some(tz.into()));
+            }
+        }
 
         if options.rfc7809 {
             // Populate our map of chrono timezones with those we can populate ourselves
-            use std::str::FromStr;
             for tzid in inner.get_tzids() {
-                if let Ok(tz) = chrono_tz::Tz::from_str(tzid) {
-                    timezones.insert(tzid.to_owned(), Some(tz));
+                if let Some(tz) = options.tzid_cache.resolve_tzid(tzid) {
+                    timezones.insert(tzid.to_owned(), Some(tz.into()));
                 }
             }
         }
         let inner = inner.build(options, Some(&timezones))?;
-        if options.rfc7809 {
+        // Without RFC 7809 the default timezone is the only one that can be missing
+        if options.rfc7809 || localized {
             for tzid in inner.get_tzids() {
                 if !vtimezones.contains_key(tzid)
                     && let Some(tz) = IcalTimeZone::from_tzid(tzid)
@@ -482,16 +744,19 @@ impl ComponentMut for IcalCalendarObjectBuilder {
 }
 
 impl Emitter for CalendarInnerData {
-    fn generate(&self) -> String {
+    fn emit(&self, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
         match self {
             Self::Event(main, overrides) => {
-                main.generate() + &overrides.iter().map(Emitter::generate).collect::<String>()
+                main.emit(out)?;
+                overrides.emit(out)
             }
             Self::Todo(main, overrides) => {
-                main.generate() + &overrides.iter().map(Emitter::generate).collect::<String>()
+                main.emit(out)?;
+                overrides.emit(out)
             }
             Self::Journal(main, overrides) => {
-                main.generate() + &overrides.iter().map(Emitter::generate).collect::<String>()
+                main.emit(out)?;
+                overrides.emit(out)
             }
         }
     }
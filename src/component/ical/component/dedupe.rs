@@ -0,0 +1,223 @@
+use crate::{
+    component::{Component, IcalCalendar, Timezones, recurrence_id, sequence},
+    types::CalDateTime,
+};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+/// How [`IcalCalendar::dedupe`] resolves components sharing a UID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupeStrategy {
+    /// Only keeps the components with the highest SEQUENCE of a UID,
+    /// so overrides of outdated versions are dropped.
+    /// Of components with the same RECURRENCE-ID the one with the latest DTSTAMP is kept.
+    KeepLatest,
+    /// Keeps the overrides of all versions of a UID.
+    /// Of components with the same RECURRENCE-ID the one with the highest SEQUENCE
+    /// and latest DTSTAMP is kept.
+    MergeOverrides,
+}
+
+fn uid<C: Component>(component: &C) -> &str {
+    component
+        .get_property("UID")
+        .map(|uid| uid.value.as_str())
+        .unwrap_or_default()
+}
+
+fn version<C: Component>(component: &C) -> (i64, Option<DateTime<Utc>>) {
+    let dtstamp = component
+        .get_property("DTSTAMP")
+        .and_then(|dtstamp| CalDateTime::parse(&dtstamp.value, None).ok())
+        .map(|dtstamp| dtstamp.utc());
+    (sequence(component.get_properties()), dtstamp)
+}
+
+fn duplicate_uids<'a, C: Component>(
+    components: &'a [C],
+    timezones: &Timezones,
+) -> impl Iterator<Item = &'a str> {
+    let mut counts: HashMap<(&str, Option<String>), usize> = HashMap::new();
+    for component in components {
+        *counts
+            .entry((
+                uid(component),
+                recurrence_id(component.get_properties(), timezones),
+            ))
+            .or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|((uid, _), _)| uid)
+}
+
+fn dedupe<C: Component>(
+    components: Vec<C>,
+    strategy: DedupeStrategy,
+    timezones: &Timezones,
+) -> Vec<C> {
+    let mut max_sequences: HashMap<String, i64> = HashMap::new();
+    if strategy == DedupeStrategy::KeepLatest {
+        for component in &components {
+            let max = max_sequences.entry(uid(component).to_owned()).or_default();
+            *max = (*max).max(sequence(component.get_properties()));
+        }
+    }
+
+    let mut out: Vec<C> = vec![];
+    let mut indices: HashMap<(String, Option<String>), usize> = HashMap::new();
+    for component in components {
+        if max_sequences
+            .get(uid(&component))
+            .is_some_and(|max| sequence(component.get_properties()) < *max)
+        {
+            continue;
+        }
+        let key = (
+            uid(&component).to_owned(),
+            recurrence_id(component.get_properties(), timezones),
+        );
+        match indices.get(&key) {
+            Some(&index) => {
+                if version(&component) > version(&out[index]) {
+                    out[index] = component;
+                }
+            }
+            None => {
+                indices.insert(key, out.len());
+                out.push(component);
+            }
+        }
+    }
+    out
+}
+
+impl IcalCalendar {
+    /// UIDs that occur in multiple components with the same RECURRENCE-ID
+    /// or in components of different types, in alphabetical order.
+    ///
+    /// Calendars with duplicate UIDs can't be split into objects, see [`IcalCalendar::dedupe`].
+    pub fn find_duplicate_uids(&self) -> Vec<&str> {
+        let mut duplicates: BTreeSet<&str> = BTreeSet::new();
+        duplicates.extend(duplicate_uids(&self.events, &self.timezones));
+        duplicates.extend(duplicate_uids(&self.todos, &self.timezones));
+        duplicates.extend(duplicate_uids(&self.journals, &self.timezones));
+
+        let mut types: HashMap<&str, &str> = HashMap::new();
+        let components = (self.events.iter().map(|event| (event.get_uid(), "VEVENT")))
+            .chain(self.todos.iter().map(|todo| (todo.get_uid(), "VTODO")))
+            .chain((self.journals.iter()).map(|journal| (journal.get_uid(), "VJOURNAL")));
+        for (uid, name) in components {
+            if *types.entry(uid).or_insert(name) != name {
+                duplicates.insert(uid);
+            }
+        }
+        duplicates.into_iter().collect()
+    }
+
+    /// Removes components with duplicate UIDs according to `strategy`.
+    ///
+    /// The order of the remaining components is preserved.
+    /// UIDs shared between components of different types are left untouched.
+    #[must_use]
+    pub fn dedupe(mut self, strategy: DedupeStrategy) -> Self {
+        self.events = dedupe(self.events, strategy, &self.timezones);
+        self.todos = dedupe(self.todos, strategy, &self.timezones);
+        self.journals = dedupe(self.journals, strategy, &self.timezones);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupeStrategy;
+    use crate::{IcalParser, component::Component};
+
+    const FEED: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//Aggregator//EN\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260302T080000Z\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+SUMMARY:Weekly\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260101T120000Z\r
+RECURRENCE-ID:20260309T080000Z\r
+DTSTART:20260309T090000Z\r
+SUMMARY:Weekly (moved)\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260201T120000Z\r
+SEQUENCE:1\r
+DTSTART:20260302T080000Z\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+SUMMARY:Weekly meeting\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260201T120000Z\r
+SEQUENCE:1\r
+RECURRENCE-ID:20260316T080000Z\r
+DTSTART:20260316T100000Z\r
+SUMMARY:Weekly meeting (moved)\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:single@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260302T080000Z\r
+SUMMARY:Single\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:single@example.com\r
+DTSTAMP:20260101T120000Z\r
+SUMMARY:Single\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    fn summaries(calendar: &crate::component::IcalCalendar) -> Vec<&str> {
+        calendar
+            .events
+            .iter()
+            .map(|event| event.get_property("SUMMARY").unwrap().value.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let calendar = IcalParser::from_slice(FEED.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(
+            calendar.find_duplicate_uids(),
+            vec!["single@example.com", "weekly@example.com"]
+        );
+        assert!(calendar.clone().into_objects().is_err());
+
+        let latest = calendar.clone().dedupe(DedupeStrategy::KeepLatest);
+        assert_eq!(
+            summaries(&latest),
+            vec!["Weekly meeting", "Weekly meeting (moved)", "Single"]
+        );
+        assert_eq!(latest.find_duplicate_uids(), vec!["single@example.com"]);
+        assert_eq!(latest.into_objects().unwrap().len(), 3);
+
+        let merged = calendar.dedupe(DedupeStrategy::MergeOverrides);
+        assert_eq!(
+            summaries(&merged),
+            vec![
+                "Weekly meeting",
+                "Weekly (moved)",
+                "Weekly meeting (moved)",
+                "Single"
+            ]
+        );
+        assert_eq!(merged.into_objects().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,269 @@
+use super::calendar_object::shift_properties;
+use crate::{
+    component::{
+        AttendeesMut, CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendarObject,
+        IcalEvent,
+    },
+    parser::{ContentLine, ParserError, ParserOptions},
+    property::PartStat,
+    types::Tz,
+};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+
+/// Replaces all properties named `name` with a single one at the position of the first
+fn replace_property(properties: &mut Vec<ContentLine>, name: &str, value: &str) {
+    let line = ContentLine {
+        name: name.to_owned(),
+        params: Default::default(),
+        value: value.to_owned(),
+    };
+    match properties.iter().position(|prop| prop.name == name) {
+        Some(pos) => {
+            properties.retain(|prop| prop.name != name);
+            properties.insert(pos, line);
+        }
+        None => properties.push(line),
+    }
+}
+
+/// Gives a component the identity of a newly created one
+fn renew_identity<C: AttendeesMut>(component: &mut C, uid: &str, now: &str) {
+    let properties = component.get_properties_mut();
+    replace_property(properties, "UID", uid);
+    replace_property(properties, "DTSTAMP", now);
+    replace_property(properties, "CREATED", now);
+    if properties.iter().any(|prop| prop.name == "LAST-MODIFIED") {
+        replace_property(properties, "LAST-MODIFIED", now);
+    }
+    component.remove_property("SEQUENCE");
+    for mut attendee in component.attendees_mut() {
+        attendee.respond(PartStat::NeedsAction);
+    }
+}
+
+fn now() -> String {
+    Utc::now().format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+impl CalendarInnerDataBuilder {
+    fn renew_identity(&mut self, uid: &str) {
+        let now = now();
+        match self {
+            Self::Event(events) => {
+                for event in events {
+                    renew_identity(event, uid, &now);
+                }
+            }
+            Self::Todo(todos) => {
+                for todo in todos {
+                    renew_identity(todo, uid, &now);
+                }
+            }
+            Self::Journal(journals) => {
+                for journal in journals {
+                    renew_identity(journal, uid, &now);
+                }
+            }
+        }
+    }
+}
+
+impl IcalEvent {
+    /// A copy of the event to be saved as a new one, e.g. when duplicating it in a calendar app.
+    ///
+    /// The copy gets the UID `uid`, new DTSTAMP and CREATED timestamps, and SEQUENCE is reset.
+    /// All attendees are reset to NEEDS-ACTION since they haven't answered for the copy yet.
+    /// A copied override loses its RECURRENCE-ID and becomes a standalone event.
+    /// With `shift` the copy is moved like with [`IcalCalendarObject::shift`].
+    ///
+    /// Only the timezones of DTSTART and DTEND are known to an event,
+    /// use [`IcalCalendarObject::duplicate`] if other properties reference further timezones.
+    pub fn duplicate(&self, uid: &str, shift: Option<Duration>) -> Result<Self, ParserError> {
+        let timezones: HashMap<String, Option<Tz>> = [
+            Some((&self.dtstart.1, self.dtstart.0.timezone())),
+            self.dtend
+                .as_ref()
+                .map(|dtend| (&dtend.1, dtend.0.timezone())),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|(params, timezone)| {
+            // Unresolved TZIDs are floating
+            let timezone = (!timezone.is_local()).then_some(timezone);
+            Some((params.get_tzid()?.to_owned(), timezone))
+        })
+        .collect();
+
+        let mut builder = self.clone().mutable();
+        renew_identity(&mut builder, uid, &now());
+        // Without its master the copy of an override is no instance of anything
+        builder.remove_property("RECURRENCE-ID");
+        if let Some(duration) = shift {
+            shift_properties(&mut builder.properties, duration, Some(&timezones))?;
+            for alarm in &mut builder.alarms {
+                shift_properties(&mut alarm.properties, duration, Some(&timezones))?;
+            }
+        }
+        builder.build(&ParserOptions::reverify(&timezones), Some(&timezones))
+    }
+}
+
+impl IcalCalendarObject {
+    /// A copy of the object including its overrides to be saved as a new one,
+    /// see [`IcalEvent::duplicate`].
+    pub fn duplicate(&self, uid: &str, shift: Option<Duration>) -> Result<Self, ParserError> {
+        let mut inner = self.inner.clone().mutable();
+        inner.renew_identity(uid);
+        if let Some(duration) = shift {
+            inner.shift(duration, Some(&self.timezones))?;
+        }
+        let inner = inner.build(
+            &ParserOptions::reverify(&self.timezones),
+            Some(&self.timezones),
+        )?;
+        Ok(Self {
+            inner,
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalObjectParser,
+        component::{CalendarInnerData, Component, IcalCalendarObject},
+        generator::Emitter,
+        parser::ContentLine,
+    };
+    use chrono::Duration;
+
+    const OBJECT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+DTSTART:19700329T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260101T120000Z\r
+CREATED:20260101T120000Z\r
+SEQUENCE:3\r
+DTSTART;TZID=Europe/Berlin:20260323T090000\r
+DTEND;TZID=Europe/Berlin:20260323T100000\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+SUMMARY:Planning\r
+ORGANIZER:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=DECLINED;RSVP=TRUE:mailto:john@example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260101T120000Z\r
+RECURRENCE-ID;TZID=Europe/Berlin:20260330T090000\r
+DTSTART;TZID=Europe/Berlin:20260330T110000\r
+DTEND;TZID=Europe/Berlin:20260330T120000\r
+SUMMARY:Planning\r
+ATTENDEE;PARTSTAT=TENTATIVE:mailto:john@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse() -> IcalCalendarObject {
+        IcalObjectParser::from_slice(OBJECT.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn assert_renewed(properties: &[ContentLine]) {
+        let get = |name: &str| properties.iter().find(|prop| prop.name == name);
+        assert_eq!(get("UID").unwrap().value, "copy@example.com");
+        assert_ne!(get("DTSTAMP").unwrap().value, "20260101T120000Z");
+        assert_eq!(get("CREATED").unwrap().value, get("DTSTAMP").unwrap().value);
+        assert!(get("SEQUENCE").is_none());
+        for attendee in properties.iter().filter(|prop| prop.name == "ATTENDEE") {
+            assert_eq!(attendee.params.get_param("PARTSTAT"), Some("NEEDS-ACTION"));
+        }
+    }
+
+    #[test]
+    fn test_duplicate_object() {
+        let object = parse();
+        let copy = object
+            .duplicate("copy@example.com", Some(Duration::days(7)))
+            .unwrap();
+        assert_eq!(copy.get_uid(), "copy@example.com");
+        let CalendarInnerData::Event(main, overrides) = copy.get_inner() else {
+            unreachable!()
+        };
+        assert_renewed(main.get_properties());
+        assert_renewed(overrides[0].get_properties());
+        // Still at 09:00 after the change to daylight saving time
+        assert_eq!(
+            main.dtstart.0.utc().to_rfc3339(),
+            "2026-03-30T07:00:00+00:00"
+        );
+        assert_eq!(
+            overrides[0].get_property("RECURRENCE-ID").unwrap().value,
+            "20260406T090000"
+        );
+        // The original is untouched
+        assert_eq!(object.generate(), parse().generate());
+    }
+
+    #[test]
+    fn test_duplicate_event() {
+        let object = parse();
+        let CalendarInnerData::Event(event, _) = object.get_inner() else {
+            unreachable!()
+        };
+        let copy = event.duplicate("copy@example.com", None).unwrap();
+        assert_renewed(copy.get_properties());
+        assert_eq!(copy.get_uid(), "copy@example.com");
+        assert_eq!(copy.dtstart.0, event.dtstart.0);
+        assert_eq!(copy.get_summary(), Some("Planning"));
+
+        let shifted = event
+            .duplicate("copy@example.com", Some(Duration::hours(2)))
+            .unwrap();
+        assert_eq!(
+            shifted.get_property("DTEND").unwrap().value,
+            "20260323T120000"
+        );
+        assert_eq!(
+            shifted.get_property("DTEND").unwrap().params.get_tzid(),
+            Some("Europe/Berlin")
+        );
+    }
+
+    #[test]
+    fn test_duplicate_override() {
+        let object = parse();
+        let CalendarInnerData::Event(_, overrides) = object.get_inner() else {
+            unreachable!()
+        };
+        let copy = overrides[0]
+            .duplicate("copy@example.com", Some(Duration::days(1)))
+            .unwrap();
+        assert_renewed(copy.get_properties());
+        assert!(copy.get_property("RECURRENCE-ID").is_none());
+        assert!(copy.recurid.is_none());
+        assert_eq!(
+            copy.get_property("DTSTART").unwrap().value,
+            "20260331T110000"
+        );
+    }
+}
@@ -2,13 +2,13 @@ use chrono::DateTime;
 
 use crate::{
     ContentLineParser,
-    component::{Component, ComponentMut, IcalAlarmBuilder, IcalEvent},
-    parser::{ContentLine, ParserError, ParserOptions},
+    component::{Component, ComponentMut, IcalAlarmBuilder, IcalEvent, RecurrenceData, get_rules},
+    parser::{ContentLine, ICalProperty, ParserError, ParserOptions, quirks::repair_local_until},
     property::{
-        GetProperty, IcalDTENDProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty,
-        IcalDURATIONProperty, IcalEXDATEProperty, IcalEXRULEProperty, IcalMETHODProperty,
-        IcalRDATEProperty, IcalRECURIDProperty, IcalRRULEProperty, IcalSUMMARYProperty,
-        IcalUIDProperty,
+        IcalDESCRIPTIONProperty, IcalDTENDProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty,
+        IcalDURATIONProperty, IcalEXDATEProperty, IcalLOCATIONProperty, IcalMETHODProperty,
+        IcalRDATEProperty, IcalRECURIDProperty, IcalSUMMARYProperty, IcalUIDProperty,
+        PropertyIndex, PropertyOrder, lookup_language, replace_text_property,
     },
     types::{CalDateOrDateTime, CalDateTime, Tz},
 };
@@ -18,6 +18,7 @@ use std::{
 };
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalEventBuilder {
     pub properties: Vec<ContentLine>,
     pub alarms: Vec<IcalAlarmBuilder>,
@@ -38,9 +39,21 @@ impl IcalEventBuilder {
             .collect()
     }
 
-    pub fn with_summary(mut self, summary: String) -> Self {
-        self.properties
-            .push(IcalSUMMARYProperty(summary, Default::default()).into());
+    /// Sets the SUMMARY, an ALTREP of the previous SUMMARY is kept
+    pub fn with_summary(mut self, summary: impl Into<IcalSUMMARYProperty>) -> Self {
+        replace_text_property(&mut self.properties, summary.into());
+        self
+    }
+
+    /// Sets the DESCRIPTION, an ALTREP of the previous DESCRIPTION is kept
+    pub fn with_description(mut self, description: impl Into<IcalDESCRIPTIONProperty>) -> Self {
+        replace_text_property(&mut self.properties, description.into());
+        self
+    }
+
+    /// Sets the LOCATION, an ALTREP of the previous LOCATION is kept
+    pub fn with_location(mut self, location: impl Into<IcalLOCATIONProperty>) -> Self {
+        replace_text_property(&mut self.properties, location.into());
         self
     }
 
@@ -102,52 +115,60 @@ impl ComponentMut for IcalEventBuilder {
     }
 
     fn build(
-        self,
+        mut self,
         options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalEvent, ParserError> {
+        // Kept by the event, the repairs below only change values
+        let property_order = PropertyOrder::new(&self.properties);
+        let props = PropertyIndex::with_order(&self.properties, &property_order);
         // The following are REQUIRED, but MUST NOT occur more than once: dtstamp / uid
-        let dtstamp = self.safe_get_required(timezones)?;
-        let IcalUIDProperty(uid, _) = self.safe_get_required(timezones)?;
+        let dtstamp = props.safe_get_required(timezones)?;
+        let IcalUIDProperty(uid, _) = props.safe_get_required(timezones)?;
         // REQUIRED if METHOD not specified:
         // For now just ensure that no METHOD property exists
         assert!(
-            self.safe_get_optional::<IcalMETHODProperty>(timezones)?
+            props
+                .safe_get_optional::<IcalMETHODProperty>(timezones)?
                 .is_none()
         );
-        let dtstart: IcalDTSTARTProperty = self.safe_get_required(timezones)?;
+        let dtstart: IcalDTSTARTProperty = props.safe_get_required(timezones)?;
 
         // OPTIONAL, but NOT MORE THAN ONCE: class / created / description / geo / last-mod / location / organizer / priority / seq / status / summary / transp / url / recurid / rrule
-        let summary = self.safe_get_optional::<IcalSUMMARYProperty>(timezones)?;
-        let recurid = self.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
+        // SUMMARY may occur once per LANGUAGE, duplicates are reported by the validation
+        let summary = lookup_language(
+            props
+                .get_named_properties(IcalSUMMARYProperty::NAME)
+                .iter()
+                .copied(),
+            &[],
+        )
+        .map(|prop| IcalSUMMARYProperty::parse_prop(prop, timezones))
+        .transpose()?;
+        let recurid = props.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
         if let Some(recurid) = &recurid {
             recurid.validate_dtstart(&dtstart.0)?;
         }
 
         // OPTIONAL, but MUTUALLY EXCLUSIVE
-        if self.has_prop::<IcalDTENDProperty>() && self.has_prop::<IcalDURATIONProperty>() {
+        if props.has_prop::<IcalDTENDProperty>() && props.has_prop::<IcalDURATIONProperty>() {
             return Err(ParserError::PropertyConflict(
                 "both DTEND and DURATION are defined",
             ));
         }
-        let dtend = self.safe_get_optional::<IcalDTENDProperty>(timezones)?;
-        let duration = self.safe_get_optional::<IcalDURATIONProperty>(timezones)?;
+        let dtend = props.safe_get_optional::<IcalDTENDProperty>(timezones)?;
+        let duration = props.safe_get_optional::<IcalDURATIONProperty>(timezones)?;
 
         // OPTIONAL, allowed multiple times: attach / attendee / categories / comment / contact / exdate / rstatus / related / resources / rdate / x-prop / iana-prop
         let rrule_dtstart: DateTime<Tz> = dtstart.0.clone().into();
-        let rdates = self.safe_get_all::<IcalRDATEProperty>(timezones)?;
-        let exdates = self.safe_get_all::<IcalEXDATEProperty>(timezones)?;
-        let rrules = self
-            .safe_get_all::<IcalRRULEProperty>(timezones)?
-            .into_iter()
-            // RRules are crated against local times instead of UTC
-            .map(|rrule| rrule.0.validate(rrule_dtstart))
-            .collect::<Result<Vec<_>, _>>()?;
-        let exrules = self
-            .safe_get_all::<IcalEXRULEProperty>(timezones)?
-            .into_iter()
-            .map(|rrule| rrule.0.validate(rrule_dtstart))
-            .collect::<Result<Vec<_>, _>>()?;
+        let rdates = props.safe_get_all::<IcalRDATEProperty>(timezones)?;
+        let exdates = props.safe_get_all::<IcalEXDATEProperty>(timezones)?;
+        if options.repair_local_until {
+            repair_local_until(&mut self.properties, dtstart.0.timezone())?;
+        }
+        let props = PropertyIndex::with_order(&self.properties, &property_order);
+        // RRules are crated against local times instead of UTC
+        let (rrules, exrules) = get_rules(&props, options, rrule_dtstart)?;
 
         Ok(IcalEvent {
             uid,
@@ -155,13 +176,11 @@ impl ComponentMut for IcalEventBuilder {
             dtstart,
             dtend,
             duration,
-            rdates,
-            rrules,
-            exdates,
-            exrules,
+            recurrence: RecurrenceData::new(rdates, rrules, exdates, exrules),
             recurid,
             summary,
             properties: self.properties,
+            property_order,
             alarms: self
                 .alarms
                 .into_iter()
@@ -174,11 +193,13 @@ impl ComponentMut for IcalEventBuilder {
 #[cfg(test)]
 mod tests {
     use crate::{
-        component::{Component, ComponentMut, IcalEvent},
+        component::{Component, ComponentMut, IcalEvent, IcalEventBuilder},
         generator::Emitter,
-        parser::ParserOptions,
+        parser::{ContentLineParser, ParserOptions},
+        property::IcalLOCATIONProperty,
     };
     use chrono::Utc;
+    use std::collections::HashMap;
 
     #[test]
     fn test_builder() {
@@ -187,7 +208,7 @@ mod tests {
             .with_dtstart(Utc::now().into())
             .with_uid("alskdj".to_string())
             .with_summary("Hello World!".to_string())
-            .build(&ParserOptions { rfc7809: false }, None)
+            .build(&ParserOptions::default(), None)
             .unwrap();
         insta::assert_snapshot!(ical_event.generate(), @r"
         BEGIN:VEVENT
@@ -198,4 +219,109 @@ mod tests {
         END:VEVENT
         ");
     }
+
+    #[test]
+    fn test_altrep() {
+        let builder = IcalEventBuilder {
+            properties: ContentLineParser::from_slice(
+                b"DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":Old\r\nLOCATION:Office\r\n",
+            )
+            .collect::<Result<_, _>>()
+            .unwrap(),
+            alarms: vec![],
+        }
+        .with_description("New".to_owned())
+        .with_location(
+            IcalLOCATIONProperty::from("Room 1".to_owned())
+                .with_altrep("http://example.com/room1.vcf".parse().unwrap()),
+        )
+        .with_summary("Summary".to_owned());
+        assert_eq!(
+            builder
+                .get_properties()
+                .iter()
+                .map(Emitter::generate)
+                .collect::<String>(),
+            "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":New\r\nLOCATION;ALTREP=\"http://example.com/room1.vcf\":Room 1\r\nSUMMARY:Summary\r\n"
+        );
+    }
+
+    #[test]
+    fn test_text_escaping() {
+        let event = IcalEvent::builder()
+            .with_dtstamp(Utc::now().into())
+            .with_dtstart(Utc::now().into())
+            .with_uid("escaping".to_string())
+            .with_summary("Planning, Q2; all hands")
+            .with_location("Room 1\nFloor 2")
+            .build(&ParserOptions::default(), None)
+            .unwrap();
+        assert_eq!(
+            event.get_property("SUMMARY").unwrap().value,
+            r"Planning\, Q2\; all hands"
+        );
+        assert_eq!(event.get_summary(), Some("Planning, Q2; all hands"));
+        assert_eq!(
+            event.get_property("LOCATION").unwrap().value,
+            r"Room 1\nFloor 2"
+        );
+        assert_eq!(event.get_location().unwrap().as_str(), "Room 1\nFloor 2");
+        assert!(event.get_description().is_none());
+    }
+
+    #[test]
+    fn test_summary_language() {
+        let builder = IcalEventBuilder {
+            properties: ContentLineParser::from_slice(
+                b"UID:lang\r\nDTSTAMP:20260101T000000Z\r\nDTSTART:20260101T090000Z\r\nSUMMARY;LANGUAGE=de:Besprechung\r\nSUMMARY:Meeting\r\n",
+            )
+            .collect::<Result<_, _>>()
+            .unwrap(),
+            alarms: vec![],
+        };
+        let event = builder.build(&ParserOptions::default(), None).unwrap();
+        assert_eq!(event.get_summary(), Some("Meeting"));
+        assert_eq!(
+            event.get_localized_summary(&[]).unwrap().as_str(),
+            "Meeting"
+        );
+        assert_eq!(
+            event.get_localized_summary(&["de"]).unwrap().as_str(),
+            "Besprechung"
+        );
+    }
+
+    #[test]
+    fn test_property_lookup() {
+        let builder = IcalEventBuilder {
+            properties: ContentLineParser::from_slice(
+                b"UID:lookup\r\nATTENDEE:mailto:b@example.com\r\nDTSTAMP:20260101T000000Z\r\nDTSTART;TZID=Europe/Berlin:20260101T090000\r\nATTENDEE:mailto:a@example.com\r\n",
+            )
+            .collect::<Result<_, _>>()
+            .unwrap(),
+            alarms: vec![],
+        };
+        let timezones = HashMap::from([(
+            "Europe/Berlin".to_owned(),
+            Some(chrono_tz::Europe::Berlin.into()),
+        )]);
+        let event = builder
+            .build(&ParserOptions::default(), Some(&timezones))
+            .unwrap();
+        let attendees: Vec<_> = event
+            .get_named_properties("ATTENDEE")
+            .map(|prop| prop.value.as_str())
+            .collect();
+        assert_eq!(attendees, ["mailto:b@example.com", "mailto:a@example.com"]);
+        assert_eq!(event.get_property("UID").unwrap().value, "lookup");
+        assert!(event.get_property("SUMMARY").is_none());
+
+        // Replacing properties keeps the lookups in sync
+        let event = event.to_utc_or_local();
+        assert_eq!(
+            event.get_property("DTSTART").unwrap().value,
+            "20260101T080000Z"
+        );
+        assert_eq!(event.get_named_properties("ATTENDEE").count(), 2);
+    }
 }
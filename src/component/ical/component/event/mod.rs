@@ -1,37 +1,72 @@
 use crate::{
-    component::{Component, IcalAlarm},
+    component::{Component, IcalAlarm, RecurrenceData},
     parser::{ContentLine, ICalProperty},
     property::{
-        IcalDTENDProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty, IcalDURATIONProperty,
-        IcalEXDATEProperty, IcalRDATEProperty, IcalRECURIDProperty, IcalSUMMARYProperty,
-        RecurIdRange,
+        GetProperty, IcalDTENDProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty,
+        IcalDURATIONProperty, IcalRECURIDProperty, IcalSUMMARYProperty,
+        IcalXAPPLETRAVELDURATIONProperty, PropertyOrder, StructuredLocation,
+        TravelAdvisoryBehavior,
     },
-    types::{CalDate, CalDateOrDateTime, CalDateTime, Tz, Value},
+    types::{CalDateOrDateTime, Text},
 };
-use chrono::{DateTime, Duration, Utc};
-use std::collections::HashSet;
+use chrono::Duration;
+use std::{collections::HashSet, fmt};
 
-use crate::rrule::{RRule, RRuleSet};
+#[cfg(feature = "recurrence")]
+use crate::{
+    component::expand_recurring,
+    property::RecurIdRange,
+    rrule::RRuleSet,
+    types::{CalDate, CalDateTime, Tz, Value},
+};
 pub use builder::IcalEventBuilder;
+#[cfg(feature = "recurrence")]
+use chrono::{DateTime, Utc};
 mod builder;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalEvent {
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     uid: String,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     dtstamp: IcalDTSTAMPProperty,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstart: IcalDTSTARTProperty,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtend: Option<IcalDTENDProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     duration: Option<IcalDURATIONProperty>,
-    rdates: Vec<IcalRDATEProperty>,
-    rrules: Vec<RRule>,
-    exdates: Vec<IcalEXDATEProperty>,
-    exrules: Vec<RRule>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    pub(crate) recurrence: Option<Box<RecurrenceData>>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub(crate) recurid: Option<IcalRECURIDProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     summary: Option<IcalSUMMARYProperty>,
     pub(crate) properties: Vec<ContentLine>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    property_order: PropertyOrder,
     pub(crate) alarms: Vec<IcalAlarm>,
 }
 
+/// Leaves out the property order which only mirrors `properties`
+impl fmt::Debug for IcalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IcalEvent")
+            .field("uid", &self.uid)
+            .field("dtstamp", &self.dtstamp)
+            .field("dtstart", &self.dtstart)
+            .field("dtend", &self.dtend)
+            .field("duration", &self.duration)
+            .field("recurrence", &self.recurrence)
+            .field("recurid", &self.recurid)
+            .field("summary", &self.summary)
+            .field("properties", &self.properties)
+            .field("alarms", &self.alarms)
+            .finish()
+    }
+}
+
 impl IcalEvent {
     pub fn get_uid(&self) -> &str {
         &self.uid
@@ -40,6 +75,59 @@ impl IcalEvent {
     pub fn get_alarms(&self) -> &[IcalAlarm] {
         &self.alarms
     }
+
+    /// The unescaped SUMMARY, preferring the one without a LANGUAGE like
+    /// [`Self::get_description`]. It's picked when the event is built,
+    /// see [`Self::get_localized_summary`] for other languages.
+    pub fn get_summary(&self) -> Option<&str> {
+        self.summary.as_ref().map(|summary| summary.0.as_str())
+    }
+
+    /// The unescaped DESCRIPTION, preferring the one without a LANGUAGE
+    pub fn get_description(&self) -> Option<Text> {
+        self.get_text("DESCRIPTION")
+    }
+
+    /// The unescaped LOCATION, preferring the one without a LANGUAGE
+    pub fn get_location(&self) -> Option<Text> {
+        self.get_text("LOCATION")
+    }
+
+    /// The unescaped SUMMARY in the language best matching the BCP 47 tags `lang_tags`
+    /// in order of preference, see [`lookup_language`](crate::property::lookup_language)
+    pub fn get_localized_summary(&self, lang_tags: &[&str]) -> Option<Text> {
+        self.get_localized_text("SUMMARY", lang_tags)
+    }
+
+    /// The unescaped DESCRIPTION in the language best matching the BCP 47 tags `lang_tags`
+    /// in order of preference, see [`lookup_language`](crate::property::lookup_language)
+    pub fn get_localized_description(&self, lang_tags: &[&str]) -> Option<Text> {
+        self.get_localized_text("DESCRIPTION", lang_tags)
+    }
+
+    /// The time Apple Calendar blocks for travelling to the event, X-APPLE-TRAVEL-DURATION
+    pub fn get_travel_duration(&self) -> Option<Duration> {
+        self.get_property(IcalXAPPLETRAVELDURATIONProperty::NAME)
+            .and_then(|prop| IcalXAPPLETRAVELDURATIONProperty::parse_prop(prop, None).ok())
+            .map(|IcalXAPPLETRAVELDURATIONProperty(duration, _)| duration)
+    }
+
+    pub fn get_travel_advisory_behavior(&self) -> Option<TravelAdvisoryBehavior> {
+        self.get_property(TravelAdvisoryBehavior::PROPERTY)
+            .map(|prop| prop.value.parse().unwrap_or_else(|never| match never {}))
+    }
+
+    /// Where the travel to the event starts, X-APPLE-TRAVEL-START
+    pub fn get_travel_start(&self) -> Option<StructuredLocation> {
+        self.get_property(StructuredLocation::TRAVEL_START_PROPERTY)
+            .and_then(|prop| StructuredLocation::parse(prop).ok())
+    }
+
+    /// The map location of the event, X-APPLE-STRUCTURED-LOCATION
+    pub fn get_structured_location(&self) -> Option<StructuredLocation> {
+        self.get_property(StructuredLocation::PROPERTY)
+            .and_then(|prop| StructuredLocation::parse(prop).ok())
+    }
 }
 
 impl Component for IcalEvent {
@@ -50,6 +138,17 @@ impl Component for IcalEvent {
         &self.properties
     }
 
+    fn get_property<'c>(&'c self, name: &str) -> Option<&'c ContentLine> {
+        self.property_order
+            .get_named_properties(&self.properties, name)
+            .next()
+    }
+
+    fn get_named_properties<'c>(&'c self, name: &'c str) -> impl Iterator<Item = &'c ContentLine> {
+        self.property_order
+            .get_named_properties(&self.properties, name)
+    }
+
     fn mutable(self) -> Self::Builder {
         IcalEventBuilder {
             properties: self.properties,
@@ -86,16 +185,9 @@ impl IcalEvent {
         // Very naive way to replace known properties with UTC props
         let dtstart = self.dtstart.utc_or_local();
         let dtstamp = self.dtstamp.utc_or_local();
-        let exdates = self
-            .exdates
-            .into_iter()
-            .map(|dt| dt.utc_or_local())
-            .collect();
-        let rdates = self
-            .rdates
-            .into_iter()
-            .map(|dt| dt.utc_or_local())
-            .collect();
+        let recurrence = self
+            .recurrence
+            .map(|recurrence| Box::new(recurrence.utc_or_local()));
         let dtend = self.dtend.map(|dt| dt.utc_or_local());
         let recurid = self.recurid.map(|dt| dt.utc_or_local());
 
@@ -105,13 +197,11 @@ impl IcalEvent {
             dtstart: dtstart.clone(),
             dtend: dtend.clone(),
             duration: self.duration,
-            rrules: self.rrules,
-            rdates,
-            exrules: self.exrules,
-            exdates,
+            recurrence,
             summary: self.summary,
             recurid: recurid.clone(),
             properties: self.properties,
+            property_order: self.property_order,
             alarms: self.alarms,
         };
         ev.replace_or_push_property(dtstart);
@@ -135,40 +225,14 @@ impl IcalEvent {
     }
 
     pub fn has_rruleset(&self) -> bool {
-        !self.rrules.is_empty()
-            || !self.rdates.is_empty()
-            || !self.exrules.is_empty()
-            || !self.exdates.is_empty()
+        self.recurrence.is_some()
     }
 
+    #[cfg(feature = "recurrence")]
     pub fn get_rruleset(&self) -> Option<RRuleSet> {
-        if !self.has_rruleset() {
-            return None;
-        }
         // TODO: Remove clone
-        let dtstart = self.dtstart.0.clone().into();
-        Some(
-            RRuleSet::new(dtstart)
-                .set_rrules(self.rrules.to_owned())
-                .set_rdates(
-                    self.rdates
-                        .iter()
-                        .flat_map(|IcalRDATEProperty(dates, _)| {
-                            // TODO: Support periods
-                            dates.iter().map(|date| date.start().into())
-                        })
-                        .collect(),
-                )
-                .set_exrules(self.exrules.to_owned())
-                .set_exdates(
-                    self.exdates
-                        .iter()
-                        .flat_map(|IcalEXDATEProperty(dates, _)| {
-                            dates.iter().map(|date| date.to_owned().into())
-                        })
-                        .collect(),
-                ),
-        )
+        let recurrence = self.recurrence.as_ref()?;
+        Some(recurrence.rruleset(self.dtstart.0.clone().into()))
     }
 
     fn replace_or_push_property<T: ICalProperty + Into<ContentLine>>(&mut self, prop: T) {
@@ -179,37 +243,32 @@ impl IcalEvent {
         } else {
             self.properties.push(prop.into());
         }
+        self.property_order = PropertyOrder::new(&self.properties);
     }
 
+    #[cfg(feature = "recurrence")]
     pub fn expand_recurrence(
         &self,
         start: Option<DateTime<Utc>>,
         end: Option<DateTime<Utc>>,
         overrides: &[Self],
     ) -> Vec<Self> {
-        let main = self.clone();
-        let mut overrides: Vec<Self> = overrides.to_vec();
-        overrides.sort_by_key(|over| over.recurid.as_ref().unwrap().0.clone());
-        let Some(mut rrule_set) = main.get_rruleset() else {
-            return std::iter::once(main)
-                .chain(overrides)
-                .map(|ev| ev.to_utc_or_local())
-                .collect();
-        };
-
-        if let Some(start) = start {
-            rrule_set = rrule_set.after(start.with_timezone(&Tz::UTC));
-        }
-        if let Some(end) = end {
-            rrule_set = rrule_set.before(end.with_timezone(&Tz::UTC));
-        }
+        expand_recurring(self, start, end, overrides)
+    }
 
+    /// Builds the instances starting at `dates` from a recurring event.
+    #[cfg(feature = "recurrence")]
+    pub(crate) fn expand_instances(
+        &self,
+        dates: impl IntoIterator<Item = DateTime<Tz>>,
+        overrides: &[Self],
+    ) -> Vec<Self> {
         let mut events = vec![];
 
-        let mut template = &main;
-        'recurrence: for instance in rrule_set.all(2048).dates {
+        let mut template = self;
+        'recurrence: for instance in dates {
             // Is UTC or local
-            let recurid = if main.dtstart.0.is_date() {
+            let recurid = if self.dtstart.0.is_date() {
                 CalDateOrDateTime::Date(CalDate(instance.to_utc().date_naive(), Tz::utc()))
             } else {
                 CalDateOrDateTime::DateTime(CalDateTime::from(instance)).utc_or_local()
@@ -220,7 +279,7 @@ impl IcalEvent {
                 assert!(matches!(recurid.timezone(), Tz::Local | Tz::UTC));
             }
 
-            for over in &overrides {
+            for over in overrides {
                 let IcalRECURIDProperty(override_recurid, _, range) =
                     over.recurid.as_ref().unwrap();
                 if override_recurid != &recurid {
@@ -237,14 +296,18 @@ impl IcalEvent {
             }
 
             // We were not overriden, construct recurrence instance:
-            let mut properties = template.properties.clone();
-            // Remove recurrence props
-            properties.retain(|prop| {
-                !["RRULE", "RDATE", "EXRULE", "EXDATE"].contains(&prop.name.as_str())
-            });
-            properties.retain(|prop| prop.name != "DTEND");
+            // Recurrence props are removed, DTEND is derived from the duration
+            let properties: Vec<ContentLine> = template
+                .properties
+                .iter()
+                .filter(|prop| {
+                    !["RRULE", "RDATE", "EXRULE", "EXDATE", "DTEND"].contains(&prop.name.as_str())
+                })
+                .cloned()
+                .collect();
 
             let dtstart = IcalDTSTARTProperty(recurid.clone(), Default::default());
+            let duration = template.get_duration();
 
             let mut ev = IcalEvent {
                 uid: template.uid.clone(),
@@ -256,15 +319,13 @@ impl IcalEvent {
                     Default::default(),
                     RecurIdRange::This,
                 )),
-                dtend: template.get_duration().map(|duration| {
+                dtend: duration.map(|duration| {
                     IcalDTENDProperty((recurid.clone() + duration).into(), Default::default())
                 }),
                 alarms: vec![],
                 duration: None, // Set by DTEND
-                rdates: vec![],
-                rrules: vec![],
-                exdates: vec![],
-                exrules: vec![],
+                recurrence: None,
+                property_order: PropertyOrder::new(&properties),
                 properties,
             };
             ev.replace_or_push_property(dtstart);
@@ -274,14 +335,14 @@ impl IcalEvent {
                 Default::default(),
                 RecurIdRange::This,
             ));
-            if let Some(duration) = template.get_duration() {
+            if let Some(duration) = duration {
                 ev.replace_or_push_property(IcalDURATIONProperty(duration, Default::default()));
             }
 
             #[cfg(test)]
             {
                 assert!(
-                    self.get_tzids().is_empty(),
+                    ev.get_tzids().is_empty(),
                     "Expanded events MUST NOT refer to timezones"
                 )
             }
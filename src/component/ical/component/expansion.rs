@@ -0,0 +1,335 @@
+use crate::{
+    component::{
+        CalendarInnerData, CalendarInnerDataBuilder, IcalCalendarObject, IcalEvent, IcalTimeZone,
+        IcalTodo, rezone_properties,
+    },
+    parser::{ParserError, ParserOptions},
+    rrule::RRuleSet,
+    types::{CalDateOrDateTime, CalDateTime, Tz},
+};
+use chrono::{DateTime, NaiveDateTime, TimeDelta};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
+
+/// The range of instances generated by [`IcalCalendarObject::expand`].
+///
+/// Both bounds are inclusive and compared against the start of an instance.
+/// Overrides are part of the range if either their RECURRENCE-ID or their own start is.
+/// DATE bounds cover the whole day: a DATE start bound begins at midnight and a DATE end bound
+/// ends right before the following midnight, both in the output timezone.
+/// Floating bounds are also interpreted in the output timezone.
+///
+/// DATE-valued and floating instances don't refer to an absolute point in time, they are
+/// clipped by their wall-clock start in the output timezone. An all-day event on 2026-01-01
+/// is thus part of a range ending on `DATE:20260101` but not of a range ending on
+/// `20251231T235959Z` if the output timezone is UTC.
+#[derive(Debug, Clone)]
+pub struct ExpansionRange {
+    start: Option<CalDateOrDateTime>,
+    end: Option<CalDateOrDateTime>,
+    timezone: Tz,
+    limit: u16,
+}
+
+impl Default for ExpansionRange {
+    fn default() -> Self {
+        Self {
+            start: None,
+            end: None,
+            timezone: Tz::UTC,
+            limit: 2048,
+        }
+    }
+}
+
+impl ExpansionRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_start(mut self, start: impl Into<CalDateOrDateTime>) -> Self {
+        self.start = Some(start.into());
+        self
+    }
+
+    pub fn with_end(mut self, end: impl Into<CalDateOrDateTime>) -> Self {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Timezone the instances are generated in, defaults to UTC.
+    ///
+    /// With an IANA timezone other than UTC, DTSTART, DTEND, DUE and RECURRENCE-ID carry its
+    /// TZID and the matching VTIMEZONE is added to the expanded object.
+    /// Fixed offsets and floating time can't be referenced by a TZID: they only decide which
+    /// instances DATE bounds and floating values select, the instances are still written in UTC.
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Maximum number of generated instances, defaults to 2048.
+    pub fn with_limit(mut self, limit: u16) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Resolves a bound to the wall-clock time in the output timezone.
+    fn resolve(&self, bound: &CalDateOrDateTime, is_end: bool) -> NaiveDateTime {
+        match bound {
+            CalDateOrDateTime::Date(date) if is_end => {
+                date.naive_date().and_time(Default::default()) + TimeDelta::days(1)
+                    - TimeDelta::nanoseconds(1)
+            }
+            CalDateOrDateTime::Date(date) => date.naive_date().and_time(Default::default()),
+            CalDateOrDateTime::DateTime(datetime) if datetime.timezone().is_local() => {
+                datetime.0.naive_local()
+            }
+            CalDateOrDateTime::DateTime(datetime) => {
+                datetime.0.with_timezone(&self.timezone).naive_local()
+            }
+        }
+    }
+
+    /// Wall-clock start of an instance in the output timezone
+    fn instance_start(&self, instance: &DateTime<Tz>, is_date: bool) -> NaiveDateTime {
+        if is_date || instance.timezone().is_local() {
+            instance.naive_local()
+        } else {
+            instance.with_timezone(&self.timezone).naive_local()
+        }
+    }
+
+    /// Whether an instance starting at `instance` lies inside the range
+    fn contains(&self, instance: &DateTime<Tz>, is_date: bool) -> bool {
+        let instance_start = self.instance_start(instance, is_date);
+        self.start
+            .as_ref()
+            .is_none_or(|start| instance_start >= self.resolve(start, false))
+            && self
+                .end
+                .as_ref()
+                .is_none_or(|end| instance_start <= self.resolve(end, true))
+    }
+
+    /// Adds the instances of overrides that were moved into the range from outside of it,
+    /// RFC 4791 section 9.6.5 requires them even though their RECURRENCE-ID isn't inside.
+    /// `overrides` yields the RECURRENCE-ID and start of every override.
+    fn select_moved(
+        &self,
+        rrule_set: &RRuleSet,
+        (dates, truncated): &mut (Vec<DateTime<Tz>>, Option<usize>),
+        overrides: impl IntoIterator<Item = (CalDateOrDateTime, CalDateOrDateTime)>,
+    ) {
+        let contains = |value: &CalDateOrDateTime| {
+            self.contains(&CalDateTime::from(value.clone()).0, value.is_date())
+        };
+        for (recurid, start) in overrides {
+            // Instances with a RECURRENCE-ID inside the range were already selected
+            if contains(&recurid) || !contains(&start) {
+                continue;
+            }
+            let recurid = recurid.utc();
+            // Overrides of instances that aren't part of the set stay hidden
+            let Some(instance) = rrule_set
+                .into_iter()
+                .take_while(|date| date.to_utc() <= recurid)
+                .find(|date| date.to_utc() == recurid)
+            else {
+                continue;
+            };
+            if dates.len() < self.limit as usize {
+                dates.push(instance);
+            } else if let Some(truncated) = truncated {
+                *truncated += 1;
+            }
+        }
+        dates.sort();
+    }
+
+    /// Selects the instances of `rrule_set` inside the range.
+    /// Also returns the number of instances that were dropped because of the limit.
+    fn select(&self, rrule_set: &RRuleSet, is_date: bool) -> (Vec<DateTime<Tz>>, Option<usize>) {
+        let start = self.start.as_ref().map(|start| self.resolve(start, false));
+        let end = self.end.as_ref().map(|end| self.resolve(end, true));
+        let mut dates = vec![];
+        let mut truncated = 0;
+        for instance in rrule_set {
+            let instance_start = self.instance_start(&instance, is_date);
+            if start.is_some_and(|start| instance_start < start) {
+                continue;
+            }
+            if end.is_some_and(|end| instance_start > end) {
+                break;
+            }
+            if dates.len() < self.limit as usize {
+                dates.push(instance);
+                continue;
+            }
+            if end.is_none() && !rrule_set.is_finite() {
+                // We would never stop counting
+                return (dates, None);
+            }
+            truncated += 1;
+        }
+        (dates, Some(truncated))
+    }
+}
+
+/// The result of [`IcalCalendarObject::expand`]
+#[derive(Debug, Clone)]
+pub struct Expansion<'a> {
+    /// The expanded object, `None` if no instance lies inside the range.
+    pub object: Option<Cow<'a, IcalCalendarObject>>,
+    /// Number of instances inside the range that were dropped because of the limit.
+    /// `None` if there are infinitely many, i.e. for an infinite recurrence without end bound.
+    pub truncated: Option<usize>,
+}
+
+impl IcalCalendarObject {
+    /// Expands the recurrence set into single instances inside of `range`.
+    ///
+    /// Objects without recurrence set are returned as with [`Self::expand_recurrence`].
+    /// In contrast to it, `object` is `None` if no instance lies inside the range.
+    /// Journals are never expanded.
+    pub fn expand(&self, range: &ExpansionRange) -> Result<Expansion<'_>, ParserError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("expand", uid = self.get_uid()).entered();
+        let (inner, truncated) = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let Some(rrule_set) = main.get_rruleset() else {
+                    return Ok(Expansion {
+                        object: self.expand_instances(None, None),
+                        truncated: Some(0),
+                    });
+                };
+                let mut selected = range.select(&rrule_set, main.dtstart.0.is_date());
+                range.select_moved(
+                    &rrule_set,
+                    &mut selected,
+                    overrides.iter().filter_map(|over| {
+                        Some((over.recurid.as_ref()?.0.clone(), over.dtstart.0.clone()))
+                    }),
+                );
+                let (dates, truncated) = selected;
+                let mut events = main.expand_instances(dates, overrides).into_iter();
+                let Some(first) = events.next() else {
+                    return Ok(Expansion {
+                        object: None,
+                        truncated,
+                    });
+                };
+                (
+                    CalendarInnerData::Event(first, events.collect::<Vec<IcalEvent>>()),
+                    truncated,
+                )
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let Some(rrule_set) = main.get_rruleset() else {
+                    return Ok(Expansion {
+                        object: self.expand_instances(None, None),
+                        truncated: Some(0),
+                    });
+                };
+                let is_date = main
+                    .dtstart
+                    .as_ref()
+                    .is_some_and(|dtstart| dtstart.0.is_date());
+                let mut selected = range.select(&rrule_set, is_date);
+                range.select_moved(
+                    &rrule_set,
+                    &mut selected,
+                    overrides.iter().filter_map(|over| {
+                        Some((
+                            over.recurid.as_ref()?.0.clone(),
+                            over.dtstart.as_ref()?.0.clone(),
+                        ))
+                    }),
+                );
+                let (dates, truncated) = selected;
+                let mut todos = main.expand_instances(dates, overrides).into_iter();
+                let Some(first) = todos.next() else {
+                    return Ok(Expansion {
+                        object: None,
+                        truncated,
+                    });
+                };
+                (
+                    CalendarInnerData::Todo(first, todos.collect::<Vec<IcalTodo>>()),
+                    truncated,
+                )
+            }
+            CalendarInnerData::Journal(..) => {
+                return Ok(Expansion {
+                    object: Some(Cow::Borrowed(self)),
+                    truncated: Some(0),
+                });
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            instances = match &inner {
+                CalendarInnerData::Event(_, overrides) => overrides.len() + 1,
+                CalendarInnerData::Todo(_, overrides) => overrides.len() + 1,
+                CalendarInnerData::Journal(_, overrides) => overrides.len() + 1,
+            },
+            truncated,
+            "expanded recurrence"
+        );
+
+        let mut object = IcalCalendarObject {
+            properties: self.properties.clone(),
+            inner,
+            timezones: HashMap::new(),
+            vtimezones: BTreeMap::new(),
+        };
+        if let Tz::Olson(tz) = range.timezone
+            && tz != chrono_tz::UTC
+        {
+            object = object.localize(tz)?;
+        }
+
+        Ok(Expansion {
+            object: Some(Cow::Owned(object)),
+            truncated,
+        })
+    }
+
+    /// Moves the UTC datetimes of an expanded object into `tz`
+    fn localize(self, tz: chrono_tz::Tz) -> Result<Self, ParserError> {
+        let mut inner = self.inner.mutable();
+        match &mut inner {
+            CalendarInnerDataBuilder::Event(events) => {
+                for event in events {
+                    rezone_properties(&mut event.properties, &HashMap::new(), tz)?;
+                }
+            }
+            CalendarInnerDataBuilder::Todo(todos) => {
+                for todo in todos {
+                    rezone_properties(&mut todo.properties, &HashMap::new(), tz)?;
+                }
+            }
+            CalendarInnerDataBuilder::Journal(journals) => {
+                for journal in journals {
+                    rezone_properties(&mut journal.properties, &HashMap::new(), tz)?;
+                }
+            }
+        }
+
+        let timezones = HashMap::from([(tz.name().to_owned(), Some(tz.into()))]);
+        let inner = inner.build(&ParserOptions::reverify(&timezones), Some(&timezones))?;
+        let vtimezones = IcalTimeZone::from_tzid(tz.name())
+            .map(|vtimezone| (tz.name().to_owned(), vtimezone.clone()))
+            .into_iter()
+            .collect();
+        Ok(Self {
+            inner,
+            timezones,
+            vtimezones,
+            ..self
+        })
+    }
+}
@@ -7,19 +7,24 @@ use crate::{
     property::{
         GetProperty, IcalDTENDProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty, IcalUIDProperty,
     },
+    types::Tz,
 };
 #[cfg(not(tarpaulin_include))]
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalFreeBusyBuilder {
     pub properties: Vec<ContentLine>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalFreeBusy {
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub uid: String,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstamp: IcalDTSTAMPProperty,
     pub properties: Vec<ContentLine>,
 }
@@ -81,7 +86,7 @@ impl ComponentMut for IcalFreeBusyBuilder {
     fn build(
         self,
         _options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalFreeBusy, ParserError> {
         // REQUIRED, but NOT MORE THAN ONCE
         let IcalUIDProperty(uid, _) = self.safe_get_required(timezones)?;
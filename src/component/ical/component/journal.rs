@@ -1,13 +1,11 @@
-use crate::rrule::RRule;
 use crate::types::Tz;
 use crate::{
     ContentLineParser,
-    component::{Component, ComponentMut},
-    parser::{ContentLine, ParserError, ParserOptions},
+    component::{Component, ComponentMut, RecurrenceData, get_rules},
+    parser::{ContentLine, ParserError, ParserOptions, quirks::repair_local_until},
     property::{
         GetProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty, IcalEXDATEProperty,
-        IcalEXRULEProperty, IcalRDATEProperty, IcalRECURIDProperty, IcalRRULEProperty,
-        IcalUIDProperty,
+        IcalRDATEProperty, IcalRECURIDProperty, IcalUIDProperty,
     },
 };
 use std::{
@@ -16,20 +14,24 @@ use std::{
 };
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalJournalBuilder {
     pub properties: Vec<ContentLine>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalJournal {
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     uid: String,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstamp: IcalDTSTAMPProperty,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstart: Option<IcalDTSTARTProperty>,
     pub properties: Vec<ContentLine>,
-    rdates: Vec<IcalRDATEProperty>,
-    rrules: Vec<RRule>,
-    exdates: Vec<IcalEXDATEProperty>,
-    exrules: Vec<RRule>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    recurrence: Option<Box<RecurrenceData>>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub(crate) recurid: Option<IcalRECURIDProperty>,
 }
 
@@ -54,10 +56,7 @@ impl IcalJournal {
     }
 
     pub fn has_rruleset(&self) -> bool {
-        !self.rrules.is_empty()
-            || !self.rdates.is_empty()
-            || !self.exrules.is_empty()
-            || !self.exdates.is_empty()
+        self.recurrence.is_some()
     }
 }
 
@@ -107,17 +106,18 @@ impl ComponentMut for IcalJournalBuilder {
     }
 
     fn build(
-        self,
-        _options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        mut self,
+        options: &ParserOptions,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalJournal, ParserError> {
+        let props = self.property_index();
         // REQUIRED, ONLY ONCE
-        let IcalUIDProperty(uid, _) = self.safe_get_required(timezones)?;
-        let dtstamp = self.safe_get_required(timezones)?;
+        let IcalUIDProperty(uid, _) = props.safe_get_required(timezones)?;
+        let dtstamp = props.safe_get_required(timezones)?;
 
         // OPTIONAL, ONLY ONCE: class / created / dtstart / last-mod / organizer / recurid / seq / status / summary / url / rrule
-        let dtstart = self.safe_get_optional::<IcalDTSTARTProperty>(timezones)?;
-        let recurid = self.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
+        let dtstart = props.safe_get_optional::<IcalDTSTARTProperty>(timezones)?;
+        let recurid = props.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
         if let Some(IcalDTSTARTProperty(dtstart, _)) = &dtstart
             && let Some(recurid) = &recurid
         {
@@ -125,21 +125,16 @@ impl ComponentMut for IcalJournalBuilder {
         }
 
         // OPTIONAL, MULTIPLE ALLOWED: attach / attendee / categories / comment / contact / description / exdate / related / rdate / rstatus / x-prop / iana-prop
-        let rdates = self.safe_get_all::<IcalRDATEProperty>(timezones)?;
-        let exdates = self.safe_get_all::<IcalEXDATEProperty>(timezones)?;
+        let rdates = props.safe_get_all::<IcalRDATEProperty>(timezones)?;
+        let exdates = props.safe_get_all::<IcalEXDATEProperty>(timezones)?;
+        if options.repair_local_until
+            && let Some(IcalDTSTARTProperty(dtstart, _)) = &dtstart
+        {
+            repair_local_until(&mut self.properties, dtstart.timezone())?;
+        }
+        let props = self.property_index();
         let (rrules, exrules) = if let Some(dtstart) = dtstart.as_ref() {
-            let rrule_dtstart = dtstart.0.utc().with_timezone(&Tz::UTC);
-            let rrules = self
-                .safe_get_all::<IcalRRULEProperty>(timezones)?
-                .into_iter()
-                .map(|rrule| rrule.0.validate(rrule_dtstart))
-                .collect::<Result<Vec<_>, _>>()?;
-            let exrules = self
-                .safe_get_all::<IcalEXRULEProperty>(timezones)?
-                .into_iter()
-                .map(|rrule| rrule.0.validate(rrule_dtstart))
-                .collect::<Result<Vec<_>, _>>()?;
-            (rrules, exrules)
+            get_rules(&props, options, dtstart.0.utc().with_timezone(&Tz::UTC))?
         } else {
             (vec![], vec![])
         };
@@ -148,10 +143,7 @@ impl ComponentMut for IcalJournalBuilder {
             uid,
             dtstamp,
             dtstart,
-            rdates,
-            rrules,
-            exdates,
-            exrules,
+            recurrence: RecurrenceData::new(rdates, rrules, exdates, exrules),
             recurid,
             properties: self.properties,
         };
@@ -2,6 +2,15 @@ mod calendar;
 pub use calendar::*;
 mod calendar_object;
 pub use calendar_object::*;
+mod attendee;
+pub use attendee::{Attendee, AttendeesMut, ParticipationSummary};
+mod dedupe;
+mod duplicate;
+pub use dedupe::DedupeStrategy;
+mod redact;
+pub use redact::{RedactionLevel, RedactionPolicy};
+mod rezone;
+pub(crate) use rezone::*;
 mod alarm;
 pub use alarm::*;
 mod event;
@@ -14,3 +23,23 @@ mod timezone;
 pub use timezone::*;
 mod freebusy;
 pub use freebusy::*;
+#[cfg(feature = "recurrence")]
+mod expansion;
+#[cfg(feature = "recurrence")]
+pub use expansion::*;
+#[cfg(feature = "recurrence")]
+mod normalize;
+#[cfg(feature = "recurrence")]
+mod occurrence_index;
+mod recurring;
+#[cfg(feature = "recurrence")]
+pub use occurrence_index::*;
+#[cfg(feature = "recurrence")]
+pub use recurring::RecurrenceIssue;
+#[cfg(feature = "recurrence")]
+pub(crate) use recurring::expand_recurring;
+pub(crate) use recurring::{RecurrenceData, get_rules};
+#[cfg(feature = "recurrence")]
+mod tzdata;
+#[cfg(feature = "recurrence")]
+pub use tzdata::TimezoneShift;
@@ -0,0 +1,141 @@
+use super::recurring::{RecurringComponent, is_instance};
+use crate::{
+    component::{
+        CalendarInnerData, CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendarObject,
+    },
+    generator::Emitter,
+    parser::{ContentLine, ParserError, ParserOptions},
+    property::{IcalRECURIDProperty, RecurIdRange},
+};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use std::collections::HashSet;
+
+/// Properties that are expected to differ between a master component and its instances
+const INSTANCE_PROPERTIES: &[&str] = &[
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "RECURRENCE-ID",
+    "DTSTAMP",
+    "RRULE",
+    "RDATE",
+    "EXRULE",
+    "EXDATE",
+];
+
+/// Whether both components only differ in their instance specific properties
+fn same_content<C: RecurringComponent>(main: &C, over: &C) -> bool {
+    let content = |component: &C| {
+        component
+            .get_properties()
+            .iter()
+            .filter(|prop| !INSTANCE_PROPERTIES.contains(&prop.name.as_str()))
+            .cloned()
+            .counts()
+    };
+    let alarms = |component: &C| {
+        component
+            .alarms()
+            .iter()
+            .map(Emitter::generate)
+            .sorted()
+            .collect_vec()
+    };
+    content(main) == content(over) && alarms(main) == alarms(over)
+}
+
+/// Folds redundant overrides back into the master component.
+///
+/// Returns the builders of the master and the remaining overrides.
+fn normalize<C: RecurringComponent>(main: C, overrides: Vec<C>) -> (C::Builder, Vec<C::Builder>) {
+    let Some(rrule_set) = main.rruleset() else {
+        return (
+            main.mutable(),
+            overrides.into_iter().map(Component::mutable).collect(),
+        );
+    };
+    let mut additional_lines: Vec<ContentLine> = vec![];
+    let mut rdates: HashSet<DateTime<Utc>> = HashSet::new();
+    let mut remaining = vec![];
+
+    for over in overrides {
+        let (
+            Some(main_start),
+            Some(over_start),
+            Some(IcalRECURIDProperty(recurid, _, RecurIdRange::This)),
+        ) = (main.start(), over.start(), over.recurid())
+        else {
+            remaining.push(over.mutable());
+            continue;
+        };
+        if main_start.is_date() != over_start.is_date()
+            || main.instance_duration() != over.instance_duration()
+            || !same_content(&main, &over)
+            || !is_instance(&rrule_set, recurid)
+        {
+            remaining.push(over.mutable());
+            continue;
+        }
+
+        if over_start.utc() == recurid.utc() {
+            // Identical to the generated instance
+            continue;
+        }
+
+        // A moved instance can be expressed as EXDATE and RDATE,
+        // unless it would collide with another instance
+        if is_instance(&rrule_set, over_start) || !rdates.insert(over_start.utc()) {
+            remaining.push(over.mutable());
+            continue;
+        }
+        let (Some(recurid_line), Some(dtstart_line)) = (
+            over.get_property("RECURRENCE-ID"),
+            over.get_property("DTSTART"),
+        ) else {
+            unreachable!("verified components contain both properties");
+        };
+        additional_lines.push(ContentLine {
+            name: "EXDATE".to_owned(),
+            ..recurid_line.clone()
+        });
+        additional_lines.push(ContentLine {
+            name: "RDATE".to_owned(),
+            ..dtstart_line.clone()
+        });
+    }
+
+    let mut main = main.mutable();
+    for line in additional_lines {
+        main.add_content_line(line);
+    }
+    (main, remaining)
+}
+
+impl IcalCalendarObject {
+    /// Removes overrides that are identical to the instance the master component would generate
+    /// and replaces overrides that only move an instance with an EXDATE and RDATE pair.
+    ///
+    /// Apart from DTSTAMP an override is only considered redundant if all of its properties and
+    /// alarms match the master component. Overrides with `RANGE=THISANDFUTURE` are kept.
+    /// Journals are returned unchanged.
+    pub fn normalize_overrides(self) -> Result<Self, ParserError> {
+        let inner = match self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let (main, overrides) = normalize(main, overrides);
+                CalendarInnerDataBuilder::Event(std::iter::once(main).chain(overrides).collect())
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                let (main, overrides) = normalize(main, overrides);
+                CalendarInnerDataBuilder::Todo(std::iter::once(main).chain(overrides).collect())
+            }
+            inner @ CalendarInnerData::Journal(..) => return Ok(Self { inner, ..self }),
+        };
+        let inner = inner.build(
+            &ParserOptions::reverify(&self.timezones),
+            Some(&self.timezones),
+        )?;
+        Ok(Self { inner, ..self })
+    }
+}
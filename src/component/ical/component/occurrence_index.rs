@@ -0,0 +1,286 @@
+use crate::{
+    component::{CalendarInnerData, IcalCalendarObject, IcalEvent},
+    types::Tz,
+};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// The maximum number of instances expanded for a single window
+const LIMIT: u16 = 2048;
+
+/// Instances are keyed by their start and RECURRENCE-ID,
+/// an override can be moved to the start of another instance
+type InstanceKey = (DateTime<Utc>, Option<DateTime<Utc>>);
+
+/// Caches the expanded instances of an [`IcalCalendarObject`].
+///
+/// CalDAV servers evaluate many overlapping time-range filters against the same object.
+/// The index remembers which windows were already expanded and only expands the parts of a
+/// query that aren't covered yet.
+///
+/// Only VEVENT objects are expanded, other objects yield no occurrences.
+#[derive(Debug, Clone)]
+pub struct OccurrenceIndex {
+    object: IcalCalendarObject,
+    /// Sorted and non-overlapping windows of recurrence dates that were completely expanded
+    windows: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    /// Expanded instances, overrides are indexed up front since they can be moved outside of
+    /// the window of their RECURRENCE-ID
+    instances: BTreeMap<InstanceKey, IcalEvent>,
+}
+
+impl OccurrenceIndex {
+    pub fn new(object: IcalCalendarObject) -> Self {
+        let mut index = Self {
+            object,
+            windows: vec![],
+            instances: BTreeMap::new(),
+        };
+        if let CalendarInnerData::Event(main, overrides) = &index.object.inner
+            && main.has_rruleset()
+        {
+            for over in overrides {
+                let Some(recurid) = &over.recurid else {
+                    continue;
+                };
+                // Only overrides of actual instances are part of the expansion
+                let recurid = recurid.0.utc();
+                let instances = main.expand_recurrence(
+                    Some(recurid),
+                    Some(recurid),
+                    std::slice::from_ref(over),
+                );
+                for instance in instances {
+                    index.instances.insert(instance_key(&instance), instance);
+                }
+            }
+        }
+        index
+    }
+
+    pub const fn get_object(&self) -> &IcalCalendarObject {
+        &self.object
+    }
+
+    pub fn into_inner(self) -> IcalCalendarObject {
+        self.object
+    }
+
+    /// Returns all instances that start within `start..=end`, ordered by their start.
+    ///
+    /// Like [`IcalEvent::expand_recurrence`] a single expanded window is limited to 2048
+    /// instances, the rest of a truncated window is expanded by later queries.
+    pub fn occurrences_between(
+        &mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Iterator<Item = &IcalEvent> {
+        let mut covered = vec![];
+        if let CalendarInnerData::Event(main, overrides) = &self.object.inner {
+            for (gap_start, gap_end) in self.missing_windows(start, end) {
+                let Some(rrule_set) = main.get_rruleset() else {
+                    for instance in main.expand_recurrence(None, None, overrides) {
+                        self.instances
+                            .entry(instance_key(&instance))
+                            .or_insert(instance);
+                    }
+                    covered.push((gap_start, gap_end));
+                    continue;
+                };
+                let result = rrule_set
+                    .after(gap_start.with_timezone(&Tz::UTC))
+                    .before(gap_end.with_timezone(&Tz::UTC))
+                    .all(LIMIT);
+                // Only the part of a truncated window up to its last instance is complete
+                let covered_end = match (result.limited, result.dates.last()) {
+                    (false, _) => Some(gap_end),
+                    (true, last) => last.map(DateTime::to_utc),
+                };
+                for instance in main.expand_instances(result.dates, overrides) {
+                    self.instances
+                        .entry(instance_key(&instance))
+                        .or_insert(instance);
+                }
+                if let Some(covered_end) = covered_end {
+                    covered.push((gap_start, covered_end));
+                }
+            }
+        }
+        for (window_start, window_end) in covered {
+            self.insert_window(window_start, window_end);
+        }
+
+        // An empty range would make BTreeMap::range panic
+        let range = if start <= end {
+            Some(
+                self.instances
+                    .range((start, None)..=(end, Some(DateTime::<Utc>::MAX_UTC))),
+            )
+        } else {
+            None
+        };
+        range.into_iter().flatten().map(|(_, instance)| instance)
+    }
+
+    fn covers(&self, dt: DateTime<Utc>) -> bool {
+        self.windows.iter().any(|&(a, b)| a <= dt && dt <= b)
+    }
+
+    fn missing_windows(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut gaps = vec![];
+        if start > end {
+            return gaps;
+        }
+        let mut cursor = start;
+        for &(window_start, window_end) in &self.windows {
+            if window_end < cursor {
+                continue;
+            }
+            if window_start > end {
+                break;
+            }
+            if window_start > cursor {
+                gaps.push((cursor, window_start));
+            }
+            cursor = cursor.max(window_end);
+        }
+        if cursor < end || !self.covers(cursor) {
+            gaps.push((cursor, end));
+        }
+        gaps
+    }
+
+    fn insert_window(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) {
+        if start > end {
+            return;
+        }
+        self.windows.push((start, end));
+        self.windows.sort_unstable();
+        let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = vec![];
+        for (window_start, window_end) in self.windows.drain(..) {
+            match merged.last_mut() {
+                Some((_, last_end)) if window_start <= *last_end => {
+                    *last_end = (*last_end).max(window_end);
+                }
+                _ => merged.push((window_start, window_end)),
+            }
+        }
+        self.windows = merged;
+    }
+}
+
+fn instance_key(instance: &IcalEvent) -> InstanceKey {
+    (
+        instance.dtstart.0.utc(),
+        instance.recurid.as_ref().map(|recurid| recurid.0.utc()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OccurrenceIndex;
+    use crate::IcalObjectParser;
+    use chrono::{TimeZone, Utc};
+
+    const INPUT: &str = r#"BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:test
+BEGIN:VEVENT
+UID:daily
+DTSTAMP:20250101T000000Z
+DTSTART:20250101T090000Z
+DURATION:PT1H
+RRULE:FREQ=DAILY;COUNT=10
+END:VEVENT
+BEGIN:VEVENT
+UID:daily
+DTSTAMP:20250101T000000Z
+RECURRENCE-ID:20250104T090000Z
+DTSTART:20250104T120000Z
+DURATION:PT1H
+END:VEVENT
+END:VCALENDAR
+"#;
+
+    #[test]
+    fn overlapping_windows() {
+        let object = IcalObjectParser::from_slice(INPUT.as_bytes())
+            .expect_one()
+            .unwrap();
+        let mut index = OccurrenceIndex::new(object);
+        let day = |d| Utc.with_ymd_and_hms(2025, 1, d, 0, 0, 0).unwrap();
+
+        assert_eq!(index.occurrences_between(day(3), day(6)).count(), 3);
+        assert_eq!(index.occurrences_between(day(1), day(4)).count(), 3);
+        assert_eq!(index.occurrences_between(day(5), day(9)).count(), 4);
+        assert_eq!(index.windows, vec![(day(1), day(9))]);
+
+        let occurrences: Vec<_> = index
+            .occurrences_between(day(1), day(31))
+            .map(|ev| ev.dtstart.0.utc())
+            .collect();
+        assert_eq!(occurrences.len(), 10);
+        assert_eq!(
+            occurrences[3],
+            Utc.with_ymd_and_hms(2025, 1, 4, 12, 0, 0).unwrap()
+        );
+
+        // Uncached expansion must produce the same instances
+        let fresh: Vec<_> = OccurrenceIndex::new(index.into_inner())
+            .occurrences_between(day(1), day(31))
+            .map(|ev| ev.dtstart.0.utc())
+            .collect();
+        assert_eq!(occurrences, fresh);
+    }
+
+    #[test]
+    fn moved_override() {
+        // The instance of the 4th is moved after the last regular one
+        let input = INPUT.replace("DTSTART:20250104T120000Z", "DTSTART:20250110T120000Z");
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let mut index = OccurrenceIndex::new(object);
+        let day = |d| Utc.with_ymd_and_hms(2025, 1, d, 0, 0, 0).unwrap();
+
+        let starts: Vec<_> = index
+            .occurrences_between(day(10), day(11))
+            .map(|ev| ev.dtstart.0.utc())
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                Utc.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 1, 10, 12, 0, 0).unwrap()
+            ]
+        );
+        assert_eq!(index.occurrences_between(day(4), day(5)).count(), 0);
+        assert_eq!(index.occurrences_between(day(1), day(31)).count(), 10);
+    }
+
+    #[test]
+    fn truncated_window() {
+        let input = INPUT.replace("FREQ=DAILY;COUNT=10", "FREQ=MINUTELY");
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let mut index = OccurrenceIndex::new(object);
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 3, 9, 0, 0).unwrap();
+
+        assert_eq!(index.occurrences_between(start, end).count(), 2048);
+        // Only the expanded part of the window is cached
+        assert_eq!(
+            index.windows,
+            vec![(start, Utc.with_ymd_and_hms(2025, 1, 2, 19, 7, 0).unwrap())]
+        );
+        assert_eq!(
+            index.occurrences_between(start, end).count(),
+            2 * 24 * 60 + 1
+        );
+    }
+}
@@ -0,0 +1,347 @@
+#[cfg(feature = "recurrence")]
+use crate::{
+    component::{CalendarInnerData, Component, IcalAlarm, IcalCalendarObject, IcalEvent, IcalTodo},
+    property::{
+        IcalDTSTARTProperty, IcalEXRULEProperty, IcalRECURIDProperty, IcalRRULEProperty, get_rrules,
+    },
+    rrule::{RRule, RRuleSet},
+    types::CalDateOrDateTime,
+};
+use crate::{
+    parser::{ICalProperty, ParserError, ParserOptions},
+    property::{IcalEXDATEProperty, IcalRDATEProperty, PropertyIndex},
+    types::Tz,
+};
+use chrono::DateTime;
+#[cfg(feature = "recurrence")]
+use chrono::{Duration, Utc};
+#[cfg(feature = "recurrence")]
+use itertools::Itertools;
+#[cfg(feature = "recurrence")]
+use std::collections::HashSet;
+
+/// A recurrence rule, kept as it's written without the `recurrence` feature
+#[cfg(feature = "recurrence")]
+pub(crate) type Rule = RRule;
+#[cfg(not(feature = "recurrence"))]
+pub(crate) type Rule = String;
+
+/// The rules of the RRULE and EXRULE properties, validated against `dtstart`
+#[cfg(feature = "recurrence")]
+pub(crate) fn get_rules(
+    props: &PropertyIndex,
+    options: &ParserOptions,
+    dtstart: DateTime<Tz>,
+) -> Result<(Vec<Rule>, Vec<Rule>), ParserError> {
+    let rrules = get_rrules::<IcalRRULEProperty>(props, options)?
+        .into_iter()
+        .map(|rrule| rrule.validate(dtstart))
+        .collect::<Result<Vec<_>, _>>()?;
+    let exrules = get_rrules::<IcalEXRULEProperty>(props, options)?
+        .into_iter()
+        .map(|rrule| rrule.validate(dtstart))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((rrules, exrules))
+}
+
+/// The values of the RRULE and EXRULE properties
+#[cfg(not(feature = "recurrence"))]
+pub(crate) fn get_rules(
+    props: &PropertyIndex,
+    _options: &ParserOptions,
+    _dtstart: DateTime<Tz>,
+) -> Result<(Vec<Rule>, Vec<Rule>), ParserError> {
+    let values = |name| {
+        props
+            .get_named_properties(name)
+            .iter()
+            .map(|prop| prop.value.to_owned())
+            .collect()
+    };
+    Ok((values("RRULE"), values("EXRULE")))
+}
+
+/// The recurrence set of a component.
+///
+/// Most components don't recur, so it's kept behind a single pointer that is only allocated
+/// for recurring ones.
+#[derive(Debug, Clone)]
+// Without the recurrence feature rules only mark the component as recurring
+#[cfg_attr(not(feature = "recurrence"), allow(dead_code))]
+pub(crate) struct RecurrenceData {
+    pub rdates: Vec<IcalRDATEProperty>,
+    pub rrules: Vec<Rule>,
+    pub exdates: Vec<IcalEXDATEProperty>,
+    pub exrules: Vec<Rule>,
+}
+
+impl RecurrenceData {
+    /// `None` if the recurrence set is empty
+    pub fn new(
+        rdates: Vec<IcalRDATEProperty>,
+        rrules: Vec<Rule>,
+        exdates: Vec<IcalEXDATEProperty>,
+        exrules: Vec<Rule>,
+    ) -> Option<Box<Self>> {
+        if rdates.is_empty() && rrules.is_empty() && exdates.is_empty() && exrules.is_empty() {
+            return None;
+        }
+        Some(Box::new(Self {
+            rdates,
+            rrules,
+            exdates,
+            exrules,
+        }))
+    }
+
+    #[cfg(feature = "recurrence")]
+    pub fn rruleset(&self, dtstart: DateTime<Tz>) -> RRuleSet {
+        RRuleSet::new(dtstart)
+            .set_rrules(self.rrules.to_owned())
+            .set_rdates(
+                self.rdates
+                    .iter()
+                    .flat_map(|IcalRDATEProperty(dates, _)| {
+                        // TODO: Support periods
+                        dates.iter().map(|date| date.start().into())
+                    })
+                    .collect(),
+            )
+            .set_exrules(self.exrules.to_owned())
+            .set_exdates(
+                self.exdates
+                    .iter()
+                    .flat_map(|IcalEXDATEProperty(dates, _)| {
+                        dates.iter().map(|date| date.to_owned().into())
+                    })
+                    .collect(),
+            )
+    }
+
+    pub fn utc_or_local(self) -> Self {
+        Self {
+            rdates: self
+                .rdates
+                .into_iter()
+                .map(|dt| dt.utc_or_local())
+                .collect(),
+            exdates: self
+                .exdates
+                .into_iter()
+                .map(|dt| dt.utc_or_local())
+                .collect(),
+            ..self
+        }
+    }
+}
+
+/// Common interface of recurring components that can be overridden
+#[cfg(feature = "recurrence")]
+pub(crate) trait RecurringComponent: Component {
+    fn start(&self) -> Option<&CalDateOrDateTime>;
+    fn recurid(&self) -> Option<&IcalRECURIDProperty>;
+    fn instance_duration(&self) -> Option<Duration>;
+    fn rruleset(&self) -> Option<RRuleSet>;
+    fn alarms(&self) -> &[IcalAlarm];
+    fn exdates(&self) -> &[IcalEXDATEProperty];
+    fn into_utc_or_local(self) -> Self;
+    /// Builds the instances starting at `dates`
+    fn instances(&self, dates: Vec<DateTime<Tz>>, overrides: &[Self]) -> Vec<Self>
+    where
+        Self: Sized;
+}
+
+/// Expands the recurrence set of `main` into single instances starting within `start..=end`,
+/// at most 2048 of them.
+///
+/// Components without recurrence set are returned with their overrides ordered by
+/// RECURRENCE-ID.
+#[cfg(feature = "recurrence")]
+pub(crate) fn expand_recurring<C: RecurringComponent + Clone>(
+    main: &C,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    overrides: &[C],
+) -> Vec<C> {
+    let Some(mut rrule_set) = main.rruleset() else {
+        let mut overrides: Vec<&C> = overrides.iter().collect();
+        overrides.sort_by(|a, b| a.recurid().unwrap().0.cmp(&b.recurid().unwrap().0));
+        return std::iter::once(main)
+            .chain(overrides)
+            .map(|component| component.clone().into_utc_or_local())
+            .collect();
+    };
+
+    if let Some(start) = start {
+        rrule_set = rrule_set.after(start.with_timezone(&Tz::UTC));
+    }
+    if let Some(end) = end {
+        rrule_set = rrule_set.before(end.with_timezone(&Tz::UTC));
+    }
+
+    main.instances(rrule_set.all(2048).dates, overrides)
+}
+
+#[cfg(feature = "recurrence")]
+impl RecurringComponent for IcalEvent {
+    fn start(&self) -> Option<&CalDateOrDateTime> {
+        Some(&self.dtstart.0)
+    }
+
+    fn recurid(&self) -> Option<&IcalRECURIDProperty> {
+        self.recurid.as_ref()
+    }
+
+    fn instance_duration(&self) -> Option<Duration> {
+        self.get_duration()
+    }
+
+    fn rruleset(&self) -> Option<RRuleSet> {
+        self.get_rruleset()
+    }
+
+    fn alarms(&self) -> &[IcalAlarm] {
+        self.get_alarms()
+    }
+
+    fn exdates(&self) -> &[IcalEXDATEProperty] {
+        self.recurrence
+            .as_ref()
+            .map_or(&[], |recurrence| &recurrence.exdates)
+    }
+
+    fn into_utc_or_local(self) -> Self {
+        self.to_utc_or_local()
+    }
+
+    fn instances(&self, dates: Vec<DateTime<Tz>>, overrides: &[Self]) -> Vec<Self> {
+        self.expand_instances(dates, overrides)
+    }
+}
+
+#[cfg(feature = "recurrence")]
+impl RecurringComponent for IcalTodo {
+    fn start(&self) -> Option<&CalDateOrDateTime> {
+        self.dtstart
+            .as_ref()
+            .map(|IcalDTSTARTProperty(dtstart, _)| dtstart)
+    }
+
+    fn recurid(&self) -> Option<&IcalRECURIDProperty> {
+        self.recurid.as_ref()
+    }
+
+    fn instance_duration(&self) -> Option<Duration> {
+        self.get_duration()
+    }
+
+    fn rruleset(&self) -> Option<RRuleSet> {
+        self.get_rruleset()
+    }
+
+    fn alarms(&self) -> &[IcalAlarm] {
+        self.get_alarms()
+    }
+
+    fn exdates(&self) -> &[IcalEXDATEProperty] {
+        self.recurrence
+            .as_ref()
+            .map_or(&[], |recurrence| &recurrence.exdates)
+    }
+
+    fn into_utc_or_local(self) -> Self {
+        self.to_utc_or_local()
+    }
+
+    fn instances(&self, dates: Vec<DateTime<Tz>>, overrides: &[Self]) -> Vec<Self> {
+        self.expand_instances(dates, overrides)
+    }
+}
+
+#[cfg(feature = "recurrence")]
+pub(crate) fn is_instance(rrule_set: &RRuleSet, dt: &CalDateOrDateTime) -> bool {
+    let dt: DateTime<Tz> = dt.clone().into();
+    !rrule_set
+        .clone()
+        .after(dt)
+        .before(dt)
+        .all(1)
+        .dates
+        .is_empty()
+}
+
+/// Common corruption patterns of recurring objects, usually caused by buggy client edits
+#[cfg(feature = "recurrence")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecurrenceIssue {
+    /// The RECURRENCE-ID of an override doesn't correspond to any generated instance
+    OrphanOverride(CalDateOrDateTime),
+    /// The instance of an override is excluded by an EXDATE
+    ExcludedOverride(CalDateOrDateTime),
+    /// Multiple overrides exist for the same instance
+    DuplicateOverride(CalDateOrDateTime),
+    /// An EXDATE doesn't exclude any instance
+    UnusedExdate(CalDateOrDateTime),
+}
+
+#[cfg(feature = "recurrence")]
+fn check_recurrence<C: RecurringComponent>(main: &C, overrides: &[C]) -> Vec<RecurrenceIssue> {
+    let Some(rrule_set) = main.rruleset() else {
+        // Without recurrence set every override is an orphan
+        return overrides
+            .iter()
+            .filter_map(|over| over.recurid())
+            .map(|IcalRECURIDProperty(recurid, ..)| {
+                RecurrenceIssue::OrphanOverride(recurid.clone())
+            })
+            .collect();
+    };
+    let mut issues = vec![];
+
+    let recurids = overrides
+        .iter()
+        .filter_map(|over| over.recurid())
+        .map(|IcalRECURIDProperty(recurid, ..)| recurid)
+        .collect_vec();
+    let without_exdates = rrule_set.clone().set_exdates(vec![]);
+    for recurid in &recurids {
+        if !is_instance(&without_exdates, recurid) {
+            issues.push(RecurrenceIssue::OrphanOverride((*recurid).clone()));
+        } else if !is_instance(&rrule_set, recurid) {
+            issues.push(RecurrenceIssue::ExcludedOverride((*recurid).clone()));
+        }
+    }
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    for recurid in &recurids {
+        if !seen.insert(recurid.utc()) && reported.insert(recurid.utc()) {
+            issues.push(RecurrenceIssue::DuplicateOverride((*recurid).clone()));
+        }
+    }
+
+    for exdate in main
+        .exdates()
+        .iter()
+        .flat_map(|IcalEXDATEProperty(dates, _)| dates)
+    {
+        if !is_instance(&without_exdates, exdate) {
+            issues.push(RecurrenceIssue::UnusedExdate(exdate.clone()));
+        }
+    }
+    issues
+}
+
+#[cfg(feature = "recurrence")]
+impl IcalCalendarObject {
+    /// Detects overrides that don't correspond to any generated instance, overrides of excluded
+    /// instances, duplicate overrides for the same instance and EXDATEs that exclude nothing.
+    ///
+    /// Journals are not checked.
+    pub fn check_recurrence(&self) -> Vec<RecurrenceIssue> {
+        match &self.inner {
+            CalendarInnerData::Event(main, overrides) => check_recurrence(main, overrides),
+            CalendarInnerData::Todo(main, overrides) => check_recurrence(main, overrides),
+            CalendarInnerData::Journal(..) => vec![],
+        }
+    }
+}
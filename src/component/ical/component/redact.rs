@@ -0,0 +1,241 @@
+use crate::{
+    component::{Component, ComponentMut, IcalCalendar},
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use std::collections::HashMap;
+
+/// Which components [`IcalCalendar::redact`] redacts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactionLevel {
+    /// Components with a CLASS other than PUBLIC,
+    /// overrides without CLASS inherit the CLASS of their main component
+    NonPublic,
+    /// All components, e.g. to publish a free/busy like copy of a calendar
+    All,
+}
+
+/// What is removed from redacted components
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionPolicy {
+    /// Properties whose value is replaced by the given one, their parameters are removed
+    pub masked: Vec<(String, String)>,
+    /// Properties that are removed
+    pub removed: Vec<String>,
+    /// Whether experimental (X-) properties are removed
+    pub remove_x_properties: bool,
+    /// Whether alarms are removed
+    pub remove_alarms: bool,
+}
+
+impl Default for RedactionPolicy {
+    fn default() -> Self {
+        Self {
+            masked: vec![("SUMMARY".to_owned(), "Busy".to_owned())],
+            removed: [
+                "DESCRIPTION",
+                "LOCATION",
+                "ATTENDEE",
+                "ORGANIZER",
+                "ATTACH",
+                "COMMENT",
+                "CONTACT",
+                "URL",
+                "GEO",
+                "CATEGORIES",
+                "RESOURCES",
+                "CONFERENCE",
+                "IMAGE",
+            ]
+            .map(ToOwned::to_owned)
+            .to_vec(),
+            remove_x_properties: true,
+            remove_alarms: true,
+        }
+    }
+}
+
+fn property<'a>(properties: &'a [ContentLine], name: &str) -> Option<&'a str> {
+    properties
+        .iter()
+        .find(|prop| prop.name == name)
+        .map(|prop| prop.value.as_str())
+}
+
+/// The CLASS of the main components by their UID
+fn main_classes<'a>(
+    components: impl Iterator<Item = &'a Vec<ContentLine>>,
+) -> HashMap<String, String> {
+    components
+        .filter(|properties| property(properties, "RECURRENCE-ID").is_none())
+        .filter_map(|properties| {
+            Some((
+                property(properties, "UID")?.to_owned(),
+                property(properties, "CLASS")?.to_owned(),
+            ))
+        })
+        .collect()
+}
+
+impl RedactionLevel {
+    /// Overrides without CLASS inherit the CLASS of their main component in `main_classes`
+    fn applies_to(
+        self,
+        properties: &[ContentLine],
+        main_classes: &HashMap<String, String>,
+    ) -> bool {
+        match self {
+            Self::All => true,
+            Self::NonPublic => property(properties, "CLASS")
+                .or_else(|| {
+                    property(properties, "RECURRENCE-ID")?;
+                    main_classes
+                        .get(property(properties, "UID")?)
+                        .map(String::as_str)
+                })
+                .is_some_and(|class| !class.eq_ignore_ascii_case("PUBLIC")),
+        }
+    }
+}
+
+impl RedactionPolicy {
+    fn apply(&self, properties: &mut Vec<ContentLine>) {
+        properties.retain(|prop| {
+            !(self.removed.contains(&prop.name)
+                || (self.remove_x_properties && prop.name.starts_with("X-")))
+        });
+        for prop in properties {
+            if let Some((_, value)) = self.masked.iter().find(|(name, _)| *name == prop.name) {
+                prop.value = value.to_owned();
+                prop.params = Default::default();
+            }
+        }
+    }
+}
+
+impl IcalCalendar {
+    /// Redacts events, to-dos and journals with the [default policy](RedactionPolicy::default),
+    /// see [`IcalCalendar::redact_with`].
+    pub fn redact(self, level: RedactionLevel) -> Result<Self, ParserError> {
+        self.redact_with(level, &RedactionPolicy::default())
+    }
+
+    /// Removes or masks the properties of events, to-dos and journals selected by `level`
+    /// according to `policy`.
+    ///
+    /// Times and recurrence are preserved, so the redacted calendar still shows
+    /// when its owner is busy.
+    pub fn redact_with(
+        self,
+        level: RedactionLevel,
+        policy: &RedactionPolicy,
+    ) -> Result<Self, ParserError> {
+        let mut builder = self.mutable();
+        let classes = main_classes(builder.events.iter().map(|event| &event.properties));
+        for event in &mut builder.events {
+            if level.applies_to(&event.properties, &classes) {
+                policy.apply(&mut event.properties);
+                if policy.remove_alarms {
+                    event.alarms.clear();
+                }
+            }
+        }
+        let classes = main_classes(builder.todos.iter().map(|todo| &todo.properties));
+        for todo in &mut builder.todos {
+            if level.applies_to(&todo.properties, &classes) {
+                policy.apply(&mut todo.properties);
+                if policy.remove_alarms {
+                    todo.alarms.clear();
+                }
+            }
+        }
+        let classes = main_classes(builder.journals.iter().map(|journal| &journal.properties));
+        for journal in &mut builder.journals {
+            if level.applies_to(&journal.properties, &classes) {
+                policy.apply(&mut journal.properties);
+            }
+        }
+        let options = ParserOptions::reverify(&builder.timezones);
+        builder.build(&options, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RedactionLevel;
+    use crate::{IcalParser, component::Component, generator::Emitter};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:public@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+SUMMARY:Team meeting\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:private@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260324T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+CLASS:PRIVATE\r
+SUMMARY;LANGUAGE=en:Doctor's appointment\r
+DESCRIPTION:Bring the results\r
+LOCATION:Main street 1\r
+ATTENDEE:mailto:doctor@example.com\r
+X-ALT-DESC;FMTTYPE=text/html:<b>Bring the results</b>\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Doctor's appointment\r
+TRIGGER:-PT1H\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:private@example.com\r
+DTSTAMP:20260101T120000Z\r
+RECURRENCE-ID:20260331T080000Z\r
+DTSTART:20260331T090000Z\r
+DURATION:PT1H\r
+SUMMARY:Doctor's appointment (moved)\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_redact() {
+        let calendar = IcalParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap();
+
+        let redacted = calendar.clone().redact(RedactionLevel::NonPublic).unwrap();
+        similar_asserts::assert_eq!(redacted.events[0].generate(), calendar.events[0].generate());
+        similar_asserts::assert_eq!(
+            redacted.events[1].generate(),
+            "BEGIN:VEVENT\r
+UID:private@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260324T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+CLASS:PRIVATE\r
+SUMMARY:Busy\r
+END:VEVENT\r
+"
+        );
+        // The override inherits the CLASS of the main event
+        assert_eq!(
+            redacted.events[2].get_property("SUMMARY").unwrap().value,
+            "Busy"
+        );
+
+        let redacted = calendar.redact(RedactionLevel::All).unwrap();
+        assert_eq!(
+            redacted.events[0].get_property("SUMMARY").unwrap().value,
+            "Busy"
+        );
+        #[cfg(feature = "recurrence")]
+        assert!(redacted.events[1].get_rruleset().is_some());
+    }
+}
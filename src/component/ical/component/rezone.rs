@@ -0,0 +1,122 @@
+//! Helpers for comparing versions of components and converting their DATE-TIME values
+//! across the timezones they are in
+use crate::{
+    component::ParserError,
+    parser::ContentLine,
+    types::{CalDateTime, Tz},
+};
+use std::collections::HashMap;
+
+/// The timezones of a calendar by TZID, `None` for VTIMEZONEs that don't resolve
+pub(crate) type Timezones = HashMap<String, Option<Tz>>;
+
+/// Whether the properties contain recurrence rules or dates. The instances of such a
+/// component are expanded in the timezone of its DTSTART, so converting it to another
+/// timezone can move them.
+pub(crate) fn is_recurring(properties: &[ContentLine]) -> bool {
+    properties
+        .iter()
+        .any(|prop| matches!(prop.name.as_str(), "RRULE" | "RDATE" | "EXRULE"))
+}
+
+/// Converts the zoned DATE-TIME values of all date properties to UTC, dropping their TZID,
+/// see [`rezone_properties`]
+pub(crate) fn properties_to_utc(
+    properties: &mut [ContentLine],
+    timezones: &Timezones,
+) -> Result<(), ParserError> {
+    rezone_properties(properties, timezones, chrono_tz::UTC)
+}
+
+/// Rewrites the zoned and UTC DATE-TIME values of all date properties to `target`,
+/// as UTC without TZID or as local time with the TZID of `target`.
+///
+/// DATE, PERIOD and floating values are left untouched, as are values in timezones we can't
+/// resolve. The properties of recurring components are left untouched as well since their
+/// instances are expanded in the timezone of DTSTART, see [`is_recurring`].
+pub(crate) fn rezone_properties(
+    properties: &mut [ContentLine],
+    timezones: &Timezones,
+    target: chrono_tz::Tz,
+) -> Result<(), ParserError> {
+    if is_recurring(properties) {
+        return Ok(());
+    }
+    for prop in properties.iter_mut() {
+        if !matches!(
+            prop.name.as_str(),
+            "DTSTART" | "DTEND" | "DUE" | "RECURRENCE-ID" | "RDATE" | "EXDATE"
+        ) || matches!(prop.params.get_value_type(), Some("DATE" | "PERIOD"))
+        {
+            continue;
+        }
+        let timezone = match prop.params.get_tzid() {
+            Some(tzid) => match timezones.get(tzid) {
+                Some(Some(tz)) => Some(*tz),
+                _ => continue,
+            },
+            None if prop.value.split(',').all(|value| value.ends_with('Z')) => None,
+            // Floating
+            None => continue,
+        };
+        let values = prop
+            .value
+            .split(',')
+            .map(|value| {
+                let datetime = CalDateTime::parse_in(value, timezone)?.utc();
+                Ok(match target {
+                    chrono_tz::UTC => datetime.format("%Y%m%dT%H%M%SZ").to_string(),
+                    _ => datetime
+                        .with_timezone(&target)
+                        .format("%Y%m%dT%H%M%S")
+                        .to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, ParserError>>()?;
+        prop.value = values.join(",");
+        match target {
+            chrono_tz::UTC => prop.params.remove("TZID"),
+            _ => prop
+                .params
+                .replace_param("TZID".to_owned(), target.name().to_owned()),
+        }
+    }
+    Ok(())
+}
+
+/// The properties as they are compared by meaning: zoned DATE-TIME values converted to UTC
+/// as far as [`properties_to_utc`] does, and resolved TZIDs replaced by the name of their
+/// timezone so differently named VTIMEZONEs for the same timezone compare equal.
+/// Values that can't be converted are kept as they are.
+pub(crate) fn normalized(properties: &[ContentLine], timezones: &Timezones) -> Vec<ContentLine> {
+    let mut normalized = properties.to_vec();
+    if properties_to_utc(&mut normalized, timezones).is_err() {
+        normalized = properties.to_vec();
+    }
+    for prop in &mut normalized {
+        if let Some(Some(tz)) = prop.params.get_tzid().and_then(|tzid| timezones.get(tzid)) {
+            let name = tz.to_string();
+            prop.params.replace_param("TZID".to_owned(), name);
+        }
+    }
+    normalized
+}
+
+/// The normalized RECURRENCE-ID value, so overrides in different timezones match
+pub(crate) fn recurrence_id(properties: &[ContentLine], timezones: &Timezones) -> Option<String> {
+    let prop = properties
+        .iter()
+        .find(|prop| prop.name == "RECURRENCE-ID")?;
+    normalized(std::slice::from_ref(prop), timezones)
+        .pop()
+        .map(|prop| prop.value)
+}
+
+/// The SEQUENCE of a component, 0 if it's missing or invalid
+pub(crate) fn sequence(properties: &[ContentLine]) -> i64 {
+    properties
+        .iter()
+        .find(|prop| prop.name == "SEQUENCE")
+        .and_then(|sequence| sequence.value.parse().ok())
+        .unwrap_or_default()
+}
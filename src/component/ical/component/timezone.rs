@@ -1,20 +1,57 @@
 use crate::{
     ContentLineParser,
     component::{Component, ComponentMut},
-    parser::{ContentLine, ICalProperty, ParserError, ParserOptions},
-    property::{GetProperty, IcalDTSTARTProperty, IcalRRULEProperty, IcalTZRDATEProperty},
+    parser::{ContentLine, ICalProperty, ParserError, ParserOptions, quirks::repair_local_until},
+    property::{
+        GetProperty, IcalDTSTARTProperty, IcalTZOFFSETFROMProperty, IcalTZOFFSETTOProperty,
+        IcalTZRDATEProperty,
+    },
     types::Tz,
 };
-use chrono::{DateTime, Utc};
+#[cfg(feature = "recurrence")]
+use crate::{
+    property::{IcalRRULEProperty, get_rrules},
+    rrule::RRule,
+};
+use chrono::{DateTime, FixedOffset, Utc};
 #[cfg(not(tarpaulin_include))]
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock, RwLock};
 
 // Memoise generated vtimezones
 static TIMEZONES_CACHE: OnceLock<HashMap<String, OnceLock<IcalTimeZone>>> = OnceLock::new();
 
+/// Memoised resolutions of VTIMEZONE components to IANA timezones, see [`ParserOptions::tzid_cache`].
+///
+/// Clones share the same cache. Only timezones that resolve are cached, up to a fixed number.
 #[derive(Debug, Clone, Default)]
+pub struct TzidCache(Arc<RwLock<HashMap<String, ResolvedTzids>>>);
+
+/// Resolutions of a TZID by the X-LIC-LOCATION of its VTIMEZONE
+type ResolvedTzids = Vec<(Option<String>, ResolvedTzid)>;
+
+#[derive(Debug, Clone, Copy)]
+struct ResolvedTzid {
+    iana: Option<chrono_tz::Tz>,
+    proprietary: Option<chrono_tz::Tz>,
+}
+
+/// The maximum number of cached resolutions, TZIDs and X-LIC-LOCATIONs come from the input
+const TZID_CACHE_CAPACITY: usize = 1024;
+
+// Required by ParserOptions: Options only differing in their cache parse everything the same
+// way, so all caches are considered equal
+impl PartialEq for TzidCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for TzidCache {}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalTimeZone<const VERIFIED: bool = true> {
     pub properties: Vec<ContentLine>,
     pub transitions: Vec<IcalTimeZoneTransition>,
@@ -57,6 +94,18 @@ impl IcalTimeZone {
         }))
     }
 
+    /// The UTC offset if all transitions switch to the same offset
+    pub fn fixed_offset(&self) -> Option<FixedOffset> {
+        let mut offsets = self
+            .transitions
+            .iter()
+            .map(|transition| transition.offset_to.0);
+        let offset = offsets.next()?;
+        offsets
+            .all(|other| other == offset)
+            .then(|| offset.as_fixed_offset())
+    }
+
     pub fn truncate(self, start: DateTime<Utc>) -> Self {
         Self {
             properties: self.properties,
@@ -72,23 +121,143 @@ impl IcalTimeZone {
 #[cfg(feature = "chrono-tz")]
 impl From<&IcalTimeZone> for Option<chrono_tz::Tz> {
     fn from(value: &IcalTimeZone) -> Self {
+        value.resolve(&HashMap::new())
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl TzidCache {
+    fn get(&self, tzid: &str, lic_location: Option<&str>) -> Option<ResolvedTzid> {
+        let cache = self.0.read().ok()?;
+        cache
+            .get(tzid)?
+            .iter()
+            .find(|(location, _)| location.as_deref() == lic_location)
+            .map(|(_, resolved)| *resolved)
+    }
+
+    fn insert(&self, tzid: &str, lic_location: Option<&str>, resolved: ResolvedTzid) {
+        // Any input could add unresolvable TZIDs
+        if resolved.iana.is_none() && resolved.proprietary.is_none() {
+            return;
+        }
+        if let Ok(mut cache) = self.0.write()
+            && cache.values().map(Vec::len).sum::<usize>() < TZID_CACHE_CAPACITY
+        {
+            cache
+                .entry(tzid.to_owned())
+                .or_default()
+                .push((lic_location.map(ToOwned::to_owned), resolved));
+        }
+    }
+
+    /// Like [`IcalTimeZone::resolve`] but every distinct VTIMEZONE is only resolved once
+    pub fn resolve(
+        &self,
+        timezone: &IcalTimeZone,
+        aliases: &HashMap<String, chrono_tz::Tz>,
+    ) -> Option<chrono_tz::Tz> {
+        use crate::types::get_proprietary_tzid;
+
+        let tzid = timezone.get_tzid();
+        let lic_location = timezone.get_lic_location();
+        let resolved = self.get(tzid, lic_location).unwrap_or_else(|| {
+            let iana = timezone.resolve_iana();
+            let resolved = ResolvedTzid {
+                iana,
+                proprietary: iana.is_none().then(|| get_proprietary_tzid(tzid)).flatten(),
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                tzid,
+                lic_location,
+                iana = ?resolved.iana,
+                proprietary = ?resolved.proprietary,
+                "resolved VTIMEZONE"
+            );
+            self.insert(tzid, lic_location, resolved);
+            resolved
+        });
+        resolved
+            .iana
+            .or_else(|| aliases.get(tzid).copied())
+            .or(resolved.proprietary)
+    }
+
+    /// Like [`IcalTimeZone::resolve_or_fixed`] but every distinct VTIMEZONE is only resolved once
+    pub fn resolve_or_fixed(
+        &self,
+        timezone: &IcalTimeZone,
+        aliases: &HashMap<String, chrono_tz::Tz>,
+    ) -> Option<Tz> {
+        self.resolve(timezone, aliases)
+            .map(Tz::Olson)
+            .or_else(|| timezone.fixed_offset().map(Tz::Fixed))
+    }
+
+    /// The IANA timezone named `tzid`, for TZIDs without VTIMEZONE (RFC 7809)
+    pub fn resolve_tzid(&self, tzid: &str) -> Option<chrono_tz::Tz> {
+        use std::str::FromStr;
+
+        if let Some(resolved) = self.get(tzid, None) {
+            return resolved.iana;
+        }
+        let iana = chrono_tz::Tz::from_str(tzid).ok();
+        // Unresolved TZIDs aren't cached since a VTIMEZONE with that TZID also needs the
+        // proprietary lookup
+        if iana.is_some() {
+            self.insert(
+                tzid,
+                None,
+                ResolvedTzid {
+                    iana,
+                    proprietary: None,
+                },
+            );
+        }
+        iana
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl IcalTimeZone {
+    /// Finds the matching timezone from the IANA database.
+    /// The custom `aliases` (see [`ParserOptions::tzid_aliases`]) take precedence over the
+    /// built-in map of proprietary TZIDs.
+    pub fn resolve(&self, aliases: &HashMap<String, chrono_tz::Tz>) -> Option<chrono_tz::Tz> {
         use crate::types::get_proprietary_tzid;
+
+        let tzid = self.get_tzid();
+        if let Some(tz) = self.resolve_iana() {
+            return Some(tz);
+        }
+        if let Some(tz) = aliases.get(tzid) {
+            return Some(*tz);
+        }
+        // Try map of proprietary timezone IDs (mostly for Microsoft products)
+        get_proprietary_tzid(tzid)
+    }
+
+    fn resolve_iana(&self) -> Option<chrono_tz::Tz> {
         use std::str::FromStr;
 
         // Try X-LIC-LOCATION
-        if let Some(loc) = value.get_lic_location()
+        if let Some(loc) = self.get_lic_location()
             && let Ok(tz) = chrono_tz::Tz::from_str(loc)
         {
             return Some(tz);
         };
 
         // Try using TZID in Olson DB
-        let tzid = value.get_tzid();
-        if let Ok(tz) = chrono_tz::Tz::from_str(tzid) {
-            return Some(tz);
-        }
-        // Try map of proprietary timezone IDs (mostly for Microsoft products)
-        get_proprietary_tzid(tzid)
+        chrono_tz::Tz::from_str(self.get_tzid()).ok()
+    }
+
+    /// Like [`IcalTimeZone::resolve`] but falls back to a fixed offset for timezones
+    /// without changes in their UTC offset
+    pub fn resolve_or_fixed(&self, aliases: &HashMap<String, chrono_tz::Tz>) -> Option<Tz> {
+        self.resolve(aliases)
+            .map(Tz::Olson)
+            .or_else(|| self.fixed_offset().map(Tz::Fixed))
     }
 }
 
@@ -143,7 +312,7 @@ impl ComponentMut for IcalTimeZone<false> {
     fn build(
         self,
         _options: &ParserOptions,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalTimeZone, ParserError> {
         if self.get_property("TZID").is_none() {
             return Err(ParserError::MissingProperty("TZID"));
@@ -166,6 +335,7 @@ impl ComponentMut for IcalTimeZone<false> {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 #[cfg_attr(
     feature = "rkyv",
     derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
@@ -177,13 +347,20 @@ pub enum IcalTimeZoneTransitionType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalTimeZoneTransition {
     pub transition: IcalTimeZoneTransitionType,
     pub properties: Vec<ContentLine>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstart: IcalDTSTARTProperty,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    pub offset_from: IcalTZOFFSETFROMProperty,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    pub offset_to: IcalTZOFFSETTOProperty,
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalTimeZoneTransitionBuilder {
     pub transition: IcalTimeZoneTransitionType,
     pub properties: Vec<ContentLine>,
@@ -259,15 +436,26 @@ impl ComponentMut for IcalTimeZoneTransitionBuilder {
     }
 
     fn build(
-        self,
-        _options: &ParserOptions,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        mut self,
+        options: &ParserOptions,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalTimeZoneTransition, ParserError> {
+        if options.repair_local_until {
+            // UNTIL MUST be UTC inside of VTIMEZONE
+            repair_local_until(&mut self.properties, Tz::UTC)?;
+        }
+        let props = self.property_index();
         // Make sure that they are valid
-        self.safe_get_all::<IcalRRULEProperty>(None)?;
-        self.safe_get_all::<IcalTZRDATEProperty>(None)?;
+        #[cfg(feature = "recurrence")]
+        get_rrules::<IcalRRULEProperty>(&props, options)?;
+        props.safe_get_all::<IcalTZRDATEProperty>(None)?;
+        let dtstart = props.safe_get_required(None)?;
+        let offset_from = props.safe_get_required(None)?;
+        let offset_to = props.safe_get_required(None)?;
         Ok(IcalTimeZoneTransition {
-            dtstart: self.safe_get_required(None)?,
+            dtstart,
+            offset_from,
+            offset_to,
             transition: self.transition,
             properties: self.properties,
         })
@@ -284,17 +472,20 @@ impl IcalTimeZoneTransition {
         for property in &self.properties {
             match property.name.as_str() {
                 "RRULE" => {
-                    let rrule = IcalRRULEProperty::parse_prop(property, None)
-                        .expect("validated in build")
-                        .0;
-                    // In case an error occurs we simply don't try to remove the RRULE.
-                    // One example of an error is VTIMEZONEs used by Thunderbird which don't output
-                    // UNTIL in UTC.
-                    if let Ok(rrule) = rrule.validate_inside_vtimezone(dtstart)
-                        && let Some(until) = rrule.get_until()
-                        && until < &start
+                    // Without the recurrence feature RRULEs are always kept
+                    #[cfg(feature = "recurrence")]
                     {
-                        continue;
+                        let rrule =
+                            RRule::from_str_lenient(&property.value).expect("validated in build");
+                        // In case an error occurs we simply don't try to remove the RRULE.
+                        // One example of an error is VTIMEZONEs used by Thunderbird which don't
+                        // output UNTIL in UTC.
+                        if let Ok(rrule) = rrule.validate_inside_vtimezone(dtstart)
+                            && let Some(until) = rrule.get_until()
+                            && until < &start
+                        {
+                            continue;
+                        }
                     }
                     rrules.push(property)
                 }
@@ -340,7 +531,15 @@ mod tests {
     use insta::assert_snapshot;
     use rstest::rstest;
 
-    use crate::{component::IcalTimeZone, generator::Emitter};
+    use super::TZID_CACHE_CAPACITY;
+    use crate::{
+        IcalObjectParser,
+        component::{CalendarInnerData, IcalTimeZone, TzidCache},
+        generator::Emitter,
+        parser::{ComponentParser, ContentLine},
+        types::Tz,
+    };
+    use std::collections::HashMap;
 
     #[rstest]
     #[case(0, "Europe/Bratislava")]
@@ -370,4 +569,129 @@ mod tests {
             assert!(tz.generate().contains(tzid));
         }
     }
+
+    #[test]
+    fn test_fixed_offset() {
+        let input = "BEGIN:VTIMEZONE\r
+TZID:Custom/Kolkata\r
+BEGIN:STANDARD\r
+DTSTART:19700101T000000\r
+TZOFFSETFROM:+0530\r
+TZOFFSETTO:+0530\r
+END:STANDARD\r
+END:VTIMEZONE\r
+";
+        let tz = ComponentParser::<IcalTimeZone, _>::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(tz.resolve(&HashMap::new()), None);
+        assert_eq!(tz.resolve_or_fixed(&HashMap::new()), Tz::fixed(19800));
+
+        let berlin = IcalTimeZone::from_tzid("Europe/Berlin").unwrap();
+        assert_eq!(berlin.fixed_offset(), None);
+        assert_eq!(
+            berlin.resolve_or_fixed(&HashMap::new()),
+            Some(Tz::Olson(chrono_tz::Europe::Berlin))
+        );
+    }
+
+    #[test]
+    fn test_parse_fixed_offset() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Custom/Kolkata\r
+BEGIN:STANDARD\r
+DTSTART:19700101T000000\r
+TZOFFSETFROM:+0530\r
+TZOFFSETTO:+0530\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+DTSTAMP:20240101T000000Z\r
+DTSTART;TZID=Custom/Kolkata:20240301T093000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(object.get_timezones()["Custom/Kolkata"], Tz::fixed(19800));
+        let CalendarInnerData::Event(event, _) = object.get_inner() else {
+            unreachable!()
+        };
+        assert_eq!(event.dtstart.0.timezone(), Tz::fixed(19800).unwrap());
+        assert_eq!(
+            ContentLine::from(event.dtstart.clone()).generate(),
+            "DTSTART:20240301T040000Z\r\n"
+        );
+    }
+
+    #[test]
+    fn test_tzid_cache() {
+        let input = "BEGIN:VTIMEZONE\r
+TZID:W. Europe Standard Time\r
+BEGIN:STANDARD\r
+DTSTART:19700101T000000\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+";
+        let windows = ComponentParser::<IcalTimeZone, _>::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let berlin = IcalTimeZone::from_tzid("Europe/Berlin").unwrap();
+        let cache = TzidCache::default();
+        let aliases = [(
+            "W. Europe Standard Time".to_owned(),
+            chrono_tz::Europe::Vienna,
+        )]
+        .into();
+
+        for _ in 0..2 {
+            assert_eq!(
+                cache.resolve(&windows, &HashMap::new()),
+                windows.resolve(&HashMap::new())
+            );
+            // Aliases still take precedence over the cached proprietary TZID
+            assert_eq!(
+                cache.resolve(&windows, &aliases),
+                Some(chrono_tz::Europe::Vienna)
+            );
+            assert_eq!(
+                cache.resolve(berlin, &HashMap::new()),
+                Some(chrono_tz::Europe::Berlin)
+            );
+            assert_eq!(
+                cache.resolve_tzid("Europe/Berlin"),
+                Some(chrono_tz::Europe::Berlin)
+            );
+            assert_eq!(cache.resolve_tzid("Custom/Kolkata"), None);
+        }
+        // Clones share the cache
+        assert_eq!(cache.clone().0.read().unwrap().len(), 2);
+
+        // Unresolved TZIDs aren't cached and the cache is bounded
+        let custom = input.replace("W. Europe Standard Time", "Custom Zone");
+        let custom = ComponentParser::<IcalTimeZone, _>::from_slice(custom.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(cache.resolve(&custom, &HashMap::new()), None);
+        assert_eq!(cache.0.read().unwrap().len(), 2);
+        for index in 0..TZID_CACHE_CAPACITY {
+            let location = input.replace(
+                "TZID:W. Europe Standard Time\r\n",
+                &format!("TZID:W. Europe Standard Time\r\nX-LIC-LOCATION:Custom/{index}\r\n"),
+            );
+            let timezone = ComponentParser::<IcalTimeZone, _>::from_slice(location.as_bytes())
+                .expect_one()
+                .unwrap();
+            assert!(cache.resolve(&timezone, &HashMap::new()).is_some());
+        }
+        let cached: usize = cache.0.read().unwrap().values().map(Vec::len).sum();
+        assert_eq!(cached, TZID_CACHE_CAPACITY);
+    }
 }
@@ -1,18 +1,25 @@
-use chrono::DateTime;
+use chrono::Duration;
+#[cfg(feature = "recurrence")]
+use chrono::{DateTime, Utc};
 
-use crate::rrule::RRule;
+#[cfg(feature = "recurrence")]
+use crate::{
+    component::expand_recurring,
+    property::RecurIdRange,
+    rrule::RRuleSet,
+    types::{CalDate, CalDateTime, Value},
+};
 
-use crate::types::Tz;
 use crate::{
     ContentLineParser,
-    component::{Component, ComponentMut, IcalAlarm, IcalAlarmBuilder},
-    parser::{ContentLine, ParserError, ParserOptions},
+    component::{Component, ComponentMut, IcalAlarm, IcalAlarmBuilder, RecurrenceData, get_rules},
+    parser::{ContentLine, ICalProperty, ParserError, ParserOptions, quirks::repair_local_until},
     property::{
         GetProperty, IcalDTSTAMPProperty, IcalDTSTARTProperty, IcalDUEProperty,
-        IcalDURATIONProperty, IcalEXDATEProperty, IcalEXRULEProperty, IcalRDATEProperty,
-        IcalRECURIDProperty, IcalRRULEProperty, IcalUIDProperty,
+        IcalDURATIONProperty, IcalEXDATEProperty, IcalRDATEProperty, IcalRECURIDProperty,
+        IcalUIDProperty,
     },
-    types::CalDateOrDateTime,
+    types::{CalDateOrDateTime, Tz},
 };
 use std::{
     borrow::Cow,
@@ -20,22 +27,28 @@ use std::{
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct IcalTodo {
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     uid: String,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstart: Option<IcalDTSTARTProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub due: Option<IcalDUEProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub duration: Option<IcalDURATIONProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub dtstamp: IcalDTSTAMPProperty,
     pub properties: Vec<ContentLine>,
     pub alarms: Vec<IcalAlarm>,
-    rdates: Vec<IcalRDATEProperty>,
-    rrules: Vec<RRule>,
-    exdates: Vec<IcalEXDATEProperty>,
-    exrules: Vec<RRule>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
+    pub(crate) recurrence: Option<Box<RecurrenceData>>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub(crate) recurid: Option<IcalRECURIDProperty>,
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct IcalTodoBuilder {
     pub properties: Vec<ContentLine>,
     pub alarms: Vec<IcalAlarmBuilder>,
@@ -47,10 +60,7 @@ impl IcalTodo {
     }
 
     pub fn has_rruleset(&self) -> bool {
-        !self.rrules.is_empty()
-            || !self.rdates.is_empty()
-            || !self.exrules.is_empty()
-            || !self.exdates.is_empty()
+        self.recurrence.is_some()
     }
 
     pub fn get_alarms(&self) -> &[IcalAlarm] {
@@ -74,6 +84,169 @@ impl IcalTodo {
 
         None
     }
+
+    pub fn get_duration(&self) -> Option<Duration> {
+        if let Some(IcalDTSTARTProperty(dtstart, _)) = &self.dtstart
+            && let Some(IcalDUEProperty(due, _)) = &self.due
+        {
+            return Some(due.clone() - dtstart);
+        }
+        self.duration
+            .as_ref()
+            .map(|IcalDURATIONProperty(duration, _)| duration.to_owned())
+    }
+
+    #[cfg(feature = "recurrence")]
+    pub fn get_rruleset(&self) -> Option<RRuleSet> {
+        // Without DTSTART there is nothing to apply the recurrence to
+        let dtstart = self.dtstart.as_ref()?;
+        let recurrence = self.recurrence.as_ref()?;
+        Some(recurrence.rruleset(dtstart.0.clone().into()))
+    }
+
+    fn replace_or_push_property<T: ICalProperty + Into<ContentLine>>(&mut self, prop: T) {
+        let position = self.properties.iter().position(|prop| T::NAME == prop.name);
+        if let Some(pos) = position {
+            self.properties.retain(|line| line.name != T::NAME);
+            self.properties.insert(pos, prop.into());
+        } else {
+            self.properties.push(prop.into());
+        }
+    }
+
+    pub fn to_utc_or_local(self) -> Self {
+        let dtstart = self.dtstart.map(|dt| dt.utc_or_local());
+        let due = self.due.map(|dt| dt.utc_or_local());
+        let dtstamp = self.dtstamp.utc_or_local();
+        let recurid = self.recurid.map(|dt| dt.utc_or_local());
+        let recurrence = self
+            .recurrence
+            .map(|recurrence| Box::new(recurrence.utc_or_local()));
+
+        let mut todo = Self {
+            dtstart: dtstart.clone(),
+            due: due.clone(),
+            dtstamp: dtstamp.clone(),
+            recurid: recurid.clone(),
+            recurrence,
+            ..self
+        };
+        if let Some(dtstart) = dtstart {
+            todo.replace_or_push_property(dtstart);
+        }
+        if let Some(due) = due {
+            todo.replace_or_push_property(due);
+        }
+        todo.replace_or_push_property(dtstamp);
+        if let Some(recurid) = recurid {
+            todo.replace_or_push_property(recurid);
+        }
+        todo
+    }
+
+    /// Expands the recurrence set of this task into single instances.
+    ///
+    /// The recurrence is applied to DTSTART as per RFC 5545 and every instance keeps the
+    /// distance between DTSTART and DUE of the task it was generated from.
+    #[cfg(feature = "recurrence")]
+    pub fn expand_recurrence(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        overrides: &[Self],
+    ) -> Vec<Self> {
+        expand_recurring(self, start, end, overrides)
+    }
+
+    /// Builds the instances starting at `dates` from a recurring task.
+    #[cfg(feature = "recurrence")]
+    pub(crate) fn expand_instances(
+        &self,
+        dates: impl IntoIterator<Item = DateTime<Tz>>,
+        overrides: &[Self],
+    ) -> Vec<Self> {
+        let is_date = self
+            .dtstart
+            .as_ref()
+            .is_some_and(|IcalDTSTARTProperty(dtstart, _)| dtstart.is_date());
+        let mut todos = vec![];
+
+        let mut template = self;
+        'recurrence: for instance in dates {
+            // Is UTC or local
+            let recurid = if is_date {
+                CalDateOrDateTime::Date(CalDate(instance.to_utc().date_naive(), Tz::utc()))
+            } else {
+                CalDateOrDateTime::DateTime(CalDateTime::from(instance)).utc_or_local()
+            };
+
+            for over in overrides {
+                let IcalRECURIDProperty(override_recurid, _, range) =
+                    over.recurid.as_ref().unwrap();
+                if override_recurid != &recurid {
+                    continue;
+                }
+                // RECURRENCE IDs match
+                todos.push(over.clone().to_utc_or_local());
+
+                if range == &RecurIdRange::ThisAndFuture {
+                    // Set this override as the base task for the future
+                    template = over;
+                }
+                continue 'recurrence;
+            }
+
+            // We were not overriden, construct recurrence instance:
+            // Remove recurrence props
+            let properties = template
+                .properties
+                .iter()
+                .filter(|prop| {
+                    !["RRULE", "RDATE", "EXRULE", "EXDATE"].contains(&prop.name.as_str())
+                })
+                .cloned()
+                .collect();
+
+            let dtstart = IcalDTSTARTProperty(recurid.clone(), Default::default());
+            // DUE is derived from the distance to DTSTART, DURATION can be kept as is
+            let due = template
+                .due
+                .as_ref()
+                .and(template.get_duration())
+                .and_then(|duration| recurid.clone().shift(duration).ok())
+                .map(|due| IcalDUEProperty(due, Default::default()));
+
+            let mut todo = Self {
+                uid: template.uid.clone(),
+                dtstamp: template.dtstamp.clone(),
+                dtstart: Some(dtstart.clone()),
+                due: due.clone(),
+                duration: template.duration.clone(),
+                recurid: Some(IcalRECURIDProperty(
+                    recurid.clone(),
+                    Default::default(),
+                    RecurIdRange::This,
+                )),
+                alarms: vec![],
+                recurrence: None,
+                properties,
+            };
+            todo.replace_or_push_property(dtstart);
+            if let Some(due) = due {
+                todo.replace_or_push_property(due);
+            }
+            todo.replace_or_push_property(IcalRECURIDProperty(
+                recurid,
+                // This is fine since this is UTC anyway
+                Default::default(),
+                RecurIdRange::This,
+            ));
+
+            todos.push(todo);
+        }
+
+        todos
+    }
 }
 
 impl Component for IcalTodo {
@@ -136,25 +309,26 @@ impl ComponentMut for IcalTodoBuilder {
     }
 
     fn build(
-        self,
+        mut self,
         options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<IcalTodo, ParserError> {
+        let props = self.property_index();
         // REQUIRED, but ONLY ONCE
-        let IcalUIDProperty(uid, _) = self.safe_get_required(timezones)?;
-        let dtstamp = self.safe_get_required(timezones)?;
+        let IcalUIDProperty(uid, _) = props.safe_get_required(timezones)?;
+        let dtstamp = props.safe_get_required(timezones)?;
 
         // OPTIONAL, but ONLY ONCE: class / completed / created / description / dtstart / geo / last-mod / location / organizer / percent / priority / recurid / seq / status / summary / url / rrule
-        let dtstart = self.safe_get_optional::<IcalDTSTARTProperty>(timezones)?;
-        let recurid = self.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
+        let dtstart = props.safe_get_optional::<IcalDTSTARTProperty>(timezones)?;
+        let recurid = props.safe_get_optional::<IcalRECURIDProperty>(timezones)?;
         if let Some(IcalDTSTARTProperty(dtstart, _)) = &dtstart
             && let Some(recurid) = &recurid
         {
             recurid.validate_dtstart(dtstart)?;
         }
         // OPTIONAL, but MUTUALLY EXCLUSIVE
-        let duration = self.safe_get_optional::<IcalDURATIONProperty>(timezones)?;
-        let due = self.safe_get_optional::<IcalDUEProperty>(timezones)?;
+        let duration = props.safe_get_optional::<IcalDURATIONProperty>(timezones)?;
+        let due = props.safe_get_optional::<IcalDUEProperty>(timezones)?;
         if duration.is_some() && due.is_some() {
             return Err(ParserError::PropertyConflict(
                 "both DUE and DURATION are defined",
@@ -162,21 +336,16 @@ impl ComponentMut for IcalTodoBuilder {
         }
 
         // OPTIONAL, MULTIPLE ALLOWED: attach / attendee / categories / comment / contact / exdate / rstatus / related / resources / rdate / x-prop / iana-prop
-        let rdates = self.safe_get_all::<IcalRDATEProperty>(timezones)?;
-        let exdates = self.safe_get_all::<IcalEXDATEProperty>(timezones)?;
+        let rdates = props.safe_get_all::<IcalRDATEProperty>(timezones)?;
+        let exdates = props.safe_get_all::<IcalEXDATEProperty>(timezones)?;
+        if options.repair_local_until
+            && let Some(IcalDTSTARTProperty(dtstart, _)) = &dtstart
+        {
+            repair_local_until(&mut self.properties, dtstart.timezone())?;
+        }
+        let props = self.property_index();
         let (rrules, exrules) = if let Some(dtstart) = dtstart.as_ref() {
-            let rrule_dtstart: DateTime<Tz> = dtstart.0.clone().into();
-            let rrules = self
-                .safe_get_all::<IcalRRULEProperty>(timezones)?
-                .into_iter()
-                .map(|rrule| rrule.0.validate(rrule_dtstart))
-                .collect::<Result<Vec<_>, _>>()?;
-            let exrules = self
-                .safe_get_all::<IcalEXRULEProperty>(timezones)?
-                .into_iter()
-                .map(|rrule| rrule.0.validate(rrule_dtstart))
-                .collect::<Result<Vec<_>, _>>()?;
-            (rrules, exrules)
+            get_rules(&props, options, dtstart.0.clone().into())?
         } else {
             (vec![], vec![])
         };
@@ -187,10 +356,7 @@ impl ComponentMut for IcalTodoBuilder {
             dtstart,
             due,
             duration,
-            rdates,
-            rrules,
-            exdates,
-            exrules,
+            recurrence: RecurrenceData::new(rdates, rrules, exdates, exrules),
             recurid,
             properties: self.properties,
             alarms: self
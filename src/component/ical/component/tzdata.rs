@@ -0,0 +1,215 @@
+use crate::{
+    component::{Component, IcalCalendar, IcalTimeZone, IcalTimeZoneTransition},
+    parser::ICalProperty,
+    property::{IcalRECURIDProperty, IcalTZRDATEProperty},
+    rrule::{RRule, RRuleSet},
+    types::{CalDateOrDateTime, Tz},
+};
+use chrono::{FixedOffset, NaiveDateTime, TimeDelta};
+
+/// An event that takes place at a different time after [`IcalCalendar::refresh_timezones`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimezoneShift {
+    pub uid: String,
+    /// The RECURRENCE-ID if the event is an override
+    pub recurrence_id: Option<CalDateOrDateTime>,
+    pub tzid: String,
+    /// The wall-clock start of the first instance that moves
+    pub start: NaiveDateTime,
+    /// The UTC offset of `start` in the embedded VTIMEZONE
+    pub old_offset: FixedOffset,
+    /// The UTC offset of `start` in the current IANA timezone
+    pub new_offset: FixedOffset,
+}
+
+impl IcalTimeZoneTransition {
+    /// The latest onset of this observance at or before the wall-clock time `local`
+    fn latest_onset(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        // Onsets are wall-clock times, they are expanded as if they were UTC
+        let dtstart = self.dtstart.0.utc().with_timezone(&Tz::UTC);
+        if dtstart.naive_utc() > local {
+            return None;
+        }
+        let rrules = self
+            .get_named_properties("RRULE")
+            .filter_map(|prop| {
+                RRule::from_str_lenient(&prop.value)
+                    .ok()?
+                    .validate(dtstart)
+                    .ok()
+            })
+            .collect();
+        let rdates = self
+            .get_named_properties("RDATE")
+            .filter_map(|prop| IcalTZRDATEProperty::parse_prop(prop, None).ok())
+            .flat_map(|IcalTZRDATEProperty(rdates, _)| rdates)
+            .map(|rdate| rdate.utc().with_timezone(&Tz::UTC))
+            .collect();
+        let recurrences = RRuleSet::new(dtstart)
+            .set_rrules(rrules)
+            .set_rdates(rdates)
+            .occurrences_before(
+                local.and_utc().with_timezone(&Tz::UTC) + TimeDelta::seconds(1),
+                1,
+            );
+        recurrences
+            .dates
+            .first()
+            .map(|onset| onset.naive_utc())
+            .max(Some(dtstart.naive_utc()))
+    }
+}
+
+impl IcalTimeZone {
+    /// The UTC offset this VTIMEZONE defines for the wall-clock time `local`,
+    /// `None` if `local` is before its first observance
+    pub fn offset_at(&self, local: NaiveDateTime) -> Option<FixedOffset> {
+        self.transitions
+            .iter()
+            .filter_map(|transition| Some((transition.latest_onset(local)?, transition)))
+            .max_by_key(|(onset, _)| *onset)
+            .map(|(_, transition)| transition.offset_to.0.as_fixed_offset())
+    }
+}
+
+impl IcalCalendar {
+    /// Replaces the embedded VTIMEZONEs with the current definitions of the IANA timezones
+    /// their TZIDs resolve to, VTIMEZONEs that don't resolve are kept.
+    ///
+    /// Clients compute the times of events from the embedded VTIMEZONE, so a definition
+    /// from an outdated tzdata moves events once it's refreshed. These events are returned,
+    /// recurring events with the first instance that moves within the first 2048 instances.
+    pub fn refresh_timezones(&mut self) -> Vec<TimezoneShift> {
+        let mut refreshed = vec![];
+        for (tzid, vtimezone) in &mut self.vtimezones {
+            let Some(Some(Tz::Olson(tz))) = self.timezones.get(tzid) else {
+                continue;
+            };
+            let Some(current) = IcalTimeZone::from_tzid(tz.name()) else {
+                continue;
+            };
+            let mut current = current.clone();
+            // Events still reference the old TZID
+            for prop in &mut current.properties {
+                if prop.name == "TZID" {
+                    prop.value = tzid.to_owned();
+                }
+            }
+            let old = std::mem::replace(vtimezone, current.clone());
+            refreshed.push((tzid.as_str(), old, current));
+        }
+
+        let mut shifts = vec![];
+        for event in &self.events {
+            let Some(tzid) = event.dtstart.1.get_tzid() else {
+                continue;
+            };
+            let Some((_, old, new)) = refreshed.iter().find(|(refreshed, ..)| *refreshed == tzid)
+            else {
+                continue;
+            };
+            let instances = if event.has_rruleset() {
+                event.expand_recurrence(None, None, &[])
+            } else {
+                vec![event.clone()]
+            };
+            // Instances are expanded in UTC
+            let timezone = event.dtstart.0.timezone();
+            let shift = instances.iter().find_map(|instance| {
+                if instance.dtstart.0.is_date() {
+                    return None;
+                }
+                let start = instance
+                    .dtstart
+                    .0
+                    .utc()
+                    .with_timezone(&timezone)
+                    .naive_local();
+                let (old_offset, new_offset) = (old.offset_at(start)?, new.offset_at(start)?);
+                (old_offset != new_offset).then_some((start, old_offset, new_offset))
+            });
+            if let Some((start, old_offset, new_offset)) = shift {
+                shifts.push(TimezoneShift {
+                    uid: event.get_uid().to_owned(),
+                    recurrence_id: event
+                        .recurid
+                        .as_ref()
+                        .map(|IcalRECURIDProperty(recurid, ..)| recurid.clone()),
+                    tzid: tzid.to_owned(),
+                    start,
+                    old_offset,
+                    new_offset,
+                });
+            }
+        }
+        shifts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IcalParser, component::IcalTimeZone, generator::Emitter};
+    use chrono::{FixedOffset, NaiveDate};
+
+    // A Europe/Berlin VTIMEZONE as if daylight saving time had been abolished
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19700101T000000\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:winter@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260105T090000\r
+DURATION:PT1H\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weekly@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;TZID=Europe/Berlin:20260302T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=10\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_offset_at() {
+        let berlin = IcalTimeZone::from_tzid("Europe/Berlin").unwrap();
+        let at = |month, day| {
+            NaiveDate::from_ymd_opt(2026, month, day)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        };
+        let hours = |hours| FixedOffset::east_opt(hours * 3600);
+        assert_eq!(berlin.offset_at(at(1, 5)), hours(1));
+        assert_eq!(berlin.offset_at(at(7, 1)), hours(2));
+        assert_eq!(berlin.offset_at(at(10, 26)), hours(1));
+    }
+
+    #[test]
+    fn test_refresh_timezones() {
+        let mut calendar = IcalParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap();
+        let shifts = calendar.refresh_timezones();
+        assert_eq!(shifts.len(), 1);
+        assert_eq!(shifts[0].uid, "weekly@example.com");
+        assert_eq!(shifts[0].tzid, "Europe/Berlin");
+        // The first instance after the switch to daylight saving time
+        assert_eq!(shifts[0].start.to_string(), "2026-03-30 09:00:00");
+        assert_eq!(shifts[0].old_offset, FixedOffset::east_opt(3600).unwrap());
+        assert_eq!(shifts[0].new_offset, FixedOffset::east_opt(7200).unwrap());
+
+        let vtimezone = calendar.vtimezones["Europe/Berlin"].generate();
+        assert!(vtimezone.contains("BEGIN:DAYLIGHT"));
+        assert!(calendar.refresh_timezones().is_empty());
+    }
+}
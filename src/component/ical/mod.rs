@@ -1,10 +1,10 @@
 //! Parse an ICAL calendar.
 //!
-//! Wrap the result of the `PropertyParser` into components.
+//! Wrap the result of the `ContentLineParser` into components.
 //!
 //! Each component contains properties (ie: `Property`) or sub-components.
 //!
-//! * The `VcardParser` return `IcalCalendar` objects.
+//! * The `IcalParser` return `IcalCalendar` objects.
 //!
 //! # Examples
 //!
@@ -0,0 +1,194 @@
+//! Conversions from the components of the `ical` crate this crate descends from,
+//! so parsers can be switched incrementally and their results compared.
+use crate::{
+    component::{
+        ComponentMut, IcalAlarmBuilder, IcalCalendar, IcalCalendarBuilder, IcalEventBuilder,
+        IcalFreeBusyBuilder, IcalJournalBuilder, IcalTimeZone, IcalTimeZoneTransitionBuilder,
+        IcalTimeZoneTransitionType, IcalTodoBuilder,
+    },
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use ::ical::{parser::ical::component as legacy, property::Property};
+
+impl From<Property> for ContentLine {
+    fn from(property: Property) -> Self {
+        Self {
+            name: property.name,
+            params: property.params.unwrap_or_default().into(),
+            value: property.value.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&ContentLine> for Property {
+    fn from(line: &ContentLine) -> Self {
+        let params: Vec<(String, Vec<String>)> = line
+            .params
+            .iter()
+            .map(|(name, values)| (name.to_owned(), values.to_owned()))
+            .collect();
+        Self {
+            name: line.name.to_owned(),
+            params: (!params.is_empty()).then_some(params),
+            value: Some(line.value.to_owned()),
+        }
+    }
+}
+
+fn lines(properties: Vec<Property>) -> Vec<ContentLine> {
+    properties.into_iter().map(ContentLine::from).collect()
+}
+
+impl From<legacy::IcalAlarm> for IcalAlarmBuilder {
+    fn from(alarm: legacy::IcalAlarm) -> Self {
+        Self {
+            properties: lines(alarm.properties),
+        }
+    }
+}
+
+impl From<legacy::IcalEvent> for IcalEventBuilder {
+    fn from(event: legacy::IcalEvent) -> Self {
+        Self {
+            properties: lines(event.properties),
+            alarms: event.alarms.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<legacy::IcalTodo> for IcalTodoBuilder {
+    fn from(todo: legacy::IcalTodo) -> Self {
+        Self {
+            properties: lines(todo.properties),
+            alarms: todo.alarms.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<legacy::IcalJournal> for IcalJournalBuilder {
+    fn from(journal: legacy::IcalJournal) -> Self {
+        Self {
+            properties: lines(journal.properties),
+        }
+    }
+}
+
+impl From<legacy::IcalFreeBusy> for IcalFreeBusyBuilder {
+    fn from(free_busy: legacy::IcalFreeBusy) -> Self {
+        Self {
+            properties: lines(free_busy.properties),
+        }
+    }
+}
+
+impl From<legacy::IcalTimeZoneTransition> for IcalTimeZoneTransitionBuilder {
+    fn from(transition: legacy::IcalTimeZoneTransition) -> Self {
+        let mut builder = Self::new(match transition.transition {
+            legacy::IcalTimeZoneTransitionType::STANDARD => IcalTimeZoneTransitionType::STANDARD,
+            legacy::IcalTimeZoneTransitionType::DAYLIGHT => IcalTimeZoneTransitionType::DAYLIGHT,
+        });
+        builder.properties = lines(transition.properties);
+        builder
+    }
+}
+
+impl TryFrom<legacy::IcalTimeZone> for IcalTimeZone {
+    type Error = ParserError;
+
+    fn try_from(timezone: legacy::IcalTimeZone) -> Result<Self, Self::Error> {
+        let options = ParserOptions::default();
+        let transitions = timezone
+            .transitions
+            .into_iter()
+            .map(|transition| IcalTimeZoneTransitionBuilder::from(transition).build(&options, None))
+            .collect::<Result<_, _>>()?;
+        IcalTimeZone::<false> {
+            properties: lines(timezone.properties),
+            transitions,
+        }
+        .build(&options, None)
+    }
+}
+
+impl TryFrom<legacy::IcalCalendar> for IcalCalendarBuilder {
+    type Error = ParserError;
+
+    /// Fails if one of the VTIMEZONEs is invalid since those are verified right away
+    fn try_from(calendar: legacy::IcalCalendar) -> Result<Self, Self::Error> {
+        let vtimezones = calendar
+            .timezones
+            .into_iter()
+            .map(|timezone| {
+                let timezone = IcalTimeZone::try_from(timezone)?;
+                Ok((timezone.get_tzid().to_owned(), timezone))
+            })
+            .collect::<Result<_, ParserError>>()?;
+        Ok(Self {
+            properties: lines(calendar.properties),
+            events: calendar.events.into_iter().map(Into::into).collect(),
+            alarms: calendar.alarms.into_iter().map(Into::into).collect(),
+            todos: calendar.todos.into_iter().map(Into::into).collect(),
+            journals: calendar.journals.into_iter().map(Into::into).collect(),
+            free_busys: calendar.free_busys.into_iter().map(Into::into).collect(),
+            vtimezones,
+            timezones: Default::default(),
+        })
+    }
+}
+
+impl TryFrom<legacy::IcalCalendar> for IcalCalendar {
+    type Error = ParserError;
+
+    /// Verifies the calendar like [`IcalParser`](crate::IcalParser) with default options
+    fn try_from(calendar: legacy::IcalCalendar) -> Result<Self, Self::Error> {
+        IcalCalendarBuilder::try_from(calendar)?.build(&ParserOptions::default(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IcalParser, component::IcalCalendar, generator::Emitter, parser::ContentLine};
+    use ::ical::property::Property;
+    use rstest::rstest;
+    use std::io::BufReader;
+
+    #[rstest]
+    #[case("./tests/resources/ical_events.ics")]
+    #[case("./tests/resources/ical_freebusy.ics")]
+    #[case("./tests/resources/ical_journals.ics")]
+    #[case("./tests/resources/ical_todos.ics")]
+    #[case("./tests/resources/ical_multiple.ics")]
+    fn test_legacy_calendar(#[case] path: &str) {
+        let input = std::fs::read(path).unwrap();
+        let legacy = ::ical::IcalParser::new(BufReader::new(input.as_slice()));
+        let parsed = IcalParser::from_slice(&input);
+        for (legacy, parsed) in legacy.zip(parsed) {
+            let converted = IcalCalendar::try_from(legacy.unwrap()).unwrap();
+            assert_eq!(converted.generate(), parsed.unwrap().generate());
+        }
+    }
+
+    #[test]
+    fn test_property() {
+        let property = Property {
+            name: "ATTENDEE".to_owned(),
+            params: Some(vec![("CN".to_owned(), vec!["Jane".to_owned()])]),
+            value: Some("mailto:jane@example.com".to_owned()),
+        };
+        let line = ContentLine::from(property.clone());
+        assert_eq!(
+            line.generate(),
+            "ATTENDEE;CN=Jane:mailto:jane@example.com\r\n"
+        );
+        let roundtrip = Property::from(&line);
+        assert_eq!(roundtrip.params, property.params);
+        assert_eq!(roundtrip.value, property.value);
+
+        let empty = ContentLine::from(Property {
+            name: "X-EMPTY".to_owned(),
+            params: None,
+            value: None,
+        });
+        assert_eq!(empty.generate(), "X-EMPTY:\r\n");
+    }
+}
@@ -0,0 +1,196 @@
+//! Conversions to and from the components of the `icalendar` crate.
+//!
+//! Components are converted through their textual representation,
+//! so everything both crates can represent is kept.
+//! Components from `icalendar` often reference IANA timezones without a VTIMEZONE,
+//! these are resolved as with [`ParserOptions::rfc7809`].
+use crate::{
+    IcalParser,
+    component::{IcalCalendar, IcalEvent, IcalTodo},
+    generator::Emitter,
+    parser::{ParserError, ParserOptions},
+};
+use icalendar::{Calendar, CalendarComponent, Event, Todo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum IcalendarError {
+    #[error("icalendar could not parse the component: {0}")]
+    Icalendar(String),
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+    #[error("Expected a single {0}")]
+    UnexpectedComponent(&'static str),
+}
+
+fn parse_calendar(text: &str) -> Result<IcalCalendar, IcalendarError> {
+    Ok(IcalParser::from_slice(text.as_bytes())
+        .with_options(ParserOptions {
+            rfc7809: true,
+            ..Default::default()
+        })
+        .expect_one()?)
+}
+
+fn parse_component(text: &str) -> Result<CalendarComponent, IcalendarError> {
+    text.parse().map_err(IcalendarError::Icalendar)
+}
+
+impl TryFrom<&IcalCalendar> for Calendar {
+    type Error = IcalendarError;
+
+    fn try_from(calendar: &IcalCalendar) -> Result<Self, Self::Error> {
+        calendar
+            .generate()
+            .parse()
+            .map_err(IcalendarError::Icalendar)
+    }
+}
+
+impl TryFrom<&Calendar> for IcalCalendar {
+    type Error = IcalendarError;
+
+    fn try_from(calendar: &Calendar) -> Result<Self, Self::Error> {
+        parse_calendar(&calendar.to_string())
+    }
+}
+
+impl TryFrom<&IcalEvent> for Event {
+    type Error = IcalendarError;
+
+    fn try_from(event: &IcalEvent) -> Result<Self, Self::Error> {
+        match parse_component(&event.generate())? {
+            CalendarComponent::Event(event) => Ok(event),
+            _ => Err(IcalendarError::UnexpectedComponent("VEVENT")),
+        }
+    }
+}
+
+impl TryFrom<&Event> for IcalEvent {
+    type Error = IcalendarError;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        let mut calendar = Calendar::new();
+        calendar.push(event.clone());
+        let IcalCalendar { mut events, .. } = parse_calendar(&calendar.to_string())?;
+        match events.len() {
+            1 => Ok(events.remove(0)),
+            _ => Err(IcalendarError::UnexpectedComponent("VEVENT")),
+        }
+    }
+}
+
+impl TryFrom<&IcalTodo> for Todo {
+    type Error = IcalendarError;
+
+    fn try_from(todo: &IcalTodo) -> Result<Self, Self::Error> {
+        match parse_component(&todo.generate())? {
+            CalendarComponent::Todo(todo) => Ok(todo),
+            _ => Err(IcalendarError::UnexpectedComponent("VTODO")),
+        }
+    }
+}
+
+impl TryFrom<&Todo> for IcalTodo {
+    type Error = IcalendarError;
+
+    fn try_from(todo: &Todo) -> Result<Self, Self::Error> {
+        let mut calendar = Calendar::new();
+        calendar.push(todo.clone());
+        let IcalCalendar { mut todos, .. } = parse_calendar(&calendar.to_string())?;
+        match todos.len() {
+            1 => Ok(todos.remove(0)),
+            _ => Err(IcalendarError::UnexpectedComponent("VTODO")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalParser,
+        component::{Component, IcalCalendar, IcalEvent, IcalTodo, semantically_eq},
+    };
+    use icalendar::{Calendar, CalendarDateTime, Component as _, Event, EventLike, Todo};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T090000Z\r
+DTEND:20260323T100000Z\r
+SUMMARY:Planning\\, Q2\r
+ATTENDEE;CN=Jane:mailto:jane@example.com\r
+ATTENDEE;CN=John:mailto:john@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:report@example.com\r
+DTSTAMP:20260101T120000Z\r
+SUMMARY:Write report\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_calendar() {
+        let calendar = IcalParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap();
+        let converted = Calendar::try_from(&calendar).unwrap();
+        let event = converted.events().next().unwrap();
+        assert_eq!(event.get_summary(), Some("Planning, Q2"));
+        assert_eq!(event.multi_properties()["ATTENDEE"].len(), 2);
+        assert_eq!(converted.todos().count(), 1);
+
+        let roundtrip = IcalCalendar::try_from(&converted).unwrap();
+        assert_eq!(roundtrip.events.len(), 1);
+        assert_eq!(roundtrip.events[0].get_uid(), "meeting@example.com");
+        assert_eq!(roundtrip.events[0].get_alarms().len(), 1);
+        assert_eq!(
+            roundtrip.events[0].get_named_properties("ATTENDEE").count(),
+            2
+        );
+        assert_eq!(roundtrip.todos.len(), 1);
+
+        let event = Event::try_from(&calendar.events[0]).unwrap();
+        assert_eq!(event.get_uid(), Some("meeting@example.com"));
+        let todo = Todo::try_from(&calendar.todos[0]).unwrap();
+        assert_eq!(todo.get_summary(), Some("Write report"));
+        assert!(semantically_eq(
+            &IcalTodo::try_from(&todo).unwrap(),
+            &calendar.todos[0]
+        ));
+    }
+
+    #[test]
+    fn test_from_builder() {
+        let event = Event::new()
+            .uid("built@example.com")
+            .summary("Built; with icalendar")
+            .starts(CalendarDateTime::WithTimezone {
+                date_time: "2026-03-23T09:00:00".parse().unwrap(),
+                tzid: "Europe/Berlin".to_owned(),
+            })
+            .ends(CalendarDateTime::WithTimezone {
+                date_time: "2026-03-23T10:00:00".parse().unwrap(),
+                tzid: "Europe/Berlin".to_owned(),
+            })
+            .done();
+        let event = IcalEvent::try_from(&event).unwrap();
+        assert_eq!(event.get_uid(), "built@example.com");
+        assert_eq!(
+            event.get_property("SUMMARY").unwrap().value,
+            "Built\\; with icalendar"
+        );
+        assert_eq!(
+            event.dtstart.0.utc().to_rfc3339(),
+            "2026-03-23T08:00:00+00:00"
+        );
+    }
+}
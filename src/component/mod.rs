@@ -1,13 +1,50 @@
 pub mod ical;
 pub use ical::{IcalObjectParser, IcalParser, component::*};
 pub mod vcard;
-pub use vcard::component::*;
+pub use vcard::{VcardParser, component::*};
+pub(crate) mod semantic_eq;
+pub use semantic_eq::{SemanticEq, semantically_eq};
+pub(crate) mod content_hash;
+#[cfg(feature = "ical")]
+mod ical_compat;
+#[cfg(feature = "icalendar")]
+mod icalendar_compat;
+#[cfg(feature = "rkyv")]
+mod rkyv_impls;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod str_impls;
+#[cfg(feature = "icalendar")]
+pub use icalendar_compat::IcalendarError;
 
 use crate::ParserError;
-use crate::parser::{ContentLine, ContentLineParser, ParserOptions};
+use crate::parser::{
+    ContentLine, ContentLineParser, DuplicateParams, ParserOptions, quirks::clamp_leap_seconds,
+};
+use crate::types::Tz;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+fn check_duplicate_params(line: &ContentLine, options: &ParserOptions) -> Result<(), ParserError> {
+    if options.duplicate_params == DuplicateParams::Ignore {
+        return Ok(());
+    }
+    let Some(param) = line.params.conflicting_param() else {
+        return Ok(());
+    };
+    if options.duplicate_params == DuplicateParams::Reject {
+        return Err(ParserError::ConflictingParameter(
+            line.name.to_owned(),
+            param.to_owned(),
+        ));
+    }
+    log::warn!(
+        "conflicting values for the parameter {param} of {}, using the first one",
+        line.name
+    );
+    Ok(())
+}
+
 /// An immutable interface for an Ical/Vcard component.
 /// This is also implemented by verified components
 pub trait Component: Clone {
@@ -70,7 +107,7 @@ pub trait ComponentMut: Component + Default {
     fn build(
         self,
         options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self::Verified, ParserError>;
 
     /// Parse the content from `line_parser` and fill the component with.
@@ -80,12 +117,18 @@ pub trait ComponentMut: Component + Default {
         options: &ParserOptions,
     ) -> Result<(), ParserError> {
         loop {
-            let line = line_parser.next().ok_or(ParserError::NotComplete)??;
+            let mut line = line_parser.next().ok_or(ParserError::NotComplete)??;
 
             match line.name.as_ref() {
                 "END" => break,
                 "BEGIN" => self.add_sub_component(&line.value, line_parser, options)?,
-                _ => self.add_content_line(line),
+                _ => {
+                    if options.clamp_leap_seconds {
+                        clamp_leap_seconds(&mut line);
+                    }
+                    check_duplicate_params(&line, options)?;
+                    self.add_content_line(line)
+                }
             };
         }
         Ok(())
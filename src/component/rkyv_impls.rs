@@ -0,0 +1,308 @@
+//! Verified components only archive their content lines and sub-components
+//! and the IANA names of the timezones they resolved.
+//!
+//! Deserialising an archived component rebuilds it, so the typed fields go through the same
+//! verification as parsed components, see [`ParserOptions::reverify`]. Archived components can
+//! also be deserialised into their builders to skip that step.
+use crate::{
+    component::{
+        ArchivedCalendarInnerData, ArchivedIcalAlarm, ArchivedIcalCalendar,
+        ArchivedIcalCalendarObject, ArchivedIcalEvent, ArchivedIcalFreeBusy, ArchivedIcalJournal,
+        ArchivedIcalTimeZone, ArchivedIcalTimeZoneTransition, ArchivedIcalTodo,
+        ArchivedVcardContact, CalendarInnerDataBuilder, ComponentMut, IcalAlarm, IcalAlarmBuilder,
+        IcalCalendar, IcalCalendarBuilder, IcalCalendarObject, IcalCalendarObjectBuilder,
+        IcalEvent, IcalEventBuilder, IcalFreeBusy, IcalFreeBusyBuilder, IcalJournal,
+        IcalJournalBuilder, IcalTimeZone, IcalTimeZoneTransitionBuilder, IcalTodo, IcalTodoBuilder,
+        Timezones, VcardContact, VcardContactBuilder,
+    },
+    parser::ParserOptions,
+    types::Tz,
+};
+use rkyv::{
+    Archive, Deserialize, Place, Serialize,
+    rancor::{Fallible, Source},
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    with::{ArchiveWith, SerializeWith},
+};
+use std::str::FromStr;
+
+/// Archives resolved timezones as pairs of TZID and IANA name,
+/// fixed offsets and unresolved timezones are resolved again from their VTIMEZONE
+pub(crate) struct TimezoneNames;
+
+type ArchivedTimezoneNames = ArchivedVec<<(String, String) as Archive>::Archived>;
+
+impl TimezoneNames {
+    fn names(timezones: &Timezones) -> Vec<(String, String)> {
+        let mut names: Vec<_> = timezones
+            .iter()
+            .filter_map(|(tzid, tz)| match tz {
+                Some(Tz::Olson(tz)) => Some((tzid.to_owned(), tz.name().to_owned())),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn timezones(archived: &ArchivedTimezoneNames) -> Timezones {
+        archived
+            .iter()
+            .filter_map(|archived| {
+                // Names missing from this version of the IANA database are resolved again
+                let tz = chrono_tz::Tz::from_str(archived.1.as_str()).ok()?;
+                Some((archived.0.as_str().to_owned(), Some(Tz::Olson(tz))))
+            })
+            .collect()
+    }
+}
+
+impl ArchiveWith<Timezones> for TimezoneNames {
+    type Archived = ArchivedTimezoneNames;
+    type Resolver = VecResolver;
+
+    fn resolve_with(field: &Timezones, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(Self::names(field).len(), resolver, out);
+    }
+}
+
+impl<S: Fallible + Allocator + Writer + ?Sized> SerializeWith<Timezones, S> for TimezoneNames
+where
+    (String, String): Serialize<S>,
+{
+    fn serialize_with(field: &Timezones, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(&Self::names(field), serializer)
+    }
+}
+
+fn deserialize_all<A: Deserialize<B, D>, B, D: Fallible + ?Sized>(
+    archived: &ArchivedVec<A>,
+    deserializer: &mut D,
+) -> Result<Vec<B>, D::Error> {
+    archived
+        .iter()
+        .map(|component| component.deserialize(deserializer))
+        .collect()
+}
+
+macro_rules! deserialize_builder {
+    ($archived:ty, $builder:ident, $($field:ident),*) => {
+        impl<D: Fallible + ?Sized> Deserialize<$builder, D> for $archived
+        where
+            D::Error: Source,
+        {
+            fn deserialize(&self, deserializer: &mut D) -> Result<$builder, D::Error> {
+                Ok($builder {
+                    properties: self.properties.deserialize(deserializer)?,
+                    $($field: deserialize_all(&self.$field, deserializer)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Only implemented for self-contained components
+/// since other components might reference timezones they don't contain
+macro_rules! deserialize_verified {
+    ($archived:ty, $verified:ty, $builder:ty) => {
+        deserialize_verified!($archived, $verified, $builder, |_| Timezones::default());
+    };
+    ($archived:ty, $verified:ty, $builder:ty, $timezones:expr) => {
+        impl<D: Fallible + ?Sized> Deserialize<$verified, D> for $archived
+        where
+            D::Error: Source,
+        {
+            fn deserialize(&self, deserializer: &mut D) -> Result<$verified, D::Error> {
+                let timezones: fn(&Self) -> Timezones = $timezones;
+                let builder: $builder = self.deserialize(deserializer)?;
+                builder
+                    .build(&ParserOptions::reverify(&timezones(self)), None)
+                    .map_err(D::Error::new)
+            }
+        }
+    };
+}
+
+deserialize_builder!(ArchivedIcalAlarm, IcalAlarmBuilder,);
+deserialize_builder!(ArchivedIcalFreeBusy, IcalFreeBusyBuilder,);
+deserialize_builder!(ArchivedIcalJournal, IcalJournalBuilder,);
+deserialize_builder!(ArchivedIcalEvent, IcalEventBuilder, alarms);
+deserialize_builder!(ArchivedIcalTodo, IcalTodoBuilder, alarms);
+deserialize_builder!(ArchivedVcardContact, VcardContactBuilder,);
+
+impl<D: Fallible + ?Sized> Deserialize<IcalTimeZoneTransitionBuilder, D>
+    for ArchivedIcalTimeZoneTransition
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<IcalTimeZoneTransitionBuilder, D::Error> {
+        Ok(IcalTimeZoneTransitionBuilder {
+            transition: self.transition.deserialize(deserializer)?,
+            properties: self.properties.deserialize(deserializer)?,
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<IcalTimeZone<false>, D> for ArchivedIcalTimeZone<true>
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<IcalTimeZone<false>, D::Error> {
+        let transitions: Vec<IcalTimeZoneTransitionBuilder> =
+            deserialize_all(&self.transitions, deserializer)?;
+        Ok(IcalTimeZone {
+            properties: self.properties.deserialize(deserializer)?,
+            transitions: transitions
+                .into_iter()
+                .map(|transition| {
+                    transition.build(&ParserOptions::reverify(&Timezones::default()), None)
+                })
+                .collect::<Result<_, _>>()
+                .map_err(D::Error::new)?,
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<CalendarInnerDataBuilder, D> for ArchivedCalendarInnerData
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<CalendarInnerDataBuilder, D::Error> {
+        Ok(match self {
+            Self::Event(main, overrides) => {
+                let mut events = vec![main.deserialize(deserializer)?];
+                events.extend(deserialize_all(overrides, deserializer)?);
+                CalendarInnerDataBuilder::Event(events)
+            }
+            Self::Todo(main, overrides) => {
+                let mut todos = vec![main.deserialize(deserializer)?];
+                todos.extend(deserialize_all(overrides, deserializer)?);
+                CalendarInnerDataBuilder::Todo(todos)
+            }
+            Self::Journal(main, overrides) => {
+                let mut journals = vec![main.deserialize(deserializer)?];
+                journals.extend(deserialize_all(overrides, deserializer)?);
+                CalendarInnerDataBuilder::Journal(journals)
+            }
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<IcalCalendarObjectBuilder, D> for ArchivedIcalCalendarObject
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<IcalCalendarObjectBuilder, D::Error> {
+        Ok(IcalCalendarObjectBuilder {
+            properties: self.properties.deserialize(deserializer)?,
+            inner: Some(self.inner.deserialize(deserializer)?),
+            vtimezones: self.vtimezones.deserialize(deserializer)?,
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<IcalCalendarBuilder, D>
+    for ArchivedIcalCalendar<true, IcalAlarm, IcalEvent, IcalFreeBusy, IcalJournal, IcalTodo>
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<IcalCalendarBuilder, D::Error> {
+        Ok(IcalCalendarBuilder {
+            properties: self.properties.deserialize(deserializer)?,
+            events: deserialize_all(&self.events, deserializer)?,
+            alarms: deserialize_all(&self.alarms, deserializer)?,
+            todos: deserialize_all(&self.todos, deserializer)?,
+            journals: deserialize_all(&self.journals, deserializer)?,
+            free_busys: deserialize_all(&self.free_busys, deserializer)?,
+            vtimezones: self.vtimezones.deserialize(deserializer)?,
+            timezones: TimezoneNames::timezones(&self.timezones),
+        })
+    }
+}
+
+deserialize_verified!(
+    ArchivedIcalTimeZone<true>,
+    IcalTimeZone,
+    IcalTimeZone<false>
+);
+deserialize_verified!(
+    ArchivedIcalCalendarObject,
+    IcalCalendarObject,
+    IcalCalendarObjectBuilder,
+    |archived| TimezoneNames::timezones(&archived.timezones)
+);
+deserialize_verified!(
+    ArchivedIcalCalendar<true, IcalAlarm, IcalEvent, IcalFreeBusy, IcalJournal, IcalTodo>,
+    IcalCalendar,
+    IcalCalendarBuilder,
+    |archived| TimezoneNames::timezones(&archived.timezones)
+);
+deserialize_verified!(ArchivedVcardContact, VcardContact, VcardContactBuilder);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalObjectParser, IcalParser,
+        component::{
+            ArchivedIcalCalendar, ArchivedIcalCalendarObject, IcalCalendar, IcalCalendarObject,
+        },
+        generator::Emitter,
+        parser::ParserOptions,
+    };
+    use rkyv::rancor::Error;
+
+    fn roundtrip(object: &IcalCalendarObject) -> IcalCalendarObject {
+        let bytes = rkyv::to_bytes::<Error>(object).unwrap();
+        let archived = rkyv::access::<ArchivedIcalCalendarObject, Error>(&bytes).unwrap();
+        rkyv::deserialize::<_, Error>(archived).unwrap()
+    }
+
+    #[test]
+    fn test_calendar_roundtrip() {
+        let input = std::fs::read_to_string("tests/resources/ical_everything.ics").unwrap();
+        let calendar = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&calendar).unwrap();
+        let archived = rkyv::access::<ArchivedIcalCalendar, Error>(&bytes).unwrap();
+        assert_eq!(archived.events.len(), calendar.events.len());
+        let roundtrip: IcalCalendar = rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(roundtrip.generate(), calendar.generate());
+    }
+
+    #[test]
+    fn test_calendar_object_roundtrip() {
+        let input = std::fs::read_to_string("tests/resources/ical_recurring_todo.ics").unwrap();
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(roundtrip(&object).generate(), object.generate());
+    }
+
+    #[test]
+    fn test_options_roundtrip() {
+        let input = include_str!("../../tests/resources/ical_rrule_extension.ics");
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .with_options(ParserOptions {
+                lenient_rrule: true,
+                ..Default::default()
+            })
+            .expect_one()
+            .unwrap();
+        assert_eq!(roundtrip(&object).generate(), object.generate());
+
+        let input = include_str!("../../tests/resources/ical_lotus_notes.ics");
+        let tzid = "Z=-1$DST=1$DSTSTART=0-3-1-1$DSTEND=0-10-1-1$ZN=W. Europe";
+        let object = IcalObjectParser::from_slice(input.as_bytes())
+            .with_options(ParserOptions {
+                tzid_aliases: [(tzid.to_owned(), chrono_tz::Europe::Berlin)].into(),
+                ..Default::default()
+            })
+            .expect_one()
+            .unwrap();
+        let roundtrip = roundtrip(&object);
+        assert_eq!(roundtrip.get_timezones(), object.get_timezones());
+        assert_eq!(roundtrip.generate(), object.generate());
+    }
+}
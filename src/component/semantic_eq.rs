@@ -0,0 +1,411 @@
+use crate::{
+    component::{
+        CalendarInnerData, Component, IcalAlarm, IcalCalendar, IcalCalendarObject, IcalEvent,
+        IcalFreeBusy, IcalJournal, IcalTodo, Timezones, VcardContact, normalized,
+    },
+    parser::ContentLine,
+};
+
+/// Default value types of properties, VALUE parameters stating them are redundant
+const DEFAULT_VALUE_TYPES: &[(&str, &str)] = &[
+    ("DTSTART", "DATE-TIME"),
+    ("DTEND", "DATE-TIME"),
+    ("DUE", "DATE-TIME"),
+    ("RECURRENCE-ID", "DATE-TIME"),
+    ("RDATE", "DATE-TIME"),
+    ("EXDATE", "DATE-TIME"),
+    ("TRIGGER", "DURATION"),
+    ("DURATION", "DURATION"),
+    ("ATTACH", "URI"),
+    ("URL", "URI"),
+    ("TZURL", "URI"),
+    ("FREEBUSY", "PERIOD"),
+    // vCard
+    ("BDAY", "DATE-AND-OR-TIME"),
+    ("ANNIVERSARY", "DATE-AND-OR-TIME"),
+    ("PHOTO", "URI"),
+    ("LOGO", "URI"),
+    ("SOUND", "URI"),
+    ("KEY", "URI"),
+];
+
+type CanonicalParams = Vec<(String, Vec<String>)>;
+
+/// Representation of a component that is equal for semantically equal components
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Canonical {
+    name: &'static str,
+    properties: Vec<(String, CanonicalParams, String)>,
+    children: Vec<Canonical>,
+}
+
+impl Canonical {
+    /// Appends an unambiguous byte representation, e.g. for hashes that must stay stable
+    pub(crate) fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        fn write_len(bytes: &mut Vec<u8>, len: usize) {
+            bytes.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+        fn write_str(bytes: &mut Vec<u8>, value: &str) {
+            write_len(bytes, value.len());
+            bytes.extend_from_slice(value.as_bytes());
+        }
+
+        write_str(bytes, self.name);
+        write_len(bytes, self.properties.len());
+        for (name, params, value) in &self.properties {
+            write_str(bytes, name);
+            write_len(bytes, params.len());
+            for (key, values) in params {
+                write_str(bytes, key);
+                write_len(bytes, values.len());
+                for value in values {
+                    write_str(bytes, value);
+                }
+            }
+            write_str(bytes, value);
+        }
+        write_len(bytes, self.children.len());
+        for child in &self.children {
+            child.write_bytes(bytes);
+        }
+    }
+}
+
+pub(crate) fn canonical_line(line: ContentLine) -> (String, CanonicalParams, String) {
+    let (name, params, value) = canonical_line_as_written(line);
+    // Without the recurrence feature the parts of rules are only sorted
+    #[cfg(feature = "recurrence")]
+    let value = match name.as_str() {
+        "RRULE" | "EXRULE" => crate::rrule::RRule::from_str_lenient(&value)
+            .map(|rrule| rrule.to_string())
+            .unwrap_or(value),
+        _ => value,
+    };
+    (name, params, value)
+}
+
+/// Like [`canonical_line`] but the parts of rules are only sorted instead of parsed,
+/// so the result doesn't depend on the `recurrence` feature
+fn canonical_line_as_written(line: ContentLine) -> (String, CanonicalParams, String) {
+    let ContentLine {
+        name,
+        params,
+        value,
+    } = line;
+    let default_type = DEFAULT_VALUE_TYPES
+        .iter()
+        .find(|(prop, _)| *prop == name)
+        .map(|(_, value_type)| *value_type);
+    let mut params: CanonicalParams = params
+        .0
+        .into_iter()
+        .filter(|(key, values)| {
+            !(key == "VALUE"
+                && default_type.is_some_and(|default| {
+                    values.len() == 1 && values[0].eq_ignore_ascii_case(default)
+                }))
+        })
+        .map(|(key, mut values)| {
+            values.sort();
+            (key.as_str().to_owned(), values)
+        })
+        .collect();
+    params.sort();
+
+    let value = match name.as_str() {
+        "RRULE" | "EXRULE" => {
+            let mut parts: Vec<_> = value.split(';').collect();
+            parts.sort_unstable();
+            parts.join(";")
+        }
+        _ => value,
+    };
+    (name, params, value)
+}
+
+fn build<C: Component>(
+    component: &C,
+    properties: Vec<ContentLine>,
+    line: fn(ContentLine) -> (String, CanonicalParams, String),
+    mut children: Vec<Canonical>,
+) -> Canonical {
+    let mut properties: Vec<_> = properties.into_iter().map(line).collect();
+    properties.sort();
+    children.sort();
+    Canonical {
+        name: component.get_comp_name(),
+        properties,
+        children,
+    }
+}
+
+pub(crate) fn canonical<C: Component>(
+    component: &C,
+    timezones: Option<&Timezones>,
+    children: Vec<Canonical>,
+) -> Canonical {
+    let properties = match timezones {
+        Some(timezones) => normalized(component.get_properties(), timezones),
+        None => component.get_properties().clone(),
+    };
+    build(component, properties, canonical_line, children)
+}
+
+/// The canonical form with values as they are written, see [`canonical_line_as_written`]
+pub(crate) fn canonical_as_written<C: Component>(
+    component: &C,
+    children: Vec<Canonical>,
+) -> Canonical {
+    build(
+        component,
+        component.get_properties().clone(),
+        canonical_line_as_written,
+        children,
+    )
+}
+
+pub(crate) fn alarms(alarms: &[IcalAlarm], timezones: Option<&Timezones>) -> Vec<Canonical> {
+    alarms
+        .iter()
+        .map(|alarm| canonical(alarm, timezones, vec![]))
+        .collect()
+}
+
+/// Comparison of components by their meaning rather than their representation.
+///
+/// Components are compared ignoring the order of properties, parameters and sub-components,
+/// VALUE parameters stating the default value type and, with the `recurrence` feature,
+/// the order of RRULE parts.
+/// When the timezones of a calendar (object) are known, DATE-TIME values in a timezone
+/// are compared by the instant they describe, so VTIMEZONE components aren't compared.
+/// Recurring components are the exception: their instances are expanded in the timezone of
+/// DTSTART, so their values are compared by local time and timezone.
+pub trait SemanticEq {
+    fn semantically_eq(&self, other: &Self) -> bool;
+}
+
+/// Whether `a` and `b` have the same meaning, see [`SemanticEq`]
+pub fn semantically_eq<C: SemanticEq>(a: &C, b: &C) -> bool {
+    a.semantically_eq(b)
+}
+
+trait ToCanonical {
+    fn to_canonical(&self, timezones: Option<&Timezones>) -> Canonical;
+}
+
+impl<T: ToCanonical> SemanticEq for T {
+    fn semantically_eq(&self, other: &Self) -> bool {
+        self.to_canonical(None) == other.to_canonical(None)
+    }
+}
+
+macro_rules! leaf_canonical {
+    ($($component:ty),*) => {
+        $(
+            impl ToCanonical for $component {
+                fn to_canonical(&self, timezones: Option<&Timezones>) -> Canonical {
+                    canonical(self, timezones, vec![])
+                }
+            }
+        )*
+    };
+}
+
+leaf_canonical!(IcalAlarm, IcalFreeBusy, IcalJournal, VcardContact);
+
+impl ToCanonical for IcalEvent {
+    fn to_canonical(&self, timezones: Option<&Timezones>) -> Canonical {
+        canonical(self, timezones, alarms(&self.alarms, timezones))
+    }
+}
+
+impl ToCanonical for IcalTodo {
+    fn to_canonical(&self, timezones: Option<&Timezones>) -> Canonical {
+        canonical(self, timezones, alarms(&self.alarms, timezones))
+    }
+}
+
+fn all_canonical<'a, C: ToCanonical + 'a>(
+    components: impl IntoIterator<Item = &'a C>,
+    timezones: Option<&Timezones>,
+) -> impl Iterator<Item = Canonical> {
+    components
+        .into_iter()
+        .map(move |component| component.to_canonical(timezones))
+}
+
+impl ToCanonical for IcalCalendar {
+    fn to_canonical(&self, _timezones: Option<&Timezones>) -> Canonical {
+        let timezones = Some(&self.timezones);
+        let children = all_canonical(&self.events, timezones)
+            .chain(all_canonical(&self.todos, timezones))
+            .chain(all_canonical(&self.journals, timezones))
+            .chain(all_canonical(&self.free_busys, timezones))
+            .chain(all_canonical(&self.alarms, timezones))
+            .collect();
+        canonical(self, None, children)
+    }
+}
+
+impl ToCanonical for IcalCalendarObject {
+    fn to_canonical(&self, _timezones: Option<&Timezones>) -> Canonical {
+        let timezones = Some(&self.timezones);
+        let children = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                all_canonical(std::iter::once(main).chain(overrides), timezones).collect()
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                all_canonical(std::iter::once(main).chain(overrides), timezones).collect()
+            }
+            CalendarInnerData::Journal(main, overrides) => {
+                all_canonical(std::iter::once(main).chain(overrides), timezones).collect()
+            }
+        };
+        canonical(self, None, children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::semantically_eq;
+    use crate::{IcalObjectParser, IcalParser, VcardParser};
+
+    const ZONED: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+DTSTART:19971026T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+DTSTART:19970330T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:semantic@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;VALUE=DATE-TIME;TZID=Europe/Berlin:20260323T090000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=3\r
+ATTENDEE;ROLE=REQ-PARTICIPANT;CN=Jane:mailto:jane@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT10M\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:AUDIO\r
+TRIGGER:-PT5M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    const UTC: &str = "BEGIN:VCALENDAR\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART:20260323T080000Z\r
+UID:semantic@example.com\r
+DTSTAMP:20260101T120000Z\r
+RRULE:COUNT=3;FREQ=WEEKLY\r
+DURATION:PT1H\r
+ATTENDEE;CN=Jane;ROLE=REQ-PARTICIPANT:mailto:jane@example.com\r
+BEGIN:VALARM\r
+TRIGGER:-PT5M\r
+ACTION:AUDIO\r
+END:VALARM\r
+BEGIN:VALARM\r
+TRIGGER;VALUE=DURATION:-PT10M\r
+ACTION:DISPLAY\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    /// Removes the RRULE, the zoned and UTC events then describe the same instant
+    fn single(ics: &str) -> String {
+        ics.replace("RRULE:FREQ=WEEKLY;COUNT=3\r\n", "")
+            .replace("RRULE:COUNT=3;FREQ=WEEKLY\r\n", "")
+    }
+
+    #[test]
+    fn test_calendar() {
+        let zoned = IcalParser::from_slice(single(ZONED).as_bytes())
+            .expect_one()
+            .unwrap();
+        let utc = IcalParser::from_slice(single(UTC).as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(semantically_eq(&zoned, &utc));
+        assert!(semantically_eq(&zoned.events[0], &zoned.events[0]));
+        // Without the timezones of the calendar TZIDs are compared as they are
+        assert!(!semantically_eq(&zoned.events[0], &utc.events[0]));
+
+        let other = single(UTC).replace("DURATION:PT1H", "DURATION:PT2H");
+        let other = IcalParser::from_slice(other.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(!semantically_eq(&zoned, &other));
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_recurring() {
+        let zoned = IcalParser::from_slice(ZONED.as_bytes())
+            .expect_one()
+            .unwrap();
+        // The second instance is after the switch to summer time, 07:00 UTC in Berlin
+        let utc = IcalParser::from_slice(UTC.as_bytes()).expect_one().unwrap();
+        assert!(!semantically_eq(&zoned, &utc));
+
+        let renamed = ZONED
+            .replace("TZID:Europe/Berlin", "TZID:W. Europe Standard Time")
+            .replace("TZID=Europe/Berlin", "TZID=W. Europe Standard Time")
+            .replace("RRULE:FREQ=WEEKLY;COUNT=3", "RRULE:COUNT=3;FREQ=WEEKLY");
+        let renamed = IcalParser::from_slice(renamed.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(semantically_eq(&zoned, &renamed));
+    }
+
+    #[test]
+    fn test_calendar_object() {
+        let zoned = IcalObjectParser::from_slice(single(ZONED).as_bytes())
+            .expect_one()
+            .unwrap();
+        let utc = IcalObjectParser::from_slice(single(UTC).as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(semantically_eq(&zoned, &utc));
+
+        let zoned = IcalObjectParser::from_slice(ZONED.as_bytes())
+            .expect_one()
+            .unwrap();
+        let utc = IcalObjectParser::from_slice(UTC.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(!semantically_eq(&zoned, &utc));
+    }
+
+    #[test]
+    fn test_contact() {
+        let a = VcardParser::from_slice(
+            b"BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nTEL;VALUE=uri;TYPE=work,voice:tel:+1-555-555-5555\r\nEND:VCARD\r\n",
+        )
+        .expect_one()
+        .unwrap();
+        let b = VcardParser::from_slice(
+            b"BEGIN:VCARD\r\nTEL;TYPE=voice,work;VALUE=uri:tel:+1-555-555-5555\r\nFN:Jane Doe\r\nVERSION:4.0\r\nEND:VCARD\r\n",
+        )
+        .expect_one()
+        .unwrap();
+        assert!(semantically_eq(&a, &b));
+    }
+}
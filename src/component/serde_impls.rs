@@ -0,0 +1,105 @@
+//! Components are (de)serialized as their textual representation.
+//!
+//! This keeps the typed fields consistent with the content lines since deserialized components
+//! go through the same verification as parsed ones.
+use crate::{
+    component::{
+        IcalAlarm, IcalCalendar, IcalCalendarObject, IcalEvent, IcalFreeBusy, IcalJournal,
+        IcalTimeZone, IcalTodo, VcardContact,
+    },
+    generator::Emitter,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use std::borrow::Cow;
+
+macro_rules! serialize_as_text {
+    ($($struct:ty),*) => {
+        $(
+            impl Serialize for $struct {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.generate())
+                }
+            }
+        )*
+    };
+}
+
+/// Only implemented for self-contained components
+/// since other components might reference timezones they don't contain
+macro_rules! deserialize_from_text {
+    ($($struct:ty),*) => {
+        $(
+            impl<'de> Deserialize<'de> for $struct {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let text = Cow::<'de, str>::deserialize(deserializer)?;
+                    text.parse().map_err(D::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+serialize_as_text!(
+    IcalAlarm,
+    IcalFreeBusy,
+    IcalJournal,
+    IcalEvent,
+    IcalTodo,
+    IcalTimeZone,
+    IcalCalendar,
+    IcalCalendarObject,
+    VcardContact
+);
+deserialize_from_text!(IcalCalendar, IcalCalendarObject, VcardContact);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalObjectParser, IcalParser,
+        component::{IcalCalendar, IcalCalendarObject, VcardContact},
+        generator::Emitter,
+    };
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:20010712T182145Z-123401@example.com\r
+DTSTAMP:20060206T001121Z\r
+DTSTART:20060102T120000Z\r
+DURATION:PT1H\r
+SUMMARY:Event #2\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_calendar_roundtrip() {
+        let calendar = IcalParser::from_slice(EVENT.as_bytes())
+            .expect_one()
+            .unwrap();
+        let json = serde_json::to_string(&calendar).unwrap();
+        let roundtrip: IcalCalendar = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.generate(), calendar.generate());
+
+        let object = IcalObjectParser::from_slice(EVENT.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(serde_json::to_string(&object).unwrap(), json);
+        let roundtrip: IcalCalendarObject = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.generate(), object.generate());
+    }
+
+    #[test]
+    fn test_contact_roundtrip() {
+        let json = r#""BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n""#;
+        let contact: VcardContact = serde_json::from_str(json).unwrap();
+        assert_eq!(contact.full_name.len(), 1);
+        assert_eq!(serde_json::to_string(&contact).unwrap(), json);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(serde_json::from_str::<IcalCalendarObject>(r#""BEGIN:VEVENT""#).is_err());
+    }
+}
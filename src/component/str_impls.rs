@@ -0,0 +1,73 @@
+//! Components are parsed from their textual representation,
+//! the generator provides the matching `Display` implementations.
+use crate::{
+    component::{IcalCalendar, IcalCalendarObject, VcardContact},
+    parser::{ComponentParser, ParserError},
+};
+use std::str::FromStr;
+
+/// Parses exactly one component like [`ComponentParser::expect_one`].
+/// Only implemented for self-contained components
+/// since other components might reference timezones they don't contain
+macro_rules! from_str {
+    ($($struct:ty),*) => {
+        $(
+            impl FromStr for $struct {
+                type Err = ParserError;
+
+                fn from_str(text: &str) -> Result<Self, Self::Err> {
+                    ComponentParser::<Self, _>::from_slice(text.as_bytes()).expect_one()
+                }
+            }
+        )*
+    };
+}
+
+from_str!(IcalCalendar, IcalCalendarObject, VcardContact);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ParserError,
+        component::{IcalCalendar, IcalCalendarObject, VcardContact},
+    };
+
+    const EVENT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:20010712T182145Z-123401@example.com\r
+DTSTAMP:20060206T001121Z\r
+DTSTART:20060102T120000Z\r
+DURATION:PT1H\r
+SUMMARY:Event #2\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_and_format() {
+        let calendar: IcalCalendar = EVENT.parse().unwrap();
+        assert_eq!(calendar.to_string(), EVENT);
+        let object: IcalCalendarObject = EVENT.parse().unwrap();
+        assert_eq!(format!("{object}"), EVENT);
+
+        let contact = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n";
+        assert_eq!(
+            contact.parse::<VcardContact>().unwrap().to_string(),
+            contact
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            "".parse::<IcalCalendar>(),
+            Err(ParserError::EmptyInput)
+        ));
+        assert!(matches!(
+            EVENT.repeat(2).parse::<IcalCalendarObject>(),
+            Err(ParserError::TooManyComponents)
+        ));
+    }
+}
@@ -4,20 +4,28 @@ use crate::property::{
     GetProperty, IcalUIDProperty, VcardANNIVERSARYProperty, VcardBDAYProperty, VcardFNProperty,
     VcardNProperty,
 };
+use crate::types::{Text, Tz};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize))]
 pub struct VcardContact {
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub uid: Option<String>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub full_name: Vec<VcardFNProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub name: Option<VcardNProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub birthday: Option<VcardBDAYProperty>,
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::Skip))]
     pub anniversary: Option<VcardANNIVERSARYProperty>,
     pub properties: Vec<ContentLine>,
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct VcardContactBuilder {
     pub properties: Vec<ContentLine>,
 }
@@ -26,6 +34,41 @@ impl VcardContact {
     pub fn get_uid(&self) -> Option<&str> {
         self.uid.as_deref()
     }
+
+    /// The unescaped formatted name, the first if there are several
+    pub fn get_full_name(&self) -> Option<Text> {
+        self.full_name
+            .first()
+            .map(|VcardFNProperty(name, _)| Text::parse(name))
+    }
+
+    /// The EMAIL addresses, without a `mailto:` scheme some clients add
+    pub fn get_emails(&self) -> impl Iterator<Item = &str> {
+        self.get_named_properties("EMAIL")
+            .map(|email| match email.value.split_once(':') {
+                Some((scheme, address)) if scheme.eq_ignore_ascii_case("mailto") => address,
+                _ => &email.value,
+            })
+    }
+
+    /// The PHOTO as URI, inline vCard 3 photos are turned into `data:` URIs
+    pub fn get_photo(&self) -> Option<Cow<'_, str>> {
+        let photo = self.get_property("PHOTO")?;
+        let encoding = photo.params.get_param("ENCODING");
+        if !encoding.is_some_and(|encoding| encoding.eq_ignore_ascii_case("b")) {
+            return Some(Cow::Borrowed(&photo.value));
+        }
+        let media_type = photo
+            .params
+            .get_param("TYPE")
+            .map_or_else(String::new, |kind| {
+                format!("image/{}", kind.to_ascii_lowercase())
+            });
+        Some(Cow::Owned(format!(
+            "data:{media_type};base64,{}",
+            photo.value
+        )))
+    }
 }
 
 impl Component for VcardContactBuilder {
@@ -75,16 +118,17 @@ impl ComponentMut for VcardContactBuilder {
     fn build(
         self,
         _options: &ParserOptions,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self::Verified, ParserError> {
-        let uid = self
+        let props = self.property_index();
+        let uid = props
             .safe_get_optional(timezones)?
             .map(|IcalUIDProperty(uid, _)| uid);
 
-        let name = self.safe_get_optional(timezones)?;
-        let full_name = self.safe_get_all(timezones)?;
-        let birthday = self.safe_get_optional(timezones)?;
-        let anniversary = self.safe_get_optional(timezones)?;
+        let name = props.safe_get_optional(timezones)?;
+        let full_name = props.safe_get_all(timezones)?;
+        let birthday = props.safe_get_optional(timezones)?;
+        let anniversary = props.safe_get_optional(timezones)?;
 
         let verified = VcardContact {
             uid,
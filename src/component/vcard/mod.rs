@@ -1,6 +1,6 @@
 //! Parse a VCARD address book.
 //!
-//! Wrap the result of the `PropertyParser` into components.
+//! Wrap the result of the `ContentLineParser` into components.
 //!
 //! Each component contains properties (ie: Property) or sub-components.
 //!
@@ -0,0 +1,195 @@
+//! Linking of the calendar users in ATTENDEE and ORGANIZER properties to vCard contacts
+use crate::{
+    agenda::Occurrence,
+    component::{Component, IcalEvent, VcardContact},
+    parser::ContentLine,
+    types::{CalAddress, Text},
+};
+use std::collections::HashMap;
+
+/// Strips the `urn:uuid:` prefix vCard UIDs are often written with
+fn bare_uid(uid: &str) -> &str {
+    match uid.get(..9) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("urn:uuid:") => &uid[9..],
+        _ => uid,
+    }
+}
+
+/// A calendar user of an event together with its contact
+#[derive(Debug, Clone)]
+pub struct Participant<'c> {
+    /// The CAL-ADDRESS of the ATTENDEE or ORGANIZER
+    pub address: String,
+    pub organizer: bool,
+    pub contact: Option<&'c VcardContact>,
+    /// The formatted name of the contact, otherwise the CN parameter
+    pub display_name: Option<String>,
+    /// The photo of the contact as URI, see [`VcardContact::get_photo`]
+    pub photo: Option<String>,
+}
+
+/// Finds the contacts of calendar users.
+///
+/// A calendar user matches a contact whose UID is referenced by the DIR parameter or an
+/// experimental (X-) parameter of the property, otherwise a contact with an EMAIL equal to
+/// its `mailto:` address ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct ContactResolver<'c> {
+    by_uid: HashMap<&'c str, &'c VcardContact>,
+    by_email: HashMap<String, &'c VcardContact>,
+}
+
+impl<'c> ContactResolver<'c> {
+    /// Indexes `contacts`, the first contact wins if several share a UID or an email address
+    pub fn new(contacts: impl IntoIterator<Item = &'c VcardContact>) -> Self {
+        let mut resolver = Self::default();
+        for contact in contacts {
+            if let Some(uid) = contact.get_uid() {
+                resolver.by_uid.entry(bare_uid(uid)).or_insert(contact);
+            }
+            for email in contact.get_emails() {
+                resolver
+                    .by_email
+                    .entry(email.to_lowercase())
+                    .or_insert(contact);
+            }
+        }
+        resolver
+    }
+
+    /// The contact of the calendar user of the ATTENDEE or ORGANIZER property `line`
+    pub fn resolve(&self, line: &ContentLine) -> Option<&'c VcardContact> {
+        line.params
+            .iter()
+            .filter(|(name, _)| *name == "DIR" || name.starts_with("X-"))
+            .flat_map(|(_, values)| values)
+            .find_map(|value| self.by_uid.get(bare_uid(value)).copied())
+            .or_else(|| {
+                let address: CalAddress = line.value.parse().ok()?;
+                self.by_email.get(&address.email()?.to_lowercase()).copied()
+            })
+    }
+
+    /// The ORGANIZER followed by the ATTENDEEs of `event`
+    pub fn participants(&self, event: &IcalEvent) -> Vec<Participant<'c>> {
+        event
+            .get_named_properties("ORGANIZER")
+            .chain(event.get_named_properties("ATTENDEE"))
+            .map(|line| {
+                let contact = self.resolve(line);
+                Participant {
+                    address: line.value.to_owned(),
+                    organizer: line.name == "ORGANIZER",
+                    contact,
+                    display_name: contact
+                        .and_then(VcardContact::get_full_name)
+                        .map(Text::into_inner)
+                        .or_else(|| line.params.get_param("CN").map(ToOwned::to_owned)),
+                    photo: contact
+                        .and_then(VcardContact::get_photo)
+                        .map(|photo| photo.into_owned()),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Occurrence<'_> {
+    /// The calendar users of the instance with their contacts, see [`ContactResolver`]
+    pub fn participants<'c>(&self, resolver: &ContactResolver<'c>) -> Vec<Participant<'c>> {
+        resolver.participants(&self.event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContactResolver;
+    use crate::{IcalObjectParser, VcardParser, component::CalendarInnerData};
+
+    const CONTACTS: &str = "BEGIN:VCARD\r
+VERSION:4.0\r
+UID:urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1\r
+FN:Jane Doe\r
+EMAIL;TYPE=work:Jane@Example.com\r
+PHOTO:https://example.com/jane.jpg\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:3.0\r
+UID:john\r
+FN:John Smith\\, Jr.\r
+EMAIL:mailto:john@example.com\r
+PHOTO;ENCODING=b;TYPE=JPEG:/9j/4AAQ\r
+END:VCARD\r
+BEGIN:VCARD\r
+VERSION:4.0\r
+UID:urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6\r
+FN:Meeting Room\r
+END:VCARD\r
+";
+
+    const OBJECT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T090000Z\r
+DURATION:PT1H\r
+ORGANIZER;CN=Jane:mailto:jane@example.com\r
+ATTENDEE;CN=Johnny:MAILTO:JOHN@example.com\r
+ATTENDEE;CUTYPE=ROOM;DIR=\"urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6\":mailto:room@example.com\r
+ATTENDEE;CN=Guest:mailto:guest@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_participants() {
+        let contacts = VcardParser::from_slice(CONTACTS.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let resolver = ContactResolver::new(&contacts);
+        let object = IcalObjectParser::from_slice(OBJECT.as_bytes())
+            .expect_one()
+            .unwrap();
+        let CalendarInnerData::Event(event, _) = object.get_inner() else {
+            unreachable!()
+        };
+        let participants: Vec<_> = resolver
+            .participants(event)
+            .into_iter()
+            .map(|participant| {
+                (
+                    participant.organizer,
+                    participant.contact.and_then(|contact| contact.get_uid()),
+                    participant.display_name,
+                    participant.photo,
+                )
+            })
+            .collect();
+        assert_eq!(
+            participants,
+            [
+                (
+                    true,
+                    Some("urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1"),
+                    Some("Jane Doe".to_owned()),
+                    Some("https://example.com/jane.jpg".to_owned())
+                ),
+                (
+                    false,
+                    Some("john"),
+                    Some("John Smith, Jr.".to_owned()),
+                    Some("data:image/jpeg;base64,/9j/4AAQ".to_owned())
+                ),
+                (
+                    false,
+                    Some("urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6"),
+                    Some("Meeting Room".to_owned()),
+                    None
+                ),
+                (false, None, Some("Guest".to_owned()), None),
+            ]
+        );
+    }
+}
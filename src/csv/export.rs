@@ -0,0 +1,290 @@
+use crate::{
+    agenda::{Occurrence, occurrences, wall_clock},
+    component::{CalendarInnerData, Component, IcalCalendarObject},
+    property::GetProperty,
+    types::{CalAddress, Text, Tz, parse_text_list},
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::{io::Write, ops::RangeInclusive};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// A column of a CSV export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    Uid,
+    RecurrenceId,
+    Summary,
+    /// Wall-clock start in the export's timezone
+    Start,
+    /// Wall-clock end in the export's timezone, exclusive
+    End,
+    AllDay,
+    Location,
+    Description,
+    Status,
+    /// The email address of the organizer
+    Organizer,
+    /// The email addresses of the attendees separated by `; `
+    Attendees,
+    /// The categories of all CATEGORIES properties separated by `, `
+    Categories,
+    /// The raw value of the first property with this name
+    Property(String),
+}
+
+impl CsvColumn {
+    /// The column name in the header row
+    pub fn header(&self) -> &str {
+        match self {
+            Self::Uid => "UID",
+            Self::RecurrenceId => "Recurrence ID",
+            Self::Summary => "Summary",
+            Self::Start => "Start",
+            Self::End => "End",
+            Self::AllDay => "All Day",
+            Self::Location => "Location",
+            Self::Description => "Description",
+            Self::Status => "Status",
+            Self::Organizer => "Organizer",
+            Self::Attendees => "Attendees",
+            Self::Categories => "Categories",
+            Self::Property(name) => name,
+        }
+    }
+}
+
+/// Writes events as CSV with one row per event or occurrence.
+///
+/// Dates are formatted as `YYYY-MM-DD`, datetimes as `YYYY-MM-DD HH:MM:SS`
+/// in the export's timezone, floating values are taken as is.
+#[derive(Debug, Clone)]
+pub struct CsvExporter {
+    columns: Vec<CsvColumn>,
+    tz: Tz,
+}
+
+impl Default for CsvExporter {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                CsvColumn::Uid,
+                CsvColumn::Summary,
+                CsvColumn::Start,
+                CsvColumn::End,
+                CsvColumn::AllDay,
+                CsvColumn::Location,
+            ],
+            tz: Tz::utc(),
+        }
+    }
+}
+
+fn address(value: &str) -> String {
+    value
+        .parse::<CalAddress>()
+        .ok()
+        .and_then(|address| address.email().map(str::to_owned))
+        .unwrap_or_else(|| value.to_owned())
+}
+
+fn format(value: NaiveDateTime, all_day: bool) -> String {
+    if all_day {
+        value.format(DATE_FORMAT).to_string()
+    } else {
+        value.format(DATE_TIME_FORMAT).to_string()
+    }
+}
+
+impl CsvExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// The timezone zoned and UTC values are converted to, UTC by default
+    pub fn with_timezone(mut self, tz: Tz) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    fn cell(&self, column: &CsvColumn, occurrence: &Occurrence<'_>) -> String {
+        let event = &occurrence.event;
+        let text = |name| {
+            event
+                .get_text(name)
+                .map(Text::into_inner)
+                .unwrap_or_default()
+        };
+        match column {
+            CsvColumn::Uid => event.get_uid().to_owned(),
+            CsvColumn::RecurrenceId => event
+                .recurid
+                .as_ref()
+                .map(|recurid| format(wall_clock(&recurid.0, &self.tz), recurid.0.is_date()))
+                .unwrap_or_default(),
+            CsvColumn::Summary => text("SUMMARY"),
+            CsvColumn::Start => format(occurrence.start, occurrence.all_day),
+            CsvColumn::End => format(occurrence.end, occurrence.all_day),
+            CsvColumn::AllDay => if occurrence.all_day { "TRUE" } else { "FALSE" }.to_owned(),
+            CsvColumn::Location => text("LOCATION"),
+            CsvColumn::Description => text("DESCRIPTION"),
+            CsvColumn::Status => text("STATUS"),
+            CsvColumn::Organizer => event
+                .get_property("ORGANIZER")
+                .map(|organizer| address(&organizer.value))
+                .unwrap_or_default(),
+            CsvColumn::Attendees => event
+                .get_named_properties("ATTENDEE")
+                .map(|attendee| address(&attendee.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+            CsvColumn::Categories => event
+                .get_named_properties("CATEGORIES")
+                .flat_map(|categories| parse_text_list(&categories.value))
+                .collect::<Vec<_>>()
+                .join(", "),
+            CsvColumn::Property(name) => event
+                .get_property(name)
+                .map(|prop| prop.value.to_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn write<'a>(
+        &self,
+        occurrences: impl IntoIterator<Item = Occurrence<'a>>,
+        out: impl Write,
+    ) -> std::io::Result<()> {
+        let mut writer = ::csv::Writer::from_writer(out);
+        writer.write_record(self.columns.iter().map(CsvColumn::header))?;
+        for occurrence in occurrences {
+            writer.write_record(
+                self.columns
+                    .iter()
+                    .map(|column| self.cell(column, &occurrence)),
+            )?;
+        }
+        writer.flush()
+    }
+
+    /// Writes a row for the main event and every override of `objects`.
+    /// To-dos and journals are skipped.
+    pub fn export_events<'a>(
+        &self,
+        objects: impl IntoIterator<Item = &'a IcalCalendarObject>,
+        out: impl Write,
+    ) -> std::io::Result<()> {
+        let occurrences = objects.into_iter().flat_map(|object| {
+            let events = match object.get_inner() {
+                CalendarInnerData::Event(main, overrides) => {
+                    std::iter::once(main).chain(overrides).collect()
+                }
+                _ => vec![],
+            };
+            events
+                .into_iter()
+                .map(|event| Occurrence::new(object, event.clone(), &self.tz))
+                .collect::<Vec<_>>()
+        });
+        self.write(occurrences, out)
+    }
+
+    /// Writes a row for every occurrence listed on the days of `range`, ordered by start.
+    /// Like [`crate::agenda`] the days are taken in the export's timezone.
+    pub fn export_occurrences<'a>(
+        &self,
+        objects: impl IntoIterator<Item = &'a IcalCalendarObject>,
+        range: RangeInclusive<NaiveDate>,
+        out: impl Write,
+    ) -> std::io::Result<()> {
+        let mut rows: Vec<Occurrence<'a>> = objects
+            .into_iter()
+            .flat_map(|object| occurrences(object, &range, &self.tz))
+            .collect();
+        rows.sort_by(|a, b| {
+            (a.start, a.end, a.event.get_uid()).cmp(&(b.start, b.end, b.event.get_uid()))
+        });
+        self.write(rows, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvColumn, CsvExporter};
+    use crate::{IcalObjectParser, component::IcalCalendarObject};
+    use chrono::NaiveDate;
+
+    fn object(lines: &str) -> IcalCalendarObject {
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\n{lines}END:VCALENDAR\r\n"
+        );
+        IcalObjectParser::from_slice(ics.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn objects() -> [IcalCalendarObject; 2] {
+        [
+            object(
+                "BEGIN:VEVENT\r\nUID:standup\r\nDTSTAMP:20260101T000000Z\r\nDTSTART:20260319T080000Z\r\nDURATION:PT15M\r\nRRULE:FREQ=DAILY;COUNT=3\r\nSUMMARY:Stand-up\\, daily\r\nATTENDEE:mailto:jane@example.com\r\nATTENDEE:mailto:john@example.com\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:standup\r\nDTSTAMP:20260101T000000Z\r\nRECURRENCE-ID:20260320T080000Z\r\nDTSTART:20260320T090000Z\r\nDURATION:PT15M\r\nSUMMARY:Moved stand-up\r\nEND:VEVENT\r\n",
+            ),
+            object(
+                "BEGIN:VEVENT\r\nUID:holiday\r\nDTSTAMP:20260101T000000Z\r\nDTSTART;VALUE=DATE:20260320\r\nSUMMARY:Holiday\r\nLOCATION:Beach\r\nEND:VEVENT\r\n",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_export_events() {
+        let mut out = vec![];
+        CsvExporter::new()
+            .export_events(&objects(), &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "UID,Summary,Start,End,All Day,Location
+standup,\"Stand-up, daily\",2026-03-19 08:00:00,2026-03-19 08:15:00,FALSE,
+standup,Moved stand-up,2026-03-20 09:00:00,2026-03-20 09:15:00,FALSE,
+holiday,Holiday,2026-03-20,2026-03-21,TRUE,Beach
+"
+        );
+    }
+
+    #[test]
+    fn test_export_occurrences() {
+        let mut out = vec![];
+        CsvExporter::new()
+            .with_timezone(chrono_tz::Europe::Berlin.into())
+            .with_columns(vec![
+                CsvColumn::Start,
+                CsvColumn::RecurrenceId,
+                CsvColumn::Summary,
+                CsvColumn::Attendees,
+                CsvColumn::Property("DTSTAMP".to_owned()),
+            ])
+            .export_occurrences(
+                &objects(),
+                date("2026-03-20")..=date("2026-03-21"),
+                &mut out,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Start,Recurrence ID,Summary,Attendees,DTSTAMP
+2026-03-20,,Holiday,,20260101T000000Z
+2026-03-20 10:00:00,2026-03-20 09:00:00,Moved stand-up,,20260101T000000Z
+2026-03-21 09:00:00,2026-03-21 09:00:00,\"Stand-up, daily\",jane@example.com; john@example.com,20260101T000000Z
+"
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use crate::{
+    component::{ComponentMut, IcalEvent, IcalEventBuilder, content_hash::Fnv1a},
+    parser::{ContentLine, ParserError, ParserOptions},
+    property::{IcalDTENDProperty, IcalDTSTARTProperty},
+    types::{CalDateOrDateTime, CalDateTime, CalDateTimeError, Tz, escape_text},
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hasher,
+    io::Read,
+};
+
+const DATE_FORMATS: &[&str] = &["%m/%d/%Y", "%Y-%m-%d", "%d.%m.%Y"];
+const TIME_FORMATS: &[&str] = &["%I:%M %p", "%I:%M:%S %p", "%H:%M", "%H:%M:%S"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum CsvImportError {
+    #[error(transparent)]
+    Csv(#[from] ::csv::Error),
+    #[error("Missing column {0}")]
+    MissingColumn(&'static str),
+    #[error("Line {line}: Invalid {column} {value}")]
+    InvalidValue {
+        line: u64,
+        column: &'static str,
+        value: String,
+    },
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+}
+
+/// The columns of the Google and Outlook CSV calendar format we understand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Column {
+    Subject,
+    StartDate,
+    StartTime,
+    EndDate,
+    EndTime,
+    AllDay,
+    Description,
+    Location,
+    Private,
+    Categories,
+}
+
+impl Column {
+    fn parse(header: &str) -> Option<Self> {
+        Some(match header.trim().to_ascii_lowercase().as_str() {
+            "subject" => Self::Subject,
+            "start date" => Self::StartDate,
+            "start time" => Self::StartTime,
+            "end date" => Self::EndDate,
+            "end time" => Self::EndTime,
+            "all day event" => Self::AllDay,
+            "description" => Self::Description,
+            "location" => Self::Location,
+            "private" => Self::Private,
+            "categories" => Self::Categories,
+            _ => return None,
+        })
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Subject => "Subject",
+            Self::StartDate => "Start Date",
+            Self::StartTime => "Start Time",
+            Self::EndDate => "End Date",
+            Self::EndTime => "End Time",
+            Self::AllDay => "All Day Event",
+            Self::Description => "Description",
+            Self::Location => "Location",
+            Self::Private => "Private",
+            Self::Categories => "Categories",
+        }
+    }
+}
+
+struct Row<'a> {
+    record: &'a ::csv::StringRecord,
+    columns: &'a HashMap<Column, usize>,
+}
+
+impl Row<'_> {
+    /// The trimmed value of `column`, `None` if it's missing or empty
+    fn get(&self, column: Column) -> Option<&str> {
+        let value = self.record.get(*self.columns.get(&column)?)?.trim();
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn invalid(&self, column: Column, value: &str) -> CsvImportError {
+        CsvImportError::InvalidValue {
+            line: self.record.position().map_or(0, ::csv::Position::line),
+            column: column.name(),
+            value: value.to_owned(),
+        }
+    }
+
+    fn date(&self, column: Column) -> Result<Option<NaiveDate>, CsvImportError> {
+        let Some(value) = self.get(column) else {
+            return Ok(None);
+        };
+        DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+            .map(Some)
+            .ok_or_else(|| self.invalid(column, value))
+    }
+
+    fn time(&self, column: Column) -> Result<Option<NaiveTime>, CsvImportError> {
+        let Some(value) = self.get(column) else {
+            return Ok(None);
+        };
+        TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+            .map(Some)
+            .ok_or_else(|| self.invalid(column, value))
+    }
+
+    fn flag(&self, column: Column) -> bool {
+        self.get(column).is_some_and(|value| {
+            ["true", "yes", "1"]
+                .iter()
+                .any(|flag| value.eq_ignore_ascii_case(flag))
+        })
+    }
+}
+
+/// Imports events from CSV files in the format Google Calendar and Outlook use,
+/// e.g. with the columns `Subject`, `Start Date`, `Start Time`, `End Date`, `End Time`,
+/// `All Day Event`, `Description`, `Location` and `Private`.
+///
+/// Column names are matched case-insensitively, unknown columns are ignored.
+/// Dates may be formatted as `MM/DD/YYYY`, `YYYY-MM-DD` or `DD.MM.YYYY`,
+/// times as `HH:MM AM` or `HH:MM` with optional seconds.
+/// Like in Google's format the end date of all-day events is inclusive.
+#[derive(Debug, Clone)]
+pub struct CsvImporter {
+    tz: Option<chrono_tz::Tz>,
+    uid_domain: String,
+}
+
+impl Default for CsvImporter {
+    fn default() -> Self {
+        Self {
+            tz: None,
+            uid_domain: "caldata".to_owned(),
+        }
+    }
+}
+
+impl CsvImporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The timezone of start and end times, floating by default
+    pub fn with_timezone(mut self, tz: chrono_tz::Tz) -> Self {
+        self.tz = Some(tz);
+        self
+    }
+
+    /// The domain part of generated UIDs
+    pub fn with_uid_domain(mut self, domain: String) -> Self {
+        self.uid_domain = domain;
+        self
+    }
+
+    fn datetime(&self, value: NaiveDateTime) -> Result<CalDateOrDateTime, CalDateTimeError> {
+        let Some(tz) = self.tz else {
+            return Ok(value.into());
+        };
+        let datetime = value
+            .and_local_timezone(Tz::from(tz))
+            .earliest()
+            .ok_or(CalDateTimeError::LocalTimeGap)?;
+        Ok(CalDateTime(datetime).into())
+    }
+
+    /// A UID derived from the content of the row, so importing the same file again
+    /// results in the same UIDs. Identical rows are told apart by a counter.
+    fn uid(&self, record: &::csv::StringRecord, uids: &mut HashSet<String>) -> String {
+        let mut hasher = Fnv1a::default();
+        for field in record {
+            hasher.write(field.as_bytes());
+            hasher.write_u8(0);
+        }
+        let hash = hasher.finish();
+        let mut uid = format!("{hash:016x}@{}", self.uid_domain);
+        let mut counter = 1;
+        while uids.contains(&uid) {
+            counter += 1;
+            uid = format!("{hash:016x}-{counter}@{}", self.uid_domain);
+        }
+        uids.insert(uid.clone());
+        uid
+    }
+
+    fn event(&self, row: &Row<'_>, uid: String) -> Result<IcalEvent, CsvImportError> {
+        let start_date = row
+            .date(Column::StartDate)?
+            .ok_or_else(|| row.invalid(Column::StartDate, ""))?;
+        let start_time = row.time(Column::StartTime)?;
+        let end_date = row.date(Column::EndDate)?;
+        let end_time = row.time(Column::EndTime)?;
+
+        let (start, end) = match start_time {
+            Some(start_time) if !row.flag(Column::AllDay) => {
+                let start = self
+                    .datetime(start_date.and_time(start_time))
+                    .map_err(ParserError::from)?;
+                let end = match end_time {
+                    Some(end_time) => Some(
+                        self.datetime(end_date.unwrap_or(start_date).and_time(end_time))
+                            .map_err(ParserError::from)?,
+                    ),
+                    None => None,
+                };
+                (start, end)
+            }
+            _ => {
+                let end = end_date.unwrap_or(start_date).max(start_date) + Duration::days(1);
+                (start_date.into(), Some(end.into()))
+            }
+        };
+
+        let mut builder = IcalEventBuilder::new()
+            .with_uid(uid)
+            .with_dtstamp(Utc::now().into());
+        let tzid = self.tz.filter(|_| !start.is_date()).map(|tz| tz.name());
+        let mut zoned = |mut line: ContentLine| {
+            if let Some(tzid) = tzid {
+                line.params
+                    .replace_param("TZID".to_owned(), tzid.to_owned());
+            }
+            builder.properties.push(line);
+        };
+        zoned(IcalDTSTARTProperty(start, Default::default()).into());
+        if let Some(end) = end {
+            zoned(IcalDTENDProperty(end, Default::default()).into());
+        }
+        for (column, name) in [
+            (Column::Subject, "SUMMARY"),
+            (Column::Description, "DESCRIPTION"),
+            (Column::Location, "LOCATION"),
+        ] {
+            if let Some(value) = row.get(column) {
+                builder.properties.push(ContentLine {
+                    name: name.to_owned(),
+                    params: Default::default(),
+                    value: escape_text(value),
+                });
+            }
+        }
+        if let Some(categories) = row.get(Column::Categories) {
+            // Outlook separates categories by semicolons
+            let categories: Vec<String> = categories
+                .split([';', ','])
+                .map(str::trim)
+                .filter(|category| !category.is_empty())
+                .map(escape_text)
+                .collect();
+            builder.properties.push(ContentLine {
+                name: "CATEGORIES".to_owned(),
+                params: Default::default(),
+                value: categories.join(","),
+            });
+        }
+        if row.flag(Column::Private) {
+            builder.properties.push(ContentLine {
+                name: "CLASS".to_owned(),
+                params: Default::default(),
+                value: "PRIVATE".to_owned(),
+            });
+        }
+
+        let timezones = HashMap::from_iter(
+            tzid.zip(self.tz)
+                .map(|(name, tz)| (name.to_owned(), Some(tz.into()))),
+        );
+        Ok(builder.build(&ParserOptions::default(), Some(&timezones))?)
+    }
+
+    /// Reads a CSV file with a header row and creates an event for every row
+    pub fn import(&self, input: impl Read) -> Result<Vec<IcalEvent>, CsvImportError> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(input);
+        let columns: HashMap<Column, usize> = reader
+            .headers()?
+            .iter()
+            .enumerate()
+            .filter_map(|(index, header)| Some((Column::parse(header)?, index)))
+            .collect();
+        for required in [Column::Subject, Column::StartDate] {
+            if !columns.contains_key(&required) {
+                return Err(CsvImportError::MissingColumn(required.name()));
+            }
+        }
+
+        let mut uids = HashSet::new();
+        let mut events = vec![];
+        for record in reader.records() {
+            let record = record?;
+            let uid = self.uid(&record, &mut uids);
+            let row = Row {
+                record: &record,
+                columns: &columns,
+            };
+            events.push(self.event(&row, uid)?);
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvImportError, CsvImporter};
+    use crate::{component::Component, generator::Emitter};
+
+    const GOOGLE: &str = "\
+Subject,Start Date,Start Time,End Date,End Time,All Day Event,Description,Location,Private
+Final exam,05/12/2026,10:00 AM,05/12/2026,1:00 PM,False,\"Bring pencils, eraser\",Room 101,True
+Vacation,05/20/2026,,05/22/2026,,True,,,False
+Standup,05/13/2026,09:00,,,,,,
+Standup,05/13/2026,09:00,,,,,,
+";
+
+    #[test]
+    fn test_import() {
+        let events = CsvImporter::new().import(GOOGLE.as_bytes()).unwrap();
+        assert_eq!(events.len(), 4);
+        let lines = |index: usize| {
+            let event = &events[index];
+            [
+                "DTSTART",
+                "DTEND",
+                "SUMMARY",
+                "DESCRIPTION",
+                "LOCATION",
+                "CLASS",
+            ]
+            .into_iter()
+            .filter_map(|name| event.get_property(name))
+            .map(|prop| prop.generate())
+            .collect::<String>()
+        };
+        assert_eq!(
+            lines(0),
+            "DTSTART:20260512T100000\r\nDTEND:20260512T130000\r\nSUMMARY:Final exam\r\n\
+             DESCRIPTION:Bring pencils\\, eraser\r\nLOCATION:Room 101\r\nCLASS:PRIVATE\r\n"
+        );
+        assert_eq!(
+            lines(1),
+            "DTSTART;VALUE=DATE:20260520\r\nDTEND;VALUE=DATE:20260523\r\nSUMMARY:Vacation\r\n"
+        );
+        assert_eq!(lines(2), "DTSTART:20260513T090000\r\nSUMMARY:Standup\r\n");
+
+        // UIDs are stable and unique
+        let again = CsvImporter::new().import(GOOGLE.as_bytes()).unwrap();
+        assert!(
+            events
+                .iter()
+                .zip(&again)
+                .all(|(a, b)| a.get_uid() == b.get_uid())
+        );
+        assert_ne!(events[2].get_uid(), events[3].get_uid());
+        assert!(events[0].get_uid().ends_with("@caldata"));
+    }
+
+    #[test]
+    fn test_import_outlook() {
+        let outlook = "\"Subject\",\"Start Date\",\"Start Time\",\"End Date\",\"End Time\",\"All day event\",\"Categories\"
+\"Review\",\"2026-03-23\",\"14:30:00\",\"2026-03-23\",\"15:00:00\",\"False\",\"Work;Important\"
+";
+        let events = CsvImporter::new()
+            .with_timezone(chrono_tz::Europe::Berlin)
+            .with_uid_domain("example.com".to_owned())
+            .import(outlook.as_bytes())
+            .unwrap();
+        let event = &events[0];
+        assert_eq!(
+            event.get_property("DTSTART").unwrap().generate(),
+            "DTSTART;TZID=Europe/Berlin:20260323T143000\r\n"
+        );
+        assert_eq!(
+            event.dtend.as_ref().unwrap().0.utc().to_rfc3339(),
+            "2026-03-23T14:00:00+00:00"
+        );
+        assert_eq!(
+            event.get_property("CATEGORIES").unwrap().value,
+            "Work,Important"
+        );
+        assert!(event.get_uid().ends_with("@example.com"));
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(
+            CsvImporter::new().import("Title,Start Date\nA,05/12/2026\n".as_bytes()),
+            Err(CsvImportError::MissingColumn("Subject"))
+        ));
+        assert!(matches!(
+            CsvImporter::new().import("Subject,Start Date\nA,tomorrow\n".as_bytes()),
+            Err(CsvImportError::InvalidValue {
+                line: 2,
+                column: "Start Date",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+//! Spreadsheet-friendly CSV export and import of events
+mod export;
+pub use export::*;
+mod import;
+pub use import::*;
@@ -0,0 +1,669 @@
+//! Structured changes between two versions of a calendar.
+//!
+//! Components are matched by their UID and RECURRENCE-ID, properties of matched components
+//! by their name (and their value for ATTENDEE) so a changed PARTSTAT shows up as a modified
+//! attendee rather than a removed and an added one.
+//! Properties are compared like in [`SemanticEq`](crate::component::SemanticEq),
+//! so representational differences don't count as changes.
+//! Free/busy information and alarms outside of events and to-dos are not compared.
+use crate::{
+    component::{
+        CalendarInnerData, Component, IcalAlarm, IcalCalendar, IcalCalendarObject, IcalEvent,
+        IcalJournal, IcalTimeZone, IcalTodo, Timezones, normalized, recurrence_id,
+        semantic_eq::{alarms, canonical_line},
+    },
+    parser::ContentLine,
+};
+use std::collections::{BTreeMap, HashMap, btree_map::Entry};
+
+mod patch;
+pub use patch::*;
+mod sequence;
+pub use sequence::*;
+
+/// Identifies a component across versions of a calendar
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ComponentKey {
+    /// VEVENT, VTODO or VJOURNAL
+    pub name: &'static str,
+    pub uid: String,
+    /// The RECURRENCE-ID of overrides, converted to UTC if it's in a known timezone
+    pub recurrence_id: Option<String>,
+}
+
+/// A component that can be added or changed by a [`Diff`]
+#[derive(Debug, Clone)]
+pub enum DiffComponent {
+    Event(IcalEvent),
+    Todo(IcalTodo),
+    Journal(IcalJournal),
+}
+
+impl DiffComponent {
+    pub fn get_uid(&self) -> &str {
+        match self {
+            Self::Event(event) => event.get_uid(),
+            Self::Todo(todo) => todo.get_uid(),
+            Self::Journal(journal) => journal.get_uid(),
+        }
+    }
+
+    pub fn get_properties(&self) -> &[ContentLine] {
+        match self {
+            Self::Event(event) => event.get_properties(),
+            Self::Todo(todo) => todo.get_properties(),
+            Self::Journal(journal) => journal.get_properties(),
+        }
+    }
+
+    pub fn get_alarms(&self) -> &[IcalAlarm] {
+        match self {
+            Self::Event(event) => event.get_alarms(),
+            Self::Todo(todo) => todo.get_alarms(),
+            Self::Journal(_) => &[],
+        }
+    }
+
+    fn get_comp_name(&self) -> &'static str {
+        match self {
+            Self::Event(event) => event.get_comp_name(),
+            Self::Todo(todo) => todo.get_comp_name(),
+            Self::Journal(journal) => journal.get_comp_name(),
+        }
+    }
+
+    fn key(&self, timezones: &Timezones) -> ComponentKey {
+        component_key(
+            self.get_comp_name(),
+            self.get_uid(),
+            self.get_properties(),
+            timezones,
+        )
+    }
+}
+
+fn component_key(
+    name: &'static str,
+    uid: &str,
+    properties: &[ContentLine],
+    timezones: &Timezones,
+) -> ComponentKey {
+    ComponentKey {
+        name,
+        uid: uid.to_owned(),
+        recurrence_id: recurrence_id(properties, timezones),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyChange {
+    Added(ContentLine),
+    Removed(ContentLine),
+    Modified { old: ContentLine, new: ContentLine },
+}
+
+impl PropertyChange {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Added(prop) | Self::Removed(prop) | Self::Modified { new: prop, .. } => {
+                &prop.name
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ComponentChange {
+    Added(ComponentKey, Box<DiffComponent>),
+    Removed(ComponentKey),
+    Modified {
+        key: ComponentKey,
+        properties: Vec<PropertyChange>,
+        /// The new alarms if they changed
+        alarms: Option<Vec<IcalAlarm>>,
+    },
+}
+
+impl ComponentChange {
+    pub fn key(&self) -> &ComponentKey {
+        match self {
+            Self::Added(key, _) | Self::Removed(key) | Self::Modified { key, .. } => key,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Diff {
+    /// Changes to the properties of the calendar itself
+    pub properties: Vec<PropertyChange>,
+    pub components: Vec<ComponentChange>,
+    /// VTIMEZONE components of the new version referenced by added or modified components
+    pub vtimezones: BTreeMap<String, IcalTimeZone>,
+    /// Keys shared by several components of a version, only the first of them is compared
+    pub duplicates: Vec<ComponentKey>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.components.is_empty()
+    }
+}
+
+/// Properties of the same name that are told apart by their value
+const KEYED_BY_VALUE: &[&str] = &["ATTENDEE"];
+
+fn property_key(prop: &ContentLine) -> (&str, Option<&str>) {
+    let value = KEYED_BY_VALUE
+        .contains(&prop.name.as_str())
+        .then_some(prop.value.as_str());
+    (prop.name.as_str(), value)
+}
+
+/// Changes between two versions of the properties of a component
+fn diff_properties(
+    old: &[ContentLine],
+    old_timezones: &Timezones,
+    new: &[ContentLine],
+    new_timezones: &Timezones,
+) -> Vec<PropertyChange> {
+    type Group<'a> = (
+        Vec<(&'a ContentLine, ContentLine)>,
+        Vec<(&'a ContentLine, ContentLine)>,
+    );
+    // Group by key, keeping the order in which keys first appear
+    let mut keys = vec![];
+    let mut groups: HashMap<(&str, Option<&str>), Group> = HashMap::new();
+    for (prop, normalized) in old.iter().zip(normalized(old, old_timezones)) {
+        let key = property_key(prop);
+        if !groups.contains_key(&key) {
+            keys.push(key);
+        }
+        groups.entry(key).or_default().0.push((prop, normalized));
+    }
+    for (prop, normalized) in new.iter().zip(normalized(new, new_timezones)) {
+        let key = property_key(prop);
+        if !groups.contains_key(&key) {
+            keys.push(key);
+        }
+        groups.entry(key).or_default().1.push((prop, normalized));
+    }
+
+    let mut changes = vec![];
+    for key in keys {
+        let (mut old, mut new) = groups.remove(&key).unwrap_or_default();
+        // Unchanged properties
+        old.retain(|(_, old)| {
+            let old = canonical_line(old.clone());
+            match new
+                .iter()
+                .position(|(_, new)| canonical_line(new.clone()) == old)
+            {
+                Some(index) => {
+                    new.remove(index);
+                    false
+                }
+                None => true,
+            }
+        });
+        let mut old = old.into_iter();
+        let mut new = new.into_iter();
+        loop {
+            match (old.next(), new.next()) {
+                (Some((old, _)), Some((new, _))) => changes.push(PropertyChange::Modified {
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                (Some((old, _)), None) => changes.push(PropertyChange::Removed(old.clone())),
+                (None, Some((new, _))) => changes.push(PropertyChange::Added(new.clone())),
+                (None, None) => break,
+            }
+        }
+    }
+    changes
+}
+
+/// The components by their key, keys of further components are added to `duplicates`
+fn by_key(
+    components: Vec<DiffComponent>,
+    timezones: &Timezones,
+    duplicates: &mut Vec<ComponentKey>,
+) -> BTreeMap<ComponentKey, DiffComponent> {
+    let mut by_key = BTreeMap::new();
+    for component in components {
+        match by_key.entry(component.key(timezones)) {
+            Entry::Vacant(entry) => {
+                entry.insert(component);
+            }
+            Entry::Occupied(entry) => {
+                if !duplicates.contains(entry.key()) {
+                    duplicates.push(entry.key().clone());
+                }
+            }
+        }
+    }
+    by_key
+}
+
+fn diff_components(
+    old: Vec<DiffComponent>,
+    old_timezones: &Timezones,
+    new: Vec<DiffComponent>,
+    new_timezones: &Timezones,
+    duplicates: &mut Vec<ComponentKey>,
+) -> Vec<ComponentChange> {
+    let mut old = by_key(old, old_timezones, duplicates);
+    let new = by_key(new, new_timezones, duplicates);
+
+    let mut changes = vec![];
+    for (key, new) in new {
+        let Some(old) = old.remove(&key) else {
+            changes.push(ComponentChange::Added(key, Box::new(new)));
+            continue;
+        };
+        let properties = diff_properties(
+            old.get_properties(),
+            old_timezones,
+            new.get_properties(),
+            new_timezones,
+        );
+        let mut old_alarms = alarms(old.get_alarms(), Some(old_timezones));
+        let mut new_alarms = alarms(new.get_alarms(), Some(new_timezones));
+        old_alarms.sort();
+        new_alarms.sort();
+        let alarms = (old_alarms != new_alarms).then(|| new.get_alarms().to_vec());
+        if !properties.is_empty() || alarms.is_some() {
+            changes.push(ComponentChange::Modified {
+                key,
+                properties,
+                alarms,
+            });
+        }
+    }
+    changes.extend(old.into_keys().map(ComponentChange::Removed));
+    changes
+}
+
+fn vtimezones_for(
+    components: &[ComponentChange],
+    vtimezones: &BTreeMap<String, IcalTimeZone>,
+) -> BTreeMap<String, IcalTimeZone> {
+    let tzids: Vec<&str> = components
+        .iter()
+        .flat_map(|change| -> Box<dyn Iterator<Item = &ContentLine>> {
+            match change {
+                ComponentChange::Added(_, component) => Box::new(
+                    component.get_properties().iter().chain(
+                        component
+                            .get_alarms()
+                            .iter()
+                            .flat_map(|alarm| alarm.get_properties()),
+                    ),
+                ),
+                ComponentChange::Modified {
+                    properties, alarms, ..
+                } => Box::new(
+                    properties
+                        .iter()
+                        .filter_map(|change| match change {
+                            PropertyChange::Added(prop)
+                            | PropertyChange::Modified { new: prop, .. } => Some(prop),
+                            PropertyChange::Removed(_) => None,
+                        })
+                        .chain(
+                            alarms
+                                .iter()
+                                .flatten()
+                                .flat_map(|alarm| alarm.get_properties()),
+                        ),
+                ),
+                ComponentChange::Removed(_) => Box::new(std::iter::empty()),
+            }
+        })
+        .filter_map(|prop| prop.params.get_tzid())
+        .collect();
+    vtimezones
+        .iter()
+        .filter(|(tzid, _)| tzids.contains(&tzid.as_str()))
+        .map(|(tzid, tz)| (tzid.to_owned(), tz.clone()))
+        .collect()
+}
+
+fn calendar_components(calendar: &IcalCalendar) -> Vec<DiffComponent> {
+    (calendar.events.iter().cloned().map(DiffComponent::Event))
+        .chain(calendar.todos.iter().cloned().map(DiffComponent::Todo))
+        .chain(
+            calendar
+                .journals
+                .iter()
+                .cloned()
+                .map(DiffComponent::Journal),
+        )
+        .collect()
+}
+
+fn object_components(object: &IcalCalendarObject) -> Vec<DiffComponent> {
+    match object.get_inner() {
+        CalendarInnerData::Event(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .cloned()
+            .map(DiffComponent::Event)
+            .collect(),
+        CalendarInnerData::Todo(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .cloned()
+            .map(DiffComponent::Todo)
+            .collect(),
+        CalendarInnerData::Journal(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .cloned()
+            .map(DiffComponent::Journal)
+            .collect(),
+    }
+}
+
+/// Changes from `old` to `new`
+pub fn diff_calendars(old: &IcalCalendar, new: &IcalCalendar) -> Diff {
+    let mut duplicates = vec![];
+    let components = diff_components(
+        calendar_components(old),
+        &old.timezones,
+        calendar_components(new),
+        &new.timezones,
+        &mut duplicates,
+    );
+    Diff {
+        properties: diff_properties(
+            &old.properties,
+            &old.timezones,
+            &new.properties,
+            &new.timezones,
+        ),
+        vtimezones: vtimezones_for(&components, &new.vtimezones),
+        components,
+        duplicates,
+    }
+}
+
+/// Changes from `old` to `new`
+pub fn diff_objects(old: &IcalCalendarObject, new: &IcalCalendarObject) -> Diff {
+    let mut duplicates = vec![];
+    let components = diff_components(
+        object_components(old),
+        old.get_timezones(),
+        object_components(new),
+        new.get_timezones(),
+        &mut duplicates,
+    );
+    Diff {
+        properties: diff_properties(
+            &old.properties,
+            old.get_timezones(),
+            &new.properties,
+            new.get_timezones(),
+        ),
+        vtimezones: vtimezones_for(&components, new.get_vtimezones()),
+        components,
+        duplicates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentChange, ComponentKey, PropertyChange, bump_sequence, diff_calendars};
+    use crate::{
+        IcalObjectParser, IcalParser,
+        component::{CalendarInnerData, Component, IcalCalendarObject},
+    };
+
+    const BASE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=3\r
+SUMMARY:Meeting\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:john@example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID:20260330T080000Z\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260330T090000Z\r
+DURATION:PT1H\r
+SUMMARY:Meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_diff_objects() {
+        use super::diff_objects;
+
+        let changed = BASE
+            .replace(
+                "ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:jane",
+                "ATTENDEE;PARTSTAT=ACCEPTED:mailto:jane",
+            )
+            .replace("RRULE:FREQ=WEEKLY;COUNT=3", "RRULE:COUNT=3;FREQ=WEEKLY")
+            .replace(
+                "RECURRENCE-ID:20260330T080000Z",
+                "RECURRENCE-ID:20260406T080000Z",
+            )
+            .replace(
+                "SUMMARY:Meeting\r\nEND:VEVENT\r\nEND:VCALENDAR",
+                "SUMMARY:Moved meeting\r\nEND:VEVENT\r\nEND:VCALENDAR",
+            );
+        let old = IcalObjectParser::from_slice(BASE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let new = IcalObjectParser::from_slice(changed.as_bytes())
+            .expect_one()
+            .unwrap();
+        let diff = diff_objects(&old, &new);
+        assert!(diff.properties.is_empty());
+        assert_eq!(diff.components.len(), 3);
+
+        let ComponentChange::Modified {
+            key,
+            properties,
+            alarms,
+        } = &diff.components[0]
+        else {
+            panic!("{:?}", diff.components[0]);
+        };
+        assert_eq!(
+            key,
+            &ComponentKey {
+                name: "VEVENT",
+                uid: "meeting@example.com".to_owned(),
+                recurrence_id: None
+            }
+        );
+        assert!(alarms.is_none());
+        let [PropertyChange::Modified { old, new }] = properties.as_slice() else {
+            panic!("{properties:?}");
+        };
+        assert_eq!(old.params.get_param("PARTSTAT"), Some("NEEDS-ACTION"));
+        assert_eq!(new.params.get_param("PARTSTAT"), Some("ACCEPTED"));
+
+        let ComponentChange::Added(key, _) = &diff.components[1] else {
+            panic!("{:?}", diff.components[1]);
+        };
+        assert_eq!(key.recurrence_id.as_deref(), Some("20260406T080000Z"));
+        let ComponentChange::Removed(key) = &diff.components[2] else {
+            panic!("{:?}", diff.components[2]);
+        };
+        assert_eq!(key.recurrence_id.as_deref(), Some("20260330T080000Z"));
+    }
+
+    #[test]
+    fn test_diff_calendars() {
+        let old = IcalParser::from_slice(BASE.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(diff_calendars(&old, &old).is_empty());
+
+        let changed = BASE
+            .replace("VERSION:2.0\r\n", "VERSION:2.0\r\nX-WR-CALNAME:Work\r\n")
+            .replace(
+                "ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:john@example.com\r\n",
+                "",
+            );
+        let new = IcalParser::from_slice(changed.as_bytes())
+            .expect_one()
+            .unwrap();
+        let diff = diff_calendars(&old, &new);
+        assert!(matches!(
+            diff.properties.as_slice(),
+            [PropertyChange::Added(prop)] if prop.name == "X-WR-CALNAME"
+        ));
+        let [ComponentChange::Modified { properties, .. }] = diff.components.as_slice() else {
+            panic!("{:?}", diff.components);
+        };
+        assert!(matches!(
+            properties.as_slice(),
+            [PropertyChange::Removed(prop)] if prop.value == "mailto:john@example.com"
+        ));
+    }
+
+    const BERLIN: &str = "BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+DTSTART:19971026T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:+0100\r
+TZOFFSETTO:+0200\r
+DTSTART:19970330T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+";
+
+    #[test]
+    fn test_diff_timezones() {
+        let old = IcalParser::from_slice(BASE.as_bytes())
+            .expect_one()
+            .unwrap();
+        // The same first instance, but later instances are expanded in Berlin time
+        let zoned = BASE
+            .replace("BEGIN:VEVENT\r\n", &format!("{BERLIN}BEGIN:VEVENT\r\n"))
+            .replacen(
+                "DTSTART:20260323T080000Z",
+                "DTSTART;TZID=Europe/Berlin:20260323T090000",
+                1,
+            )
+            .replace(
+                "RECURRENCE-ID:20260330T080000Z",
+                "RECURRENCE-ID;TZID=Europe/Berlin:20260330T100000",
+            );
+        let new = IcalParser::from_slice(zoned.as_bytes())
+            .expect_one()
+            .unwrap();
+        let diff = diff_calendars(&old, &new);
+        // The override is matched by the instant of its RECURRENCE-ID
+        let [
+            ComponentChange::Modified {
+                key, properties, ..
+            },
+        ] = diff.components.as_slice()
+        else {
+            panic!("{:?}", diff.components);
+        };
+        assert_eq!(key.recurrence_id, None);
+        assert!(matches!(
+            properties.as_slice(),
+            [PropertyChange::Modified { new, .. }] if new.name == "DTSTART"
+        ));
+        assert!(diff.vtimezones.contains_key("Europe/Berlin"));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let old = IcalParser::from_slice(BASE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let override_start = BASE.rfind("BEGIN:VEVENT").unwrap();
+        let override_end = BASE.rfind("END:VCALENDAR").unwrap();
+        let duplicated = BASE.replace(
+            "END:VCALENDAR",
+            &format!(
+                "{}END:VCALENDAR",
+                BASE[override_start..override_end].replace("SUMMARY:Meeting", "SUMMARY:Other")
+            ),
+        );
+        let new = IcalParser::from_slice(duplicated.as_bytes())
+            .expect_one()
+            .unwrap();
+        let diff = diff_calendars(&old, &new);
+        assert!(diff.components.is_empty());
+        assert_eq!(
+            diff.duplicates,
+            [ComponentKey {
+                name: "VEVENT",
+                uid: "meeting@example.com".to_owned(),
+                recurrence_id: Some("20260330T080000Z".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bump_sequence() {
+        let parse = |input: &str| {
+            IcalObjectParser::from_slice(input.as_bytes())
+                .expect_one()
+                .unwrap()
+        };
+        let sequences = |object: &IcalCalendarObject| {
+            let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+                panic!();
+            };
+            std::iter::once(main)
+                .chain(overrides)
+                .map(|event| event.get_property("SEQUENCE").map(|seq| seq.value.clone()))
+                .collect::<Vec<_>>()
+        };
+        let old = parse(BASE);
+
+        // A changed participation status isn't significant
+        let accepted = BASE.replace(
+            "ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:jane",
+            "ATTENDEE;PARTSTAT=ACCEPTED:mailto:jane",
+        );
+        let new = bump_sequence(&old, parse(&accepted)).unwrap();
+        assert_eq!(sequences(&new), vec![None, None]);
+
+        let moved = BASE.replace("DTSTART:20260330T090000Z", "DTSTART:20260330T100000Z");
+        let new = bump_sequence(&old, parse(&moved)).unwrap();
+        assert_eq!(sequences(&new), vec![None, Some("1".to_owned())]);
+        // Bumping again against the same version doesn't increment it twice
+        let new = bump_sequence(&old, new).unwrap();
+        assert_eq!(sequences(&new), vec![None, Some("1".to_owned())]);
+
+        let located = BASE.replace(
+            "RRULE:FREQ=WEEKLY;COUNT=3\r\n",
+            "RRULE:FREQ=WEEKLY;COUNT=3\r\nLOCATION:Room 1\r\n",
+        );
+        let new = bump_sequence(&old, parse(&located)).unwrap();
+        assert_eq!(sequences(&new), vec![Some("1".to_owned()), None]);
+
+        // The first instance stays, but later ones are expanded in Berlin time
+        let zoned = BASE
+            .replace("BEGIN:VEVENT\r\n", &format!("{BERLIN}BEGIN:VEVENT\r\n"))
+            .replacen(
+                "DTSTART:20260323T080000Z",
+                "DTSTART;TZID=Europe/Berlin:20260323T090000",
+                1,
+            );
+        let new = bump_sequence(&old, parse(&zoned)).unwrap();
+        assert_eq!(sequences(&new), vec![Some("1".to_owned()), None]);
+    }
+}
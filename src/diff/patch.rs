@@ -0,0 +1,494 @@
+//! Application of diffs and three-way merges
+use super::{
+    ComponentChange, ComponentKey, Diff, DiffComponent, PropertyChange, Timezones, alarms,
+    canonical_line, component_key, diff_calendars, diff_properties, normalized, property_key,
+};
+use crate::{
+    component::{
+        Component, ComponentMut, IcalAlarm, IcalCalendar, IcalEvent, IcalJournal, IcalTodo,
+    },
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use std::collections::HashMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError {
+    #[error("component doesn't exist: {0:?}")]
+    MissingComponent(ComponentKey),
+    #[error("component already exists: {0:?}")]
+    ExistingComponent(ComponentKey),
+    #[error("property {1} doesn't match the patch in {0:?}")]
+    PropertyMismatch(Option<ComponentKey>, String),
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+}
+
+/// Components that can be added or changed by a patch
+trait PatchTarget: Component<Builder: ComponentMut<Verified = Self>> {
+    fn get_uid(&self) -> &str;
+    fn set_alarms(builder: &mut Self::Builder, alarms: &[IcalAlarm]);
+    fn from_diff(component: &DiffComponent) -> Option<Self>;
+}
+
+impl PatchTarget for IcalEvent {
+    fn get_uid(&self) -> &str {
+        self.get_uid()
+    }
+
+    fn set_alarms(builder: &mut Self::Builder, alarms: &[IcalAlarm]) {
+        builder.alarms = alarms.iter().cloned().map(Component::mutable).collect();
+    }
+
+    fn from_diff(component: &DiffComponent) -> Option<Self> {
+        match component {
+            DiffComponent::Event(event) => Some(event.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl PatchTarget for IcalTodo {
+    fn get_uid(&self) -> &str {
+        self.get_uid()
+    }
+
+    fn set_alarms(builder: &mut Self::Builder, alarms: &[IcalAlarm]) {
+        builder.alarms = alarms.iter().cloned().map(Component::mutable).collect();
+    }
+
+    fn from_diff(component: &DiffComponent) -> Option<Self> {
+        match component {
+            DiffComponent::Todo(todo) => Some(todo.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl PatchTarget for IcalJournal {
+    fn get_uid(&self) -> &str {
+        self.get_uid()
+    }
+
+    fn set_alarms(_builder: &mut Self::Builder, _alarms: &[IcalAlarm]) {}
+
+    fn from_diff(component: &DiffComponent) -> Option<Self> {
+        match component {
+            DiffComponent::Journal(journal) => Some(journal.clone()),
+            _ => None,
+        }
+    }
+}
+
+fn same_line(a: &ContentLine, b: &ContentLine, timezones: &Timezones) -> bool {
+    a == b
+        || canonical_line(normalized(std::slice::from_ref(a), timezones).remove(0))
+            == canonical_line(normalized(std::slice::from_ref(b), timezones).remove(0))
+}
+
+fn patch_properties(
+    properties: &mut Vec<ContentLine>,
+    changes: &[PropertyChange],
+    timezones: &Timezones,
+    key: Option<&ComponentKey>,
+) -> Result<(), PatchError> {
+    for change in changes {
+        let (old, new) = match change {
+            PropertyChange::Added(new) => (None, Some(new)),
+            PropertyChange::Removed(old) => (Some(old), None),
+            PropertyChange::Modified { old, new } => (Some(old), Some(new)),
+        };
+        let Some(old) = old else {
+            properties.extend(new.cloned());
+            continue;
+        };
+        let index = properties
+            .iter()
+            .position(|prop| same_line(prop, old, timezones))
+            .ok_or_else(|| PatchError::PropertyMismatch(key.cloned(), old.name.to_owned()))?;
+        match new {
+            Some(new) => properties[index] = new.clone(),
+            None => {
+                properties.remove(index);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn patch_components<C: PatchTarget>(
+    components: &mut Vec<C>,
+    change: &ComponentChange,
+    timezones: &Timezones,
+) -> Result<(), PatchError> {
+    let key = change.key();
+    let position = components.iter().position(|component| {
+        &component_key(
+            component.get_comp_name(),
+            component.get_uid(),
+            component.get_properties(),
+            timezones,
+        ) == key
+    });
+    match (change, position) {
+        (ComponentChange::Added(_, component), None) => {
+            components.extend(C::from_diff(component));
+        }
+        (ComponentChange::Added(..), Some(_)) => {
+            return Err(PatchError::ExistingComponent(key.clone()));
+        }
+        (ComponentChange::Removed(_), Some(index)) => {
+            components.remove(index);
+        }
+        (
+            ComponentChange::Modified {
+                properties, alarms, ..
+            },
+            Some(index),
+        ) => {
+            let mut builder = components[index].clone().mutable();
+            patch_properties(
+                builder.get_properties_mut(),
+                properties,
+                timezones,
+                Some(key),
+            )?;
+            if let Some(alarms) = alarms {
+                C::set_alarms(&mut builder, alarms);
+            }
+            components[index] =
+                builder.build(&ParserOptions::reverify(timezones), Some(timezones))?;
+        }
+        (_, None) => return Err(PatchError::MissingComponent(key.clone())),
+    }
+    Ok(())
+}
+
+/// Applies the changes of `diff` to `calendar`.
+///
+/// Fails if a change doesn't apply, e.g. if a modified property doesn't have its old value.
+pub fn apply_patch(mut calendar: IcalCalendar, diff: &Diff) -> Result<IcalCalendar, PatchError> {
+    for (tzid, vtimezone) in &diff.vtimezones {
+        if !calendar.vtimezones.contains_key(tzid) {
+            calendar
+                .timezones
+                .insert(tzid.to_owned(), vtimezone.resolve_or_fixed(&HashMap::new()));
+            calendar
+                .vtimezones
+                .insert(tzid.to_owned(), vtimezone.clone());
+        }
+    }
+    patch_properties(
+        &mut calendar.properties,
+        &diff.properties,
+        &calendar.timezones,
+        None,
+    )?;
+    for change in &diff.components {
+        match change.key().name {
+            "VEVENT" => patch_components(&mut calendar.events, change, &calendar.timezones)?,
+            "VTODO" => patch_components(&mut calendar.todos, change, &calendar.timezones)?,
+            _ => patch_components(&mut calendar.journals, change, &calendar.timezones)?,
+        }
+    }
+    Ok(calendar)
+}
+
+/// Concurrent changes of the local and the remote version
+#[derive(Debug, Clone)]
+pub enum Conflict {
+    /// Both versions added, removed or modified the same component in different ways
+    Component {
+        local: ComponentChange,
+        remote: ComponentChange,
+    },
+    /// Both versions changed the same property in different ways
+    Property {
+        component: Option<ComponentKey>,
+        local: PropertyChange,
+        remote: PropertyChange,
+    },
+    /// Both versions changed the alarms of a component in different ways
+    Alarms(ComponentKey),
+}
+
+#[derive(Debug, Clone)]
+pub struct Merge {
+    pub calendar: IcalCalendar,
+    /// Changes of the remote version that were dropped in favour of the local version
+    pub conflicts: Vec<Conflict>,
+}
+
+fn change_key(change: &PropertyChange) -> (&str, Option<&str>) {
+    match change {
+        PropertyChange::Added(prop)
+        | PropertyChange::Removed(prop)
+        | PropertyChange::Modified { old: prop, .. } => property_key(prop),
+    }
+}
+
+fn same_change(a: &PropertyChange, b: &PropertyChange, timezones: &Timezones) -> bool {
+    match (a, b) {
+        (PropertyChange::Added(a), PropertyChange::Added(b))
+        | (PropertyChange::Removed(a), PropertyChange::Removed(b))
+        | (PropertyChange::Modified { new: a, .. }, PropertyChange::Modified { new: b, .. }) => {
+            same_line(a, b, timezones)
+        }
+        _ => false,
+    }
+}
+
+/// The remote changes that don't conflict with local changes
+fn merge_properties(
+    local: &[PropertyChange],
+    remote: &[PropertyChange],
+    component: Option<&ComponentKey>,
+    timezones: &Timezones,
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<PropertyChange> {
+    let mut accepted = vec![];
+    for remote in remote {
+        let key = change_key(remote);
+        let local: Vec<_> = local
+            .iter()
+            .filter(|local| change_key(local) == key)
+            .collect();
+        if local.is_empty() {
+            accepted.push(remote.clone());
+        } else if !local
+            .iter()
+            .any(|local| same_change(local, remote, timezones))
+        {
+            conflicts.push(Conflict::Property {
+                component: component.cloned(),
+                local: local[0].clone(),
+                remote: remote.clone(),
+            });
+        }
+    }
+    accepted
+}
+
+fn same_alarms(a: &[IcalAlarm], b: &[IcalAlarm], timezones: &Timezones) -> bool {
+    let mut a = alarms(a, Some(timezones));
+    let mut b = alarms(b, Some(timezones));
+    a.sort();
+    b.sort();
+    a == b
+}
+
+/// Merges the changes from `base` to `local` and from `base` to `remote`.
+///
+/// Non-conflicting remote changes are applied to `local`,
+/// conflicting ones are dropped and reported.
+pub fn merge(
+    base: &IcalCalendar,
+    local: &IcalCalendar,
+    remote: &IcalCalendar,
+) -> Result<Merge, PatchError> {
+    let local_diff = diff_calendars(base, local);
+    let remote_diff = diff_calendars(base, remote);
+    let timezones = &local.timezones;
+    let mut conflicts = vec![];
+
+    let local_changes: HashMap<_, _> = local_diff
+        .components
+        .iter()
+        .map(|change| (change.key(), change))
+        .collect();
+    let mut components = vec![];
+    for remote_change in remote_diff.components {
+        let Some(local_change) = local_changes.get(remote_change.key()) else {
+            components.push(remote_change);
+            continue;
+        };
+        match (local_change, &remote_change) {
+            (ComponentChange::Removed(_), ComponentChange::Removed(_)) => {}
+            (ComponentChange::Added(_, a), ComponentChange::Added(_, b))
+                if diff_properties(
+                    a.get_properties(),
+                    timezones,
+                    b.get_properties(),
+                    &remote.timezones,
+                )
+                .is_empty()
+                    && same_alarms(a.get_alarms(), b.get_alarms(), timezones) => {}
+            (
+                ComponentChange::Modified {
+                    properties: local_properties,
+                    alarms: local_alarms,
+                    ..
+                },
+                ComponentChange::Modified {
+                    key,
+                    properties: remote_properties,
+                    alarms: remote_alarms,
+                },
+            ) => {
+                let properties = merge_properties(
+                    local_properties,
+                    remote_properties,
+                    Some(key),
+                    timezones,
+                    &mut conflicts,
+                );
+                let alarms = match (local_alarms, remote_alarms) {
+                    (Some(local), Some(remote)) => {
+                        if !same_alarms(local, remote, timezones) {
+                            conflicts.push(Conflict::Alarms(key.clone()));
+                        }
+                        None
+                    }
+                    (None, remote) => remote.clone(),
+                    (_, None) => None,
+                };
+                if !properties.is_empty() || alarms.is_some() {
+                    components.push(ComponentChange::Modified {
+                        key: key.clone(),
+                        properties,
+                        alarms,
+                    });
+                }
+            }
+            _ => conflicts.push(Conflict::Component {
+                local: (*local_change).clone(),
+                remote: remote_change.clone(),
+            }),
+        }
+    }
+
+    let mut duplicates = local_diff.duplicates.clone();
+    for key in remote_diff.duplicates {
+        if !duplicates.contains(&key) {
+            duplicates.push(key);
+        }
+    }
+    let accepted = Diff {
+        properties: merge_properties(
+            &local_diff.properties,
+            &remote_diff.properties,
+            None,
+            timezones,
+            &mut conflicts,
+        ),
+        components,
+        vtimezones: remote_diff.vtimezones,
+        duplicates,
+    };
+    Ok(Merge {
+        calendar: apply_patch(local.clone(), &accepted)?,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Conflict, Merge, PatchError, apply_patch, merge};
+    use crate::{
+        IcalParser,
+        component::{IcalCalendar, semantically_eq},
+        diff::{PropertyChange, diff_calendars},
+    };
+
+    const BASE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+SUMMARY:Meeting\r
+DESCRIPTION:Agenda\r
+ATTENDEE;PARTSTAT=NEEDS-ACTION:mailto:jane@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT10M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    const NEW_EVENT: &str = "BEGIN:VEVENT\r
+UID:lunch@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T110000Z\r
+DURATION:PT1H\r
+SUMMARY:Lunch\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn parse(input: &str) -> IcalCalendar {
+        IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let base = parse(BASE);
+        let remote = parse(
+            &BASE
+                .replace("SUMMARY:Meeting", "SUMMARY:Team meeting")
+                .replace("TRIGGER:-PT10M", "TRIGGER:-PT15M")
+                .replace("END:VCALENDAR\r\n", NEW_EVENT),
+        );
+        let diff = diff_calendars(&base, &remote);
+        let patched = apply_patch(base.clone(), &diff).unwrap();
+        assert!(semantically_eq(&patched, &remote));
+
+        // The patch doesn't apply twice
+        assert!(matches!(
+            apply_patch(patched, &diff),
+            Err(PatchError::PropertyMismatch(..) | PatchError::ExistingComponent(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = parse(BASE);
+        let local = parse(
+            &BASE
+                .replace("SUMMARY:Meeting", "SUMMARY:Team meeting")
+                .replace("DESCRIPTION:Agenda", "DESCRIPTION:Local agenda"),
+        );
+        let remote = parse(
+            &BASE
+                .replace(
+                    "PARTSTAT=NEEDS-ACTION:mailto:jane",
+                    "PARTSTAT=ACCEPTED:mailto:jane",
+                )
+                .replace("DESCRIPTION:Agenda", "DESCRIPTION:Remote agenda")
+                .replace("END:VCALENDAR\r\n", NEW_EVENT),
+        );
+        let Merge {
+            calendar,
+            conflicts,
+        } = merge(&base, &local, &remote).unwrap();
+
+        let expected = parse(
+            &BASE
+                .replace("SUMMARY:Meeting", "SUMMARY:Team meeting")
+                .replace("DESCRIPTION:Agenda", "DESCRIPTION:Local agenda")
+                .replace(
+                    "PARTSTAT=NEEDS-ACTION:mailto:jane",
+                    "PARTSTAT=ACCEPTED:mailto:jane",
+                )
+                .replace("END:VCALENDAR\r\n", NEW_EVENT),
+        );
+        assert!(semantically_eq(&calendar, &expected));
+        let [
+            Conflict::Property {
+                component: Some(key),
+                local: PropertyChange::Modified { new: local, .. },
+                remote: PropertyChange::Modified { new: remote, .. },
+            },
+        ] = conflicts.as_slice()
+        else {
+            panic!("{conflicts:?}");
+        };
+        assert_eq!(key.uid, "meeting@example.com");
+        assert_eq!(local.value, "Local agenda");
+        assert_eq!(remote.value, "Remote agenda");
+    }
+}
@@ -0,0 +1,150 @@
+//! Detection of changes that require a new SEQUENCE
+use super::{
+    ComponentChange, ComponentKey, PropertyChange, Timezones, component_key, diff_objects,
+};
+use crate::{
+    component::{CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendarObject, sequence},
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use std::collections::HashMap;
+
+/// Properties whose change is significant according to RFC 5546, section 2.1.4
+const SIGNIFICANT_PROPERTIES: &[&str] = &[
+    "DTSTART", "DTEND", "DURATION", "DUE", "RRULE", "RDATE", "EXDATE", "EXRULE", "STATUS",
+    "LOCATION",
+];
+
+impl PropertyChange {
+    /// Whether attendees have to be notified of this change.
+    ///
+    /// Adding or removing an ATTENDEE is significant, a changed participation status isn't.
+    pub fn is_significant(&self) -> bool {
+        match self {
+            Self::Added(prop) | Self::Removed(prop) => {
+                prop.name == "ATTENDEE" || SIGNIFICANT_PROPERTIES.contains(&prop.name.as_str())
+            }
+            Self::Modified { new, .. } => SIGNIFICANT_PROPERTIES.contains(&new.name.as_str()),
+        }
+    }
+}
+
+impl ComponentChange {
+    /// Whether attendees have to be notified of this change,
+    /// added and removed overrides always are significant
+    pub fn is_significant(&self) -> bool {
+        match self {
+            Self::Added(..) | Self::Removed(_) => true,
+            Self::Modified { properties, .. } => {
+                properties.iter().any(PropertyChange::is_significant)
+            }
+        }
+    }
+}
+
+fn key<C: Component>(component: &C, timezones: &Timezones) -> ComponentKey {
+    let uid = component
+        .get_property("UID")
+        .map(|uid| uid.value.as_str())
+        .unwrap_or_default();
+    component_key(
+        component.get_comp_name(),
+        uid,
+        component.get_properties(),
+        timezones,
+    )
+}
+
+fn set_sequences<B: ComponentMut>(
+    builders: &mut [B],
+    sequences: &HashMap<ComponentKey, i64>,
+    timezones: &Timezones,
+) {
+    for builder in builders {
+        let Some(&new) = sequences.get(&key(builder, timezones)) else {
+            continue;
+        };
+        // The SEQUENCE might have been incremented already
+        if sequence(builder.get_properties()) >= new {
+            continue;
+        }
+        builder.remove_property("SEQUENCE");
+        builder.add_content_line(ContentLine {
+            name: "SEQUENCE".to_owned(),
+            params: Default::default(),
+            value: new.to_string(),
+        });
+    }
+}
+
+/// Increments the SEQUENCE of the components of `edited` that changed significantly
+/// compared to `previous`, see [`ComponentChange::is_significant`].
+///
+/// Added overrides get a SEQUENCE higher than the one of the previous main component,
+/// removing an override counts as a significant change of the main component.
+/// Components whose SEQUENCE already is higher than before are left unchanged.
+pub fn bump_sequence(
+    previous: &IcalCalendarObject,
+    edited: IcalCalendarObject,
+) -> Result<IcalCalendarObject, ParserError> {
+    let old_timezones = previous.get_timezones();
+    let old_sequences: HashMap<ComponentKey, i64> = super::object_components(previous)
+        .iter()
+        .map(|component| {
+            (
+                component.key(old_timezones),
+                sequence(component.get_properties()),
+            )
+        })
+        .collect();
+    let main_sequence = |key: &ComponentKey| {
+        old_sequences
+            .get(&ComponentKey {
+                recurrence_id: None,
+                ..key.clone()
+            })
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let mut sequences = HashMap::new();
+    for change in diff_objects(previous, &edited).components {
+        if !change.is_significant() {
+            continue;
+        }
+        match change {
+            ComponentChange::Modified { key, .. } => {
+                let sequence = old_sequences.get(&key).copied().unwrap_or_default();
+                sequences.insert(key, sequence + 1);
+            }
+            ComponentChange::Added(key, _) => {
+                let sequence = main_sequence(&key);
+                sequences.insert(key, sequence + 1);
+            }
+            ComponentChange::Removed(key) => {
+                let sequence = main_sequence(&key);
+                sequences.insert(
+                    ComponentKey {
+                        recurrence_id: None,
+                        ..key
+                    },
+                    sequence + 1,
+                );
+            }
+        }
+    }
+    if sequences.is_empty() {
+        return Ok(edited);
+    }
+
+    let timezones = &edited.timezones;
+    let mut inner = edited.inner.mutable();
+    match &mut inner {
+        CalendarInnerDataBuilder::Event(events) => set_sequences(events, &sequences, timezones),
+        CalendarInnerDataBuilder::Todo(todos) => set_sequences(todos, &sequences, timezones),
+        CalendarInnerDataBuilder::Journal(journals) => {
+            set_sequences(journals, &sequences, timezones);
+        }
+    }
+    let inner = inner.build(&ParserOptions::reverify(timezones), Some(timezones))?;
+    Ok(IcalCalendarObject { inner, ..edited })
+}
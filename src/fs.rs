@@ -0,0 +1,120 @@
+//! Reading and writing calendar and contact files
+use crate::{
+    IcalParser, ParserError, VcardParser,
+    component::{IcalCalendar, VcardContact},
+    generator::Emitter,
+};
+use std::{borrow::Cow, path::Path};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FsError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("invalid UTF-16 content")]
+    InvalidUtf16,
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16_LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16_BE_BOM: &[u8] = b"\xFE\xFF";
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<Vec<u8>, FsError> {
+    let units = bytes.chunks_exact(2);
+    if !units.remainder().is_empty() {
+        return Err(FsError::InvalidUtf16);
+    }
+    let units = units.map(|unit| from_bytes([unit[0], unit[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map(String::into_bytes)
+        .map_err(|_| FsError::InvalidUtf16)
+}
+
+/// Strips the byte order mark and converts UTF-16 (as exported by some Windows tools) to UTF-8
+pub fn decode(bytes: &[u8]) -> Result<Cow<'_, [u8]>, FsError> {
+    if let Some(bytes) = bytes.strip_prefix(UTF8_BOM) {
+        Ok(Cow::Borrowed(bytes))
+    } else if let Some(bytes) = bytes.strip_prefix(UTF16_LE_BOM) {
+        decode_utf16(bytes, u16::from_le_bytes).map(Cow::Owned)
+    } else if let Some(bytes) = bytes.strip_prefix(UTF16_BE_BOM) {
+        decode_utf16(bytes, u16::from_be_bytes).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(bytes))
+    }
+}
+
+/// Loads all VCALENDAR objects of an iCalendar file
+pub fn load_calendar(path: impl AsRef<Path>) -> Result<Vec<IcalCalendar>, FsError> {
+    let bytes = std::fs::read(path)?;
+    let calendars = IcalParser::from_slice(&decode(&bytes)?).collect::<Result<_, _>>()?;
+    Ok(calendars)
+}
+
+/// Loads all VCARD objects of a vCard file
+pub fn load_contacts(path: impl AsRef<Path>) -> Result<Vec<VcardContact>, FsError> {
+    let bytes = std::fs::read(path)?;
+    let contacts = VcardParser::from_slice(&decode(&bytes)?).collect::<Result<_, _>>()?;
+    Ok(contacts)
+}
+
+/// Writes a calendar, calendar object or contact to `path` as UTF-8 without byte order mark
+pub fn save(path: impl AsRef<Path>, object: &(impl Emitter + ?Sized)) -> Result<(), FsError> {
+    std::fs::write(path, object.generate())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FsError, decode, load_calendar, load_contacts, save};
+    use crate::generator::Emitter;
+
+    #[test]
+    fn test_decode() {
+        let text = "BEGIN:VCALENDAR\r\nSUMMARY:Grüße\r\n";
+        assert_eq!(decode(text.as_bytes()).unwrap(), text.as_bytes());
+        let with_bom = [b"\xEF\xBB\xBF", text.as_bytes()].concat();
+        assert_eq!(decode(&with_bom).unwrap(), text.as_bytes());
+
+        let le: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(decode(&le).unwrap(), text.as_bytes());
+        let be: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+        assert_eq!(decode(&be).unwrap(), text.as_bytes());
+
+        assert!(matches!(
+            decode(&[0xFF, 0xFE, 0x00, 0xD8]),
+            Err(FsError::InvalidUtf16)
+        ));
+        assert!(matches!(
+            decode(&[0xFF, 0xFE, 0x41]),
+            Err(FsError::InvalidUtf16)
+        ));
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let calendars = load_calendar("./tests/resources/ical_multiple.ics").unwrap();
+        assert!(calendars.len() > 1);
+        let contacts = load_contacts("./tests/resources/vcard_input.vcf").unwrap();
+        assert!(!contacts.is_empty());
+
+        let path = std::env::temp_dir().join(format!("caldata-fs-{}.ics", std::process::id()));
+        save(&path, &calendars[0]).unwrap();
+        let saved = load_calendar(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].generate(), calendars[0].generate());
+
+        assert!(matches!(
+            load_calendar("./tests/resources/does_not_exist.ics"),
+            Err(FsError::Io(_))
+        ));
+    }
+}
@@ -4,30 +4,23 @@ use crate::component::{
     IcalTimeZoneTransition, IcalTodo,
 };
 use crate::generator::Emitter;
-
-impl Emitter for IcalTimeZoneTransition {
-    fn generate(&self) -> String {
-        let compname = &crate::component::Component::get_comp_name(self);
-        format!(
-            "BEGIN:{compname}\r\n{inner}END:{compname}\r\n",
-            inner = &self
-                .properties
-                .iter()
-                .map(Emitter::generate)
-                .collect::<String>()
-        )
-    }
-}
+use std::fmt;
 
 macro_rules! generate_emitter {
     ($struct:ty, $($prop:ident),*) => {
         impl Emitter for $struct {
-            fn generate(&self) -> String {
-                let compname = &crate::component::Component::get_comp_name(self);
-                let mut text = format!("BEGIN:{compname}\r\n");
-                text += &crate::component::Component::get_properties(self).generate();
-                $(text += &self.$prop.generate();)*
-                text + "END:" + compname + "\r\n"
+            fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+                let compname = crate::component::Component::get_comp_name(self);
+                write!(out, "BEGIN:{compname}\r\n")?;
+                crate::component::Component::get_properties(self).emit(out)?;
+                $(self.$prop.emit(out)?;)*
+                write!(out, "END:{compname}\r\n")
+            }
+        }
+
+        impl fmt::Display for $struct {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.emit(f)
             }
         }
     };
@@ -37,18 +30,35 @@ use crate::component::VcardContact;
 generate_emitter!(VcardContact,);
 
 generate_emitter!(IcalAlarm,);
+generate_emitter!(IcalTimeZoneTransition,);
 generate_emitter!(IcalFreeBusy,);
 generate_emitter!(IcalJournal,);
 generate_emitter!(IcalEvent, alarms);
 generate_emitter!(IcalTodo, alarms);
 generate_emitter!(IcalTimeZone<true>, transitions);
-generate_emitter!(
-    IcalCalendar,
-    vtimezones,
-    events,
-    alarms,
-    todos,
-    journals,
-    free_busys
-);
 generate_emitter!(IcalCalendarObject, vtimezones, inner);
+
+// Also covers calendars whose components are wrapped, e.g. in [`Tracked`](super::Tracked)
+impl<A: Emitter, E: Emitter, F: Emitter, J: Emitter, T: Emitter> Emitter
+    for IcalCalendar<true, A, E, F, J, T>
+{
+    fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str("BEGIN:VCALENDAR\r\n")?;
+        self.properties.emit(out)?;
+        self.vtimezones.emit(out)?;
+        self.events.emit(out)?;
+        self.alarms.emit(out)?;
+        self.todos.emit(out)?;
+        self.journals.emit(out)?;
+        self.free_busys.emit(out)?;
+        out.write_str("END:VCALENDAR\r\n")
+    }
+}
+
+impl<A: Emitter, E: Emitter, F: Emitter, J: Emitter, T: Emitter> fmt::Display
+    for IcalCalendar<true, A, E, F, J, T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.emit(f)
+    }
+}
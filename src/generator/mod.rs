@@ -1,6 +1,8 @@
 mod ical;
 mod property;
-use std::collections::BTreeMap;
+mod tracked;
+use std::{collections::BTreeMap, fmt};
+pub use tracked::*;
 
 pub use crate::component::ical::component::{IcalCalendar, IcalEvent};
 pub use crate::component::vcard::component::VcardContact;
@@ -10,20 +12,29 @@ pub use crate::parser::ContentLine;
 /// Emits the content of the Component in ical-format.
 ///
 pub trait Emitter {
+    /// writes the textual-representation of this object and all it's properties
+    /// in ical-format to `out`.
+    fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result;
+
     /// creates a textual-representation of this object and all it's properties
     /// in ical-format.
-    fn generate(&self) -> String;
+    fn generate(&self) -> String {
+        let mut output = String::new();
+        self.emit(&mut output)
+            .expect("writing to a String doesn't fail");
+        output
+    }
 }
 
 impl<K, T: Emitter> Emitter for BTreeMap<K, T> {
-    fn generate(&self) -> String {
-        self.values().map(Emitter::generate).collect()
+    fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.values().try_for_each(|value| value.emit(out))
     }
 }
 
 impl<T: Emitter> Emitter for Vec<T> {
-    fn generate(&self) -> String {
-        self.iter().map(Emitter::generate).collect()
+    fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.iter().try_for_each(|value| value.emit(out))
     }
 }
 
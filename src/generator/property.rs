@@ -1,60 +1,51 @@
 use crate::generator::Emitter;
-use crate::parser::{ContentLine, ContentLineParams};
+use crate::parser::ContentLine;
 use crate::{PARAM_DELIMITER, PARAM_VALUE_DELIMITER, VALUE_DELIMITER};
-use itertools::Itertools;
+use std::fmt::{self, Write};
 
-pub(crate) fn split_line(line: String) -> String {
-    let break_estimate = line.len().div_ceil(74);
-    let mut output = String::with_capacity(line.len() + 3 * break_estimate + 2);
+/// Folds the lines written through it after 75 characters
+struct Folded<'a, W: Write + ?Sized> {
+    out: &'a mut W,
+    line_len: usize,
+}
 
-    let mut chars = line.char_indices().map(|(offset, _)| offset).peekable();
-    let mut first_char_idx = 0;
-    // Iterate over lines
-    loop {
-        // Find start of next line and find out if it was the last one
-        let (line_boundary, last_line) = {
-            let mut line_len = if first_char_idx == 0 { 0 } else { 1 };
-            loop {
-                let Some(_) = chars.next() else {
-                    // We are at the end, the boundary is given bv the line length (since we don't
-                    // know how wide the last character is)
-                    break (line.len(), true);
-                };
-                line_len += 1;
-
-                // A line should SHOULD NOT be longer than 75 characters
-                if line_len == 75 {
-                    // We've reached our desired length.
-                    // We peek for the line boundary
-                    // char_idx currently is the start of the last character
-                    if let Some(&boundary) = chars.peek() {
-                        break (boundary, false);
-                    } else {
-                        break (line.len(), true);
-                    };
-                }
-            }
-        };
+impl<'a, W: Write + ?Sized> Folded<'a, W> {
+    fn new(out: &'a mut W) -> Self {
+        Self { out, line_len: 0 }
+    }
 
-        if first_char_idx == line_boundary {
-            // There were no new characters
-            break;
-        }
+    /// Ends the current line
+    fn end_line(&mut self) -> fmt::Result {
+        self.line_len = 0;
+        self.out.write_str("\r\n")
+    }
+}
 
-        // This will not panic
-        let left = line.split_at(line_boundary).0;
-        #[cfg(test)]
-        assert!(first_char_idx < line_boundary);
-        output.push_str(left.split_at(first_char_idx).1);
-        if last_line {
-            break;
-        } else {
-            output.push_str("\r\n ");
+impl<W: Write + ?Sized> Write for Folded<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let mut start = 0;
+        for (offset, _) in text.char_indices() {
+            // A line should SHOULD NOT be longer than 75 characters
+            if self.line_len == 75 {
+                self.out.write_str(&text[start..offset])?;
+                self.out.write_str("\r\n ")?;
+                start = offset;
+                // The leading space
+                self.line_len = 1;
+            }
+            self.line_len += 1;
         }
-        first_char_idx = line_boundary;
+        self.out.write_str(&text[start..])
     }
+}
 
-    output.push_str("\r\n");
+#[cfg(test)]
+pub(crate) fn split_line(line: String) -> String {
+    let break_estimate = line.len().div_ceil(74);
+    let mut output = String::with_capacity(line.len() + 3 * break_estimate + 2);
+    let mut folded = Folded::new(&mut output);
+    folded.write_str(&line).unwrap();
+    folded.end_line().unwrap();
     output
 }
 
@@ -73,37 +64,79 @@ pub(crate) fn split_line(line: String) -> String {
 //     Any character except CONTROLs not needed by the current
 //     character set, DQUOTE, ";", ":", "\", ","
 //
-pub(crate) fn protect_param(param: &str) -> String {
+fn write_param(out: &mut impl Write, param: &str) -> fmt::Result {
     // let len = param.len() - 1;
     // starts and ends the param with quotes?
     let in_quotes = param.len() > 1 && param.starts_with('"') && param.ends_with('"');
 
-    let mut escaped = String::with_capacity(param.len());
     let mut previous_char = None;
     for (pos, char) in param.chars().enumerate() {
         match char {
             '\n' => {
-                escaped.push_str("\\n");
+                out.write_str("\\n")?;
             }
             '"' if !in_quotes || (pos > 0 && pos < param.len() - 1) => {
-                escaped.push_str("\\\"");
+                out.write_str("\\\"")?;
             }
             ';' | ':' | ',' | '\\' if !in_quotes && previous_char != Some('\\') => {
-                escaped.push('\\');
-                escaped.push(char)
+                out.write_char('\\')?;
+                out.write_char(char)?;
             }
             _ => {
-                escaped.push(char);
+                out.write_char(char)?;
             }
         }
         previous_char = Some(char);
     }
+    Ok(())
+}
+
+/// Parameters with URI values which RFC 5545 requires to be DQUOTEd
+const QUOTED_PARAMS: &[&str] = &[
+    "ALTREP",
+    "DELEGATED-FROM",
+    "DELEGATED-TO",
+    "DIR",
+    "MEMBER",
+    "SENT-BY",
+];
+
+#[cfg(test)]
+pub(crate) fn protect_param(param: &str) -> String {
+    let mut escaped = String::with_capacity(param.len());
+    write_param(&mut escaped, param).unwrap();
     escaped
 }
 
-#[allow(unused)]
+impl Emitter for ContentLine {
+    fn emit(&self, out: &mut dyn Write) -> fmt::Result {
+        let mut out = Folded::new(out);
+        out.write_str(&self.name)?;
+        for (name, values) in self.params.iter() {
+            out.write_char(PARAM_DELIMITER)?;
+            out.write_str(name)?;
+            out.write_char('=')?;
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    out.write_char(PARAM_VALUE_DELIMITER)?;
+                }
+                if QUOTED_PARAMS.contains(&name) && !value.starts_with('"') {
+                    write_param(&mut out, &format!("\"{value}\""))?;
+                } else {
+                    write_param(&mut out, value)?;
+                }
+            }
+        }
+        out.write_char(VALUE_DELIMITER)?;
+        out.write_str(&self.value)?;
+        out.end_line()
+    }
+}
+
+#[cfg(test)]
 mod should {
     use super::{protect_param, split_line};
+    use crate::{ContentLineParser, generator::Emitter};
 
     #[test]
     fn split_line_75() {
@@ -181,31 +214,18 @@ mod should {
         assert_eq!(protect_param("\""), "\\\"");
         assert_eq!(protect_param("ÄÖsÜa,ßø"), "ÄÖsÜa\\,ßø");
     }
-}
 
-fn get_params(params: &ContentLineParams) -> String {
-    params
-        .0
-        .iter()
-        .map(|(name, values)| {
-            let value: String = values
-                .iter()
-                .map(|value| protect_param(value))
-                .join(&PARAM_VALUE_DELIMITER.to_string());
-            format!("{name}={value}")
-        })
-        .join(&PARAM_DELIMITER.to_string())
-}
-
-impl Emitter for ContentLine {
-    fn generate(&self) -> String {
-        let mut output = self.name.to_owned();
-        if !self.params.is_empty() {
-            output.push(PARAM_DELIMITER);
-            output.push_str(&get_params(&self.params));
+    #[test]
+    fn quote_uri_params() {
+        for input in [
+            "DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\";LANGUAGE=en:Text\r\n",
+            "ATTENDEE;DELEGATED-TO=\"mailto:a@example.com\",\"mailto:b@example.com\":x\r\n",
+        ] {
+            let line = ContentLineParser::from_slice(input.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap();
+            assert_eq!(line.generate(), input);
         }
-        output.push(VALUE_DELIMITER);
-        output.push_str(&self.value);
-        split_line(output)
     }
 }
@@ -0,0 +1,172 @@
+use crate::component::{IcalAlarm, IcalCalendar, IcalEvent, IcalFreeBusy, IcalJournal, IcalTodo};
+use crate::generator::Emitter;
+use std::{fmt, ops::Deref, sync::OnceLock};
+
+/// Caches the generated text of a component until it's modified.
+///
+/// Servers that rewrite a large calendar after editing a single component only have to
+/// re-emit the modified one, see [`IcalCalendar::into_tracked`].
+#[derive(Debug, Clone)]
+pub struct Tracked<T> {
+    inner: T,
+    generated: OnceLock<String>,
+}
+
+impl<T> Tracked<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            generated: OnceLock::new(),
+        }
+    }
+
+    /// Mutable access to the component, discards the cached text
+    pub fn get_mut(&mut self) -> &mut T {
+        self.generated.take();
+        &mut self.inner
+    }
+
+    /// Replaces the component and returns the previous one
+    pub fn replace(&mut self, inner: T) -> T {
+        std::mem::replace(self.get_mut(), inner)
+    }
+
+    /// Whether the component has been modified since it was last generated
+    pub fn is_dirty(&self) -> bool {
+        self.generated.get().is_none()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> From<T> for Tracked<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: Emitter> Emitter for Tracked<T> {
+    fn emit(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str(self.generated.get_or_init(|| self.inner.generate()))
+    }
+}
+
+/// A calendar that caches the generated text of each of its components
+pub type TrackedCalendar = IcalCalendar<
+    true,
+    Tracked<IcalAlarm>,
+    Tracked<IcalEvent>,
+    Tracked<IcalFreeBusy>,
+    Tracked<IcalJournal>,
+    Tracked<IcalTodo>,
+>;
+
+fn track<T>(components: Vec<T>) -> Vec<Tracked<T>> {
+    components.into_iter().map(Tracked::new).collect()
+}
+
+fn untrack<T>(components: Vec<Tracked<T>>) -> Vec<T> {
+    components.into_iter().map(Tracked::into_inner).collect()
+}
+
+impl IcalCalendar {
+    /// Wraps every component in [`Tracked`] so that generating the calendar again only
+    /// re-emits components that were modified in the meantime
+    pub fn into_tracked(self) -> TrackedCalendar {
+        TrackedCalendar {
+            properties: self.properties,
+            events: track(self.events),
+            alarms: track(self.alarms),
+            todos: track(self.todos),
+            journals: track(self.journals),
+            free_busys: track(self.free_busys),
+            vtimezones: self.vtimezones,
+            timezones: self.timezones,
+        }
+    }
+}
+
+impl TrackedCalendar {
+    pub fn into_untracked(self) -> IcalCalendar {
+        IcalCalendar {
+            properties: self.properties,
+            events: untrack(self.events),
+            alarms: untrack(self.alarms),
+            todos: untrack(self.todos),
+            journals: untrack(self.journals),
+            free_busys: untrack(self.free_busys),
+            vtimezones: self.vtimezones,
+            timezones: self.timezones,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IcalParser,
+        component::{Component, ComponentMut},
+        generator::Emitter,
+        parser::{ContentLine, ParserOptions},
+    };
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:first@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+SUMMARY:First\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:second@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260324T080000Z\r
+SUMMARY:Second\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_tracked() {
+        let calendar = IcalParser::from_slice(ICS.as_bytes()).expect_one().unwrap();
+        let mut tracked = calendar.clone().into_tracked();
+        assert!(tracked.events.iter().all(|event| event.is_dirty()));
+        assert_eq!(tracked.generate(), calendar.generate());
+        assert!(tracked.events.iter().all(|event| !event.is_dirty()));
+
+        let mut second = tracked.events[1].clone().into_inner().mutable();
+        second.remove_property("SUMMARY");
+        second.add_content_line(ContentLine {
+            name: "SUMMARY".to_owned(),
+            value: "Second (moved)".to_owned(),
+            params: Default::default(),
+        });
+        let second = second.build(&ParserOptions::default(), None).unwrap();
+        tracked.events[1].replace(second);
+        assert!(!tracked.events[0].is_dirty());
+        assert!(tracked.events[1].is_dirty());
+        assert_eq!(
+            tracked.to_string(),
+            ICS.replace("SUMMARY:Second", "SUMMARY:Second (moved)")
+        );
+        assert_eq!(
+            tracked.into_untracked().events[1]
+                .get_property("SUMMARY")
+                .unwrap()
+                .value,
+            "Second (moved)"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Transport of iTIP messages by email as defined in RFC 6047 (iMIP)
+use crate::{
+    component::{Component, IcalCalendar},
+    generator::Emitter,
+    parser::ParserError,
+    types::{Binary, Value},
+};
+
+/// Maximum length of encoded lines according to RFC 2045
+const MAX_ENCODED_LINE_LENGTH: usize = 76;
+
+/// Content-Transfer-Encoding of the calendar data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferEncoding {
+    #[default]
+    Base64,
+    /// Keeps the data readable for humans
+    QuotedPrintable,
+}
+
+impl TransferEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Base64 => "base64",
+            Self::QuotedPrintable => "quoted-printable",
+        }
+    }
+
+    fn encode(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Base64 => encode_base64(data),
+            Self::QuotedPrintable => encode_quoted_printable(data),
+        }
+    }
+}
+
+/// A MIME body part containing an iTIP message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImipPart {
+    /// The iTIP method in upper case, e.g. `REQUEST`
+    pub method: String,
+    pub transfer_encoding: TransferEncoding,
+    /// Suggested name of the attached file, e.g. `invite.ics`
+    pub filename: String,
+    /// The encoded calendar data with CRLF line breaks
+    pub body: Vec<u8>,
+}
+
+impl ImipPart {
+    /// Value of the Content-Type header,
+    /// e.g. `text/calendar; method=REQUEST; charset=UTF-8; name="invite.ics"`
+    pub fn content_type(&self) -> String {
+        format!(
+            "text/calendar; method={}; charset=UTF-8; name=\"{}\"",
+            self.method, self.filename
+        )
+    }
+
+    /// The MIME headers of the part
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Content-Type", self.content_type()),
+            (
+                "Content-Transfer-Encoding",
+                self.transfer_encoding.as_str().to_owned(),
+            ),
+            (
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", self.filename),
+            ),
+        ]
+    }
+
+    /// The headers followed by the body, ready to be embedded into a multipart message
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![];
+        for (name, value) in self.headers() {
+            out.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        out.extend_from_slice(b"\r\n");
+        out.extend_from_slice(&self.body);
+        out
+    }
+}
+
+fn encode_base64(data: &[u8]) -> Vec<u8> {
+    let encoded = Binary::from(data.to_vec()).value();
+    let mut out = Vec::with_capacity(encoded.len() + encoded.len() / MAX_ENCODED_LINE_LENGTH * 2);
+    for line in encoded.as_bytes().chunks(MAX_ENCODED_LINE_LENGTH) {
+        out.extend_from_slice(line);
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+fn encode_quoted_printable(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for (index, line) in data.split(|&byte| byte == b'\n').enumerate() {
+        if index > 0 {
+            out.extend_from_slice(b"\r\n");
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut length = 0;
+        for (position, &byte) in line.iter().enumerate() {
+            let last = position == line.len() - 1;
+            let literal = matches!(byte, b'!'..=b'<' | b'>'..=b'~')
+                // Trailing whitespace would be removed by transports
+                || (matches!(byte, b' ' | b'\t') && !last);
+            let encoded = if literal {
+                vec![byte]
+            } else {
+                format!("={byte:02X}").into_bytes()
+            };
+            // Leave room for the soft line break
+            if length + encoded.len() > MAX_ENCODED_LINE_LENGTH - 1 {
+                out.extend_from_slice(b"=\r\n");
+                length = 0;
+            }
+            length += encoded.len();
+            out.extend_from_slice(&encoded);
+        }
+    }
+    out
+}
+
+/// Renders the iTIP message `calendar` into a MIME body part for an email.
+///
+/// Fails if the calendar has no METHOD, which iMIP requires.
+///
+/// # Examples
+///
+/// ```rust
+/// use caldata::{IcalObjectParser, imip::{TransferEncoding, render}, itip::request};
+///
+/// let input = "BEGIN:VCALENDAR\r
+/// VERSION:2.0\r
+/// PRODID:-//Example Corp.//CalDAV Client//EN\r
+/// BEGIN:VEVENT\r
+/// UID:meeting@example.com\r
+/// DTSTAMP:20260101T120000Z\r
+/// DTSTART:20260323T080000Z\r
+/// SUMMARY:Meeting\r
+/// ATTENDEE:mailto:john@example.com\r
+/// END:VEVENT\r
+/// END:VCALENDAR\r
+/// ";
+/// let object = IcalObjectParser::from_slice(input.as_bytes()).expect_one().unwrap();
+/// let message = request(&object, &"mailto:jane@example.com".parse().unwrap()).unwrap();
+/// let part = render(&message, TransferEncoding::Base64).unwrap();
+/// assert!(part.content_type().starts_with("text/calendar; method=REQUEST; charset=UTF-8"));
+/// assert_eq!(part.filename, "invite.ics");
+/// ```
+pub fn render(
+    calendar: &IcalCalendar,
+    transfer_encoding: TransferEncoding,
+) -> Result<ImipPart, ParserError> {
+    let method = calendar
+        .get_property("METHOD")
+        .ok_or(ParserError::MissingProperty("METHOD"))?
+        .value
+        .to_ascii_uppercase();
+    let filename = match method.as_str() {
+        "REQUEST" => "invite.ics".to_owned(),
+        method => format!("{}.ics", method.to_ascii_lowercase()),
+    };
+    Ok(ImipPart {
+        body: transfer_encoding.encode(calendar.generate().as_bytes()),
+        method,
+        transfer_encoding,
+        filename,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_base64, encode_quoted_printable};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("SUMMARY:Meeting\r\n", "SUMMARY:Meeting\r\n")]
+    #[case("SUMMARY:Caf\u{e9} = 1 \r\n", "SUMMARY:Caf=C3=A9 =3D 1=20\r\n")]
+    #[case(
+        &format!("DESCRIPTION:{}\r\n", "a".repeat(70)),
+        &format!("DESCRIPTION:{}=\r\n{}\r\n", "a".repeat(63), "a".repeat(7))
+    )]
+    fn test_quoted_printable(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            String::from_utf8(encode_quoted_printable(input.as_bytes())).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        let encoded = encode_base64(&[b'a'; 60]);
+        let lines: Vec<_> = encoded.split(|&byte| byte == b'\n').collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 77);
+        assert!(lines[2].is_empty());
+    }
+}
@@ -0,0 +1,473 @@
+//! Indices over many calendar objects
+use crate::{
+    caldav::TimeRange,
+    component::{CalendarInnerData, IcalCalendarObject, IcalEvent},
+    types::CalDateOrDateTime,
+};
+#[cfg(feature = "recurrence")]
+use crate::{component::IcalTodo, rrule::RRuleSet};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Floating and DATE values may refer to any timezone, so their bounds are widened
+/// by the maximum UTC offset
+const FLOATING_PADDING: Duration = Duration::hours(14);
+
+/// Upper bound of instances that are generated to find the last instance of a finite recurrence
+#[cfg(feature = "recurrence")]
+const MAX_INSTANCES: u16 = u16::MAX;
+
+/// Instant of a value and whether it's floating or a DATE
+fn instant(value: &CalDateOrDateTime) -> (DateTime<Utc>, bool) {
+    let floating = value.is_date() || value.timezone().is_local();
+    (value.utc(), floating)
+}
+
+/// Start of the last instance of a recurrence, `None` if it doesn't end
+#[cfg(feature = "recurrence")]
+fn last_instance(rrule_set: RRuleSet) -> Option<DateTime<Utc>> {
+    if !rrule_set.is_finite() {
+        return None;
+    }
+    let result = rrule_set.all(MAX_INSTANCES);
+    if result.limited {
+        return None;
+    }
+    result.dates.last().map(DateTime::to_utc)
+}
+
+/// Extends the bounds by a component starting at `start` and lasting `length`
+fn extend(
+    bounds: &mut Option<(DateTime<Utc>, DateTime<Utc>, bool)>,
+    start: &CalDateOrDateTime,
+    length: Duration,
+) {
+    let (start, floating) = instant(start);
+    let end = start + length.max(Duration::zero());
+    let (min, max, any_floating) = bounds.get_or_insert((start, end, floating));
+    *min = (*min).min(start);
+    *max = (*max).max(end);
+    *any_floating |= floating;
+}
+
+impl IcalCalendarObject {
+    /// Bounds of all instances of this object.
+    ///
+    /// The bounds are inclusive and cover the instances of finite recurrences and all overrides,
+    /// but not alarms. Bounds of floating and DATE values are widened by 14 hours as they
+    /// may refer to any timezone.
+    /// The end is `None` for infinite recurrences and if the object has more than 65535
+    /// instances, both bounds are `None` for objects that aren't bound to any time,
+    /// e.g. to-dos without DTSTART and DUE.
+    pub fn occurrence_bounds(&self) -> TimeRange {
+        let mut bounds = None;
+        let mut last = None;
+        let mut recurring = false;
+        match &self.inner {
+            CalendarInnerData::Event(main, overrides) => {
+                let length = |event: &IcalEvent| {
+                    event.get_duration().unwrap_or_else(|| {
+                        if event.dtstart.0.is_date() {
+                            Duration::days(1)
+                        } else {
+                            Duration::zero()
+                        }
+                    })
+                };
+                for event in std::iter::once(main).chain(overrides) {
+                    extend(&mut bounds, &event.dtstart.0, length(event));
+                }
+                #[cfg(feature = "recurrence")]
+                if let Some(rrule_set) = main.get_rruleset() {
+                    let longest = std::iter::once(main)
+                        .chain(overrides)
+                        .map(length)
+                        .max()
+                        .unwrap_or_default();
+                    recurring = true;
+                    last = last_instance(rrule_set).map(|last| last + longest);
+                }
+                // Without the recurrence feature the end of recurrences is unknown
+                #[cfg(not(feature = "recurrence"))]
+                if main.has_rruleset() {
+                    recurring = true;
+                    last = None;
+                }
+            }
+            CalendarInnerData::Todo(main, overrides) => {
+                for todo in std::iter::once(main).chain(overrides) {
+                    match (&todo.dtstart, &todo.due) {
+                        (Some(dtstart), _) => extend(
+                            &mut bounds,
+                            &dtstart.0,
+                            todo.get_duration().unwrap_or_default(),
+                        ),
+                        (None, Some(due)) => extend(&mut bounds, &due.0, Duration::zero()),
+                        // Matched by COMPLETED and CREATED or by any time range
+                        (None, None) => return TimeRange::default(),
+                    }
+                }
+                #[cfg(feature = "recurrence")]
+                if let Some(rrule_set) = main.get_rruleset() {
+                    let longest = std::iter::once(main)
+                        .chain(overrides)
+                        .filter_map(IcalTodo::get_duration)
+                        .max()
+                        .unwrap_or_default();
+                    recurring = true;
+                    last = last_instance(rrule_set).map(|last| last + longest);
+                }
+                // Without DTSTART there is nothing to apply the recurrence to
+                #[cfg(not(feature = "recurrence"))]
+                if main.has_rruleset() && main.dtstart.is_some() {
+                    recurring = true;
+                    last = None;
+                }
+            }
+            CalendarInnerData::Journal(main, overrides) => {
+                for journal in std::iter::once(main).chain(overrides) {
+                    let Some(dtstart) = &journal.dtstart else {
+                        return TimeRange::default();
+                    };
+                    let length = if dtstart.0.is_date() {
+                        Duration::days(1)
+                    } else {
+                        Duration::zero()
+                    };
+                    extend(&mut bounds, &dtstart.0, length);
+                }
+                // Journals aren't expanded
+                recurring = main.has_rruleset();
+            }
+        }
+
+        let Some((start, end, floating)) = bounds else {
+            return TimeRange::default();
+        };
+        let padding = if floating {
+            FLOATING_PADDING
+        } else {
+            Duration::zero()
+        };
+        // An infinite recurrence doesn't end
+        let end = if recurring {
+            last.map(|last| end.max(last))
+        } else {
+            Some(end)
+        };
+        TimeRange::new(Some(start - padding), end.map(|end| end + padding))
+    }
+}
+
+/// Length classes of bounded objects, class `n` contains objects shorter than 2^n seconds
+fn length_class(start: DateTime<Utc>, end: DateTime<Utc>) -> u32 {
+    let seconds = (end - start).num_seconds().max(0) as u64;
+    u64::BITS - seconds.leading_zeros()
+}
+
+/// The maximum length of objects in `class`
+fn class_length(class: u32) -> Duration {
+    Duration::seconds(
+        1_i64
+            .checked_shl(class)
+            .unwrap_or(i64::MAX)
+            .min(i64::MAX / 1000),
+    )
+}
+
+type Slots = BTreeSet<(DateTime<Utc>, usize)>;
+
+/// An index of calendar objects by the time they occupy, to quickly find the objects
+/// that may match a time range.
+///
+/// Queries return every object whose [`IcalCalendarObject::occurrence_bounds`] intersect the range.
+/// These are candidates that still have to be checked, e.g. with [`IcalCalendarObject::matches`],
+/// since recurrences may have gaps and the bounds of floating values are widened.
+/// Objects are identified by a key of the caller's choice, e.g. their path.
+#[derive(Debug, Clone)]
+pub struct TimeRangeIndex<K> {
+    keys: BTreeMap<K, usize>,
+    entries: Vec<Option<(K, TimeRange)>>,
+    free: Vec<usize>,
+    /// Objects with both bounds by their length class and start
+    bounded: BTreeMap<u32, Slots>,
+    /// Objects without end by their start
+    unbounded: Slots,
+    /// Objects without start, they intersect every range
+    unbound: BTreeSet<usize>,
+}
+
+impl<K> Default for TimeRangeIndex<K> {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            entries: vec![],
+            free: vec![],
+            bounded: BTreeMap::new(),
+            unbounded: BTreeSet::new(),
+            unbound: BTreeSet::new(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> TimeRangeIndex<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Adds `object` as `key`, replacing any object previously added as `key`
+    pub fn insert(&mut self, key: K, object: &IcalCalendarObject) {
+        self.insert_bounds(key, object.occurrence_bounds());
+    }
+
+    /// Adds an object with precomputed `bounds`, e.g. stored next to the object,
+    /// replacing any object previously added as `key`
+    pub fn insert_bounds(&mut self, key: K, bounds: TimeRange) {
+        self.remove(&key);
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.entries[slot] = Some((key.clone(), bounds));
+                slot
+            }
+            None => {
+                self.entries.push(Some((key.clone(), bounds)));
+                self.entries.len() - 1
+            }
+        };
+        self.keys.insert(key, slot);
+        match (bounds.start, bounds.end) {
+            (Some(start), Some(end)) => {
+                self.bounded
+                    .entry(length_class(start, end))
+                    .or_default()
+                    .insert((start, slot));
+            }
+            (Some(start), None) => {
+                self.unbounded.insert((start, slot));
+            }
+            (None, _) => {
+                self.unbound.insert(slot);
+            }
+        }
+    }
+
+    /// Removes the object added as `key`, returns its bounds
+    pub fn remove(&mut self, key: &K) -> Option<TimeRange> {
+        let slot = self.keys.remove(key)?;
+        let (_, bounds) = self.entries[slot].take()?;
+        self.free.push(slot);
+        match (bounds.start, bounds.end) {
+            (Some(start), Some(end)) => {
+                let class = length_class(start, end);
+                if let Some(slots) = self.bounded.get_mut(&class) {
+                    slots.remove(&(start, slot));
+                    if slots.is_empty() {
+                        self.bounded.remove(&class);
+                    }
+                }
+            }
+            (Some(start), None) => {
+                self.unbounded.remove(&(start, slot));
+            }
+            (None, _) => {
+                self.unbound.remove(&slot);
+            }
+        }
+        Some(bounds)
+    }
+
+    /// Bounds of the object added as `key`
+    pub fn bounds(&self, key: &K) -> Option<&TimeRange> {
+        let slot = self.keys.get(key)?;
+        self.entries[*slot].as_ref().map(|(_, bounds)| bounds)
+    }
+
+    /// Keys of all objects that may intersect `range`, in no particular order
+    pub fn query(&self, range: &TimeRange) -> Vec<&K> {
+        let starting_before = |slots: &Slots, after: Option<DateTime<Utc>>| {
+            let lower = after.map_or(std::ops::Bound::Unbounded, |after| {
+                std::ops::Bound::Included((after, 0))
+            });
+            let upper = range.end.map_or(std::ops::Bound::Unbounded, |end| {
+                std::ops::Bound::Included((end, usize::MAX))
+            });
+            slots
+                .range((lower, upper))
+                .map(|(_, slot)| *slot)
+                .collect::<Vec<_>>()
+        };
+
+        let mut slots: Vec<usize> = self.unbound.iter().copied().collect();
+        slots.extend(starting_before(&self.unbounded, None));
+        for (class, class_slots) in &self.bounded {
+            // Objects of this class that start before this can't reach into the range
+            let after = range
+                .start
+                .and_then(|start| start.checked_sub_signed(class_length(*class)));
+            slots.extend(
+                starting_before(class_slots, after)
+                    .into_iter()
+                    .filter(|slot| {
+                        let Some((_, bounds)) = &self.entries[*slot] else {
+                            return false;
+                        };
+                        range
+                            .start
+                            .zip(bounds.end)
+                            .is_none_or(|(start, end)| end >= start)
+                    }),
+            );
+        }
+        slots
+            .into_iter()
+            .filter_map(|slot| self.entries[slot].as_ref().map(|(key, _)| key))
+            .collect()
+    }
+}
+
+impl<'a, K: Ord + Clone> FromIterator<(K, &'a IcalCalendarObject)> for TimeRangeIndex<K> {
+    fn from_iter<T: IntoIterator<Item = (K, &'a IcalCalendarObject)>>(iter: T) -> Self {
+        let mut index = Self::new();
+        for (key, object) in iter {
+            index.insert(key, object);
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeRangeIndex;
+    use crate::{IcalObjectParser, caldav::TimeRange, component::IcalCalendarObject};
+    use chrono::{DateTime, Utc};
+    use rstest::rstest;
+
+    fn object(uid: &str, component: &str, lines: &str) -> IcalCalendarObject {
+        let input = format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\n\
+             BEGIN:{component}\r\nUID:{uid}\r\nDTSTAMP:20260101T120000Z\r\n{lines}END:{component}\r\n\
+             END:VCALENDAR\r\n"
+        );
+        IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+    }
+
+    fn datetime(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().to_utc()
+    }
+
+    fn index() -> TimeRangeIndex<&'static str> {
+        let objects = [
+            (
+                "single",
+                object(
+                    "single",
+                    "VEVENT",
+                    "DTSTART:20260323T080000Z\r\nDTEND:20260323T090000Z\r\n",
+                ),
+            ),
+            (
+                "weekly",
+                object(
+                    "weekly",
+                    "VEVENT",
+                    "DTSTART:20260323T080000Z\r\nDURATION:PT1H\r\nRRULE:FREQ=WEEKLY;COUNT=4\r\n",
+                ),
+            ),
+            (
+                "daily",
+                object(
+                    "daily",
+                    "VEVENT",
+                    "DTSTART:20260401T080000Z\r\nDURATION:PT1H\r\nRRULE:FREQ=DAILY\r\n",
+                ),
+            ),
+            (
+                "holiday",
+                object("holiday", "VEVENT", "DTSTART;VALUE=DATE:20260501\r\n"),
+            ),
+            ("todo", object("todo", "VTODO", "SUMMARY:Some day\r\n")),
+        ];
+        objects.iter().map(|(key, object)| (*key, object)).collect()
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_occurrence_bounds() {
+        let index = index();
+        assert_eq!(
+            index.bounds(&"weekly"),
+            Some(&TimeRange::new(
+                Some(datetime("2026-03-23T08:00:00Z")),
+                Some(datetime("2026-04-13T09:00:00Z"))
+            ))
+        );
+        assert_eq!(
+            index.bounds(&"daily"),
+            Some(&TimeRange::new(
+                Some(datetime("2026-04-01T08:00:00Z")),
+                None
+            ))
+        );
+        assert_eq!(
+            index.bounds(&"holiday"),
+            Some(&TimeRange::new(
+                Some(datetime("2026-04-30T10:00:00Z")),
+                Some(datetime("2026-05-02T14:00:00Z"))
+            ))
+        );
+        assert_eq!(index.bounds(&"todo"), Some(&TimeRange::default()));
+    }
+
+    #[rstest]
+    #[case("2026-03-23T08:30:00Z", "2026-03-23T08:45:00Z", vec!["single", "todo", "weekly"])]
+    #[case("2026-03-24T00:00:00Z", "2026-03-25T00:00:00Z", vec!["todo", "weekly"])]
+    // Later instances are only expanded with the recurrence feature
+    #[cfg_attr(not(feature = "recurrence"), ignore)]
+    #[case("2026-04-20T00:00:00Z", "2026-04-21T00:00:00Z", vec!["daily", "todo"])]
+    #[cfg_attr(not(feature = "recurrence"), ignore)]
+    #[case("2026-05-01T20:00:00Z", "2026-05-01T21:00:00Z", vec!["daily", "holiday", "todo"])]
+    #[case("2026-01-01T00:00:00Z", "2026-02-01T00:00:00Z", vec!["todo"])]
+    fn test_query(#[case] start: &str, #[case] end: &str, #[case] expected: Vec<&str>) {
+        let index = index();
+        let range = TimeRange::new(Some(datetime(start)), Some(datetime(end)));
+        let mut keys: Vec<&str> = index.query(&range).into_iter().copied().collect();
+        keys.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_update() {
+        let mut index = index();
+        assert_eq!(index.len(), 5);
+        let range = TimeRange::new(
+            Some(datetime("2026-06-01T00:00:00Z")),
+            Some(datetime("2026-06-02T00:00:00Z")),
+        );
+        index.insert(
+            "single",
+            &object(
+                "single",
+                "VEVENT",
+                "DTSTART:20260601T080000Z\r\nDTEND:20260601T090000Z\r\n",
+            ),
+        );
+        assert_eq!(index.len(), 5);
+        assert!(index.query(&range).contains(&&"single"));
+        assert!(index.remove(&"daily").is_some());
+        assert!(index.remove(&"daily").is_none());
+        let mut keys: Vec<&str> = index.query(&range).into_iter().copied().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["single", "todo"]);
+    }
+}
@@ -0,0 +1,387 @@
+//! Processing of incoming iTIP messages
+use super::{
+    Timezones, content_line, find_instance, recurrence_id, same_address, same_instance, sequence,
+    set_property,
+};
+use crate::{
+    component::{
+        CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendar, IcalCalendarObject,
+    },
+    parser::{ContentLine, ICalProperty, ParserError, ParserOptions},
+    property::{IcalDTSTARTProperty, IcalRDATEProperty},
+    types::{CalDate, CalDateOrDateTime, CalDateTime},
+};
+use chrono::{DateTime, Duration, Utc};
+use std::cmp::Ordering;
+
+/// Result of [`apply`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum ApplyOutcome {
+    /// The stored object was updated
+    Applied,
+    /// The whole calendar object was cancelled
+    Cancelled,
+    /// The message is older than the stored object and was ignored
+    Stale,
+    /// The message doesn't refer to the stored object
+    UidMismatch,
+    /// The METHOD of the message isn't supported or missing
+    Unsupported(Option<String>),
+    /// The updated object is invalid, the stored object was left unchanged
+    Invalid(ParserError),
+}
+
+/// Parameters of a replying ATTENDEE that are copied to the stored object
+const REPLY_PARAMS: &[&str] = &["PARTSTAT", "DELEGATED-TO", "DELEGATED-FROM"];
+
+fn dtstamp(properties: &[ContentLine]) -> Option<DateTime<Utc>> {
+    properties
+        .iter()
+        .find(|prop| prop.name == "DTSTAMP")
+        .and_then(|dtstamp| CalDateTime::parse(&dtstamp.value, None).ok())
+        .map(|dtstamp| dtstamp.utc())
+}
+
+/// Whether `incoming` is older than `stored`, according to RFC 5546, section 2.1.5
+fn is_stale(incoming: &[ContentLine], stored: &[ContentLine]) -> bool {
+    match sequence(incoming).cmp(&sequence(stored)) {
+        Ordering::Less => true,
+        Ordering::Equal => dtstamp(incoming) < dtstamp(stored),
+        Ordering::Greater => false,
+    }
+}
+
+/// The UNTIL part of a recurrence rule
+fn rule_until(rule: &str) -> Option<CalDateOrDateTime> {
+    let until = rule.split(';').find_map(|part| {
+        let (name, value) = part.split_once('=')?;
+        name.eq_ignore_ascii_case("UNTIL").then_some(value)
+    })?;
+    if until.len() == 8 {
+        CalDate::parse(until, None)
+            .ok()
+            .map(CalDateOrDateTime::Date)
+    } else {
+        CalDateTime::parse(until, None)
+            .ok()
+            .map(CalDateOrDateTime::DateTime)
+    }
+}
+
+/// Ends the series of the main component with `properties` before the instance at `end`,
+/// for cancellations with `RANGE=THISANDFUTURE`
+fn end_series(
+    properties: &mut Vec<ContentLine>,
+    end: &CalDateOrDateTime,
+    timezones: &Timezones,
+) -> Result<(), ParserError> {
+    // UNTIL is inclusive and in UTC unless DTSTART is a DATE or floating
+    let until = match end.clone() {
+        CalDateOrDateTime::Date(date) => CalDateOrDateTime::Date(date).shift(-Duration::days(1))?,
+        CalDateOrDateTime::DateTime(datetime) => {
+            let until = if datetime.timezone().is_local() {
+                datetime
+            } else {
+                datetime.utc().into()
+            };
+            CalDateOrDateTime::DateTime(until.shift(-Duration::seconds(1))?)
+        }
+    };
+    for prop in properties.iter_mut() {
+        match prop.name.as_str() {
+            "RRULE" if rule_until(&prop.value).is_none_or(|current| current >= *end) => {
+                let mut parts: Vec<_> = prop
+                    .value
+                    .split(';')
+                    .filter(|part| {
+                        let name = part.split_once('=').map_or(*part, |(name, _)| name);
+                        !name.eq_ignore_ascii_case("UNTIL") && !name.eq_ignore_ascii_case("COUNT")
+                    })
+                    .map(ToOwned::to_owned)
+                    .collect();
+                parts.push(format!("UNTIL={}", until.format()));
+                prop.value = parts.join(";");
+            }
+            "RDATE" => {
+                let IcalRDATEProperty(dates, params) =
+                    ICalProperty::parse_prop(prop, Some(timezones))?;
+                let dates = dates
+                    .into_iter()
+                    .filter(|date| date.start() < *end)
+                    .collect();
+                *prop = IcalRDATEProperty(dates, params).into();
+            }
+            _ => {}
+        }
+    }
+    properties.retain(|prop| prop.name != "RDATE" || !prop.value.is_empty());
+    Ok(())
+}
+
+/// Whether the series of the main component with `properties` starts at or after `start`
+fn starts_after(
+    properties: &[ContentLine],
+    start: &CalDateOrDateTime,
+    timezones: &Timezones,
+) -> Result<bool, ParserError> {
+    let Some(dtstart) = properties.iter().find(|prop| prop.name == "DTSTART") else {
+        return Ok(false);
+    };
+    let IcalDTSTARTProperty(dtstart, _) = ICalProperty::parse_prop(dtstart, Some(timezones))?;
+    Ok(dtstart >= *start)
+}
+
+/// Updates the ATTENDEE of `properties` that replied with `reply`
+fn update_attendee(properties: &mut Vec<ContentLine>, reply: &ContentLine) {
+    let Some(attendee) = properties
+        .iter_mut()
+        .find(|prop| prop.name == "ATTENDEE" && same_address(&prop.value, &reply.value))
+    else {
+        // Calendar users may reply to requests forwarded to them
+        properties.push(reply.clone());
+        return;
+    };
+    for param in REPLY_PARAMS {
+        attendee.params.remove(param);
+        if let Some(value) = reply.params.get_param(param) {
+            attendee
+                .params
+                .replace_param((*param).to_owned(), value.to_owned());
+        }
+    }
+    attendee.params.remove("RSVP");
+}
+
+/// Applies the components of a message with `method` to the stored components `builders`
+fn apply_components<B: ComponentMut>(
+    builders: &mut Vec<B>,
+    method: &str,
+    incoming: &[&[ContentLine]],
+    incoming_timezones: &Timezones,
+    timezones: &Timezones,
+) -> Result<ApplyOutcome, ParserError> {
+    let mut ids = builders
+        .iter()
+        .map(|builder| recurrence_id(builder.get_properties(), timezones))
+        .collect::<Result<Vec<_>, _>>()?;
+    let main = ids
+        .iter()
+        .position(Option::is_none)
+        .ok_or(ParserError::NotComplete)?;
+    let mut applied = false;
+    let mut cancelled = false;
+    // A REQUEST containing the main component replaces the whole object
+    let full_request = method == "REQUEST"
+        && incoming
+            .iter()
+            .any(|properties| !properties.iter().any(|prop| prop.name == "RECURRENCE-ID"));
+    let mut keep = vec![!full_request; builders.len()];
+
+    for &properties in incoming {
+        let id = recurrence_id(properties, incoming_timezones)?;
+        let position = ids
+            .iter()
+            .position(|stored| same_instance(stored.as_ref(), id.as_ref()));
+        let stored = &builders[position.unwrap_or(main)];
+        if is_stale(properties, stored.get_properties()) {
+            if let Some(position) = position {
+                keep[position] = true;
+            }
+            continue;
+        }
+        applied = true;
+
+        match method {
+            "REQUEST" => match position {
+                Some(position) => {
+                    *builders[position].get_properties_mut() = properties.to_vec();
+                    keep[position] = true;
+                }
+                None => {
+                    let mut builder = B::default();
+                    *builder.get_properties_mut() = properties.to_vec();
+                    builders.push(builder);
+                    ids.push(id);
+                    keep.push(true);
+                }
+            },
+            "REPLY" => {
+                let Some(reply) = properties.iter().find(|prop| prop.name == "ATTENDEE") else {
+                    continue;
+                };
+                let position = match (position, &id) {
+                    (Some(position), _) => position,
+                    (None, Some(id)) => {
+                        // Reply for an instance without override
+                        let main = &builders[main];
+                        let (_, instance) = find_instance(
+                            std::iter::once((
+                                main.get_comp_name(),
+                                main.get_properties().as_slice(),
+                            )),
+                            id,
+                            timezones,
+                        )?;
+                        let mut builder = B::default();
+                        *builder.get_properties_mut() = instance.into_owned();
+                        builders.push(builder);
+                        ids.push(Some(id.clone()));
+                        keep.push(true);
+                        builders.len() - 1
+                    }
+                    (None, None) => main,
+                };
+                update_attendee(builders[position].get_properties_mut(), reply);
+            }
+            "CANCEL" => {
+                let this_and_future = properties.iter().any(|prop| {
+                    prop.name == "RECURRENCE-ID"
+                        && prop.params.get_param("RANGE") == Some("THISANDFUTURE")
+                });
+                match id {
+                    Some(id)
+                        if this_and_future
+                            && !starts_after(builders[main].get_properties(), &id, timezones)? =>
+                    {
+                        // Later overrides are cancelled with the rest of the series
+                        for (keep, stored) in keep.iter_mut().zip(&ids) {
+                            if stored.as_ref().is_some_and(|stored| *stored >= id) {
+                                *keep = false;
+                            }
+                        }
+                        end_series(builders[main].get_properties_mut(), &id, timezones)?;
+                    }
+                    Some(_) if !this_and_future => {
+                        if let Some(position) = position {
+                            keep[position] = false;
+                        }
+                        let Some(prop) =
+                            properties.iter().find(|prop| prop.name == "RECURRENCE-ID")
+                        else {
+                            continue;
+                        };
+                        let mut exdate = ContentLine {
+                            name: "EXDATE".to_owned(),
+                            ..prop.clone()
+                        };
+                        exdate.params.remove("RANGE");
+                        builders[main].get_properties_mut().push(exdate);
+                    }
+                    // Cancelling all instances from the start on cancels the whole series
+                    _ => {
+                        // Remember the SEQUENCE so older requests are recognized as stale
+                        let sequence = sequence(properties).to_string();
+                        for builder in builders.iter_mut() {
+                            let properties = builder.get_properties_mut();
+                            set_property(
+                                properties,
+                                content_line("STATUS", "CANCELLED".to_owned()),
+                            );
+                            set_property(properties, content_line("SEQUENCE", sequence.clone()));
+                        }
+                        cancelled = true;
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    let mut keep = keep.into_iter();
+    builders.retain(|_| keep.next().unwrap_or(true));
+    Ok(if cancelled {
+        ApplyOutcome::Cancelled
+    } else if applied {
+        ApplyOutcome::Applied
+    } else {
+        ApplyOutcome::Stale
+    })
+}
+
+/// Applies the incoming iTIP message `incoming` to the stored calendar object `stored`
+/// following the rules of RFC 5546 for REQUEST, REPLY and CANCEL messages.
+///
+/// - A REQUEST updates the components it contains and replaces the whole object
+///   if it contains the main component. Stored alarms are kept.
+/// - A REPLY updates the participation status of the replying attendee.
+/// - A CANCEL cancels the whole object or excludes the cancelled instances.
+///   Cancelling an instance with `RANGE=THISANDFUTURE` ends the series before it
+///   and removes the later overrides.
+///
+/// Messages with a lower SEQUENCE than the stored components, or an equal SEQUENCE
+/// and an older DTSTAMP, are ignored as stale.
+/// `stored` is only changed if the outcome is [`ApplyOutcome::Applied`] or [`ApplyOutcome::Cancelled`].
+pub fn apply(stored: &mut IcalCalendarObject, incoming: &IcalCalendar) -> ApplyOutcome {
+    let method = incoming
+        .get_property("METHOD")
+        .map(|method| method.value.to_ascii_uppercase());
+    let method = match method.as_deref() {
+        Some("REQUEST" | "REPLY" | "CANCEL") => method.unwrap(),
+        _ => return ApplyOutcome::Unsupported(method),
+    };
+
+    let mut inner = stored.inner.clone().mutable();
+    let name = match &inner {
+        CalendarInnerDataBuilder::Event(_) => "VEVENT",
+        CalendarInnerDataBuilder::Todo(_) => "VTODO",
+        CalendarInnerDataBuilder::Journal(_) => "VJOURNAL",
+    };
+    let components: Vec<&[ContentLine]> = super::calendar_components(incoming)
+        .into_iter()
+        .filter(|(component, _)| *component == name)
+        .map(|(_, properties)| properties)
+        .collect();
+    let uid_matches = |properties: &&[ContentLine]| {
+        properties
+            .iter()
+            .any(|prop| prop.name == "UID" && prop.value == stored.get_uid())
+    };
+    if components.is_empty() || !components.iter().all(uid_matches) {
+        return ApplyOutcome::UidMismatch;
+    }
+
+    let mut vtimezones = stored.vtimezones.clone();
+    let mut timezones = stored.timezones.clone();
+    for (tzid, vtimezone) in &incoming.vtimezones {
+        vtimezones
+            .entry(tzid.clone())
+            .or_insert_with(|| vtimezone.clone());
+    }
+    for (tzid, tz) in &incoming.timezones {
+        timezones.entry(tzid.clone()).or_insert(*tz);
+    }
+
+    let outcome = match &mut inner {
+        CalendarInnerDataBuilder::Event(events) => apply_components(
+            events,
+            &method,
+            &components,
+            &incoming.timezones,
+            &timezones,
+        ),
+        CalendarInnerDataBuilder::Todo(todos) => {
+            apply_components(todos, &method, &components, &incoming.timezones, &timezones)
+        }
+        CalendarInnerDataBuilder::Journal(journals) => apply_components(
+            journals,
+            &method,
+            &components,
+            &incoming.timezones,
+            &timezones,
+        ),
+    };
+    let outcome = match outcome {
+        Ok(outcome @ (ApplyOutcome::Applied | ApplyOutcome::Cancelled)) => outcome,
+        Ok(outcome) => return outcome,
+        Err(err) => return ApplyOutcome::Invalid(err),
+    };
+    match inner.build(&ParserOptions::reverify(&timezones), Some(&timezones)) {
+        Ok(inner) => {
+            stored.inner = inner;
+            stored.vtimezones = vtimezones;
+            stored.timezones = timezones;
+            outcome
+        }
+        Err(err) => ApplyOutcome::Invalid(err),
+    }
+}
@@ -0,0 +1,61 @@
+//! METHOD:CANCEL messages
+use super::{
+    build_message, content_line, find_instance, object_components, sequence, set_property, stamp,
+};
+use crate::{
+    component::{IcalCalendar, IcalCalendarObject},
+    parser::ParserError,
+    types::CalDateOrDateTime,
+};
+use std::borrow::Cow;
+
+/// Creates a METHOD:CANCEL message for the whole series of `object` if `instances` is empty,
+/// otherwise only for the instances starting at the given RECURRENCE-IDs.
+///
+/// Cancelled components get STATUS:CANCELLED, a new DTSTAMP and a SEQUENCE higher
+/// than their own and the one of the main component. Alarms are removed.
+pub fn cancel(
+    object: &IcalCalendarObject,
+    instances: &[CalDateOrDateTime],
+) -> Result<IcalCalendar, ParserError> {
+    let components = object_components(object);
+    let main_sequence = sequence(components[0].1);
+    let cancelled = if instances.is_empty() {
+        components
+            .iter()
+            .map(|&(name, properties)| (name, Cow::Borrowed(properties)))
+            .collect()
+    } else {
+        instances
+            .iter()
+            .map(|recurrence_id| {
+                find_instance(components.iter().copied(), recurrence_id, &object.timezones)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let components = cancelled
+        .into_iter()
+        .map(|(name, properties)| {
+            let mut properties = properties.into_owned();
+            let sequence = sequence(&properties).max(main_sequence) + 1;
+            set_property(
+                &mut properties,
+                content_line("SEQUENCE", sequence.to_string()),
+            );
+            set_property(
+                &mut properties,
+                content_line("STATUS", "CANCELLED".to_owned()),
+            );
+            stamp(&mut properties);
+            (name, properties)
+        })
+        .collect();
+    build_message(
+        &object.properties,
+        &object.vtimezones,
+        &object.timezones,
+        "CANCEL",
+        components,
+    )
+}
@@ -0,0 +1,384 @@
+//! METHOD:REQUEST and METHOD:REPLY messages for VFREEBUSY
+use super::{build_message, content_line, same_address, stamp};
+#[cfg(feature = "recurrence")]
+use crate::component::{CalendarInnerData, IcalCalendarObject};
+use crate::{
+    component::{Component, IcalCalendar, IcalEvent},
+    parser::{ContentLine, ParserError},
+    property::{FbType, GetProperty, IcalDTENDProperty, IcalDTSTARTProperty},
+    types::CalAddress,
+};
+use chrono::{DateTime, Duration, Utc};
+
+fn utc_line(name: &str, datetime: DateTime<Utc>) -> ContentLine {
+    content_line(name, datetime.format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// A period in which a calendar user is busy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusyPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub fbtype: FbType,
+}
+
+impl BusyPeriod {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, fbtype: FbType) -> Self {
+        Self { start, end, fbtype }
+    }
+}
+
+impl IcalEvent {
+    /// The period in which the event keeps its attendees busy,
+    /// `None` for TRANSPARENT and CANCELLED events and events without a duration.
+    ///
+    /// Recurring events are only considered with their first instance, see [`busy_periods`].
+    /// With `include_travel_time` the period starts earlier by the X-APPLE-TRAVEL-DURATION
+    /// Apple Calendar blocks for getting to the event.
+    pub fn busy_period(&self, include_travel_time: bool) -> Option<BusyPeriod> {
+        if self
+            .get_property("TRANSP")
+            .is_some_and(|transp| transp.value.eq_ignore_ascii_case("TRANSPARENT"))
+        {
+            return None;
+        }
+        let fbtype = match self.get_property("STATUS").map(|status| &status.value) {
+            Some(status) if status.eq_ignore_ascii_case("CANCELLED") => return None,
+            Some(status) if status.eq_ignore_ascii_case("TENTATIVE") => FbType::BusyTentative,
+            _ => FbType::Busy,
+        };
+        let duration = match self.get_duration() {
+            Some(duration) => duration,
+            None if self.dtstart.0.is_date() => Duration::days(1),
+            None => return None,
+        };
+        let mut start = self.dtstart.0.utc();
+        let end = start + duration;
+        if include_travel_time && let Some(travel) = self.get_travel_duration() {
+            start -= travel;
+        }
+        (start < end).then(|| BusyPeriod::new(start, end, fbtype))
+    }
+}
+
+/// The busy periods of the instances of the VEVENT `object` overlapping `start..end`,
+/// see [`IcalEvent::busy_period`].
+#[cfg(feature = "recurrence")]
+pub fn busy_periods(
+    object: &IcalCalendarObject,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    include_travel_time: bool,
+) -> Vec<BusyPeriod> {
+    let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+        return vec![];
+    };
+    // Instances starting before `start` can still overlap it
+    let lookbehind = main.get_duration().unwrap_or_default()
+        + main
+            .get_travel_duration()
+            .filter(|_| include_travel_time)
+            .unwrap_or_default();
+    main.expand_recurrence(Some(start - lookbehind), Some(end), overrides)
+        .iter()
+        .filter_map(|instance| instance.busy_period(include_travel_time))
+        .filter(|period| period.start < end && period.end > start)
+        .collect()
+}
+
+/// Creates a METHOD:REQUEST message asking `attendees` for their busy time between `start` and `end`.
+pub fn freebusy_request(
+    prodid: &str,
+    uid: &str,
+    organizer: &CalAddress,
+    attendees: &[CalAddress],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<IcalCalendar, ParserError> {
+    if attendees.is_empty() {
+        return Err(ParserError::MissingProperty("ATTENDEE"));
+    }
+    if start >= end {
+        return Err(ParserError::InvalidPropertyValue(
+            "DTEND must be after DTSTART".to_owned(),
+        ));
+    }
+    let mut properties = vec![
+        content_line("UID", uid.to_owned()),
+        utc_line("DTSTART", start),
+        utc_line("DTEND", end),
+        content_line("ORGANIZER", organizer.to_string()),
+    ];
+    properties.extend(
+        attendees
+            .iter()
+            .map(|attendee| content_line("ATTENDEE", attendee.to_string())),
+    );
+    stamp(&mut properties);
+    build_message(
+        &[
+            content_line("VERSION", "2.0".to_owned()),
+            content_line("PRODID", prodid.to_owned()),
+        ],
+        &Default::default(),
+        &Default::default(),
+        "REQUEST",
+        vec![("VFREEBUSY", properties)],
+    )
+}
+
+type Periods = Vec<(DateTime<Utc>, DateTime<Utc>)>;
+
+/// Merges the overlapping periods of each FBTYPE and clips them to `start..end`
+fn merge_periods(
+    busy: &[BusyPeriod],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<(FbType, Periods)> {
+    let mut merged: Vec<(FbType, Periods)> = vec![];
+    let mut busy: Vec<&BusyPeriod> = busy.iter().collect();
+    busy.sort_by_key(|period| period.start);
+    for period in busy {
+        let (period_start, period_end) = (period.start.max(start), period.end.min(end));
+        if period_start >= period_end {
+            continue;
+        }
+        let periods = match merged
+            .iter_mut()
+            .find(|(fbtype, _)| *fbtype == period.fbtype)
+        {
+            Some((_, periods)) => periods,
+            None => {
+                merged.push((period.fbtype.clone(), vec![]));
+                &mut merged.last_mut().unwrap().1
+            }
+        };
+        match periods.last_mut() {
+            Some((_, last_end)) if *last_end >= period_start => {
+                *last_end = (*last_end).max(period_end);
+            }
+            _ => periods.push((period_start, period_end)),
+        }
+    }
+    merged
+}
+
+/// Creates the METHOD:REPLY message in which `attendee` answers the VFREEBUSY `request`
+/// with its `busy` periods.
+///
+/// Periods are clipped to the requested time range, overlapping periods of the same FBTYPE
+/// are merged into one and each FBTYPE gets its own FREEBUSY property.
+pub fn freebusy_reply(
+    request: &IcalCalendar,
+    attendee: &CalAddress,
+    busy: &[BusyPeriod],
+) -> Result<IcalCalendar, ParserError> {
+    let freebusy = request
+        .free_busys
+        .first()
+        .ok_or_else(|| ParserError::InvalidComponent("no VFREEBUSY in request".to_owned()))?;
+    let IcalDTSTARTProperty(start, _) = freebusy
+        .safe_get_required(Some(&request.timezones))
+        .map_err(|_| ParserError::MissingProperty("DTSTART"))?;
+    let IcalDTENDProperty(end, _) = freebusy
+        .safe_get_required(Some(&request.timezones))
+        .map_err(|_| ParserError::MissingProperty("DTEND"))?;
+    let (start, end) = (start.utc(), end.utc());
+
+    let mut properties: Vec<ContentLine> = freebusy
+        .get_properties()
+        .iter()
+        .filter(|prop| matches!(prop.name.as_str(), "UID" | "ORGANIZER"))
+        .cloned()
+        .collect();
+    properties.push(utc_line("DTSTART", start));
+    properties.push(utc_line("DTEND", end));
+    properties.push(
+        freebusy
+            .get_named_properties("ATTENDEE")
+            .find(|prop| same_address(&prop.value, attendee))
+            .cloned()
+            .unwrap_or_else(|| content_line("ATTENDEE", attendee.to_string())),
+    );
+    for (fbtype, periods) in merge_periods(busy, start, end) {
+        let mut line = content_line(
+            "FREEBUSY",
+            periods
+                .iter()
+                .map(|(start, end)| {
+                    format!(
+                        "{}/{}",
+                        start.format("%Y%m%dT%H%M%SZ"),
+                        end.format("%Y%m%dT%H%M%SZ")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        line.params
+            .replace_param(FbType::PARAM.to_owned(), fbtype.to_string());
+        properties.push(line);
+    }
+    stamp(&mut properties);
+    build_message(
+        &request.properties,
+        &Default::default(),
+        &Default::default(),
+        "REPLY",
+        vec![("VFREEBUSY", properties)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "recurrence")]
+    use super::busy_periods;
+    use super::{BusyPeriod, freebusy_reply, freebusy_request};
+    #[cfg(feature = "recurrence")]
+    use crate::{IcalObjectParser, component::CalendarInnerData, property::TravelAdvisoryBehavior};
+    use crate::{component::Component, property::FbType, validation::validate};
+    #[cfg(feature = "recurrence")]
+    use chrono::Duration;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_freebusy() {
+        let at = |hour| Utc.with_ymd_and_hms(2026, 3, 23, hour, 0, 0).unwrap();
+        let jane = "mailto:jane@example.com".parse().unwrap();
+        let john = "mailto:john@example.com".parse().unwrap();
+        assert!(
+            freebusy_request(
+                "-//Example//EN",
+                "fb@example.com",
+                &jane,
+                &[],
+                at(8),
+                at(18)
+            )
+            .is_err()
+        );
+        let request = freebusy_request(
+            "-//Example//EN",
+            "fb@example.com",
+            &jane,
+            &[john],
+            at(8),
+            at(18),
+        )
+        .unwrap();
+        assert!(validate(&request).is_valid(), "{:?}", validate(&request));
+        let freebusy = &request.free_busys[0];
+        assert_eq!(
+            freebusy.get_property("DTSTART").unwrap().value,
+            "20260323T080000Z"
+        );
+
+        let busy = [
+            BusyPeriod::new(at(6), at(9), FbType::Busy),
+            BusyPeriod::new(at(12), at(14), FbType::Busy),
+            BusyPeriod::new(at(13), at(15), FbType::Busy),
+            BusyPeriod::new(at(16), at(17), FbType::BusyTentative),
+            BusyPeriod::new(at(19), at(20), FbType::Busy),
+        ];
+        let reply =
+            freebusy_reply(&request, &"MAILTO:John@example.com".parse().unwrap(), &busy).unwrap();
+        assert_eq!(reply.get_property("METHOD").unwrap().value, "REPLY");
+        assert!(validate(&reply).is_valid(), "{:?}", validate(&reply));
+        let freebusy = &reply.free_busys[0];
+        assert_eq!(
+            freebusy.get_property("ATTENDEE").unwrap().value,
+            "mailto:john@example.com"
+        );
+        assert_eq!(
+            freebusy.get_property("UID").unwrap().value,
+            "fb@example.com"
+        );
+        let periods: Vec<_> = freebusy
+            .get_named_properties("FREEBUSY")
+            .map(|prop| {
+                (
+                    prop.params.get_param("FBTYPE").unwrap(),
+                    prop.value.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            periods,
+            vec![
+                (
+                    "BUSY",
+                    "20260323T080000Z/20260323T090000Z,20260323T120000Z/20260323T150000Z"
+                ),
+                ("BUSY-TENTATIVE", "20260323T160000Z/20260323T170000Z"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_busy_periods() {
+        let at = |day, hour, minute| Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap();
+        let object = IcalObjectParser::from_slice(
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Apple Inc.//iPhone OS 18.3//EN\r
+BEGIN:VEVENT\r
+UID:gym@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T180000Z\r
+DTEND:20260323T193000Z\r
+RRULE:FREQ=DAILY;COUNT=3\r
+SUMMARY:Gym\r
+X-APPLE-TRAVEL-ADVISORY-BEHAVIOR:AUTOMATIC\r
+X-APPLE-TRAVEL-DURATION;VALUE=DURATION:PT30M\r
+X-APPLE-TRAVEL-START;ROUTING=CAR;VALUE=URI;X-ADDRESS=Home;X-TITLE=Home:\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:gym@example.com\r
+DTSTAMP:20260101T120000Z\r
+RECURRENCE-ID:20260324T180000Z\r
+DTSTART:20260324T180000Z\r
+DTEND:20260324T193000Z\r
+STATUS:CANCELLED\r
+SUMMARY:Gym\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+            .as_bytes(),
+        )
+        .expect_one()
+        .unwrap();
+        let CalendarInnerData::Event(main, _) = object.get_inner() else {
+            unreachable!()
+        };
+        assert_eq!(main.get_travel_duration(), Some(Duration::minutes(30)));
+        assert_eq!(
+            main.get_travel_advisory_behavior(),
+            Some(TravelAdvisoryBehavior::Automatic)
+        );
+        assert_eq!(
+            main.get_travel_start().unwrap().routing.as_deref(),
+            Some("CAR")
+        );
+
+        let periods = busy_periods(&object, at(23, 0, 0), at(26, 0, 0), false);
+        assert_eq!(
+            periods,
+            vec![
+                BusyPeriod::new(at(23, 18, 0), at(23, 19, 30), FbType::Busy),
+                BusyPeriod::new(at(25, 18, 0), at(25, 19, 30), FbType::Busy),
+            ]
+        );
+        // The travel time of the first instance overlaps the range
+        let periods = busy_periods(&object, at(23, 17, 45), at(23, 18, 0), true);
+        assert_eq!(
+            periods,
+            vec![BusyPeriod::new(
+                at(23, 17, 30),
+                at(23, 19, 30),
+                FbType::Busy
+            )]
+        );
+        assert!(busy_periods(&object, at(23, 17, 45), at(23, 18, 0), false).is_empty());
+    }
+}
@@ -0,0 +1,755 @@
+//! Scheduling messages as defined in RFC 5546 (iTIP)
+use crate::{
+    component::{
+        CalendarInnerData, CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendar,
+        IcalCalendarBuilder, IcalCalendarObject, IcalEventBuilder, IcalFreeBusyBuilder,
+        IcalJournalBuilder, IcalTimeZone, IcalTodoBuilder, Timezones, sequence,
+    },
+    parser::{ContentLine, ICalProperty, ParseProp, ParserError, ParserOptions},
+    property::{IcalDTSTARTProperty, IcalDURATIONProperty, IcalRECURIDProperty, RecurIdRange},
+    types::{CalDateOrDateTime, CalDateTime},
+};
+use chrono::Utc;
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{BTreeMap, HashSet},
+};
+
+mod apply;
+pub use apply::{ApplyOutcome, apply};
+mod cancel;
+pub use cancel::cancel;
+mod freebusy;
+#[cfg(feature = "recurrence")]
+pub use freebusy::busy_periods;
+pub use freebusy::{BusyPeriod, freebusy_reply, freebusy_request};
+mod publish;
+pub use publish::{PublishOptions, publish};
+mod reconcile;
+pub use reconcile::reconcile;
+mod reply;
+pub use reply::{reply, reply_instance};
+mod request;
+pub use request::request;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ItipError {
+    /// Only events and todos can be sent as invitations
+    #[error("{0} components can't be requested")]
+    NotRequestable(&'static str),
+    /// A request without attendees would invite nobody
+    #[error("no attendees to invite")]
+    NoAttendees,
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+}
+
+/// Whether two CAL-ADDRESS values refer to the same calendar user,
+/// clients differ in the case of the scheme and email address
+pub(crate) fn same_address(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+fn content_line(name: &str, value: String) -> ContentLine {
+    ContentLine {
+        name: name.to_owned(),
+        params: Default::default(),
+        value,
+    }
+}
+
+/// Replaces all properties named like `line` with it
+fn set_property(properties: &mut Vec<ContentLine>, line: ContentLine) {
+    properties.retain(|prop| prop.name != line.name);
+    properties.push(line);
+}
+
+/// Sets DTSTAMP to the current time, as every iTIP message must
+fn stamp(properties: &mut Vec<ContentLine>) {
+    set_property(
+        properties,
+        content_line("DTSTAMP", Utc::now().format("%Y%m%dT%H%M%SZ").to_string()),
+    );
+}
+
+/// The name and properties of the events, to-dos and journals of `calendar`
+fn calendar_components(calendar: &IcalCalendar) -> Vec<(&'static str, &[ContentLine])> {
+    let events = calendar
+        .events
+        .iter()
+        .map(|event| (event.get_comp_name(), event.get_properties().as_slice()));
+    let todos = calendar
+        .todos
+        .iter()
+        .map(|todo| (todo.get_comp_name(), todo.get_properties().as_slice()));
+    let journals = calendar
+        .journals
+        .iter()
+        .map(|journal| (journal.get_comp_name(), journal.get_properties().as_slice()));
+    events.chain(todos).chain(journals).collect()
+}
+
+/// The name and properties of the components of `object`, starting with the main component
+fn object_components(object: &IcalCalendarObject) -> Vec<(&'static str, &[ContentLine])> {
+    match &object.inner {
+        CalendarInnerData::Event(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .map(|event| (event.get_comp_name(), event.get_properties().as_slice()))
+            .collect(),
+        CalendarInnerData::Todo(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .map(|todo| (todo.get_comp_name(), todo.get_properties().as_slice()))
+            .collect(),
+        CalendarInnerData::Journal(main, overrides) => std::iter::once(main)
+            .chain(overrides)
+            .map(|journal| (journal.get_comp_name(), journal.get_properties().as_slice()))
+            .collect(),
+    }
+}
+
+/// Calls `f` with the properties of every component of `inner`
+fn for_each_component(
+    inner: &mut CalendarInnerDataBuilder,
+    mut f: impl FnMut(&mut Vec<ContentLine>),
+) {
+    match inner {
+        CalendarInnerDataBuilder::Event(events) => {
+            events.iter_mut().for_each(|event| f(&mut event.properties));
+        }
+        CalendarInnerDataBuilder::Todo(todos) => {
+            todos.iter_mut().for_each(|todo| f(&mut todo.properties));
+        }
+        CalendarInnerDataBuilder::Journal(journals) => {
+            journals
+                .iter_mut()
+                .for_each(|journal| f(&mut journal.properties));
+        }
+    }
+}
+
+/// Wraps `object` into a calendar with `method`, keeping its PRODID
+fn message(object: IcalCalendarObject, method: &str) -> IcalCalendar {
+    let prodid = object
+        .properties
+        .iter()
+        .find(|prop| prop.name == "PRODID")
+        .map(|prodid| prodid.value.clone())
+        .unwrap_or_default();
+    IcalCalendar::from_objects(
+        prodid,
+        vec![object],
+        vec![content_line("METHOD", method.to_owned())],
+    )
+}
+
+/// Builds a calendar with `method` from the properties of `components`,
+/// VERSION and PRODID are taken from `calendar` and the VTIMEZONE components
+/// used by `components` from `vtimezones`, resolving to `timezones`
+fn build_message(
+    calendar: &[ContentLine],
+    vtimezones: &BTreeMap<String, IcalTimeZone>,
+    timezones: &Timezones,
+    method: &str,
+    components: Vec<(&'static str, Vec<ContentLine>)>,
+) -> Result<IcalCalendar, ParserError> {
+    if components.is_empty() {
+        return Err(ParserError::InvalidComponent(format!(
+            "no component for METHOD:{method}"
+        )));
+    }
+    let tzids: HashSet<&str> = components
+        .iter()
+        .flat_map(|(_, properties)| properties)
+        .filter_map(|prop| prop.params.get_tzid())
+        .collect();
+    let mut builder = IcalCalendarBuilder {
+        vtimezones: vtimezones
+            .iter()
+            .filter(|(tzid, _)| tzids.contains(tzid.as_str()))
+            .map(|(tzid, vtimezone)| (tzid.clone(), vtimezone.clone()))
+            .collect(),
+        ..Default::default()
+    };
+    builder.properties = calendar
+        .iter()
+        .filter(|prop| matches!(prop.name.as_str(), "VERSION" | "PRODID"))
+        .cloned()
+        .chain([content_line("METHOD", method.to_owned())])
+        .collect();
+    for (name, properties) in components {
+        match name {
+            "VTODO" => builder.todos.push(IcalTodoBuilder {
+                properties,
+                alarms: vec![],
+            }),
+            "VJOURNAL" => builder.journals.push(IcalJournalBuilder { properties }),
+            "VFREEBUSY" => builder.free_busys.push(IcalFreeBusyBuilder { properties }),
+            _ => builder.events.push(IcalEventBuilder {
+                properties,
+                alarms: vec![],
+            }),
+        }
+    }
+    builder.build(&ParserOptions::reverify(timezones), None)
+}
+
+/// The RECURRENCE-ID of a component, `None` for the main component
+fn recurrence_id(
+    properties: &[ContentLine],
+    timezones: &Timezones,
+) -> Result<Option<CalDateOrDateTime>, ParserError> {
+    properties
+        .iter()
+        .find(|prop| prop.name == "RECURRENCE-ID")
+        .map(|prop| {
+            ICalProperty::parse_prop(prop, Some(timezones))
+                .map(|IcalRECURIDProperty(value, _, _)| value)
+        })
+        .transpose()
+}
+
+fn same_instance(a: Option<&CalDateOrDateTime>, b: Option<&CalDateOrDateTime>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.cmp(b) == Ordering::Equal,
+        _ => false,
+    }
+}
+
+/// The properties of the instance starting at `recurrence_id` of a recurring component,
+/// either of its override or derived from the main component
+fn find_instance<'a>(
+    components: impl Iterator<Item = (&'static str, &'a [ContentLine])>,
+    recurrence_id: &CalDateOrDateTime,
+    timezones: &Timezones,
+) -> Result<(&'static str, Cow<'a, [ContentLine]>), ParserError> {
+    let mut main = None;
+    for (name, properties) in components {
+        let Some(prop) = properties.iter().find(|prop| prop.name == "RECURRENCE-ID") else {
+            main = Some((name, properties));
+            continue;
+        };
+        let IcalRECURIDProperty(value, _, _) = ICalProperty::parse_prop(prop, Some(timezones))?;
+        if value.cmp(recurrence_id) == Ordering::Equal {
+            return Ok((name, Cow::Borrowed(properties)));
+        }
+    }
+    let (name, properties) = main.ok_or(ParserError::NotComplete)?;
+
+    let dtstart = properties
+        .iter()
+        .find(|prop| prop.name == "DTSTART")
+        .ok_or(ParserError::MissingProperty("DTSTART"))?;
+    let IcalDTSTARTProperty(start, params) = ICalProperty::parse_prop(dtstart, Some(timezones))?;
+    let value = match (&start, recurrence_id) {
+        (CalDateOrDateTime::Date(_), CalDateOrDateTime::Date(date)) => {
+            CalDateOrDateTime::Date(date.clone())
+        }
+        (CalDateOrDateTime::DateTime(start), CalDateOrDateTime::DateTime(datetime)) => {
+            CalDateOrDateTime::DateTime(CalDateTime(datetime.0.with_timezone(&start.timezone())))
+        }
+        _ => return Err(ParserError::DtstartNotMatchingRecurId),
+    };
+    let recurrence_id: ContentLine = IcalRECURIDProperty(value, params, RecurIdRange::This).into();
+    let mut instance: Vec<ContentLine> = properties
+        .iter()
+        .filter(|prop| {
+            !matches!(
+                prop.name.as_str(),
+                "DTSTART" | "DTEND" | "DUE" | "RRULE" | "RDATE" | "EXDATE" | "EXRULE"
+            )
+        })
+        .cloned()
+        .collect();
+    // DTEND and DUE of the main component don't apply to other instances
+    if let Some(end) = properties
+        .iter()
+        .find(|prop| matches!(prop.name.as_str(), "DTEND" | "DUE"))
+        && !properties.iter().any(|prop| prop.name == "DURATION")
+    {
+        let end: CalDateOrDateTime = ParseProp::parse_prop(end, Some(timezones), "DATE-TIME")?;
+        instance.push(IcalDURATIONProperty(end.utc() - start.utc(), Default::default()).into());
+    }
+    instance.push(ContentLine {
+        name: "DTSTART".to_owned(),
+        ..recurrence_id.clone()
+    });
+    instance.push(recurrence_id);
+    Ok((name, Cow::Owned(instance)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ApplyOutcome, ItipError, PublishOptions, apply, cancel, publish, reconcile, reply,
+        reply_instance, request,
+    };
+    use crate::{
+        IcalObjectParser, IcalParser,
+        component::{CalendarInnerData, Component, IcalCalendarObject},
+        generator::Emitter,
+        property::PartStat,
+        types::CalDateOrDateTime,
+        validation::validate,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    pub(super) const INVITE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;COUNT=3\r
+SUMMARY:Meeting\r
+ORGANIZER;CN=Jane:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+ATTENDEE:mailto:john@example.com\r
+ATTENDEE;PARTSTAT=TENTATIVE:mailto:max@example.com\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Meeting\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+RECURRENCE-ID:20260330T080000Z\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260330T090000Z\r
+DURATION:PT1H\r
+SUMMARY:Meeting\r
+ORGANIZER:mailto:jane@example.com\r
+ATTENDEE:mailto:john@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_request() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let organizer = "MAILTO:Jane@example.com".parse().unwrap();
+        let message = request(&object, &organizer).unwrap();
+        assert_eq!(message.get_property("METHOD").unwrap().value, "REQUEST");
+        assert!(validate(&message).is_valid(), "{:?}", validate(&message));
+
+        let main = &message.events[0];
+        assert!(main.alarms.is_empty());
+        assert_eq!(main.get_property("SEQUENCE").unwrap().value, "0");
+        assert_ne!(
+            main.get_property("DTSTAMP").unwrap().value,
+            "20260101T120000Z"
+        );
+        // The organizer didn't change, so CN is kept
+        let organizer = main.get_property("ORGANIZER").unwrap();
+        assert_eq!(organizer.params.get_param("CN"), Some("Jane"));
+        let attendees: Vec<_> = main
+            .get_named_properties("ATTENDEE")
+            .map(|attendee| {
+                (
+                    attendee.params.get_param("PARTSTAT"),
+                    attendee.params.get_param("RSVP"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            attendees,
+            vec![
+                (Some("ACCEPTED"), None),
+                (Some("NEEDS-ACTION"), Some("TRUE")),
+                (Some("TENTATIVE"), None),
+            ]
+        );
+
+        let other = "mailto:max@example.com".parse().unwrap();
+        let message = request(&object, &other).unwrap();
+        for event in &message.events {
+            let organizer = event.get_property("ORGANIZER").unwrap();
+            assert_eq!(organizer.value, "mailto:max@example.com");
+            assert!(organizer.params.is_empty());
+        }
+
+        let journal = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VJOURNAL\r
+UID:journal@example.com\r
+DTSTAMP:20260101T120000Z\r
+ATTENDEE:mailto:john@example.com\r
+END:VJOURNAL\r
+END:VCALENDAR\r
+";
+        let object = IcalObjectParser::from_slice(journal.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(
+            request(&object, &organizer.value.parse().unwrap()).unwrap_err(),
+            ItipError::NotRequestable("VJOURNAL")
+        );
+
+        let lonely = journal.replace("VJOURNAL", "VEVENT").replace(
+            "ATTENDEE:mailto:john@example.com\r\n",
+            "DTSTART:20260105T090000Z\r\n",
+        );
+        let object = IcalObjectParser::from_slice(lonely.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(
+            request(&object, &organizer.value.parse().unwrap()).unwrap_err(),
+            ItipError::NoAttendees
+        );
+    }
+
+    #[test]
+    fn test_reply() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let invite = request(&object, &"mailto:jane@example.com".parse().unwrap()).unwrap();
+        let john = "mailto:JOHN@example.com".parse().unwrap();
+
+        let message = reply(&invite, &john, PartStat::Accepted).unwrap();
+        assert_eq!(message.get_property("METHOD").unwrap().value, "REPLY");
+        assert!(validate(&message).is_valid(), "{:?}", validate(&message));
+        assert_eq!(message.events.len(), 2);
+        for event in &message.events {
+            let [attendee] = event
+                .get_named_properties("ATTENDEE")
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            assert_eq!(attendee.value, "mailto:john@example.com");
+            assert_eq!(attendee.params.get_param("PARTSTAT"), Some("ACCEPTED"));
+            assert_eq!(attendee.params.get_param("RSVP"), None);
+            assert_eq!(
+                event.get_property("REQUEST-STATUS").unwrap().value,
+                "2.0;Success"
+            );
+            assert!(event.get_property("SUMMARY").is_none());
+        }
+
+        // Instance with an override
+        let recurrence_id: CalDateOrDateTime =
+            Utc.with_ymd_and_hms(2026, 3, 30, 8, 0, 0).unwrap().into();
+        let message = reply_instance(&invite, &john, PartStat::Declined, &recurrence_id).unwrap();
+        let [event] = message.events.as_slice() else {
+            panic!("{:?}", message.events);
+        };
+        assert_eq!(
+            event.get_property("DTSTART").unwrap().value,
+            "20260330T090000Z"
+        );
+
+        // Instance without an override
+        let recurrence_id: CalDateOrDateTime =
+            Utc.with_ymd_and_hms(2026, 4, 6, 8, 0, 0).unwrap().into();
+        let message = reply_instance(&invite, &john, PartStat::Declined, &recurrence_id).unwrap();
+        let [event] = message.events.as_slice() else {
+            panic!("{:?}", message.events);
+        };
+        assert_eq!(
+            event.get_property("RECURRENCE-ID").unwrap().value,
+            "20260406T080000Z"
+        );
+        assert_eq!(
+            event.get_property("DTSTART").unwrap().value,
+            "20260406T080000Z"
+        );
+        assert!(validate(&message).is_valid(), "{:?}", validate(&message));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+
+        let message = cancel(&object, &[]).unwrap();
+        assert_eq!(message.get_property("METHOD").unwrap().value, "CANCEL");
+        assert!(validate(&message).is_valid(), "{:?}", validate(&message));
+        assert_eq!(message.events.len(), 2);
+        for event in &message.events {
+            assert_eq!(event.get_property("STATUS").unwrap().value, "CANCELLED");
+            assert_eq!(event.get_property("SEQUENCE").unwrap().value, "1");
+            assert!(event.alarms.is_empty());
+        }
+
+        let recurrence_id: CalDateOrDateTime =
+            Utc.with_ymd_and_hms(2026, 4, 6, 8, 0, 0).unwrap().into();
+        let message = cancel(&object, &[recurrence_id]).unwrap();
+        assert!(validate(&message).is_valid(), "{:?}", validate(&message));
+        let [event] = message.events.as_slice() else {
+            panic!("{:?}", message.events);
+        };
+        assert_eq!(
+            event.get_property("RECURRENCE-ID").unwrap().value,
+            "20260406T080000Z"
+        );
+        assert_eq!(event.get_property("STATUS").unwrap().value, "CANCELLED");
+        assert!(event.get_property("RRULE").is_none());
+    }
+
+    #[test]
+    fn test_apply() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let invite = request(&object, &"mailto:jane@example.com".parse().unwrap()).unwrap();
+        let john = "mailto:john@example.com".parse().unwrap();
+        let partstat = |object: &IcalCalendarObject, index: usize| {
+            let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+                panic!();
+            };
+            let event = std::iter::once(main).chain(overrides).nth(index).unwrap();
+            event
+                .get_named_properties("ATTENDEE")
+                .find(|attendee| attendee.value == "mailto:john@example.com")
+                .and_then(|attendee| attendee.params.get_param("PARTSTAT").map(ToOwned::to_owned))
+        };
+
+        let mut stored = object.clone();
+        let message = reply(&invite, &john, PartStat::Accepted).unwrap();
+        assert_eq!(apply(&mut stored, &message), ApplyOutcome::Applied);
+        assert_eq!(partstat(&stored, 0).as_deref(), Some("ACCEPTED"));
+        assert_eq!(partstat(&stored, 1).as_deref(), Some("ACCEPTED"));
+
+        // Reply for an instance without override
+        let recurrence_id: CalDateOrDateTime =
+            Utc.with_ymd_and_hms(2026, 4, 6, 8, 0, 0).unwrap().into();
+        let message = reply_instance(&invite, &john, PartStat::Declined, &recurrence_id).unwrap();
+        assert_eq!(apply(&mut stored, &message), ApplyOutcome::Applied);
+        assert_eq!(partstat(&stored, 0).as_deref(), Some("ACCEPTED"));
+        assert_eq!(partstat(&stored, 2).as_deref(), Some("DECLINED"));
+
+        // Cancelling an instance excludes it
+        let mut stored = object.clone();
+        let message = cancel(&object, &[recurrence_id]).unwrap();
+        assert_eq!(apply(&mut stored, &message), ApplyOutcome::Applied);
+        let CalendarInnerData::Event(main, _) = stored.get_inner() else {
+            panic!();
+        };
+        assert_eq!(
+            main.get_property("EXDATE").unwrap().value,
+            "20260406T080000Z"
+        );
+
+        // A request older than the cancellation is stale
+        let message = cancel(&object, &[]).unwrap();
+        assert_eq!(apply(&mut stored, &message), ApplyOutcome::Cancelled);
+        assert_eq!(apply(&mut stored, &invite), ApplyOutcome::Stale);
+
+        let other = IcalParser::from_slice(
+            INVITE
+                .replace("meeting@example.com", "other@example.com")
+                .as_bytes(),
+        )
+        .expect_one()
+        .unwrap();
+        assert_eq!(apply(&mut stored, &other), ApplyOutcome::Unsupported(None));
+        let other = request(
+            &other.into_objects().unwrap().remove(0),
+            &"mailto:jane@example.com".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(apply(&mut stored, &other), ApplyOutcome::UidMismatch);
+    }
+
+    #[test]
+    fn test_apply_stale() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+        // A reply with the same SEQUENCE but older than the stored object
+        let message = IcalParser::from_slice(
+            "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+METHOD:REPLY\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20251201T120000Z\r
+DTSTART:20260323T080000Z\r
+ORGANIZER:mailto:jane@example.com\r
+ATTENDEE;PARTSTAT=DECLINED:mailto:john@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+            .as_bytes(),
+        )
+        .expect_one()
+        .unwrap();
+        let mut stored = object.clone();
+        assert_eq!(apply(&mut stored, &message), ApplyOutcome::Stale);
+        assert_eq!(stored.generate(), object.generate());
+    }
+
+    #[test]
+    fn test_apply_this_and_future() {
+        let object = IcalObjectParser::from_slice(INVITE.as_bytes())
+            .expect_one()
+            .unwrap();
+        let message = |recurrence_id: &str| {
+            IcalParser::from_slice(
+                format!(
+                    "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+METHOD:CANCEL\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260201T120000Z\r
+SEQUENCE:1\r
+RECURRENCE-ID;RANGE=THISANDFUTURE:{recurrence_id}\r
+DTSTART:{recurrence_id}\r
+ORGANIZER:mailto:jane@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+"
+                )
+                .as_bytes(),
+            )
+            .expect_one()
+            .unwrap()
+        };
+
+        // The series ends before the cancelled instance, later overrides are removed
+        let mut stored = object.clone();
+        assert_eq!(
+            apply(&mut stored, &message("20260330T080000Z")),
+            ApplyOutcome::Applied
+        );
+        let CalendarInnerData::Event(main, overrides) = stored.get_inner() else {
+            panic!();
+        };
+        assert!(overrides.is_empty());
+        assert_eq!(
+            main.get_property("RRULE").unwrap().value,
+            "FREQ=WEEKLY;UNTIL=20260330T075959Z"
+        );
+        assert!(main.get_property("EXDATE").is_none());
+
+        // Cancelling from the first instance on cancels the whole series
+        let mut stored = object.clone();
+        assert_eq!(
+            apply(&mut stored, &message("20260323T080000Z")),
+            ApplyOutcome::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let organizer = INVITE
+            .replace(
+                "SUMMARY:Meeting\r\nORGANIZER;CN",
+                "SUMMARY:Updated\r\nORGANIZER;CN",
+            )
+            .replace(
+                "BEGIN:VALARM\r\nACTION:DISPLAY\r\nDESCRIPTION:Meeting\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\n",
+                "",
+            )
+            .replace("RRULE", "X-ORGANIZER-ONLY:1\r\nRRULE");
+        let organizer = IcalObjectParser::from_slice(organizer.as_bytes())
+            .expect_one()
+            .unwrap();
+        // The attendee has no override and marked the meeting as free
+        let (main, _) = INVITE.split_once("END:VEVENT\r\n").unwrap();
+        let attendee = format!("{main}END:VEVENT\r\nEND:VCALENDAR\r\n").replace(
+            "RRULE",
+            "TRANSP:TRANSPARENT\r\nX-MOZ-LASTACK:20260101T120000Z\r\nRRULE",
+        );
+        let attendee = IcalObjectParser::from_slice(attendee.as_bytes())
+            .expect_one()
+            .unwrap();
+
+        let merged = reconcile(&organizer, &attendee).unwrap();
+        let CalendarInnerData::Event(main, overrides) = merged.get_inner() else {
+            panic!();
+        };
+        assert_eq!(main.get_property("SUMMARY").unwrap().value, "Updated");
+        assert!(main.get_property("X-ORGANIZER-ONLY").is_none());
+        assert!(main.get_property("X-MOZ-LASTACK").is_some());
+        assert_eq!(main.alarms.len(), 1);
+        let [instance] = overrides.as_slice() else {
+            panic!("{overrides:?}");
+        };
+        assert_eq!(
+            instance.get_property("DTSTART").unwrap().value,
+            "20260330T090000Z"
+        );
+        assert_eq!(
+            instance.get_property("TRANSP").unwrap().value,
+            "TRANSPARENT"
+        );
+        assert_eq!(instance.alarms.len(), 1);
+
+        let other = IcalObjectParser::from_slice(
+            INVITE
+                .replace("meeting@example.com", "other@example.com")
+                .as_bytes(),
+        )
+        .expect_one()
+        .unwrap();
+        assert!(reconcile(&organizer, &other).is_err());
+    }
+
+    #[test]
+    fn test_publish() {
+        let parse = |uid: &str| {
+            IcalObjectParser::from_slice(INVITE.replace("meeting@example.com", uid).as_bytes())
+                .expect_one()
+                .unwrap()
+                .in_timezone(chrono_tz::Europe::Berlin)
+                .unwrap()
+        };
+        let options = PublishOptions::new()
+            .with_name("Team")
+            .with_refresh_interval(Duration::hours(12));
+        let feed = publish(
+            "-//Example Corp.//Feed//EN",
+            vec![parse("meeting@example.com"), parse("all-hands@example.com")],
+            &options,
+        )
+        .unwrap();
+        assert!(validate(&feed).is_valid(), "{:?}", validate(&feed));
+        assert_eq!(feed.get_property("METHOD").unwrap().value, "PUBLISH");
+        assert_eq!(feed.get_property("NAME").unwrap().value, "Team");
+        assert_eq!(
+            feed.get_property("REFRESH-INTERVAL").unwrap().value,
+            "PT12H"
+        );
+        assert_eq!(feed.vtimezones.len(), 1);
+        let uids: Vec<_> = feed
+            .events
+            .iter()
+            .map(|event| event.get_uid().to_owned())
+            .collect();
+        assert_eq!(
+            uids,
+            [
+                "all-hands@example.com",
+                "all-hands@example.com",
+                "meeting@example.com",
+                "meeting@example.com"
+            ]
+        );
+        assert!(
+            feed.events
+                .iter()
+                .all(|event| event.get_property("ATTENDEE").is_none())
+        );
+
+        assert!(
+            publish(
+                "-//Example Corp.//Feed//EN",
+                vec![parse("meeting@example.com"), parse("meeting@example.com")],
+                &options,
+            )
+            .is_err()
+        );
+    }
+}
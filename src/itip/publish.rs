@@ -0,0 +1,123 @@
+//! METHOD:PUBLISH feeds
+use super::{content_line, for_each_component, set_property};
+use crate::{
+    component::{CalendarInnerData, IcalCalendar, IcalCalendarObject},
+    parser::{ContentLine, ParserError, ParserOptions},
+    types::{CalAddress, Value},
+};
+use chrono::Duration;
+
+/// Options for [`publish`]
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    name: Option<String>,
+    refresh_interval: Option<Duration>,
+    organizer: Option<CalAddress>,
+}
+
+impl PublishOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name of the feed, set as NAME and X-WR-CALNAME which Apple Calendar relies on
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// How often subscribers should poll the feed,
+    /// set as REFRESH-INTERVAL and X-PUBLISHED-TTL for older clients
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = Some(interval);
+        self
+    }
+
+    /// ORGANIZER of components that have none, PUBLISH requires one
+    pub fn with_organizer(mut self, organizer: CalAddress) -> Self {
+        self.organizer = Some(organizer);
+        self
+    }
+
+    fn properties(&self) -> Vec<ContentLine> {
+        let mut properties = vec![content_line("METHOD", "PUBLISH".to_owned())];
+        if let Some(name) = &self.name {
+            properties.push(content_line("NAME", name.clone()));
+            properties.push(content_line("X-WR-CALNAME", name.clone()));
+        }
+        if let Some(interval) = &self.refresh_interval {
+            let mut refresh = content_line("REFRESH-INTERVAL", interval.value());
+            refresh
+                .params
+                .replace_param("VALUE".to_owned(), "DURATION".to_owned());
+            properties.push(refresh);
+            properties.push(content_line("X-PUBLISHED-TTL", interval.value()));
+        }
+        properties
+    }
+}
+
+/// Prepares an object for publishing and orders its overrides by their RECURRENCE-ID
+fn publish_object(
+    object: IcalCalendarObject,
+    options: &PublishOptions,
+) -> Result<IcalCalendarObject, ParserError> {
+    let mut inner = object.inner.mutable();
+    for_each_component(&mut inner, |properties| {
+        // PUBLISH doesn't allow ATTENDEE, which would also expose the participants
+        properties.retain(|prop| prop.name != "ATTENDEE");
+        if let Some(organizer) = &options.organizer
+            && !properties.iter().any(|prop| prop.name == "ORGANIZER")
+        {
+            set_property(properties, content_line("ORGANIZER", organizer.to_string()));
+        }
+    });
+    let mut inner = inner.build(
+        &ParserOptions::reverify(&object.timezones),
+        Some(&object.timezones),
+    )?;
+    match &mut inner {
+        CalendarInnerData::Event(_, overrides) => {
+            overrides.sort_by_key(|over| over.recurid.as_ref().map(|recurid| recurid.0.clone()));
+        }
+        CalendarInnerData::Todo(_, overrides) => {
+            overrides.sort_by_key(|over| over.recurid.as_ref().map(|recurid| recurid.0.clone()));
+        }
+        CalendarInnerData::Journal(_, overrides) => {
+            overrides.sort_by_key(|over| over.recurid.as_ref().map(|recurid| recurid.0.clone()));
+        }
+    }
+    Ok(IcalCalendarObject { inner, ..object })
+}
+
+/// Assembles `objects` into a single METHOD:PUBLISH calendar, e.g. to serve a webcal subscription.
+///
+/// Objects are ordered by their UID so the feed only changes if its content does,
+/// VTIMEZONEs shared by multiple objects are included once.
+/// ATTENDEE properties are removed as PUBLISH doesn't allow them.
+/// Fails if multiple objects share the same UID.
+pub fn publish(
+    prodid: &str,
+    objects: Vec<IcalCalendarObject>,
+    options: &PublishOptions,
+) -> Result<IcalCalendar, ParserError> {
+    let mut objects = objects
+        .into_iter()
+        .map(|object| publish_object(object, options))
+        .collect::<Result<Vec<_>, _>>()?;
+    objects.sort_by(|a, b| a.get_uid().cmp(b.get_uid()));
+    if let Some(pair) = objects
+        .windows(2)
+        .find(|pair| pair[0].get_uid() == pair[1].get_uid())
+    {
+        return Err(ParserError::InvalidComponent(format!(
+            "multiple objects with UID {}",
+            pair[0].get_uid()
+        )));
+    }
+    Ok(IcalCalendar::from_objects(
+        prodid.to_owned(),
+        objects,
+        options.properties(),
+    ))
+}
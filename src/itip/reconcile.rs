@@ -0,0 +1,129 @@
+//! Merging of the organizer's and an attendee's copy of a scheduled object
+use super::{Timezones, recurrence_id, same_instance};
+use crate::{
+    component::{CalendarInnerDataBuilder, ComponentMut, IcalCalendarObject},
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use std::collections::HashSet;
+
+/// Whether a property belongs to the attendee rather than the organizer
+fn is_private(prop: &ContentLine) -> bool {
+    prop.name == "TRANSP" || prop.name.starts_with("X-")
+}
+
+/// Replaces the private properties of `organizer` with those of the matching components of `attendee`.
+/// Instances the attendee has no override for take them from the attendee's main component.
+fn reconcile_components<B: ComponentMut>(
+    organizer: &mut [B],
+    organizer_timezones: &Timezones,
+    attendee: &[B],
+    attendee_timezones: &Timezones,
+    mut keep: impl FnMut(&mut B, &B),
+) -> Result<(), ParserError> {
+    let ids = attendee
+        .iter()
+        .map(|builder| recurrence_id(builder.get_properties(), attendee_timezones))
+        .collect::<Result<Vec<_>, _>>()?;
+    let main = ids.iter().position(Option::is_none);
+    for builder in organizer {
+        let id = recurrence_id(builder.get_properties(), organizer_timezones)?;
+        let Some(private) = ids
+            .iter()
+            .position(|other| same_instance(other.as_ref(), id.as_ref()))
+            .or(main)
+            .map(|position| &attendee[position])
+        else {
+            continue;
+        };
+        let properties = builder.get_properties_mut();
+        properties.retain(|prop| !is_private(prop));
+        properties.extend(
+            private
+                .get_properties()
+                .iter()
+                .filter(|prop| is_private(prop))
+                .cloned(),
+        );
+        keep(builder, private);
+    }
+    Ok(())
+}
+
+/// Merges the organizer's copy and an attendee's copy of the same calendar object,
+/// like a CalDAV server does when delivering an update to the attendee's calendar.
+///
+/// The organizer's copy is authoritative, so its components, overrides and properties are used.
+/// The attendee keeps its alarms, TRANSP and X- properties, instances without override
+/// in the attendee's copy take them from its main component.
+pub fn reconcile(
+    organizer: &IcalCalendarObject,
+    attendee: &IcalCalendarObject,
+) -> Result<IcalCalendarObject, ParserError> {
+    if organizer.get_uid() != attendee.get_uid() {
+        return Err(ParserError::DifferingUIDs);
+    }
+    let mut inner = organizer.inner.clone().mutable();
+    match (&mut inner, attendee.inner.clone().mutable()) {
+        (CalendarInnerDataBuilder::Event(events), CalendarInnerDataBuilder::Event(private)) => {
+            reconcile_components(
+                events,
+                &organizer.timezones,
+                &private,
+                &attendee.timezones,
+                |event, private| event.alarms.clone_from(&private.alarms),
+            )?;
+        }
+        (CalendarInnerDataBuilder::Todo(todos), CalendarInnerDataBuilder::Todo(private)) => {
+            reconcile_components(
+                todos,
+                &organizer.timezones,
+                &private,
+                &attendee.timezones,
+                |todo, private| todo.alarms.clone_from(&private.alarms),
+            )?;
+        }
+        (
+            CalendarInnerDataBuilder::Journal(journals),
+            CalendarInnerDataBuilder::Journal(private),
+        ) => {
+            reconcile_components(
+                journals,
+                &organizer.timezones,
+                &private,
+                &attendee.timezones,
+                |_, _| {},
+            )?;
+        }
+        _ => {
+            return Err(ParserError::InvalidComponent(
+                "the copies contain different kinds of components".to_owned(),
+            ));
+        }
+    }
+
+    let mut vtimezones = organizer.vtimezones.clone();
+    let mut timezones = organizer.timezones.clone();
+    for (tzid, vtimezone) in &attendee.vtimezones {
+        vtimezones
+            .entry(tzid.clone())
+            .or_insert_with(|| vtimezone.clone());
+    }
+    for (tzid, tz) in &attendee.timezones {
+        timezones.entry(tzid.clone()).or_insert(*tz);
+    }
+    let tzids: HashSet<String> = inner
+        .get_tzids()
+        .into_iter()
+        .map(ToOwned::to_owned)
+        .collect();
+    timezones.retain(|tzid, _| tzids.contains(tzid));
+    vtimezones.retain(|tzid, _| tzids.contains(tzid));
+
+    let inner = inner.build(&ParserOptions::reverify(&timezones), Some(&timezones))?;
+    Ok(IcalCalendarObject {
+        properties: organizer.properties.clone(),
+        inner,
+        vtimezones,
+        timezones,
+    })
+}
@@ -0,0 +1,92 @@
+//! METHOD:REPLY messages
+use super::{build_message, content_line, find_instance, same_address, stamp};
+use crate::{
+    component::{Component, IcalCalendar},
+    parser::{ContentLine, ParserError},
+    property::PartStat,
+    types::{CalAddress, CalDateOrDateTime},
+};
+
+/// Properties of the request identifying the component a reply refers to,
+/// DTSTART is kept because events can't do without it
+const REFERENCE_PROPERTIES: &[&str] = &["UID", "RECURRENCE-ID", "SEQUENCE", "ORGANIZER", "DTSTART"];
+
+fn reply_properties(
+    request: &[ContentLine],
+    attendee: &CalAddress,
+    partstat: &PartStat,
+) -> Vec<ContentLine> {
+    let mut properties: Vec<ContentLine> = request
+        .iter()
+        .filter(|prop| REFERENCE_PROPERTIES.contains(&prop.name.as_str()))
+        .cloned()
+        .collect();
+    let mut line = request
+        .iter()
+        .find(|prop| prop.name == "ATTENDEE" && same_address(&prop.value, attendee))
+        .cloned()
+        // Calendar users may reply to requests forwarded to them
+        .unwrap_or_else(|| content_line("ATTENDEE", attendee.to_string()));
+    line.params
+        .replace_param("PARTSTAT".to_owned(), partstat.to_string());
+    line.params.remove("RSVP");
+    properties.push(line);
+    properties.push(content_line("REQUEST-STATUS", "2.0;Success".to_owned()));
+    stamp(&mut properties);
+    properties
+}
+
+/// The events and to-dos of `request`
+fn requested(request: &IcalCalendar) -> impl Iterator<Item = (&'static str, &[ContentLine])> {
+    let events = request
+        .events
+        .iter()
+        .map(|event| (event.get_comp_name(), event.get_properties().as_slice()));
+    let todos = request
+        .todos
+        .iter()
+        .map(|todo| (todo.get_comp_name(), todo.get_properties().as_slice()));
+    events.chain(todos)
+}
+
+/// Creates a minimal METHOD:REPLY message in which `attendee` responds to `request` with `partstat`.
+///
+/// The reply contains the same components as the request, so replying to a request
+/// for a single instance only answers for that instance.
+/// Only the replying attendee is included, along with the UID, RECURRENCE-ID, SEQUENCE,
+/// ORGANIZER and DTSTART of the request and a successful REQUEST-STATUS.
+pub fn reply(
+    request: &IcalCalendar,
+    attendee: &CalAddress,
+    partstat: PartStat,
+) -> Result<IcalCalendar, ParserError> {
+    let components = requested(request)
+        .map(|(name, properties)| (name, reply_properties(properties, attendee, &partstat)))
+        .collect();
+    build_message(
+        &request.properties,
+        &request.vtimezones,
+        &request.timezones,
+        "REPLY",
+        components,
+    )
+}
+
+/// Like [`reply`] but only responds for the instance starting at `recurrence_id`
+/// of the recurring component in `request`.
+pub fn reply_instance(
+    request: &IcalCalendar,
+    attendee: &CalAddress,
+    partstat: PartStat,
+    recurrence_id: &CalDateOrDateTime,
+) -> Result<IcalCalendar, ParserError> {
+    let (name, instance) = find_instance(requested(request), recurrence_id, &request.timezones)?;
+    let properties = reply_properties(&instance, attendee, &partstat);
+    build_message(
+        &request.properties,
+        &request.vtimezones,
+        &request.timezones,
+        "REPLY",
+        vec![(name, properties)],
+    )
+}
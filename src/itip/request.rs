@@ -0,0 +1,92 @@
+//! METHOD:REQUEST messages
+use super::{
+    ItipError, content_line, for_each_component, message, same_address, set_property, stamp,
+};
+use crate::{
+    component::{
+        CalendarInnerData, CalendarInnerDataBuilder, Component, IcalCalendar, IcalCalendarObject,
+    },
+    parser::{ContentLine, ParserOptions},
+    types::CalAddress,
+};
+
+fn prepare_attendees(properties: &mut [ContentLine], organizer: &CalAddress) {
+    for attendee in properties.iter_mut().filter(|prop| prop.name == "ATTENDEE") {
+        if same_address(&attendee.value, organizer) {
+            continue;
+        }
+        let partstat = attendee.params.get_param("PARTSTAT");
+        if partstat.is_none_or(|partstat| partstat.eq_ignore_ascii_case("NEEDS-ACTION")) {
+            attendee
+                .params
+                .replace_param("PARTSTAT".to_owned(), "NEEDS-ACTION".to_owned());
+            if attendee.params.get_param("RSVP").is_none() {
+                attendee
+                    .params
+                    .replace_param("RSVP".to_owned(), "TRUE".to_owned());
+            }
+        }
+    }
+}
+
+/// Creates a METHOD:REQUEST message inviting the attendees of `object` on behalf of `organizer`.
+///
+/// The ORGANIZER of every component is set to `organizer`, parameters like CN are kept
+/// if it didn't change. Attendees that haven't responded yet are asked to with `RSVP=TRUE`.
+/// DTSTAMP is set to the current time and alarms are removed since they're personal.
+/// SEQUENCE is added if missing but not incremented, use
+/// [`bump_sequence`](crate::diff::bump_sequence) when sending an update.
+///
+/// Fails for journals, which can't be requested, and objects without attendees.
+pub fn request(
+    object: &IcalCalendarObject,
+    organizer: &CalAddress,
+) -> Result<IcalCalendar, ItipError> {
+    let main = match &object.inner {
+        CalendarInnerData::Event(main, _) => main.get_properties(),
+        CalendarInnerData::Todo(main, _) => main.get_properties(),
+        CalendarInnerData::Journal(..) => {
+            return Err(ItipError::NotRequestable("VJOURNAL"));
+        }
+    };
+    if !main.iter().any(|prop| prop.name == "ATTENDEE") {
+        return Err(ItipError::NoAttendees);
+    }
+
+    let mut inner = object.inner.clone().mutable();
+    match &mut inner {
+        CalendarInnerDataBuilder::Event(events) => {
+            events.iter_mut().for_each(|event| event.alarms.clear());
+        }
+        CalendarInnerDataBuilder::Todo(todos) => {
+            todos.iter_mut().for_each(|todo| todo.alarms.clear());
+        }
+        CalendarInnerDataBuilder::Journal(_) => {}
+    }
+    for_each_component(&mut inner, |properties| {
+        let keep_organizer = properties
+            .iter()
+            .filter(|prop| prop.name == "ORGANIZER")
+            .any(|prop| same_address(&prop.value, organizer));
+        if !keep_organizer {
+            set_property(properties, content_line("ORGANIZER", organizer.to_string()));
+        }
+        prepare_attendees(properties, organizer);
+        if !properties.iter().any(|prop| prop.name == "SEQUENCE") {
+            properties.push(content_line("SEQUENCE", "0".to_owned()));
+        }
+        stamp(properties);
+    });
+
+    let inner = inner.build(
+        &ParserOptions::reverify(&object.timezones),
+        Some(&object.timezones),
+    )?;
+    Ok(message(
+        IcalCalendarObject {
+            inner,
+            ..object.clone()
+        },
+        "REQUEST",
+    ))
+}
@@ -1,19 +1,93 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+
 const PARAM_VALUE_DELIMITER: char = ',';
 const VALUE_DELIMITER: char = ':';
 const PARAM_DELIMITER: char = ';';
 const PARAM_NAME_DELIMITER: char = '=';
 
+#[cfg(feature = "std")]
 pub mod component;
-pub use component::ical::*;
-pub use component::vcard::VcardParser;
+#[cfg(feature = "std")]
+pub use component::{IcalObjectParser, IcalParser, VcardParser};
 
 pub mod parser;
-pub use parser::{ComponentParser, ContentLineParser, LineReader, ParserError};
+#[cfg(feature = "std")]
+pub use parser::{ComponentParser, ParserError, ParserErrorKind, ParserOptions};
+pub use parser::{ContentLineParser, LineReader};
 
+#[cfg(feature = "std")]
 pub mod property;
 
+#[cfg(feature = "std")]
 pub mod generator;
+#[cfg(feature = "std")]
+pub use generator::Emitter;
 
+#[cfg(feature = "std")]
 pub mod types;
 
+#[cfg(feature = "recurrence")]
 pub mod rrule;
+
+#[cfg(feature = "std")]
+pub mod prelude;
+
+#[cfg(feature = "std")]
+pub mod validation;
+#[cfg(feature = "std")]
+pub use validation::validate;
+
+#[cfg(feature = "std")]
+pub mod repair;
+
+#[cfg(feature = "std")]
+pub mod diff;
+
+#[cfg(feature = "std")]
+pub mod itip;
+
+#[cfg(feature = "std")]
+pub mod caldav;
+
+#[cfg(feature = "std")]
+pub mod index;
+
+#[cfg(feature = "std")]
+pub mod search;
+
+#[cfg(feature = "std")]
+pub mod agenda;
+#[cfg(feature = "std")]
+pub use agenda::agenda;
+
+#[cfg(feature = "std")]
+pub mod contacts;
+
+#[cfg(feature = "std")]
+pub mod synth;
+
+#[cfg(feature = "std")]
+pub mod imip;
+
+#[cfg(feature = "std")]
+pub mod fs;
+
+#[cfg(feature = "serde")]
+pub mod simple;
+
+#[cfg(feature = "csv")]
+pub mod csv;
+
+#[cfg(feature = "std")]
+pub mod roundtrip;
+#[cfg(feature = "std")]
+pub use roundtrip::roundtrip_check;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "rayon")]
+pub use parallel::{parse_many, parse_many_with_options};
+
+#[cfg(feature = "fuzz")]
+mod arbitrary_impls;
@@ -0,0 +1,55 @@
+//! Parsing many calendar objects at once, e.g. for bulk imports
+use crate::{IcalObjectParser, ParserError, component::IcalCalendarObject, parser::ParserOptions};
+use rayon::prelude::*;
+
+/// Parses every input as a single calendar object in parallel.
+///
+/// The results are in the order of `inputs`, an invalid input doesn't affect the others.
+pub fn parse_many<'a>(
+    inputs: impl IntoParallelIterator<Item = &'a [u8]>,
+) -> Vec<Result<IcalCalendarObject, ParserError>> {
+    parse_many_with_options(inputs, &ParserOptions::default())
+}
+
+/// Like [`parse_many`] but with custom [`ParserOptions`]
+pub fn parse_many_with_options<'a>(
+    inputs: impl IntoParallelIterator<Item = &'a [u8]>,
+    options: &ParserOptions,
+) -> Vec<Result<IcalCalendarObject, ParserError>> {
+    inputs
+        .into_par_iter()
+        .map(|input| {
+            IcalObjectParser::from_slice(input)
+                .with_options(options.clone())
+                .expect_one()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_many;
+    use crate::ParserError;
+
+    #[test]
+    fn test_parse_many() {
+        let object = |uid: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\nBEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20260101T120000Z\r\nDTSTART:20260323T080000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+            )
+        };
+        let mut inputs: Vec<String> = (0..100).map(|i| object(&i.to_string())).collect();
+        inputs[42] = String::new();
+        let inputs: Vec<&[u8]> = inputs.iter().map(String::as_bytes).collect();
+
+        let results = parse_many(inputs);
+        assert_eq!(results.len(), 100);
+        for (i, result) in results.into_iter().enumerate() {
+            if i == 42 {
+                assert!(matches!(result, Err(ParserError::EmptyInput)));
+            } else {
+                assert_eq!(result.unwrap().get_uid(), i.to_string());
+            }
+        }
+    }
+}
@@ -25,6 +25,16 @@ impl<'a, C: Component> ComponentParser<'a, C, BytesLines<'a>> {
         }
     }
 
+    /// Parses with temporary allocations in `arena`, see [`LineReader::from_slice_in`]
+    #[cfg(feature = "arena")]
+    pub fn from_slice_in(slice: &'a [u8], arena: &'a bumpalo::Bump) -> Self {
+        ComponentParser {
+            line_parser: ContentLineParser::from_slice_in(slice, arena),
+            _t: Default::default(),
+            options: Default::default(),
+        }
+    }
+
     pub fn with_options(mut self, options: ParserOptions) -> Self {
         self.options = options;
         self
@@ -41,7 +51,9 @@ impl<'a, C: Component, I: Iterator<Item = Cow<'a, [u8]>>> ComponentParser<'a, C,
         };
 
         if line.name != "BEGIN"
-            || !C::NAMES.contains(&line.value.to_uppercase().as_str())
+            || !C::NAMES
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&line.value))
             || !line.params.is_empty()
         {
             return Err(ParserError::MissingHeader);
@@ -68,12 +80,35 @@ impl<'a, C: Component, I: Iterator<Item = Cow<'a, [u8]>>> Iterator for Component
             Err(err) => return Some(Err(err)),
         };
 
+        #[cfg(feature = "tracing")]
+        let first_line = self.line_parser.line_number();
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "parse_component",
+            component = C::NAMES[0],
+            line = first_line,
+            lines = tracing::field::Empty,
+        )
+        .entered();
+
         let mut comp = C::Builder::default();
         let result = match comp.parse(&mut self.line_parser, &self.options) {
-            Ok(_) => comp.build(&self.options, None),
+            Ok(_) => {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::debug_span!("verify").entered();
+                comp.build(&self.options, None)
+            }
             Err(err) => Err(err),
         };
 
+        #[cfg(feature = "tracing")]
+        {
+            span.record("lines", self.line_parser.line_number() - first_line + 1);
+            if let Err(err) = &result {
+                tracing::debug!(kind = ?err.kind(), error = %err, "invalid component");
+            }
+        }
+
         #[cfg(all(feature = "test", not(feature = "bench")))]
         {
             // Run this for more test coverage
@@ -86,3 +121,75 @@ impl<'a, C: Component, I: Iterator<Item = Cow<'a, [u8]>>> Iterator for Component
         Some(result)
     }
 }
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::{IcalObjectParser, component::IcalCalendarObject};
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        Event, Metadata, Subscriber,
+        span::{Attributes, Id, Record},
+    };
+
+    /// Records the names of all spans and events
+    #[derive(Default)]
+    struct Names(Arc<Mutex<Vec<String>>>);
+
+    impl Subscriber for Names {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut names = self.0.lock().unwrap();
+            names.push(span.metadata().name().to_owned());
+            Id::from_u64(names.len() as u64)
+        }
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(event.metadata().name().to_owned());
+        }
+        fn enter(&self, _span: &Id) {}
+        fn exit(&self, _span: &Id) {}
+    }
+
+    /// The names of the spans and events emitted by `f`
+    fn record(f: impl FnOnce()) -> Vec<String> {
+        let names = Names::default();
+        let recorded = names.0.clone();
+        tracing::subscriber::with_default(names, f);
+        recorded.lock().unwrap().clone()
+    }
+
+    fn parse() -> IcalCalendarObject {
+        let input = include_bytes!("../../tests/resources/ical_example_rrule.ics");
+        IcalObjectParser::from_slice(input).expect_one().unwrap()
+    }
+
+    #[test]
+    fn test_tracing() {
+        let recorded = record(|| {
+            parse();
+        });
+        for name in ["parse_component", "verify"] {
+            assert!(recorded.iter().any(|recorded| recorded == name), "{name}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "recurrence")]
+    fn test_tracing_expansion() {
+        let object = parse();
+        let recorded = record(|| {
+            object.expand_recurrence(None, None);
+        });
+        assert!(
+            recorded
+                .iter()
+                .any(|recorded| recorded == "expand_recurrence")
+        );
+    }
+}
@@ -27,10 +27,12 @@
 //! }
 //! ```
 
-use derive_more::From;
-use std::borrow::Cow;
-use std::fmt;
-use std::iter::Iterator;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Iterator;
 
 use super::{BytesLines, Line, LineError, LineReader};
 use crate::{PARAM_DELIMITER, PARAM_NAME_DELIMITER, PARAM_VALUE_DELIMITER, VALUE_DELIMITER};
@@ -54,18 +56,159 @@ pub enum ContentLineError {
     LineError(#[from] LineError),
 }
 
-#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, From)]
-pub struct ContentLineParams(pub(crate) Vec<(String, Vec<String>)>);
+impl ContentLineError {
+    /// Number of the offending line
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::MissingName(line)
+            | Self::MissingClosingQuote(line)
+            | Self::MissingDelimiter(line, _)
+            | Self::MissingContentAfter(line, _)
+            | Self::MissingParamKey(line)
+            | Self::MissingValue(line) => Some(*line),
+            Self::LineError(_) => None,
+        }
+    }
+}
+
+/// Parameter names defined by RFC 5545, RFC 6350, RFC 6638, RFC 7986 and RFC 9073
+const KNOWN_PARAMS: &[&str] = &[
+    "ALTID",
+    "ALTREP",
+    "CALSCALE",
+    "CC",
+    "CN",
+    "CUTYPE",
+    "DELEGATED-FROM",
+    "DELEGATED-TO",
+    "DERIVED",
+    "DIR",
+    "DISPLAY",
+    "EMAIL",
+    "ENCODING",
+    "FBTYPE",
+    "FEATURE",
+    "FMTTYPE",
+    "GEO",
+    "LABEL",
+    "LANGUAGE",
+    "MEDIATYPE",
+    "MEMBER",
+    "ORDER",
+    "PARTSTAT",
+    "PID",
+    "PREF",
+    "RANGE",
+    "RELATED",
+    "RELTYPE",
+    "ROLE",
+    "RSVP",
+    "SCHEDULE-AGENT",
+    "SCHEDULE-FORCE-SEND",
+    "SCHEDULE-STATUS",
+    "SENT-BY",
+    "SORT-AS",
+    "TYPE",
+    "TZ",
+    "TZID",
+    "VALUE",
+];
+
+/// Name of a parameter, well-known names are interned instead of allocated per occurrence
+#[derive(Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub(crate) struct ParamName(
+    #[cfg_attr(feature = "rkyv", rkyv(with = rkyv::with::AsOwned))] Cow<'static, str>,
+);
+
+impl ParamName {
+    /// Parses a parameter name, which is case-insensitive
+    fn parse(name: &str) -> Self {
+        match KNOWN_PARAMS
+            .iter()
+            .find(|known| known.eq_ignore_ascii_case(name))
+        {
+            Some(known) => Self(Cow::Borrowed(known)),
+            None => Self(Cow::Owned(name.to_uppercase())),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ParamName {
+    fn from(name: String) -> Self {
+        match KNOWN_PARAMS.iter().find(|known| **known == name) {
+            Some(known) => Self(Cow::Borrowed(known)),
+            None => Self(Cow::Owned(name)),
+        }
+    }
+}
+
+impl PartialEq<str> for ParamName {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl fmt::Debug for ParamName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct ContentLineParams(pub(crate) Vec<(ParamName, Vec<String>)>);
+
+impl From<Vec<(String, Vec<String>)>> for ContentLineParams {
+    fn from(params: Vec<(String, Vec<String>)>) -> Self {
+        Self(
+            params
+                .into_iter()
+                .map(|(name, values)| (name.into(), values))
+                .collect(),
+        )
+    }
+}
 
 impl ContentLineParams {
     #[inline]
     pub fn get_param(&self, name: &str) -> Option<&str> {
         self.0
             .iter()
-            .find(|(key, _)| name == key)
+            .find(|(key, _)| key == name)
             .and_then(|(_, value)| value.iter().map(String::as_ref).next())
     }
 
+    /// All values of a parameter, e.g. the addresses of DELEGATED-TO
+    pub fn get_param_values(&self, name: &str) -> &[String] {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map_or(&[], |(_, values)| values.as_slice())
+    }
+
     #[inline]
     pub fn get_tzid(&self) -> Option<&str> {
         self.get_param("TZID")
@@ -77,10 +220,18 @@ impl ContentLineParams {
     }
 
     pub fn replace_param(&mut self, name: String, value: String) {
-        if let Some(pos) = self.0.iter().position(|(n, _)| n == &name) {
-            self.0[pos] = (name, vec![value]);
+        self.replace_param_values(name, vec![value]);
+    }
+
+    /// Like [`Self::replace_param`] for parameters with multiple values,
+    /// the parameter is removed if `values` is empty
+    pub fn replace_param_values(&mut self, name: String, values: Vec<String>) {
+        if values.is_empty() {
+            self.remove(&name);
+        } else if let Some(pos) = self.0.iter().position(|(n, _)| n == name.as_str()) {
+            self.0[pos].1 = values;
         } else {
-            self.0.push((name, vec![value]));
+            self.0.push((name.into(), values));
         }
     }
 
@@ -93,16 +244,44 @@ impl ContentLineParams {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// The first parameter that occurs more than once with differing values
+    pub fn conflicting_param(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .enumerate()
+            .find_map(|(index, (name, values))| {
+                self.0[..index]
+                    .iter()
+                    .any(|(other, other_values)| other == name && other_values != values)
+                    .then_some(name.as_str())
+            })
+    }
+
+    /// Names and values of all parameters
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0
+            .iter()
+            .map(|(name, values)| (name.as_str(), values.as_slice()))
+    }
 }
 
 /// A VCARD/ICAL property.
 #[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct ContentLine {
     /// Property name.
     pub name: String,
     /// Property list of parameters.
     pub params: ContentLineParams,
     /// Property value.
+    ///
+    /// Always owned: values of unfolded lines are copied from the input,
+    /// folded lines hand over the buffer they were unfolded into.
     pub value: String,
 }
 
@@ -122,6 +301,12 @@ impl<'a> ContentLineParser<'a, BytesLines<'a>> {
     pub fn from_slice(slice: &'a [u8]) -> Self {
         ContentLineParser(LineReader::from_slice(slice))
     }
+
+    /// See [`LineReader::from_slice_in`]
+    #[cfg(feature = "arena")]
+    pub fn from_slice_in(slice: &'a [u8], arena: &'a bumpalo::Bump) -> Self {
+        ContentLineParser(LineReader::from_slice_in(slice, arena))
+    }
 }
 
 impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> ContentLineParser<'a, T> {
@@ -129,6 +314,12 @@ impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> ContentLineParser<'a, T> {
         ContentLineParser(line_reader)
     }
 
+    /// Number of lines read so far
+    #[cfg(feature = "tracing")]
+    pub(crate) fn line_number(&self) -> usize {
+        self.0.number
+    }
+
     fn parse(&self, line: Line) -> Result<ContentLine, ContentLineError> {
         let mut to_parse = line.as_str();
 
@@ -197,18 +388,19 @@ impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> ContentLineParser<'a, T> {
                 to_parse = &to_parse[1..];
             }
 
-            params.push((key.to_uppercase(), values));
+            params.push((ParamName::parse(key), values));
         }
 
         // Parse value
         if !to_parse.starts_with(VALUE_DELIMITER) {
             return Err(ContentLineError::MissingValue(line.number()));
         }
-        to_parse = &to_parse[1..];
+        let name = prop_name.to_uppercase();
+        let value_start = line.as_str().len() - to_parse.len() + 1;
         Ok(ContentLine {
-            name: prop_name.to_uppercase(),
-            params: params.into(),
-            value: to_parse.to_owned(),
+            name,
+            params: ContentLineParams(params),
+            value: line.into_tail(value_start),
         })
     }
 }
@@ -224,3 +416,53 @@ impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> Iterator for ContentLineParser<'a, T
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ContentLineParser;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_interned_params() {
+        let line = ContentLineParser::from_slice(
+            b"DTSTART;tzid=Europe/Berlin;x-custom=1;Value=DATE-TIME:20260323T080000",
+        )
+        .next()
+        .unwrap()
+        .unwrap();
+        let names: Vec<_> = line.params.0.iter().map(|(name, _)| &name.0).collect();
+        assert!(matches!(names[0], Cow::Borrowed("TZID")));
+        assert!(matches!(names[1], Cow::Owned(name) if name == "X-CUSTOM"));
+        assert!(matches!(names[2], Cow::Borrowed("VALUE")));
+        assert_eq!(line.params.get_tzid(), Some("Europe/Berlin"));
+        assert_eq!(line.params.get_value_type(), Some("DATE-TIME"));
+        assert_eq!(line.params.get_param("X-CUSTOM"), Some("1"));
+    }
+
+    #[test]
+    fn test_conflicting_param() {
+        let params = |line: &[u8]| {
+            ContentLineParser::from_slice(line)
+                .next()
+                .unwrap()
+                .unwrap()
+                .params
+        };
+        assert_eq!(
+            params(
+                b"DTSTART;TZID=Europe/Berlin;VALUE=DATE-TIME;TZID=Europe/London:20260323T080000"
+            )
+            .conflicting_param(),
+            Some("TZID")
+        );
+        assert_eq!(
+            params(b"SUMMARY;LANGUAGE=en;LANGUAGE=en:Meeting").conflicting_param(),
+            None
+        );
+        assert_eq!(
+            params(b"ATTENDEE;MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\":mailto:c@example.com")
+                .conflicting_param(),
+            None
+        );
+    }
+}
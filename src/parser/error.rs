@@ -1,6 +1,7 @@
 use crate::{
+    PARAM_DELIMITER, VALUE_DELIMITER,
     parser::ContentLineError,
-    types::{CalDateTimeError, InvalidDuration},
+    types::{CalDateTimeError, InvalidDuration, InvalidUtcOffset, InvalidValue},
 };
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
@@ -17,6 +18,8 @@ pub enum ParserError {
     MissingHeader,
     #[error("content line error: {0}")]
     ContentLineError(#[from] ContentLineError),
+    #[error("conflicting values for the parameter {1} of {0}")]
+    ConflictingParameter(String, String),
     #[error("missing property: {0}")]
     MissingProperty(&'static str),
     #[error("missing property: UID")]
@@ -25,10 +28,15 @@ pub enum ParserError {
     PropertyConflict(&'static str),
     #[error(transparent)]
     InvalidDuration(#[from] InvalidDuration),
+    #[error(transparent)]
+    InvalidUtcOffset(#[from] InvalidUtcOffset),
+    #[error(transparent)]
+    InvalidValue(#[from] InvalidValue),
     #[error("invalid property value: {0}")]
     InvalidPropertyValue(String),
     #[error("invalid property value type for: {0}")]
     InvalidPropertyType(String),
+    #[cfg(feature = "recurrence")]
     #[error(transparent)]
     RRule(#[from] crate::rrule::RRuleError),
     #[error(transparent)]
@@ -46,3 +54,151 @@ pub enum ParserError {
     #[error("DTSTART and RECURRENCE-ID must have the same value type and timezone")]
     DtstartNotMatchingRecurId,
 }
+
+/// Category of a [`ParserError`] that stays stable across releases,
+/// e.g. to map errors to messages or metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParserErrorKind {
+    /// The input contains no component
+    EmptyInput,
+    /// The input contains more than the expected single component
+    TooManyComponents,
+    /// A component that is not allowed at its position
+    InvalidComponent,
+    /// The input ends inside of a component
+    Incomplete,
+    /// The input doesn't start with the expected BEGIN line
+    MissingHeader,
+    /// A line that is not a valid content line
+    Syntax,
+    /// A required property is missing
+    MissingProperty,
+    /// A property occurs more often than allowed or conflicts with another one
+    PropertyConflict,
+    /// A property value or its value type is invalid
+    InvalidValue,
+    /// An invalid recurrence rule
+    InvalidRRule,
+    /// Invalid CALSCALE or VERSION
+    UnsupportedVersion,
+    /// Components of a calendar object don't fit together
+    InvalidObject,
+}
+
+impl ParserError {
+    pub fn kind(&self) -> ParserErrorKind {
+        match self {
+            Self::EmptyInput => ParserErrorKind::EmptyInput,
+            Self::TooManyComponents => ParserErrorKind::TooManyComponents,
+            Self::InvalidComponent(_) => ParserErrorKind::InvalidComponent,
+            Self::NotComplete => ParserErrorKind::Incomplete,
+            Self::MissingHeader => ParserErrorKind::MissingHeader,
+            Self::ContentLineError(_) | Self::ConflictingParameter(..) => ParserErrorKind::Syntax,
+            Self::MissingProperty(_) | Self::MissingUID => ParserErrorKind::MissingProperty,
+            Self::PropertyConflict(_) => ParserErrorKind::PropertyConflict,
+            Self::InvalidDuration(_)
+            | Self::InvalidUtcOffset(_)
+            | Self::InvalidValue(_)
+            | Self::InvalidPropertyValue(_)
+            | Self::InvalidPropertyType(_)
+            | Self::DateTime(_) => ParserErrorKind::InvalidValue,
+            #[cfg(feature = "recurrence")]
+            Self::RRule(_) => ParserErrorKind::InvalidRRule,
+            Self::InvalidCalscale | Self::InvalidVersion => ParserErrorKind::UnsupportedVersion,
+            Self::MultipleMainObjects
+            | Self::DifferingUIDs
+            | Self::MissingRecurId
+            | Self::DtstartNotMatchingRecurId => ParserErrorKind::InvalidObject,
+        }
+    }
+
+    /// Name of the property the error refers to, if known
+    pub fn property(&self) -> Option<&str> {
+        match self {
+            Self::MissingProperty(name) | Self::PropertyConflict(name) => Some(name),
+            Self::ConflictingParameter(name, _) => Some(name),
+            Self::MissingUID => Some("UID"),
+            Self::InvalidCalscale => Some("CALSCALE"),
+            Self::InvalidVersion => Some("VERSION"),
+            Self::MissingRecurId | Self::DtstartNotMatchingRecurId => Some("RECURRENCE-ID"),
+            Self::DifferingUIDs => Some("UID"),
+            // Contains the generated content line
+            Self::InvalidPropertyType(line) => line
+                .split([PARAM_DELIMITER, VALUE_DELIMITER])
+                .next()
+                .filter(|name| !name.is_empty()),
+            _ => None,
+        }
+    }
+
+    /// Name of the component the error refers to, if known
+    pub fn component(&self) -> Option<&str> {
+        match self {
+            Self::InvalidComponent(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Line number in the input the error originates from, if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::ContentLineError(err) => err.line(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParserErrorKind;
+    use crate::IcalObjectParser;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", ParserErrorKind::EmptyInput, None, None, None)]
+    #[case("BEGIN:VEVENT\r\n", ParserErrorKind::MissingHeader, None, None, None)]
+    #[case(
+        "BEGIN:VCALENDAR\r\nVERSION\r\n",
+        ParserErrorKind::Syntax,
+        None,
+        None,
+        Some(2)
+    )]
+    #[case(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:test\r\nBEGIN:VEVENT\r\nDTSTAMP:20260101T000000Z\r\nDTSTART:20260101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        ParserErrorKind::MissingProperty,
+        Some("UID"),
+        None,
+        None
+    )]
+    #[case(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:test\r\nBEGIN:VWHATEVER\r\nEND:VWHATEVER\r\nEND:VCALENDAR\r\n",
+        ParserErrorKind::InvalidComponent,
+        None,
+        Some("VWHATEVER"),
+        None
+    )]
+    #[case(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:test\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTAMP:20260101T000000Z\r\nDTSTART;VALUE=PERIOD:20260101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+        ParserErrorKind::InvalidValue,
+        Some("DTSTART"),
+        None,
+        None
+    )]
+    fn test_error_context(
+        #[case] input: &str,
+        #[case] kind: ParserErrorKind,
+        #[case] property: Option<&str>,
+        #[case] component: Option<&str>,
+        #[case] line: Option<usize>,
+    ) {
+        let err = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap_err();
+        assert_eq!(err.kind(), kind, "{err}");
+        assert_eq!(err.property(), property);
+        assert_eq!(err.component(), component);
+        assert_eq!(err.line(), line);
+    }
+}
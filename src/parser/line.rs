@@ -38,11 +38,11 @@
 //! }
 //! ```
 
-use std::borrow::Cow;
-use std::fmt;
-use std::iter::{Iterator, Peekable};
-use std::str::Utf8Error;
-use std::string::FromUtf8Error;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{FromUtf8Error, String};
+use core::fmt;
+use core::iter::{Iterator, Peekable};
+use core::str::{self, Utf8Error};
 
 /// Errors arising from line unfolding
 #[derive(thiserror::Error, Debug, PartialEq, Eq)]
@@ -64,13 +64,13 @@ pub struct Line<'a> {
 }
 
 impl<'a> PartialOrd for Line<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl<'a> Ord for Line<'a> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.inner.cmp(&other.inner)
     }
 }
@@ -90,6 +90,20 @@ impl<'a> Line<'a> {
     pub fn number(&self) -> usize {
         self.number
     }
+
+    /// The line from byte `start` on.
+    ///
+    /// Lines borrowed from the input are copied. Folded lines already own the buffer they were
+    /// unfolded into, it's reused by moving the tail to its front instead of allocating again.
+    pub(crate) fn into_tail(self, start: usize) -> String {
+        match self.inner {
+            Cow::Borrowed(line) => line[start..].to_owned(),
+            Cow::Owned(mut line) => {
+                line.drain(..start);
+                line
+            }
+        }
+    }
 }
 
 impl<'a> fmt::Display for Line<'a> {
@@ -134,7 +148,9 @@ impl<'a> Iterator for BytesLines<'a> {
 /// Take an iterator over `Cow<'a, [u8]>` and return the unfolded `Line`.
 pub struct LineReader<'a, I: Iterator<Item = Cow<'a, [u8]>>> {
     lines: Peekable<I>,
-    number: usize,
+    pub(crate) number: usize,
+    #[cfg(feature = "arena")]
+    arena: Option<&'a bumpalo::Bump>,
 }
 
 impl<'a> LineReader<'a, BytesLines<'a>> {
@@ -143,6 +159,63 @@ impl<'a> LineReader<'a, BytesLines<'a>> {
         LineReader {
             lines: BytesLines(reader).peekable(),
             number: 0,
+            #[cfg(feature = "arena")]
+            arena: None,
+        }
+    }
+
+    /// Like [`Self::from_slice`] but unfolded lines are allocated in `arena` instead of the heap.
+    ///
+    /// The lines only live until the content lines are parsed from them, so the arena can be
+    /// reset or dropped as soon as the parsed components are constructed.
+    #[cfg(feature = "arena")]
+    pub fn from_slice_in(
+        reader: &'a [u8],
+        arena: &'a bumpalo::Bump,
+    ) -> LineReader<'a, BytesLines<'a>> {
+        LineReader {
+            arena: Some(arena),
+            ..Self::from_slice(reader)
+        }
+    }
+}
+
+impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> LineReader<'a, T> {
+    #[cfg(feature = "arena")]
+    fn unfold_in(
+        &mut self,
+        arena: &'a bumpalo::Bump,
+        first: Cow<'a, [u8]>,
+        line_number: usize,
+    ) -> Option<Result<Line<'a>, LineError>> {
+        let mut unfolded: Option<bumpalo::collections::Vec<'a, u8>> = None;
+        while let Some(next) = self
+            .lines
+            .next_if(|line| line.starts_with(b" ") || line.starts_with(b"\t") || line.is_empty())
+        {
+            self.number += 1;
+            if !next.is_empty() {
+                unfolded
+                    .get_or_insert_with(|| {
+                        bumpalo::collections::Vec::from_iter_in(first.iter().copied(), arena)
+                    })
+                    .extend_from_slice(next.split_at(1).1);
+            }
+        }
+
+        let bytes: &'a [u8] = match (unfolded, first) {
+            (Some(unfolded), _) => unfolded.into_bump_slice(),
+            (None, Cow::Borrowed(slice)) => slice,
+            (None, Cow::Owned(bytes)) => arena.alloc_slice_copy(&bytes),
+        };
+        let line = match str::from_utf8(bytes) {
+            Ok(val) => val,
+            Err(err) => return Some(Err(err.into())),
+        };
+        if line.is_empty() {
+            None
+        } else {
+            Some(Ok(Line::new(Cow::Borrowed(line), line_number)))
         }
     }
 }
@@ -159,6 +232,11 @@ impl<'a, T: Iterator<Item = Cow<'a, [u8]>>> Iterator for LineReader<'a, T> {
             }
         };
 
+        #[cfg(feature = "arena")]
+        if let Some(arena) = self.arena {
+            return self.unfold_in(arena, new_line, line_number);
+        }
+
         while let Some(next) = self
             .lines
             .next_if(|line| line.starts_with(b" ") || line.starts_with(b"\t") || line.is_empty())
@@ -207,5 +285,42 @@ mod tests {
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
         assert_eq!(parsed_lines, lines);
+
+        #[cfg(feature = "arena")]
+        {
+            let arena = bumpalo::Bump::new();
+            let arena_lines = LineReader::from_slice_in(input.as_bytes(), &arena)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(arena_lines, lines);
+        }
+    }
+
+    #[test]
+    fn test_into_tail() {
+        let borrowed = Line::new("DESCRIPTION:borrowed".into(), 1);
+        assert_eq!(borrowed.into_tail(12), "borrowed");
+        let mut lines = LineReader::from_slice(b"DESCRIPTION:fol\r\n ded");
+        let folded = lines.next().unwrap().unwrap();
+        let buffer = folded.as_str().as_ptr();
+        let tail = folded.into_tail(12);
+        assert_eq!(tail, "folded");
+        // The unfolded buffer is reused
+        assert_eq!(tail.as_ptr(), buffer);
+    }
+
+    #[cfg(feature = "arena")]
+    #[test]
+    fn test_arena_parse() {
+        use crate::IcalParser;
+        let input = include_bytes!("../../tests/resources/ical_everything.ics");
+        let arena = bumpalo::Bump::new();
+        let from_arena = IcalParser::from_slice_in(input, &arena)
+            .expect_one()
+            .unwrap();
+        assert!(arena.allocated_bytes() > 0);
+        drop(arena);
+        let from_heap = IcalParser::from_slice(input).expect_one().unwrap();
+        assert_eq!(format!("{from_arena:?}"), format!("{from_heap:?}"));
     }
 }
@@ -1,5 +1,7 @@
+#[cfg(feature = "std")]
 mod error;
-pub use error::ParserError;
+#[cfg(feature = "std")]
+pub use error::{ParserError, ParserErrorKind};
 
 mod line;
 pub use line::{BytesLines, Line, LineError, LineReader};
@@ -7,24 +9,117 @@ pub use line::{BytesLines, Line, LineError, LineReader};
 mod content_line;
 pub use content_line::{ContentLine, ContentLineError, ContentLineParams, ContentLineParser};
 
+#[cfg(feature = "std")]
 mod property;
+#[cfg(feature = "std")]
 pub(crate) use property::property;
+#[cfg(feature = "std")]
 pub use property::{ICalProperty, ParseProp};
 
+#[cfg(feature = "std")]
 mod component;
+#[cfg(feature = "std")]
 pub use component::ComponentParser;
 
+#[cfg(feature = "std")]
+pub(crate) mod quirks;
+
+#[cfg(feature = "std")]
+use crate::component::{Timezones, TzidCache};
+#[cfg(feature = "std")]
+use crate::types::Tz;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// How a content line with a parameter occurring more than once with differing values is
+/// handled, e.g. `DTSTART;TZID=Europe/Berlin;TZID=Europe/London:...`
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateParams {
+    /// The first value is used
+    #[default]
+    Ignore,
+    /// The first value is used and a warning is logged
+    Warn,
+    /// The component is rejected with [`ParserError::ConflictingParameter`]
+    Reject,
+}
+
+#[cfg(feature = "std")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParserOptions {
     /// RFC 7809 allows the omission of VTIMEZONE components for standard timezones
     /// When true, we try to automatically insert missing VTIMEZONE components from the IANA
     /// timezone database.
     pub rfc7809: bool,
+    /// Some clients (most notably Thunderbird) specify the UNTIL part of RRULEs in local time
+    /// where it MUST be specified in UTC.
+    /// When true, such an UNTIL gets converted to UTC using the timezone of DTSTART
+    /// (or reinterpreted as UTC inside of VTIMEZONE components) instead of raising an error.
+    pub repair_local_until: bool,
+    /// Some producers append vendor extensions (like `X-` parts) to RRULEs.
+    /// When true, unknown rule parts are accepted and preserved instead of rejecting the RRULE.
+    /// Malformed known rule parts are still rejected.
+    pub lenient_rrule: bool,
+    /// Floating DATE-TIME values are bound to this timezone when set,
+    /// e.g. the calendar timezone known to a CalDAV server.
+    /// The values get a TZID parameter and a matching VTIMEZONE is added if missing.
+    /// Only applies to components inside of a calendar or calendar object.
+    pub default_timezone: Option<chrono_tz::Tz>,
+    /// Leap seconds like `T235960Z` are valid in ISO 8601 and emitted by some scientific
+    /// systems but can't be used in every DATE-TIME value (like UNTIL of RRULEs).
+    /// When true, second 60 is clamped to 59 in all DATE-TIME values.
+    pub clamp_leap_seconds: bool,
+    /// Handling of parameters occurring more than once on a content line with differing
+    /// values, see [`DuplicateParams`]. Repeated identical values are always accepted.
+    pub duplicate_params: DuplicateParams,
+    /// Custom mappings from proprietary TZIDs (e.g. from Lotus Notes or SAP) to IANA timezones.
+    /// They are consulted for VTIMEZONE components that can't be resolved by their
+    /// X-LIC-LOCATION or TZID and take precedence over the built-in map of Microsoft TZIDs.
+    pub tzid_aliases: HashMap<String, chrono_tz::Tz>,
+    /// Resolved VTIMEZONE components, shared by a parser and all clones of its options,
+    /// so that calendars sharing a timezone only resolve it once.
+    pub tzid_cache: TzidCache,
 }
 
+#[cfg(feature = "std")]
 #[allow(clippy::derivable_impls)]
 impl Default for ParserOptions {
     fn default() -> Self {
-        Self { rfc7809: false }
+        Self {
+            rfc7809: false,
+            repair_local_until: false,
+            lenient_rrule: false,
+            default_timezone: None,
+            clamp_leap_seconds: false,
+            duplicate_params: DuplicateParams::Ignore,
+            tzid_aliases: HashMap::new(),
+            tzid_cache: TzidCache::default(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ParserOptions {
+    /// Options for verifying components again that were verified before,
+    /// e.g. after modifying or deserialising them.
+    ///
+    /// The repairs were already applied to their content lines, so only the lenient options are
+    /// enabled to accept everything the original options accepted. The resolved `timezones`
+    /// become aliases so VTIMEZONE components resolve to the same timezones again.
+    pub(crate) fn reverify(timezones: &Timezones) -> Self {
+        Self {
+            repair_local_until: true,
+            lenient_rrule: true,
+            clamp_leap_seconds: true,
+            tzid_aliases: timezones
+                .iter()
+                .filter_map(|(tzid, tz)| match tz {
+                    Some(Tz::Olson(tz)) => Some((tzid.to_owned(), *tz)),
+                    _ => None,
+                })
+                .collect(),
+            ..Default::default()
+        }
     }
 }
@@ -1,7 +1,7 @@
 pub trait ParseProp: Sized {
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError>;
 }
@@ -9,17 +9,27 @@ pub trait ParseProp: Sized {
 impl ParseProp for String {
     fn parse_prop(
         prop: &ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Ok(prop.value.to_owned())
     }
 }
 
+impl ParseProp for Text {
+    fn parse_prop(
+        prop: &ContentLine,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
+        _default_type: &str,
+    ) -> Result<Self, ParserError> {
+        Ok(Self::parse(&prop.value))
+    }
+}
+
 impl ParseProp for DateOrDateTimeOrPeriod {
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse_prop(prop, timezones, default_type)
@@ -29,7 +39,7 @@ impl ParseProp for DateOrDateTimeOrPeriod {
 impl ParseProp for CalDateOrDateTime {
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse_prop(prop, timezones, default_type)
@@ -39,7 +49,7 @@ impl ParseProp for CalDateOrDateTime {
 impl ParseProp for CalDateTime {
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse_prop(prop, timezones)
@@ -49,17 +59,79 @@ impl ParseProp for CalDateTime {
 impl ParseProp for chrono::Duration {
     fn parse_prop(
         prop: &ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Ok(parse_duration(&prop.value)?)
     }
 }
 
+impl ParseProp for UtcOffset {
+    fn parse_prop(
+        prop: &ContentLine,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
+        _default_type: &str,
+    ) -> Result<Self, ParserError> {
+        Ok(Self::parse(&prop.value)?)
+    }
+}
+
+impl ParseProp for bool {
+    fn parse_prop(
+        prop: &ContentLine,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
+        _default_type: &str,
+    ) -> Result<Self, ParserError> {
+        Ok(parse_boolean(&prop.value)?)
+    }
+}
+
+impl ParseProp for i32 {
+    fn parse_prop(
+        prop: &ContentLine,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
+        _default_type: &str,
+    ) -> Result<Self, ParserError> {
+        Ok(parse_integer(&prop.value)?)
+    }
+}
+
+macro_rules! parse_prop_from_str {
+    ($($ty:ty),*) => {
+        $(
+            impl ParseProp for $ty {
+                fn parse_prop(
+                    prop: &ContentLine,
+                    _timezones: Option<&HashMap<String, Option<Tz>>>,
+                    _default_type: &str,
+                ) -> Result<Self, ParserError> {
+                    Ok(Self::from_str(&prop.value)?)
+                }
+            }
+        )*
+    };
+}
+
+parse_prop_from_str!(Float, Geo, Uri, CalAddress, Binary);
+
+impl ParseProp for Attachment {
+    fn parse_prop(
+        prop: &ContentLine,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
+        default_type: &str,
+    ) -> Result<Self, ParserError> {
+        Ok(match prop.params.get_param("VALUE") {
+            Some("BINARY") => Self::Binary(ParseProp::parse_prop(prop, timezones, default_type)?),
+            _ => Self::Uri(ParseProp::parse_prop(prop, timezones, default_type)?),
+        })
+    }
+}
+
+#[cfg(feature = "recurrence")]
 impl ParseProp for crate::rrule::RRule<crate::rrule::Unvalidated> {
     fn parse_prop(
         prop: &ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Ok(crate::rrule::RRule::from_str(&prop.value)?)
@@ -69,7 +141,7 @@ impl ParseProp for crate::rrule::RRule<crate::rrule::Unvalidated> {
 impl<T: ParseProp> ParseProp for Vec<T> {
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError> {
         let mut out = vec![];
@@ -91,7 +163,7 @@ pub trait ICalProperty: Sized {
 
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self, ParserError>;
 
     fn utc_or_local(self) -> Self;
@@ -106,7 +178,7 @@ macro_rules! property {
             #[inline]
             fn parse_prop(
                 prop: &crate::parser::ContentLine,
-                timezones: Option<&std::collections::HashMap<String, Option<chrono_tz::Tz>>>,
+                timezones: Option<&std::collections::HashMap<String, Option<crate::types::Tz>>>,
             ) -> Result<Self, crate::parser::ParserError> {
                 Ok(Self(
                     crate::parser::ParseProp::parse_prop(prop, timezones, $default_type)?,
@@ -135,6 +207,9 @@ macro_rules! property {
                 if value_type != $default_type {
                     params.replace_param("VALUE".to_owned(), value_type.to_owned());
                 }
+                if crate::types::Value::is_fixed_offset(&inner) {
+                    params.remove("TZID");
+                }
                 crate::parser::ContentLine {
                     name: $name.to_owned(),
                     params,
@@ -151,5 +226,8 @@ pub(crate) use property;
 use crate::{
     ParserError,
     parser::ContentLine,
-    types::{CalDateOrDateTime, CalDateTime, DateOrDateTimeOrPeriod, parse_duration},
+    types::{
+        Attachment, Binary, CalAddress, CalDateOrDateTime, CalDateTime, DateOrDateTimeOrPeriod,
+        Float, Geo, Text, Tz, Uri, UtcOffset, parse_boolean, parse_duration, parse_integer,
+    },
 };
@@ -0,0 +1,169 @@
+#[cfg(feature = "recurrence")]
+use crate::rrule::RRule;
+use crate::{component::ComponentMut, parser::ContentLine, parser::ParserError, types::Tz};
+#[cfg(feature = "recurrence")]
+use chrono::TimeDelta;
+
+/// Rewrites RRULE and EXRULE properties with an UNTIL in local time to UTC,
+/// interpreting the local time in `tz`.
+/// A DATE UNTIL includes its whole day, so it's converted at the end of that day.
+#[cfg(feature = "recurrence")]
+pub(crate) fn repair_local_until(
+    properties: &mut [ContentLine],
+    tz: Tz,
+) -> Result<(), ParserError> {
+    for prop in properties
+        .iter_mut()
+        .filter(|prop| prop.name == "RRULE" || prop.name == "EXRULE")
+    {
+        let rrule = RRule::from_str_lenient(&prop.value)?;
+        let Some(until) = rrule
+            .get_until()
+            .filter(|until| until.timezone().is_local())
+            .copied()
+        else {
+            continue;
+        };
+        let is_date = prop.value.split(';').any(|part| {
+            part.split_once('=')
+                .is_some_and(|(name, value)| name.eq_ignore_ascii_case("UNTIL") && value.len() == 8)
+        });
+        let rrule = if is_date {
+            rrule.until(until + TimeDelta::days(1) - TimeDelta::seconds(1))
+        } else {
+            rrule
+        }
+        .localize_until(tz);
+        if rrule
+            .get_until()
+            .is_some_and(|until| !until.timezone().is_local())
+        {
+            prop.value = rrule.to_string();
+        }
+    }
+    Ok(())
+}
+
+/// Rules aren't parsed without the `recurrence` feature, so they're kept as they are
+#[cfg(not(feature = "recurrence"))]
+pub(crate) fn repair_local_until(
+    _properties: &mut [ContentLine],
+    _tz: Tz,
+) -> Result<(), ParserError> {
+    Ok(())
+}
+
+/// Properties that may hold floating DATE-TIME values
+const FLOATING_PROPERTIES: &[&str] = &[
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "RECURRENCE-ID",
+    "RDATE",
+    "EXDATE",
+];
+
+/// Binds floating DATE-TIME values to `tz` by adding a TZID parameter
+/// (or the `Z` suffix for UTC) and converts a floating UNTIL of RRULEs accordingly.
+/// Returns whether any value was floating.
+pub(crate) fn localize_floating(
+    properties: &mut [ContentLine],
+    tz: chrono_tz::Tz,
+) -> Result<bool, ParserError> {
+    let mut localized = false;
+    for prop in properties.iter_mut().filter(|prop| {
+        FLOATING_PROPERTIES.contains(&prop.name.as_str())
+            && prop.params.get_tzid().is_none()
+            && prop
+                .params
+                .get_value_type()
+                .is_none_or(|value_type| value_type == "DATE-TIME")
+            && prop
+                .value
+                .split(',')
+                .all(|value| value.contains('T') && !value.ends_with('Z'))
+    }) {
+        if tz == chrono_tz::UTC {
+            prop.value = prop
+                .value
+                .split(',')
+                .map(|value| format!("{value}Z"))
+                .collect::<Vec<_>>()
+                .join(",");
+        } else {
+            prop.params
+                .replace_param("TZID".to_owned(), tz.name().to_owned());
+        }
+        localized = true;
+    }
+    if localized {
+        repair_local_until(properties, tz.into())?;
+    }
+    Ok(localized)
+}
+
+/// Applies [`localize_floating`] to each component
+pub(crate) fn localize_floating_components<'a, C: ComponentMut + 'a>(
+    components: impl IntoIterator<Item = &'a mut C>,
+    tz: chrono_tz::Tz,
+) -> Result<bool, ParserError> {
+    let mut localized = false;
+    for component in components {
+        localized |= localize_floating(component.get_properties_mut(), tz)?;
+    }
+    Ok(localized)
+}
+
+/// Properties that may hold DATE-TIME values, including RRULE parts
+const DATE_TIME_PROPERTIES: &[&str] = &[
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "RECURRENCE-ID",
+    "RDATE",
+    "EXDATE",
+    "DTSTAMP",
+    "CREATED",
+    "LAST-MODIFIED",
+    "COMPLETED",
+    "ACKNOWLEDGED",
+    "TRIGGER",
+    "RRULE",
+    "EXRULE",
+    "REV",
+];
+
+/// Clamps leap seconds (second 60) in DATE-TIME values to second 59.
+/// DURATION and PERIOD values are left untouched.
+pub(crate) fn clamp_leap_seconds(line: &mut ContentLine) {
+    if !DATE_TIME_PROPERTIES.contains(&line.name.as_str()) || !line.value.contains("60") {
+        return;
+    }
+    match line.params.get_value_type() {
+        Some(value_type)
+            if value_type.eq_ignore_ascii_case("DURATION")
+                || value_type.eq_ignore_ascii_case("PERIOD") =>
+        {
+            return;
+        }
+        // Triggers are durations by default
+        None if line.name == "TRIGGER" => return,
+        _ => {}
+    }
+    let mut value = line.value.clone().into_bytes();
+    // Matches the time part of a DATE-TIME: eight digits of the date, T and exactly six digits
+    for start in 8..value.len().saturating_sub(6) {
+        let time = &value[start + 1..start + 7];
+        if value[start] == b'T'
+            && value[start - 8..start].iter().all(u8::is_ascii_digit)
+            && !(start > 8 && value[start - 9].is_ascii_digit())
+            && time.iter().all(u8::is_ascii_digit)
+            && !value.get(start + 7).is_some_and(u8::is_ascii_digit)
+            && time[4..] == *b"60"
+        {
+            value[start + 5] = b'5';
+            value[start + 6] = b'9';
+        }
+    }
+    line.value = String::from_utf8(value).expect("only ASCII digits were replaced");
+}
@@ -0,0 +1,36 @@
+//! The commonly used types in a single import.
+//!
+//! ```rust
+//! use caldata::prelude::*;
+//!
+//! let buf = std::fs::read_to_string("./tests/resources/ical_events.ics").unwrap();
+//! for calendar in IcalParser::from_slice(buf.as_bytes()) {
+//!     for event in calendar.unwrap().events {
+//!         println!("{}", event.get_uid());
+//!         print!("{}", event.generate());
+//!     }
+//! }
+//! ```
+pub use crate::{
+    component::{
+        CalendarInnerData, Component, ComponentMut, IcalAlarm, IcalAlarmBuilder, IcalCalendar,
+        IcalCalendarBuilder, IcalCalendarObject, IcalCalendarObjectBuilder, IcalEvent,
+        IcalEventBuilder, IcalFreeBusy, IcalFreeBusyBuilder, IcalJournal, IcalJournalBuilder,
+        IcalObjectParser, IcalParser, IcalTimeZone, IcalTodo, IcalTodoBuilder, SemanticEq,
+        VcardContact, VcardContactBuilder, VcardParser,
+    },
+    generator::Emitter,
+    parser::{
+        ComponentParser, ContentLine, ContentLineParser, ICalProperty, LineReader, ParseProp,
+        ParserError, ParserOptions,
+    },
+    property::{
+        GetProperty, IcalDESCRIPTIONProperty, IcalDTENDProperty, IcalDTSTAMPProperty,
+        IcalDTSTARTProperty, IcalDUEProperty, IcalDURATIONProperty, IcalEXDATEProperty,
+        IcalLOCATIONProperty, IcalRDATEProperty, IcalRECURIDProperty, IcalSUMMARYProperty,
+        IcalUIDProperty,
+    },
+    types::{CalDate, CalDateOrDateTime, CalDateTime, Tz},
+};
+#[cfg(feature = "recurrence")]
+pub use crate::{property::IcalRRULEProperty, rrule::RRule};
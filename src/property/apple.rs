@@ -0,0 +1,176 @@
+//! Properties Apple Calendar adds to events for travel time and map locations
+use crate::{
+    parser::ContentLine,
+    types::{Float, Geo, InvalidValue},
+};
+use chrono::Duration;
+use std::{convert::Infallible, fmt, str::FromStr};
+
+super::property!(
+    "X-APPLE-TRAVEL-DURATION",
+    "DURATION",
+    IcalXAPPLETRAVELDURATIONProperty,
+    Duration
+);
+
+/// Whether Apple Calendar computes the travel time itself, from X-APPLE-TRAVEL-ADVISORY-BEHAVIOR
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TravelAdvisoryBehavior {
+    /// The travel time is estimated from the start and event locations
+    Automatic,
+    /// The travel time was entered manually
+    Disabled,
+    Other(String),
+}
+
+impl TravelAdvisoryBehavior {
+    pub const PROPERTY: &'static str = "X-APPLE-TRAVEL-ADVISORY-BEHAVIOR";
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Automatic => "AUTOMATIC",
+            Self::Disabled => "DISABLED",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl FromStr for TravelAdvisoryBehavior {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(if value.eq_ignore_ascii_case("AUTOMATIC") {
+            Self::Automatic
+        } else if value.eq_ignore_ascii_case("DISABLED") {
+            Self::Disabled
+        } else {
+            Self::Other(value.to_owned())
+        })
+    }
+}
+
+impl fmt::Display for TravelAdvisoryBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A location picked from the map, from X-APPLE-STRUCTURED-LOCATION or X-APPLE-TRAVEL-START
+///
+/// ```text
+/// X-APPLE-STRUCTURED-LOCATION;VALUE=URI;X-ADDRESS=Alexanderplatz 1\n10178 Berlin;
+///  X-APPLE-RADIUS=70.5;X-TITLE=Office:geo:52.521918,13.413215
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructuredLocation {
+    /// The name of the place, X-TITLE
+    pub title: Option<String>,
+    /// The postal address, X-ADDRESS
+    pub address: Option<String>,
+    /// How far the place extends around `geo` in meters, X-APPLE-RADIUS
+    pub radius: Option<Float>,
+    /// The coordinates of the `geo:` URI value
+    pub geo: Option<Geo>,
+    /// The mode of transport for the travel to the event like CAR or WALKING, ROUTING
+    pub routing: Option<String>,
+}
+
+impl StructuredLocation {
+    pub const PROPERTY: &'static str = "X-APPLE-STRUCTURED-LOCATION";
+    pub const TRAVEL_START_PROPERTY: &'static str = "X-APPLE-TRAVEL-START";
+
+    /// Reads the location from its content line, invalid coordinates or radii are reported
+    pub fn parse(line: &ContentLine) -> Result<Self, InvalidValue> {
+        let param = |name: &str| {
+            line.params
+                .get_param(name)
+                .filter(|value| !value.is_empty())
+                // Apple escapes line breaks like in TEXT values, sometimes twice
+                .map(|value| value.replace("\\\\n", "\n").replace("\\n", "\n"))
+        };
+        let radius = line
+            .params
+            .get_param("X-APPLE-RADIUS")
+            .map(str::parse)
+            .transpose()?;
+        let geo = match line.value.get(..4) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("geo:") => {
+                // Parameters like ;u=35 follow the coordinates
+                let coordinates = line.value[4..].split(';').next().unwrap_or_default();
+                let mut coordinates = coordinates.split(',');
+                let (Some(latitude), Some(longitude)) = (coordinates.next(), coordinates.next())
+                else {
+                    return Err(InvalidValue("URI", line.value.to_owned()));
+                };
+                Some(Geo {
+                    latitude: latitude.parse()?,
+                    longitude: longitude.parse()?,
+                })
+            }
+            _ => None,
+        };
+        Ok(Self {
+            title: param("X-TITLE"),
+            address: param("X-ADDRESS"),
+            radius,
+            geo,
+            routing: param("ROUTING"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IcalXAPPLETRAVELDURATIONProperty, StructuredLocation, TravelAdvisoryBehavior};
+    use crate::{generator::Emitter, parser::ContentLine, parser::ICalProperty};
+    use chrono::Duration;
+
+    fn line(input: &str) -> ContentLine {
+        crate::ContentLineParser::from_slice(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn travel_duration() {
+        let input = "X-APPLE-TRAVEL-DURATION;VALUE=DURATION:PT30M\r\n";
+        let prop = IcalXAPPLETRAVELDURATIONProperty::parse_prop(&line(input), None).unwrap();
+        assert_eq!(prop.0, Duration::minutes(30));
+        assert_eq!(ContentLine::from(prop).generate(), input);
+        assert_eq!(
+            "automatic".parse::<TravelAdvisoryBehavior>().unwrap(),
+            TravelAdvisoryBehavior::Automatic
+        );
+    }
+
+    #[test]
+    fn structured_location() {
+        let location = StructuredLocation::parse(&line(
+            "X-APPLE-STRUCTURED-LOCATION;VALUE=URI;X-ADDRESS=Alexanderplatz 1\\\\n10178 Berlin;X-APPLE-RADIUS=70.5;X-TITLE=Office:geo:52.521918,13.413215\r\n",
+        ))
+        .unwrap();
+        assert_eq!(location.title.as_deref(), Some("Office"));
+        assert_eq!(
+            location.address.as_deref(),
+            Some("Alexanderplatz 1\n10178 Berlin")
+        );
+        assert_eq!(location.radius.unwrap().get(), 70.5);
+        let geo = location.geo.unwrap();
+        assert_eq!(
+            (geo.latitude.get(), geo.longitude.get()),
+            (52.521918, 13.413215)
+        );
+
+        let start = StructuredLocation::parse(&line(
+            "X-APPLE-TRAVEL-START;ROUTING=CAR;VALUE=URI;X-ADDRESS=Home;X-TITLE=Home:\r\n",
+        ))
+        .unwrap();
+        assert_eq!(start.routing.as_deref(), Some("CAR"));
+        assert_eq!(start.geo, None);
+
+        assert!(
+            StructuredLocation::parse(&line("X-APPLE-STRUCTURED-LOCATION:geo:52.5\r\n")).is_err()
+        );
+    }
+}
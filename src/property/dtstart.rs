@@ -24,7 +24,10 @@ mod tests {
             .unwrap()
             .unwrap();
         let mut timezones = HashMap::new();
-        timezones.insert("Europe/Berlin".to_owned(), Some(chrono_tz::Europe::Berlin));
+        timezones.insert(
+            "Europe/Berlin".to_owned(),
+            Some(chrono_tz::Europe::Berlin.into()),
+        );
         timezones.insert("W. Europe Standard Time".to_owned(), None);
         let prop = IcalDTSTARTProperty::parse_prop(&content_line, Some(&timezones)).unwrap();
         let roundtrip: ContentLine = prop.into();
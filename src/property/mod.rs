@@ -1,9 +1,16 @@
 use crate::{
     component::Component,
     parser::{ContentLine, ICalProperty, ParserError, property},
-    types::PartialDateAndOrTime,
+    types::{Attachment, CalAddress, Geo, InvalidValue, PartialDateAndOrTime, Text, Tz, Uri},
+};
+#[cfg(feature = "recurrence")]
+use crate::{
+    parser::ParserOptions,
+    rrule::{RRule, Unvalidated},
 };
 use std::collections::HashMap;
+#[cfg(feature = "recurrence")]
+use std::str::FromStr;
 
 mod duration;
 pub use duration::*;
@@ -25,36 +32,55 @@ mod calscale;
 pub use calscale::*;
 mod version;
 pub use version::*;
+mod tzoffset;
+pub use tzoffset::*;
+mod parameters;
+pub use parameters::*;
+mod apple;
+pub use apple::*;
+
+fn parse_all<'a, T: ICalProperty>(
+    props: impl Iterator<Item = &'a ContentLine>,
+    timezones: Option<&HashMap<String, Option<Tz>>>,
+) -> Result<Vec<T>, ParserError> {
+    props
+        .map(|prop| ICalProperty::parse_prop(prop, timezones))
+        .collect::<Result<Vec<_>, _>>()
+}
+
+fn parse_optional<'a, T: ICalProperty>(
+    mut props: impl Iterator<Item = &'a ContentLine>,
+    timezones: Option<&HashMap<String, Option<Tz>>>,
+) -> Result<Option<T>, ParserError> {
+    let Some(prop) = props.next() else {
+        return Ok(None);
+    };
+    if props.next().is_some() {
+        return Err(ParserError::PropertyConflict(
+            "Multiple instances of property",
+        ));
+    }
+    ICalProperty::parse_prop(prop, timezones).map(Some)
+}
 
 pub trait GetProperty: Component {
     fn safe_get_all<T: ICalProperty>(
         &self,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Vec<T>, ParserError> {
-        self.get_named_properties(T::NAME)
-            .map(|prop| ICalProperty::parse_prop(prop, timezones))
-            .collect::<Result<Vec<_>, _>>()
+        parse_all(self.get_named_properties(T::NAME), timezones)
     }
 
     fn safe_get_optional<T: ICalProperty>(
         &self,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Option<T>, ParserError> {
-        let mut props = self.get_named_properties(T::NAME);
-        let Some(prop) = props.next() else {
-            return Ok(None);
-        };
-        if props.next().is_some() {
-            return Err(ParserError::PropertyConflict(
-                "Multiple instances of property",
-            ));
-        }
-        ICalProperty::parse_prop(prop, timezones).map(Some)
+        parse_optional(self.get_named_properties(T::NAME), timezones)
     }
 
     fn safe_get_required<T: ICalProperty>(
         &self,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<T, ParserError> {
         self.safe_get_optional(timezones)?
             .ok_or(ParserError::MissingProperty(T::NAME))
@@ -63,10 +89,199 @@ pub trait GetProperty: Component {
     fn has_prop<T: ICalProperty>(&self) -> bool {
         self.get_property(T::NAME).is_some()
     }
+
+    /// The property `name` whose LANGUAGE best matches the BCP 47 tags `lang_tags`
+    /// in order of preference, see [`lookup_language`]
+    fn get_localized_property<'c>(
+        &'c self,
+        name: &'c str,
+        lang_tags: &[&str],
+    ) -> Option<&'c ContentLine> {
+        lookup_language(self.get_named_properties(name), lang_tags)
+    }
+
+    /// The unescaped value of the TEXT property `name`.
+    /// Of localized properties the one without a LANGUAGE is preferred.
+    fn get_text(&self, name: &str) -> Option<Text> {
+        self.get_localized_text(name, &[])
+    }
+
+    /// The unescaped value of the TEXT property `name`, see [`Self::get_localized_property`]
+    fn get_localized_text(&self, name: &str, lang_tags: &[&str]) -> Option<Text> {
+        self.get_localized_property(name, lang_tags)
+            .map(|prop| Text::parse(&prop.value))
+    }
+
+    /// Index for looking up many properties, see [`PropertyIndex`]
+    fn property_index(&self) -> PropertyIndex<'_> {
+        PropertyIndex::new(self.get_properties())
+    }
 }
 
 impl<C: Component> GetProperty for C {}
 
+/// The positions of a component's properties sorted by their name.
+///
+/// Verified components whose properties can't be changed from outside keep it
+/// so that their lookups are a binary search instead of a scan.
+/// Properties of the same name keep their order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PropertyOrder(Vec<usize>);
+
+impl PropertyOrder {
+    pub(crate) fn new(properties: &[ContentLine]) -> Self {
+        let mut order: Vec<usize> = (0..properties.len()).collect();
+        order.sort_by(|a, b| properties[*a].name.cmp(&properties[*b].name));
+        Self(order)
+    }
+
+    /// The properties named `name`, `properties` must be the ones the order was created for
+    pub(crate) fn get_named_properties<'a>(
+        &'a self,
+        properties: &'a [ContentLine],
+        name: &str,
+    ) -> impl Iterator<Item = &'a ContentLine> + use<'a> {
+        let start = self
+            .0
+            .partition_point(|index| properties[*index].name.as_str() < name);
+        let len = self.0[start..].partition_point(|index| properties[*index].name == name);
+        self.0[start..start + len]
+            .iter()
+            .map(|index| &properties[*index])
+    }
+}
+
+/// The properties of a component sorted by their name.
+///
+/// Every lookup on a builder scans all properties, verifying a component takes
+/// a lookup per known property. The index sorts the properties once so that each lookup
+/// is a binary search instead.
+/// Properties of the same name keep their order.
+#[derive(Debug, Clone)]
+pub struct PropertyIndex<'a> {
+    sorted: Vec<&'a ContentLine>,
+}
+
+impl<'a> PropertyIndex<'a> {
+    pub fn new(properties: &'a [ContentLine]) -> Self {
+        Self::with_order(properties, &PropertyOrder::new(properties))
+    }
+
+    /// The index for an `order` that was already computed
+    pub(crate) fn with_order(properties: &'a [ContentLine], order: &PropertyOrder) -> Self {
+        Self {
+            sorted: order.0.iter().map(|index| &properties[*index]).collect(),
+        }
+    }
+
+    pub fn get_named_properties(&self, name: &str) -> &[&'a ContentLine] {
+        let start = self
+            .sorted
+            .partition_point(|prop| prop.name.as_str() < name);
+        let len = self.sorted[start..].partition_point(|prop| prop.name == name);
+        &self.sorted[start..start + len]
+    }
+
+    pub fn get_property(&self, name: &str) -> Option<&'a ContentLine> {
+        self.get_named_properties(name).first().copied()
+    }
+
+    pub fn safe_get_all<T: ICalProperty>(
+        &self,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
+    ) -> Result<Vec<T>, ParserError> {
+        parse_all(
+            self.get_named_properties(T::NAME).iter().copied(),
+            timezones,
+        )
+    }
+
+    pub fn safe_get_optional<T: ICalProperty>(
+        &self,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
+    ) -> Result<Option<T>, ParserError> {
+        parse_optional(
+            self.get_named_properties(T::NAME).iter().copied(),
+            timezones,
+        )
+    }
+
+    pub fn safe_get_required<T: ICalProperty>(
+        &self,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
+    ) -> Result<T, ParserError> {
+        self.safe_get_optional(timezones)?
+            .ok_or(ParserError::MissingProperty(T::NAME))
+    }
+
+    pub fn has_prop<T: ICalProperty>(&self) -> bool {
+        !self.get_named_properties(T::NAME).is_empty()
+    }
+}
+
+fn truncate_language_range(range: &str) -> Option<&str> {
+    let (mut range, _) = range.rsplit_once('-')?;
+    // A singleton like the `x` of private use subtags is removed with its subtag
+    if let Some((head, last)) = range.rsplit_once('-')
+        && last.len() == 1
+    {
+        range = head;
+    }
+    Some(range)
+}
+
+/// Picks the property whose LANGUAGE matches the language ranges `lang_tags` with the
+/// lookup scheme of RFC 4647 3.4: Ranges are tried in order and progressively truncated,
+/// `de-CH-1996` falls back to `de-CH` and then `de`. Matching ignores case.
+///
+/// Without a match the property without a LANGUAGE is the default, otherwise the first one.
+pub fn lookup_language<'a>(
+    props: impl Iterator<Item = &'a ContentLine>,
+    lang_tags: &[&str],
+) -> Option<&'a ContentLine> {
+    let props: Vec<&ContentLine> = props.collect();
+    for range in lang_tags.iter().filter(|range| **range != "*") {
+        let mut range = Some(*range);
+        while let Some(current) = range {
+            let matching = props.iter().find(|prop| {
+                prop.params
+                    .get_param("LANGUAGE")
+                    .is_some_and(|lang| lang.eq_ignore_ascii_case(current))
+            });
+            if let Some(prop) = matching {
+                return Some(prop);
+            }
+            range = truncate_language_range(current);
+        }
+    }
+    props
+        .iter()
+        .find(|prop| prop.params.get_param("LANGUAGE").is_none())
+        .or(props.first())
+        .copied()
+}
+
+/// Parses all recurrence rules of the property `P` (RRULE or EXRULE).
+/// With [`ParserOptions::lenient_rrule`] unknown rule parts are accepted and preserved.
+#[cfg(feature = "recurrence")]
+pub(crate) fn get_rrules<P: ICalProperty>(
+    props: &PropertyIndex,
+    options: &ParserOptions,
+) -> Result<Vec<RRule<Unvalidated>>, ParserError> {
+    props
+        .get_named_properties(P::NAME)
+        .iter()
+        .map(|prop| {
+            if options.lenient_rrule {
+                RRule::from_str_lenient(&prop.value)
+            } else {
+                RRule::from_str(&prop.value)
+            }
+            .map_err(ParserError::from)
+        })
+        .collect()
+}
+
 property!("UID", "TEXT", IcalUIDProperty, String);
 
 impl From<String> for IcalUIDProperty {
@@ -75,14 +290,79 @@ impl From<String> for IcalUIDProperty {
     }
 }
 
-property!("SUMMARY", "TEXT", IcalSUMMARYProperty, String);
+property!("SUMMARY", "TEXT", IcalSUMMARYProperty, Text);
+property!("DESCRIPTION", "TEXT", IcalDESCRIPTIONProperty, Text);
+property!("LOCATION", "TEXT", IcalLOCATIONProperty, Text);
 
+const ALTREP: &str = "ALTREP";
+
+/// Conversions from unescaped text and accessors for the ALTREP parameter
+/// of TEXT properties, RFC 5545 3.2.1
+macro_rules! altrep {
+    ($($prop:ident),*) => {
+        $(
+            impl From<String> for $prop {
+                fn from(text: String) -> Self {
+                    Self(text.into(), Default::default())
+                }
+            }
+
+            impl From<&str> for $prop {
+                fn from(text: &str) -> Self {
+                    Self(text.into(), Default::default())
+                }
+            }
+
+            impl $prop {
+                /// The URI of an alternate representation like an HTML version of the text
+                pub fn altrep(&self) -> Result<Option<Uri>, InvalidValue> {
+                    self.1.get_param(ALTREP).map(str::parse).transpose()
+                }
+
+                pub fn with_altrep(mut self, altrep: Uri) -> Self {
+                    self.1.replace_param(ALTREP.to_owned(), altrep.to_string());
+                    self
+                }
+            }
+        )*
+    };
+}
+
+altrep!(
+    IcalSUMMARYProperty,
+    IcalDESCRIPTIONProperty,
+    IcalLOCATIONProperty
+);
+
+/// Replaces all properties named like `prop` with it.
+/// If `prop` has no ALTREP, the one of the replaced property is kept.
+pub(crate) fn replace_text_property<T: ICalProperty + Into<ContentLine>>(
+    properties: &mut Vec<ContentLine>,
+    prop: T,
+) {
+    let mut line: ContentLine = prop.into();
+    let Some(pos) = properties.iter().position(|prop| prop.name == T::NAME) else {
+        properties.push(line);
+        return;
+    };
+    if line.params.get_param(ALTREP).is_none()
+        && let Some(altrep) = properties[pos].params.get_param(ALTREP)
+    {
+        line.params
+            .replace_param(ALTREP.to_owned(), altrep.to_owned());
+    }
+    properties.retain(|prop| prop.name != T::NAME);
+    properties.insert(pos, line);
+}
+
+#[cfg(feature = "recurrence")]
 property!(
     "RRULE",
     "RECUR",
     IcalRRULEProperty,
     crate::rrule::RRule<crate::rrule::Unvalidated>
 );
+#[cfg(feature = "recurrence")]
 property!(
     "EXRULE",
     "RECUR",
@@ -93,6 +373,26 @@ property!("PRODID", "TEXT", IcalPRODIDProperty, String);
 
 property!("METHOD", "TEXT", IcalMETHODProperty, String);
 
+property!("SEQUENCE", "INTEGER", IcalSEQUENCEProperty, i32);
+property!("PRIORITY", "INTEGER", IcalPRIORITYProperty, i32);
+property!(
+    "PERCENT-COMPLETE",
+    "INTEGER",
+    IcalPERCENTCOMPLETEProperty,
+    i32
+);
+property!("REPEAT", "INTEGER", IcalREPEATProperty, i32);
+property!("GEO", "FLOAT", IcalGEOProperty, Geo);
+property!("URL", "URI", IcalURLProperty, Uri);
+property!("ATTACH", "URI", IcalATTACHProperty, Attachment);
+property!(
+    "ORGANIZER",
+    "CAL-ADDRESS",
+    IcalORGANIZERProperty,
+    CalAddress
+);
+property!("ATTENDEE", "CAL-ADDRESS", IcalATTENDEEProperty, CalAddress);
+
 property!("FN", "TEXT", VcardFNProperty, String);
 property!("N", "TEXT", VcardNProperty, String);
 property!("NICKNAME", "TEXT", VcardNICKNAMEProperty, String);
@@ -108,3 +408,164 @@ property!(
     VcardANNIVERSARYProperty,
     PartialDateAndOrTime
 );
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        IcalATTACHProperty, IcalATTENDEEProperty, IcalDESCRIPTIONProperty, IcalGEOProperty,
+        IcalPERCENTCOMPLETEProperty, IcalSUMMARYProperty, IcalURLProperty, PropertyIndex,
+    };
+    use crate::{
+        generator::Emitter,
+        parser::{ContentLine, ICalProperty},
+        types::Attachment,
+    };
+    use rstest::rstest;
+
+    fn parse<P: ICalProperty>(input: &str) -> P {
+        let content_line = crate::ContentLineParser::from_slice(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        P::parse_prop(&content_line, None).unwrap()
+    }
+
+    #[test]
+    fn typed_values() {
+        let IcalPERCENTCOMPLETEProperty(percent, _) = parse("PERCENT-COMPLETE:39\r\n");
+        assert_eq!(percent, 39);
+        let IcalGEOProperty(geo, _) = parse("GEO:37.386013;-122.082932\r\n");
+        assert_eq!(geo.longitude.get(), -122.082932);
+        let IcalATTENDEEProperty(attendee, _) =
+            parse("ATTENDEE;RSVP=TRUE:mailto:jsmith@example.com\r\n");
+        assert_eq!(attendee.email(), Some("jsmith@example.com"));
+        let IcalATTACHProperty(attachment, _) =
+            parse("ATTACH;FMTTYPE=text/plain;ENCODING=BASE64;VALUE=BINARY:Zm9vYmFy\r\n");
+        assert_eq!(attachment, Attachment::Binary(b"foobar".to_vec().into()));
+    }
+
+    #[rstest]
+    #[case("PERCENT-COMPLETE:39\r\n")]
+    #[case("GEO:37.386013;-122.082932\r\n")]
+    #[case("URL:http://example.com/pub/calendars/jsmith/mytime.ics\r\n")]
+    #[case("ATTENDEE;RSVP=TRUE:mailto:jsmith@example.com\r\n")]
+    #[case("ATTACH:ftp://example.com/pub/reports/r-960812.ps\r\n")]
+    #[case("ATTACH;FMTTYPE=text/plain;ENCODING=BASE64;VALUE=BINARY:Zm9vYmFy\r\n")]
+    fn roundtrip(#[case] input: &str) {
+        let roundtrip: ContentLine = match input.split([':', ';']).next().unwrap() {
+            "PERCENT-COMPLETE" => parse::<IcalPERCENTCOMPLETEProperty>(input).into(),
+            "GEO" => parse::<IcalGEOProperty>(input).into(),
+            "URL" => parse::<IcalURLProperty>(input).into(),
+            "ATTENDEE" => parse::<IcalATTENDEEProperty>(input).into(),
+            "ATTACH" => parse::<IcalATTACHProperty>(input).into(),
+            _ => unreachable!(),
+        };
+        similar_asserts::assert_eq!(roundtrip.generate(), input);
+    }
+
+    #[test]
+    fn invalid() {
+        let content_line = |input: &str| {
+            crate::ContentLineParser::from_slice(input.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap()
+        };
+        assert!(
+            IcalPERCENTCOMPLETEProperty::parse_prop(
+                &content_line("PERCENT-COMPLETE:high\r\n"),
+                None
+            )
+            .is_err()
+        );
+        assert!(IcalURLProperty::parse_prop(&content_line("URL:example.com\r\n"), None).is_err());
+        assert!(IcalGEOProperty::parse_prop(&content_line("GEO:37.386013\r\n"), None).is_err());
+    }
+
+    #[test]
+    fn altrep() {
+        let description: IcalDESCRIPTIONProperty =
+            parse("DESCRIPTION;ALTREP=\"CID:part3.msg.970415T083000@example.com\":Project XYZ\r\n");
+        assert_eq!(
+            description.altrep().unwrap().unwrap().as_str(),
+            "CID:part3.msg.970415T083000@example.com"
+        );
+        assert!(
+            parse::<IcalSUMMARYProperty>("SUMMARY:Meeting\r\n")
+                .altrep()
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            parse::<IcalSUMMARYProperty>("SUMMARY;ALTREP=\"invalid\":Meeting\r\n")
+                .altrep()
+                .is_err()
+        );
+
+        let summary = IcalSUMMARYProperty::from("Meeting".to_owned())
+            .with_altrep("http://example.com/meeting.html".parse().unwrap());
+        assert_eq!(
+            ContentLine::from(summary).generate(),
+            "SUMMARY;ALTREP=\"http://example.com/meeting.html\":Meeting\r\n"
+        );
+    }
+
+    #[rstest]
+    #[case(&[], "Meeting")]
+    #[case(&["de"], "Besprechung")]
+    #[case(&["DE-at"], "Besprechung")]
+    #[case(&["fr", "de-CH-1996"], "Sitzung")]
+    #[case(&["de-CH-x-zurich"], "Sitzung")]
+    #[case(&["fr", "*"], "Meeting")]
+    fn lookup_language(#[case] lang_tags: &[&str], #[case] expected: &str) {
+        let properties: Vec<ContentLine> = crate::ContentLineParser::from_slice(
+            b"SUMMARY;LANGUAGE=de:Besprechung\r\nSUMMARY:Meeting\r\nSUMMARY;LANGUAGE=de-CH:Sitzung\r\n",
+        )
+        .collect::<Result<_, _>>()
+        .unwrap();
+        assert_eq!(
+            super::lookup_language(properties.iter(), lang_tags)
+                .unwrap()
+                .value,
+            expected
+        );
+        assert_eq!(
+            super::lookup_language(properties[..1].iter(), &["en"])
+                .unwrap()
+                .value,
+            "Besprechung"
+        );
+        assert!(super::lookup_language([].into_iter(), &["en"]).is_none());
+    }
+
+    #[test]
+    fn property_index() {
+        let properties: Vec<ContentLine> =
+            crate::ContentLineParser::from_slice(b"SUMMARY:Meeting\r\nATTENDEE:mailto:b@example.com\r\nCATEGORIES:A\r\nATTENDEE:mailto:a@example.com\r\nX-EMPTY:\r\n")
+                .collect::<Result<_, _>>()
+                .unwrap();
+        let props = PropertyIndex::new(&properties);
+        let attendees: Vec<_> = props
+            .get_named_properties("ATTENDEE")
+            .iter()
+            .map(|prop| prop.value.as_str())
+            .collect();
+        assert_eq!(attendees, ["mailto:b@example.com", "mailto:a@example.com"]);
+        assert_eq!(props.get_property("X-EMPTY").unwrap().value, "");
+        assert!(props.get_property("DESCRIPTION").is_none());
+        assert!(props.has_prop::<IcalSUMMARYProperty>());
+        assert_eq!(
+            props
+                .safe_get_all::<IcalATTENDEEProperty>(None)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(
+            props
+                .safe_get_optional::<IcalATTENDEEProperty>(None)
+                .is_err()
+        );
+        assert!(props.safe_get_required::<IcalURLProperty>(None).is_err());
+    }
+}
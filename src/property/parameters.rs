@@ -0,0 +1,164 @@
+//! Values of parameters from the IANA registries of RFC 5545, RFC 7986 and RFC 9253
+use std::{convert::Infallible, fmt, str::FromStr};
+
+macro_rules! parameter {
+    ($(#[$meta:meta])* $param:literal, $name:ident, { $($variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            /// Experimental (X-) or unregistered value
+            Other(String),
+        }
+
+        impl $name {
+            pub const PARAM: &'static str = $param;
+            /// All registered values
+            pub const VALUES: &'static [&'static str] = &[$($value),*];
+
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Other(value) => value,
+                }
+            }
+
+            pub fn is_registered(&self) -> bool {
+                !matches!(self, Self::Other(_))
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $(
+                    if value.eq_ignore_ascii_case($value) {
+                        return Ok(Self::$variant);
+                    }
+                )*
+                Ok(Self::Other(value.to_owned()))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+parameter!(
+    /// Participation status of an ATTENDEE
+    "PARTSTAT", PartStat, {
+        NeedsAction => "NEEDS-ACTION",
+        Accepted => "ACCEPTED",
+        Declined => "DECLINED",
+        Tentative => "TENTATIVE",
+        Delegated => "DELEGATED",
+        Completed => "COMPLETED",
+        InProcess => "IN-PROCESS",
+    }
+);
+
+impl PartStat {
+    /// Whether this status may be used in the component `component`
+    pub fn is_valid_for(&self, component: &str) -> bool {
+        match self {
+            Self::NeedsAction | Self::Accepted | Self::Declined | Self::Other(_) => true,
+            Self::Tentative | Self::Delegated => component != "VJOURNAL",
+            Self::Completed | Self::InProcess => component == "VTODO",
+        }
+    }
+}
+
+parameter!(
+    /// Participation role of an ATTENDEE
+    "ROLE", Role, {
+        Chair => "CHAIR",
+        ReqParticipant => "REQ-PARTICIPANT",
+        OptParticipant => "OPT-PARTICIPANT",
+        NonParticipant => "NON-PARTICIPANT",
+    }
+);
+
+parameter!(
+    /// Type of calendar user
+    "CUTYPE", CuType, {
+        Individual => "INDIVIDUAL",
+        Group => "GROUP",
+        Resource => "RESOURCE",
+        Room => "ROOM",
+        Unknown => "UNKNOWN",
+    }
+);
+
+parameter!(
+    /// Type of relationship of a RELATED-TO property
+    "RELTYPE", RelType, {
+        Parent => "PARENT",
+        Child => "CHILD",
+        Sibling => "SIBLING",
+        FinishToStart => "FINISHTOSTART",
+        FinishToFinish => "FINISHTOFINISH",
+        StartToFinish => "STARTTOFINISH",
+        StartToStart => "STARTTOSTART",
+        First => "FIRST",
+        Next => "NEXT",
+        DependsOn => "DEPENDS-ON",
+        RefId => "REFID",
+        Concept => "CONCEPT",
+    }
+);
+
+parameter!(
+    /// Type of a FREEBUSY period
+    "FBTYPE", FbType, {
+        Free => "FREE",
+        Busy => "BUSY",
+        BusyUnavailable => "BUSY-UNAVAILABLE",
+        BusyTentative => "BUSY-TENTATIVE",
+    }
+);
+
+parameter!(
+    /// How an IMAGE should be displayed
+    "DISPLAY", ImageDisplay, {
+        Badge => "BADGE",
+        Graphic => "GRAPHIC",
+        Fullsize => "FULLSIZE",
+        Thumbnail => "THUMBNAIL",
+    }
+);
+
+parameter!(
+    /// Feature of a CONFERENCE
+    "FEATURE", Feature, {
+        Audio => "AUDIO",
+        Chat => "CHAT",
+        Feed => "FEED",
+        Moderator => "MODERATOR",
+        Phone => "PHONE",
+        Screen => "SCREEN",
+        Video => "VIDEO",
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{PartStat, RelType};
+
+    #[test]
+    fn test_parameters() {
+        assert_eq!("accepted".parse(), Ok(PartStat::Accepted));
+        assert_eq!(
+            "X-MAYBE".parse::<PartStat>(),
+            Ok(PartStat::Other("X-MAYBE".to_owned()))
+        );
+        assert_eq!(PartStat::InProcess.to_string(), "IN-PROCESS");
+        assert!(!PartStat::InProcess.is_valid_for("VEVENT"));
+        assert!(PartStat::InProcess.is_valid_for("VTODO"));
+        assert!(RelType::DependsOn.is_registered());
+    }
+}
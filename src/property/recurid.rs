@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use crate::{
     generator::Emitter,
     parser::{ContentLine, ContentLineParams, ICalProperty, ParseProp, ParserError},
-    types::{CalDateOrDateTime, Value},
+    types::{CalDateOrDateTime, Tz, Value},
 };
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -24,7 +24,7 @@ impl ICalProperty for IcalRECURIDProperty {
 
     fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self, ParserError> {
         let dt = ParseProp::parse_prop(prop, timezones, Self::DEFAULT_TYPE)?;
         let range = match prop.params.get_param("RANGE") {
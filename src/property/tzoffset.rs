@@ -0,0 +1,43 @@
+use crate::types::UtcOffset;
+
+super::property!(
+    "TZOFFSETFROM",
+    "UTC-OFFSET",
+    IcalTZOFFSETFROMProperty,
+    UtcOffset
+);
+super::property!(
+    "TZOFFSETTO",
+    "UTC-OFFSET",
+    IcalTZOFFSETTOProperty,
+    UtcOffset
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{IcalTZOFFSETFROMProperty, IcalTZOFFSETTOProperty};
+    use crate::{generator::Emitter, parser::ICalProperty, property::ContentLine};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("TZOFFSETFROM:+0100\r\n")]
+    #[case("TZOFFSETFROM:-045230\r\n")]
+    fn roundtrip(#[case] input: &str) {
+        let content_line = crate::ContentLineParser::from_slice(input.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        let prop = IcalTZOFFSETFROMProperty::parse_prop(&content_line, None).unwrap();
+        let roundtrip: ContentLine = prop.into();
+        similar_asserts::assert_eq!(roundtrip.generate(), input);
+    }
+
+    #[test]
+    fn invalid() {
+        let content_line = crate::ContentLineParser::from_slice(b"TZOFFSETTO:-0000\r\n")
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(IcalTZOFFSETTOProperty::parse_prop(&content_line, None).is_err());
+    }
+}
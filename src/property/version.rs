@@ -1,7 +1,7 @@
 use crate::{
     parser::{ParseProp, ParserError},
     property::ContentLine,
-    types::Value,
+    types::{Tz, Value},
 };
 use std::collections::HashMap;
 
@@ -27,7 +27,7 @@ impl Value for IcalVersion {
 impl ParseProp for IcalVersion {
     fn parse_prop(
         prop: &ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         match prop.value.to_uppercase().as_str() {
@@ -0,0 +1,401 @@
+//! Repairs for known bugs of calendar clients.
+//!
+//! The producing client is detected from the PRODID of a calendar and only the fixes known to
+//! be necessary for it are applied. Every applied fix is logged so the changes can be reviewed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use caldata::{ContentLineParser, component::{ComponentMut, IcalCalendarBuilder}};
+//!
+//! let input = std::fs::read_to_string("./tests/resources/ical_local_until.ics").unwrap();
+//! let mut line_parser = ContentLineParser::from_slice(input.as_bytes());
+//! // Skip BEGIN:VCALENDAR
+//! line_parser.next();
+//! let builder = IcalCalendarBuilder::from_parser(&mut line_parser, &Default::default()).unwrap();
+//! let (calendar, fixes) = caldata::repair::repair(builder, &Default::default()).unwrap();
+//! for fix in &fixes {
+//!     println!("{fix}");
+//! }
+//! ```
+use crate::{
+    component::{
+        Component, ComponentMut, IcalAlarmBuilder, IcalCalendar, IcalCalendarBuilder, IcalTimeZone,
+    },
+    parser::{ContentLine, ICalProperty, ParserError, ParserOptions, quirks::repair_local_until},
+    property::IcalDTSTARTProperty,
+    types::{Tz, get_proprietary_tzid},
+};
+use chrono::Utc;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+/// Calendar clients with known bugs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Vendor {
+    Thunderbird,
+    Outlook,
+    Google,
+    Apple,
+}
+
+impl Vendor {
+    /// Detects the producing client from a PRODID value
+    pub fn from_prodid(prodid: &str) -> Option<Self> {
+        let prodid = prodid.to_lowercase();
+        if prodid.contains("mozilla.org") || prodid.contains("thunderbird") {
+            Some(Self::Thunderbird)
+        } else if prodid.contains("microsoft") {
+            Some(Self::Outlook)
+        } else if prodid.contains("google") {
+            Some(Self::Google)
+        } else if prodid.contains("apple") {
+            Some(Self::Apple)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fix {
+    /// Thunderbird specifies the UNTIL of RRULEs in local time
+    LocalUntil,
+    /// Outlook uses Windows timezone names as TZID
+    ProprietaryTzid,
+    /// Google duplicates alarms
+    DuplicateAlarm,
+    /// Several clients omit the required DTSTAMP
+    MissingDtstamp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedFix {
+    pub fix: Fix,
+    /// Path to the component, e.g. `VCALENDAR/VEVENT[0]/VALARM[1]`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for AppliedFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}: {}", self.fix, self.path, self.message)
+    }
+}
+
+/// The properties of every component of a calendar except timezones, keyed by their path.
+/// Alarms are only included with `alarms`.
+fn components_mut(
+    calendar: &mut IcalCalendarBuilder,
+    alarms: bool,
+) -> Vec<(String, &mut Vec<ContentLine>)> {
+    fn with_alarms<'a>(
+        out: &mut Vec<(String, &'a mut Vec<ContentLine>)>,
+        path: String,
+        properties: &'a mut Vec<ContentLine>,
+        alarms: Option<&'a mut Vec<IcalAlarmBuilder>>,
+    ) {
+        for (index, alarm) in alarms.into_iter().flatten().enumerate() {
+            out.push((format!("{path}/VALARM[{index}]"), &mut alarm.properties));
+        }
+        out.push((path, properties));
+    }
+
+    let mut out = vec![];
+    for (index, event) in calendar.events.iter_mut().enumerate() {
+        let path = format!("VCALENDAR/VEVENT[{index}]");
+        let event_alarms = alarms.then_some(&mut event.alarms);
+        with_alarms(&mut out, path, &mut event.properties, event_alarms);
+    }
+    for (index, todo) in calendar.todos.iter_mut().enumerate() {
+        let path = format!("VCALENDAR/VTODO[{index}]");
+        let todo_alarms = alarms.then_some(&mut todo.alarms);
+        with_alarms(&mut out, path, &mut todo.properties, todo_alarms);
+    }
+    for (index, journal) in calendar.journals.iter_mut().enumerate() {
+        out.push((
+            format!("VCALENDAR/VJOURNAL[{index}]"),
+            &mut journal.properties,
+        ));
+    }
+    for (index, free_busy) in calendar.free_busys.iter_mut().enumerate() {
+        out.push((
+            format!("VCALENDAR/VFREEBUSY[{index}]"),
+            &mut free_busy.properties,
+        ));
+    }
+    if alarms {
+        for (index, alarm) in calendar.alarms.iter_mut().enumerate() {
+            out.push((format!("VCALENDAR/VALARM[{index}]"), &mut alarm.properties));
+        }
+    }
+    out
+}
+
+/// Replaces Windows timezone names by their IANA counterpart
+fn proprietary_tzids(calendar: &mut IcalCalendarBuilder, log: &mut Vec<AppliedFix>) {
+    let mut tzids: BTreeSet<String> = calendar.vtimezones.keys().cloned().collect();
+    for (_, properties) in components_mut(calendar, true) {
+        tzids.extend(
+            properties
+                .iter()
+                .filter_map(|prop| prop.params.get_tzid())
+                .map(ToOwned::to_owned),
+        );
+    }
+
+    for tzid in tzids {
+        if chrono_tz::Tz::from_str(&tzid).is_ok() {
+            continue;
+        }
+        let Some(tz) = get_proprietary_tzid(&tzid) else {
+            continue;
+        };
+        let Some(vtimezone) = IcalTimeZone::from_tzid(tz.name()) else {
+            continue;
+        };
+        calendar.vtimezones.remove(&tzid);
+        calendar
+            .vtimezones
+            .insert(tz.name().to_owned(), vtimezone.clone());
+        for (_, properties) in components_mut(calendar, true) {
+            for prop in properties
+                .iter_mut()
+                .filter(|prop| prop.params.get_tzid() == Some(tzid.as_str()))
+            {
+                prop.params
+                    .replace_param("TZID".to_owned(), tz.name().to_owned());
+            }
+        }
+        log.push(AppliedFix {
+            fix: Fix::ProprietaryTzid,
+            path: "VCALENDAR".to_owned(),
+            message: format!("Replaced TZID {tzid} with {}", tz.name()),
+        });
+    }
+}
+
+/// Removes alarms with the same action and trigger as a previous alarm of the same component
+fn duplicate_alarms(calendar: &mut IcalCalendarBuilder, log: &mut Vec<AppliedFix>) {
+    fn dedupe(alarms: &mut Vec<IcalAlarmBuilder>, path: &str, log: &mut Vec<AppliedFix>) {
+        let mut seen = HashSet::new();
+        let mut index = 0;
+        alarms.retain(|alarm| {
+            let trigger = alarm.get_property("TRIGGER");
+            let key = (
+                alarm.get_property("ACTION").map(|prop| prop.value.clone()),
+                trigger.map(|prop| prop.value.clone()),
+                trigger.and_then(|prop| prop.params.get_param("RELATED").map(ToOwned::to_owned)),
+            );
+            let keep = seen.insert(key);
+            if !keep {
+                log.push(AppliedFix {
+                    fix: Fix::DuplicateAlarm,
+                    path: format!("{path}/VALARM[{index}]"),
+                    message: "Removed duplicate alarm".to_owned(),
+                });
+            }
+            index += 1;
+            keep
+        });
+    }
+
+    for (index, event) in calendar.events.iter_mut().enumerate() {
+        dedupe(
+            &mut event.alarms,
+            &format!("VCALENDAR/VEVENT[{index}]"),
+            log,
+        );
+    }
+    for (index, todo) in calendar.todos.iter_mut().enumerate() {
+        dedupe(&mut todo.alarms, &format!("VCALENDAR/VTODO[{index}]"), log);
+    }
+}
+
+/// Adds a DTSTAMP to components lacking one, using LAST-MODIFIED, CREATED or the current time
+fn missing_dtstamp(calendar: &mut IcalCalendarBuilder, log: &mut Vec<AppliedFix>) {
+    for (path, properties) in components_mut(calendar, false) {
+        if properties.iter().any(|prop| prop.name == "DTSTAMP") {
+            continue;
+        }
+        let value = ["LAST-MODIFIED", "CREATED"]
+            .iter()
+            .find_map(|name| {
+                properties
+                    .iter()
+                    .find(|prop| prop.name == *name && prop.value.ends_with('Z'))
+            })
+            .map(|prop| prop.value.clone())
+            .unwrap_or_else(|| Utc::now().format("%Y%m%dT%H%M%SZ").to_string());
+        log.push(AppliedFix {
+            fix: Fix::MissingDtstamp,
+            path,
+            message: format!("Added DTSTAMP:{value}"),
+        });
+        properties.push(ContentLine {
+            name: "DTSTAMP".to_owned(),
+            params: Default::default(),
+            value,
+        });
+    }
+}
+
+/// Converts an UNTIL in local time to UTC using the timezone of DTSTART
+fn local_until(
+    calendar: &mut IcalCalendarBuilder,
+    options: &ParserOptions,
+    log: &mut Vec<AppliedFix>,
+) -> Result<(), ParserError> {
+    let timezones: HashMap<String, Option<Tz>> = calendar
+        .vtimezones
+        .iter()
+        .map(|(tzid, tz)| {
+            (
+                tzid.to_owned(),
+                options
+                    .tzid_cache
+                    .resolve_or_fixed(tz, &options.tzid_aliases),
+            )
+        })
+        .collect();
+    for (path, properties) in components_mut(calendar, false) {
+        let Some(Ok(IcalDTSTARTProperty(dtstart, _))) = properties
+            .iter()
+            .find(|prop| prop.name == "DTSTART")
+            .map(|prop| IcalDTSTARTProperty::parse_prop(prop, Some(&timezones)))
+        else {
+            continue;
+        };
+        let before = properties.clone();
+        repair_local_until(properties, dtstart.timezone())?;
+        if *properties != before {
+            log.push(AppliedFix {
+                fix: Fix::LocalUntil,
+                path,
+                message: "Converted UNTIL from local time to UTC".to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Applies the fixes known to be necessary for the client that produced `calendar`
+/// and builds it with `options`.
+pub fn repair(
+    mut calendar: IcalCalendarBuilder,
+    options: &ParserOptions,
+) -> Result<(IcalCalendar, Vec<AppliedFix>), ParserError> {
+    let vendor = calendar
+        .get_property("PRODID")
+        .and_then(|prodid| Vendor::from_prodid(&prodid.value));
+    let mut log = vec![];
+    if vendor == Some(Vendor::Outlook) {
+        proprietary_tzids(&mut calendar, &mut log);
+    }
+    missing_dtstamp(&mut calendar, &mut log);
+    if vendor == Some(Vendor::Google) {
+        duplicate_alarms(&mut calendar, &mut log);
+    }
+    if vendor == Some(Vendor::Thunderbird) {
+        local_until(&mut calendar, options, &mut log)?;
+    }
+    Ok((calendar.build(options, None)?, log))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fix, Vendor, repair};
+    use crate::{
+        ContentLineParser,
+        component::{ComponentMut, IcalCalendarBuilder},
+        generator::Emitter,
+    };
+    use rstest::rstest;
+
+    fn builder(input: &str) -> IcalCalendarBuilder {
+        let mut line_parser = ContentLineParser::from_slice(input.as_bytes());
+        line_parser.next();
+        IcalCalendarBuilder::from_parser(&mut line_parser, &Default::default()).unwrap()
+    }
+
+    #[rstest]
+    #[case(
+        "-//Mozilla.org/NONSGML Mozilla Calendar V1.1//EN",
+        Some(Vendor::Thunderbird)
+    )]
+    #[case(
+        "-//Microsoft Corporation//Outlook 16.0 MIMEDIR//EN",
+        Some(Vendor::Outlook)
+    )]
+    #[case("-//Google Inc//Google Calendar 70.9054//EN", Some(Vendor::Google))]
+    #[case("-//Apple Inc.//macOS 14.0//EN", Some(Vendor::Apple))]
+    #[case("-//Example Corp.//CalDAV Client//EN", None)]
+    fn test_vendor(#[case] prodid: &str, #[case] vendor: Option<Vendor>) {
+        assert_eq!(Vendor::from_prodid(prodid), vendor);
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_thunderbird() {
+        let input = include_str!("../tests/resources/ical_local_until.ics");
+        let (calendar, log) = repair(builder(input), &Default::default()).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].fix, Fix::LocalUntil);
+        assert_eq!(log[0].path, "VCALENDAR/VEVENT[0]");
+        assert!(calendar.generate().contains("UNTIL=20260406T070000Z"));
+    }
+
+    #[test]
+    fn test_outlook() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Microsoft Corporation//Outlook 16.0 MIMEDIR//EN\r
+BEGIN:VEVENT\r
+UID:outlook@example.com\r
+CREATED:20260101T100000Z\r
+DTSTART;TZID=W. Europe Standard Time:20260302T090000\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let (calendar, log) = repair(builder(input), &Default::default()).unwrap();
+        let fixes: Vec<_> = log.iter().map(|fix| fix.fix).collect();
+        assert_eq!(fixes, vec![Fix::ProprietaryTzid, Fix::MissingDtstamp]);
+        let output = calendar.generate();
+        assert!(output.contains("DTSTART;TZID=Europe/Berlin:20260302T090000\r\n"));
+        assert!(output.contains("DTSTAMP:20260101T100000Z\r\n"));
+    }
+
+    #[test]
+    fn test_google() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Google Inc//Google Calendar 70.9054//EN\r
+BEGIN:VEVENT\r
+UID:google@example.com\r
+DTSTAMP:20260101T100000Z\r
+DTSTART:20260302T090000Z\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT10M\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT10M\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT30M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let (calendar, log) = repair(builder(input), &Default::default()).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].fix, Fix::DuplicateAlarm);
+        assert_eq!(log[0].path, "VCALENDAR/VEVENT[0]/VALARM[1]");
+        assert_eq!(calendar.events[0].alarms.len(), 2);
+    }
+}
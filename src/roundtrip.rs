@@ -0,0 +1,175 @@
+//! Fidelity of parsing and regenerating calendar data
+use crate::{
+    ContentLineParser, IcalParser, ParserError,
+    component::IcalCalendar,
+    diff::{Diff, diff_calendars},
+    generator::Emitter,
+    parser::ContentLine,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RoundtripError {
+    #[error("input could not be parsed: {0}")]
+    Parse(ParserError),
+    #[error("output could not be parsed: {0}")]
+    Reparse(ParserError),
+    #[error("input contains {input} calendars, the output {output}")]
+    CalendarCount { input: usize, output: usize },
+}
+
+/// Result of [`roundtrip_check`]
+#[derive(Debug, Default)]
+pub struct RoundtripReport {
+    /// Set if the input or output couldn't be parsed, the other fields are incomplete then
+    pub error: Option<RoundtripError>,
+    /// The regenerated calendars
+    pub output: String,
+    /// Offset of the first octet in which the output differs from the input
+    pub first_difference: Option<usize>,
+    /// Content lines of the input that are missing in the output, after unfolding
+    pub removed_lines: Vec<ContentLine>,
+    /// Content lines of the output that are missing in the input, after unfolding
+    pub added_lines: Vec<ContentLine>,
+    /// Semantic changes between each parsed and reparsed calendar,
+    /// for a stable emitter all of them are empty
+    pub changes: Vec<Diff>,
+}
+
+impl RoundtripReport {
+    /// Whether the output is identical to the input
+    pub fn is_identical(&self) -> bool {
+        self.error.is_none() && self.first_difference.is_none()
+    }
+
+    /// Whether the output contains the same content lines as the input,
+    /// differences in folding, line breaks and the order of lines are ignored
+    pub fn is_lossless(&self) -> bool {
+        self.error.is_none()
+            && self.removed_lines.is_empty()
+            && self.added_lines.is_empty()
+            && self.changes.iter().all(Diff::is_empty)
+    }
+}
+
+fn parse(input: &[u8]) -> Result<Vec<IcalCalendar>, ParserError> {
+    IcalParser::from_slice(input).collect()
+}
+
+fn content_lines(input: &[u8]) -> Vec<ContentLine> {
+    ContentLineParser::from_slice(input)
+        .filter_map(Result::ok)
+        .collect()
+}
+
+/// The lines of `lines` that don't have a counterpart in `other`
+fn missing_lines(lines: &[ContentLine], other: &[ContentLine]) -> Vec<ContentLine> {
+    let mut counts: HashMap<&ContentLine, usize> = HashMap::new();
+    for line in other {
+        *counts.entry(line).or_default() += 1;
+    }
+    lines
+        .iter()
+        .filter(|line| match counts.get_mut(line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Parses `input`, regenerates it and parses the output again,
+/// reporting what was changed or lost on the way.
+///
+/// # Examples
+///
+/// ```rust
+/// let input = std::fs::read("./tests/resources/ical_everything.ics").unwrap();
+/// let report = caldata::roundtrip_check(&input);
+/// assert!(report.error.is_none());
+/// assert!(report.changes.iter().all(|diff| diff.is_empty()));
+/// ```
+pub fn roundtrip_check(input: &[u8]) -> RoundtripReport {
+    let mut report = RoundtripReport::default();
+    let calendars = match parse(input) {
+        Ok(calendars) => calendars,
+        Err(err) => {
+            report.error = Some(RoundtripError::Parse(err));
+            return report;
+        }
+    };
+    report.output = calendars.iter().map(Emitter::generate).collect();
+
+    let output = report.output.as_bytes();
+    report.first_difference = input
+        .iter()
+        .zip(output)
+        .position(|(a, b)| a != b)
+        .or_else(|| (input.len() != output.len()).then(|| input.len().min(output.len())));
+
+    let input_lines = content_lines(input);
+    let output_lines = content_lines(output);
+    report.removed_lines = missing_lines(&input_lines, &output_lines);
+    report.added_lines = missing_lines(&output_lines, &input_lines);
+
+    let reparsed = match parse(output) {
+        Ok(reparsed) => reparsed,
+        Err(err) => {
+            report.error = Some(RoundtripError::Reparse(err));
+            return report;
+        }
+    };
+    if reparsed.len() != calendars.len() {
+        report.error = Some(RoundtripError::CalendarCount {
+            input: calendars.len(),
+            output: reparsed.len(),
+        });
+        return report;
+    }
+    report.changes = calendars
+        .iter()
+        .zip(&reparsed)
+        .map(|(old, new)| diff_calendars(old, new))
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::roundtrip_check;
+
+    #[test]
+    fn test_roundtrip_check() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:roundtrip@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+DURATION:PT1H\r
+SUMMARY:Meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let report = roundtrip_check(input.as_bytes());
+        assert!(report.is_identical(), "{report:?}");
+        assert!(report.is_lossless());
+
+        let folded = input.replace("SUMMARY:Meeting\r\n", "SUMMARY:Meet\r\n ing\r\n");
+        let report = roundtrip_check(folded.as_bytes());
+        assert!(!report.is_identical());
+        assert_eq!(
+            report.first_difference,
+            input.find("SUMMARY:Meet").map(|i| i + 12)
+        );
+        assert!(report.is_lossless(), "{report:?}");
+
+        let report = roundtrip_check(b"BEGIN:VCALENDAR\r\n");
+        assert!(report.error.is_some());
+        assert!(!report.is_lossless());
+    }
+}
@@ -42,6 +42,10 @@ pub(crate) fn datetime_to_ical_format(dt: &chrono::DateTime<Tz>) -> String {
     let tz = dt.timezone();
     match tz {
         Tz::Local => {}
+        // Fixed offsets have no TZID, UTC keeps the instant
+        Tz::Fixed(_) => {
+            return format!(":{}Z", dt.to_utc().format("%Y%m%dT%H%M%S"));
+        }
         Tz::Olson(tz) => match tz {
             chrono_tz::UTC => {
                 tz_postfix = "Z".to_string();
@@ -12,6 +12,7 @@ use crate::rrule::core::get_second;
 use crate::rrule::iter::RRuleIter;
 use crate::rrule::parser::ContentLineCaptures;
 use crate::rrule::parser::ParseError;
+use crate::rrule::parser::parse_rrule_lenient;
 use crate::rrule::parser::str_to_weekday;
 use crate::rrule::validator::ValidationError;
 use crate::rrule::validator::validate_rrule_forced;
@@ -26,6 +27,7 @@ use std::marker::PhantomData;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 /// The frequency of a recurrence.
 pub enum Frequency {
     /// The recurrence occurs on a yearly basis.
@@ -273,6 +275,9 @@ pub struct RRule<Stage = Validated> {
     /// Can be a value from -366 to 366.
     /// Note: Only used when `by-easter` feature flag is set. Otherwise, it is ignored.
     pub(crate) by_easter: Option<i16>,
+    /// Unknown rule parts like `X-` vendor extensions.
+    /// Only populated when parsed with [`RRule::from_str_lenient`].
+    pub(crate) extensions: Vec<(String, String)>,
     /// A phantom data to have the stage (unvalidated or validated).
     pub(crate) stage: PhantomData<Stage>,
 }
@@ -297,6 +302,7 @@ impl Default for RRule<Unvalidated> {
             by_minute: Vec::new(),
             by_second: Vec::new(),
             by_easter: None,
+            extensions: Vec::new(),
             stage: PhantomData,
         }
     }
@@ -341,6 +347,23 @@ impl RRule<Unvalidated> {
         self
     }
 
+    /// Interprets a floating (local time) UNTIL in the timezone `tz` and converts it to UTC.
+    ///
+    /// Some clients (most notably Thunderbird) emit UNTIL in local time even where
+    /// RFC 5545 requires it to be specified in UTC.
+    /// Does nothing if UNTIL is not floating or if `tz` is floating itself.
+    #[must_use]
+    pub fn localize_until(mut self, tz: Tz) -> Self {
+        if let Some(until) = self.until
+            && until.timezone().is_local()
+            && !tz.is_local()
+            && let Some(until) = until.naive_local().and_local_timezone(tz).earliest()
+        {
+            self.until = Some(until.with_timezone(&Tz::UTC));
+        }
+        self
+    }
+
     /// The week start day. This will affect recurrences based on weekly periods.
     /// The default week start is [`Weekday::Mon`].
     #[must_use]
@@ -614,6 +637,7 @@ impl RRule<Unvalidated> {
             by_minute: rrule.by_minute,
             by_second: rrule.by_second,
             by_easter: rrule.by_easter,
+            extensions: rrule.extensions,
             stage: PhantomData,
         })
     }
@@ -645,6 +669,19 @@ impl FromStr for RRule<Unvalidated> {
     }
 }
 
+impl RRule<Unvalidated> {
+    /// Parses an RRULE like [`FromStr`] but accepts unknown rule parts (like `X-` vendor extensions).
+    /// Unknown parts are preserved and emitted again by [`Display`].
+    ///
+    /// # Errors
+    ///
+    /// Still returns an error if a known rule part is malformed.
+    pub fn from_str_lenient(s: &str) -> Result<Self, RRuleError> {
+        let parts = ContentLineCaptures::new(s)?;
+        parse_rrule_lenient(parts).map_err(From::from)
+    }
+}
+
 impl<S> Display for RRule<S> {
     /// Generates a string based on the [iCalendar RRULE spec](https://datatracker.ietf.org/doc/html/rfc5545#section-3.8.5.3).
     /// It doesn't prepend "RRULE:" to the string.
@@ -782,6 +819,10 @@ impl<S> Display for RRule<S> {
             res.push(format!("BYEASTER={}", by_easter));
         }
 
+        for (name, value) in &self.extensions {
+            res.push(format!("{name}={value}"));
+        }
+
         write!(f, "{}", res.join(";"))
     }
 }
@@ -876,4 +917,10 @@ impl<S> RRule<S> {
     pub fn get_by_easter(&self) -> Option<&i16> {
         self.by_easter.as_ref()
     }
+
+    /// Get the unknown rule parts of the recurrence.
+    #[must_use]
+    pub fn get_extensions(&self) -> &[(String, String)] {
+        &self.extensions
+    }
 }
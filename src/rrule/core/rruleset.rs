@@ -6,10 +6,12 @@
  */
 use crate::rrule::core::datetime::datetime_to_ical_format;
 use crate::rrule::core::utils::collect_with_error;
+use crate::rrule::iter::rrule_iter::WasLimited;
 use crate::rrule::parser::{ContentLine, Grammar};
-use crate::rrule::{ParseError, RRule, RRuleError};
+use crate::rrule::{ParseError, RRule, RRuleError, RRuleSetRevIter};
 use crate::types::Tz;
 use chrono::DateTime;
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -202,6 +204,76 @@ impl RRuleSet {
         )
     }
 
+    /// Returns whether the set only contains a finite number of recurrences,
+    /// meaning that every RRULE is bounded by either COUNT or UNTIL.
+    /// Only finite sets can be iterated from the back, see [`RRuleSet::iter_rev`].
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        self.rrule
+            .iter()
+            .all(|rrule| rrule.count.is_some() || rrule.until.is_some())
+    }
+
+    /// Iterates the recurrences from the latest to the earliest,
+    /// `None` if the set is unbounded (see [`RRuleSet::is_finite`]).
+    ///
+    /// All recurrences are computed from DTSTART and buffered when the iterator is created,
+    /// taking time and memory linear in the size of the set.
+    /// Use [`RRuleSet::occurrences_before`] to only keep a few of them.
+    #[must_use]
+    pub fn iter_rev(&self) -> Option<RRuleSetRevIter> {
+        self.is_finite().then(|| RRuleSetRevIter::new(self))
+    }
+
+    /// Returns up to `limit` recurrences strictly before `dt`, latest first.
+    ///
+    /// The recurrences are computed from DTSTART up to `dt`, so the time taken grows with
+    /// the number of recurrences before `dt`. In contrast to [`RRuleSet::all`] only the last
+    /// `limit` of them are kept in memory, so this also works for unbounded sets.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// use caldata::rrule::RRuleSet;
+    ///
+    /// let rrule_set: RRuleSet = "DTSTART:20210101T090000Z\nRRULE:FREQ=DAILY".parse().unwrap();
+    /// let dt = "2021-01-10T09:00:00Z".parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+    ///
+    /// let result = rrule_set.occurrences_before(dt.with_timezone(&caldata::types::Tz::UTC), 2);
+    /// assert_eq!(result.dates.len(), 2);
+    /// assert_eq!(result.dates[0].to_rfc3339(), "2021-01-09T09:00:00+00:00");
+    /// assert_eq!(result.dates[1].to_rfc3339(), "2021-01-08T09:00:00+00:00");
+    /// ```
+    #[must_use]
+    pub fn occurrences_before(mut self, dt: DateTime<Tz>, limit: u16) -> RRuleResult {
+        if limit == 0 {
+            return RRuleResult {
+                dates: vec![],
+                limited: false,
+            };
+        }
+        self.limited = true;
+        let mut dates = VecDeque::with_capacity(usize::from(limit));
+        let mut iter = self.into_iter();
+        for date in iter.by_ref() {
+            if date >= dt || self.before.is_some_and(|before| date > before) {
+                break;
+            }
+            if self.after.is_some_and(|after| date < after) {
+                continue;
+            }
+            if dates.len() == usize::from(limit) {
+                dates.pop_front();
+            }
+            dates.push_back(date);
+        }
+
+        RRuleResult {
+            dates: dates.into_iter().rev().collect(),
+            limited: iter.was_limited(),
+        }
+    }
+
     /// Returns all the recurrences of the rrule.
     ///
     /// # Note
@@ -23,7 +23,7 @@ mod yearinfo;
 use iterinfo::IterInfo;
 use pos_list::build_pos_list;
 pub(crate) use rrule_iter::RRuleIter;
-pub use rruleset_iter::RRuleSetIter;
+pub use rruleset_iter::{RRuleSetIter, RRuleSetRevIter};
 
 /// Prevent loops when searching for the next event in the iterator.
 /// If after X number of iterations it still has not found an event,
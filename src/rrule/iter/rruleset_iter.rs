@@ -235,6 +235,55 @@ impl IntoIterator for &RRuleSet {
     }
 }
 
+/// Iterator over the dates of a finite [`RRuleSet`] from the latest to the earliest,
+/// see [`RRuleSet::iter_rev`].
+///
+/// Recurrence rules can only be evaluated from DTSTART onwards, so all dates of the set
+/// are computed and kept in memory when the iterator is created.
+#[derive(Debug, Clone)]
+pub struct RRuleSetRevIter {
+    /// Remaining dates in ascending order
+    dates: std::vec::IntoIter<DateTime<Tz>>,
+    was_limited: bool,
+}
+
+impl RRuleSetRevIter {
+    pub(crate) fn new(set: &RRuleSet) -> Self {
+        let mut iter = set.into_iter();
+        let dates: Vec<_> = iter.by_ref().collect();
+        Self {
+            dates: dates.into_iter(),
+            was_limited: iter.was_limited(),
+        }
+    }
+}
+
+impl Iterator for RRuleSetRevIter {
+    type Item = DateTime<Tz>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.dates.next_back()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.dates.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for RRuleSetRevIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.dates.next()
+    }
+}
+
+impl ExactSizeIterator for RRuleSetRevIter {}
+
+impl WasLimited for RRuleSetRevIter {
+    fn was_limited(&self) -> bool {
+        self.was_limited
+    }
+}
+
 impl WasLimited for RRuleSetIter {
     fn was_limited(&self) -> bool {
         self.was_limited
@@ -111,4 +111,4 @@ pub use chrono::Weekday;
 pub use core::{Frequency, NWeekday, RRule, RRuleResult, RRuleSet};
 pub use core::{Unvalidated, Validated};
 pub use error::{ParseError, RRuleError, ValidationError};
-pub use iter::RRuleSetIter;
+pub use iter::{RRuleSetIter, RRuleSetRevIter};
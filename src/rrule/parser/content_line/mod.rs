@@ -18,6 +18,7 @@ use crate::rrule::Unvalidated;
 use crate::types::Tz;
 
 pub(crate) use content_line_parts::ContentLineCaptures;
+pub(crate) use rule_content_line::parse_rrule_lenient;
 pub(crate) use start_date_content_line::StartDateContentLine;
 
 use super::ParseError;
@@ -13,7 +13,23 @@ use crate::rrule::parser::ParseError;
 pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
     raw_parameters: &str,
 ) -> Result<HashMap<K, String>, ParseError> {
+    let (parameters, unknown) = parse_parameters_with_unknown(raw_parameters)?;
+    if let Some((raw_parameter, _)) = unknown.into_iter().next() {
+        return Err(ParseError::UnrecognizedParameter(raw_parameter));
+    }
+    Ok(parameters)
+}
+
+/// Parameters with known keys and unrecognized key-value pairs.
+type ParametersWithUnknown<K> = (HashMap<K, String>, Vec<(String, String)>);
+
+/// Like [`parse_parameters`] but collects unrecognized keys and their values
+/// in order of appearance instead of returning an error.
+pub(super) fn parse_parameters_with_unknown<K: FromStr<Err = ParseError> + Hash + Eq>(
+    raw_parameters: &str,
+) -> Result<ParametersWithUnknown<K>, ParseError> {
     let mut parameters = HashMap::new();
+    let mut unknown = vec![];
     for raw_parameter in raw_parameters.split(';') {
         if raw_parameter.is_empty() {
             continue;
@@ -21,13 +37,20 @@ pub(super) fn parse_parameters<K: FromStr<Err = ParseError> + Hash + Eq>(
         let (raw_parameter, value) = raw_parameter
             .split_once('=')
             .ok_or_else(|| ParseError::InvalidParameterFormat(raw_parameter.into()))?;
-        let parameter = K::from_str(raw_parameter)?;
+        let parameter = match K::from_str(raw_parameter) {
+            Ok(parameter) => parameter,
+            Err(ParseError::UnrecognizedParameter(_)) => {
+                unknown.push((raw_parameter.to_owned(), value.to_owned()));
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
 
         if parameters.insert(parameter, value.into()).is_some() {
             return Err(ParseError::DuplicateProperty(raw_parameter.into()));
         }
     }
-    Ok(parameters)
+    Ok((parameters, unknown))
 }
 
 #[cfg(test)]
@@ -12,7 +12,7 @@ use crate::rrule::{
     Frequency, RRule, Unvalidated,
     parser::{
         ParseError,
-        content_line::parameters::parse_parameters,
+        content_line::parameters::{parse_parameters, parse_parameters_with_unknown},
         datetime::{datestring_to_date, parse_weekdays},
         str_to_weekday,
         utils::parse_str_to_vec,
@@ -84,6 +84,26 @@ impl TryFrom<ContentLineCaptures<'_>> for RRule<Unvalidated> {
     }
 }
 
+/// Parses an RRULE content line, accepting and preserving unknown rule parts.
+pub(crate) fn parse_rrule_lenient(
+    value: ContentLineCaptures,
+) -> Result<RRule<Unvalidated>, ParseError> {
+    if let Some(parameters) = value.parameters
+        && !parameters.is_empty()
+    {
+        return Err(ParseError::PropertyParametersNotSupported(
+            parameters.into(),
+        ));
+    }
+
+    let (properties, extensions) = parse_parameters_with_unknown(value.value)?;
+
+    Ok(RRule {
+        extensions,
+        ..props_to_rrule(&properties)?
+    })
+}
+
 /// Takes a map of [`RRuleProperty`] and returns an [`RRule`].
 #[allow(clippy::too_many_lines)]
 fn props_to_rrule(
@@ -219,6 +239,7 @@ fn props_to_rrule(
         by_minute,
         by_second,
         by_easter,
+        extensions: vec![],
         stage: PhantomData,
     })
 }
@@ -293,6 +314,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lenient_parsing_preserves_unknown_parts() {
+        let input = "FREQ=DAILY;X-NAME=foo;COUNT=3;X-OTHER=bar";
+        assert_eq!(
+            RRule::from_str(input).unwrap_err(),
+            ParseError::UnrecognizedParameter("X-NAME".into()).into()
+        );
+
+        let rrule = RRule::from_str_lenient(input).unwrap();
+        assert_eq!(rrule.get_count(), Some(3));
+        assert_eq!(
+            rrule.get_extensions(),
+            [
+                ("X-NAME".to_owned(), "foo".to_owned()),
+                ("X-OTHER".to_owned(), "bar".to_owned())
+            ]
+        );
+        assert_eq!(
+            rrule.to_string(),
+            "FREQ=DAILY;COUNT=3;X-NAME=foo;X-OTHER=bar"
+        );
+
+        // Malformed known parts are still rejected
+        assert_eq!(
+            RRule::from_str_lenient("FREQ=DAILY;X-NAME=foo;COUNT=three").unwrap_err(),
+            ParseError::InvalidCount("three".into()).into()
+        );
+    }
+
     #[test]
     fn rejects_invalid_freq() {
         let mut props = HashMap::new();
@@ -14,7 +14,7 @@ mod utils;
 
 use std::str::FromStr;
 
-pub(crate) use content_line::{ContentLine, ContentLineCaptures};
+pub(crate) use content_line::{ContentLine, ContentLineCaptures, parse_rrule_lenient};
 pub(crate) use datetime::str_to_weekday;
 pub use error::ParseError;
 
@@ -657,3 +657,92 @@ fn yearly_with_interval_2() {
         &[ymd_hms(1960, 1, 1, 9, 0, 0), ymd_hms(1962, 1, 1, 9, 0, 0)],
     );
 }
+
+#[test]
+fn reverse_iteration() {
+    let set: RRuleSet = "DTSTART:19970902T090000Z\nRRULE:FREQ=DAILY;COUNT=5\nRDATE:19970901T090000Z\nEXDATE:19970904T090000Z"
+        .parse()
+        .unwrap();
+    assert!(set.is_finite());
+
+    let dates: Vec<_> = set.iter_rev().unwrap().collect();
+    assert_eq!(
+        dates,
+        [
+            ymd_hms(1997, 9, 6, 9, 0, 0),
+            ymd_hms(1997, 9, 5, 9, 0, 0),
+            ymd_hms(1997, 9, 3, 9, 0, 0),
+            ymd_hms(1997, 9, 2, 9, 0, 0),
+            ymd_hms(1997, 9, 1, 9, 0, 0),
+        ]
+    );
+
+    // The whole set is expanded up front
+    let mut iter = set.iter_rev().unwrap();
+    assert_eq!(iter.len(), 5);
+    // Both ends can be consumed alternately
+    assert_eq!(iter.next(), Some(ymd_hms(1997, 9, 6, 9, 0, 0)));
+    assert_eq!(iter.next_back(), Some(ymd_hms(1997, 9, 1, 9, 0, 0)));
+    assert_eq!(iter.next(), Some(ymd_hms(1997, 9, 5, 9, 0, 0)));
+    assert_eq!(iter.next_back(), Some(ymd_hms(1997, 9, 2, 9, 0, 0)));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next(), Some(ymd_hms(1997, 9, 3, 9, 0, 0)));
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn reverse_iteration_unbounded() {
+    let set: RRuleSet = "DTSTART:19970902T090000Z\nRRULE:FREQ=DAILY\nRRULE:FREQ=WEEKLY;COUNT=2"
+        .parse()
+        .unwrap();
+    assert!(!set.is_finite());
+    assert!(set.iter_rev().is_none());
+    // Forward iteration is unaffected
+    assert_eq!(set.into_iter().next(), Some(ymd_hms(1997, 9, 2, 9, 0, 0)));
+}
+
+#[test]
+fn occurrences_before() {
+    let set: RRuleSet = "DTSTART:19970902T090000Z\nRRULE:FREQ=DAILY\nEXDATE:19970909T090000Z"
+        .parse()
+        .unwrap();
+
+    let result = set
+        .clone()
+        .occurrences_before(ymd_hms(1997, 9, 10, 9, 0, 0), 3);
+    assert_eq!(
+        result.dates,
+        [
+            ymd_hms(1997, 9, 8, 9, 0, 0),
+            ymd_hms(1997, 9, 7, 9, 0, 0),
+            ymd_hms(1997, 9, 6, 9, 0, 0),
+        ]
+    );
+    assert!(!result.limited);
+
+    // Fewer occurrences than requested
+    let result = set
+        .clone()
+        .occurrences_before(ymd_hms(1997, 9, 4, 0, 0, 0), 3);
+    assert_eq!(
+        result.dates,
+        [ymd_hms(1997, 9, 3, 9, 0, 0), ymd_hms(1997, 9, 2, 9, 0, 0)]
+    );
+
+    // No occurrences requested
+    let result = set
+        .clone()
+        .occurrences_before(ymd_hms(1997, 9, 10, 9, 0, 0), 0);
+    assert!(result.dates.is_empty());
+
+    // Nothing before DTSTART
+    let result = set
+        .clone()
+        .occurrences_before(ymd_hms(1997, 9, 2, 9, 0, 0), 3);
+    assert!(result.dates.is_empty());
+
+    // Every recurrence since DTSTART is computed but only the last ones are kept
+    let result = set.occurrences_before(ymd_hms(2097, 9, 2, 9, 0, 0), 1);
+    assert_eq!(result.dates, [ymd_hms(2097, 9, 1, 9, 0, 0)]);
+    assert!(!result.limited);
+}
@@ -73,10 +73,10 @@ fn validate_until(
                         });
                     }
                 }
-                Tz::Olson(_) => {
+                Tz::Olson(_) | Tz::Fixed(_) => {
                     if until.timezone() != Tz::UTC {
                         return Err(ValidationError::DtStartUntilMismatchTimezone {
-                            dt_start_tz: dt_start.timezone().name().into(),
+                            dt_start_tz: dt_start.timezone().to_string(),
                             until_tz: until.timezone().name().into(),
                             expected: vec!["UTC".into()],
                         });
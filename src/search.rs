@@ -0,0 +1,205 @@
+//! Free-text search over calendars, e.g. for the quick search of calendar UIs
+use crate::{
+    caldav::{Collation, MatchType},
+    component::{
+        CalendarInnerData, Component, IcalCalendar, IcalCalendarObject, IcalEvent, IcalJournal,
+        IcalTodo,
+    },
+    parser::ContentLine,
+    types::{parse_text, parse_text_list},
+};
+
+/// The part of a component that matched a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SearchField {
+    Summary,
+    Description,
+    Location,
+    Categories,
+    /// The CN parameter of an ATTENDEE
+    AttendeeName,
+}
+
+/// A component that matched a search
+#[derive(Debug, Clone, Copy)]
+pub enum SearchComponent<'a> {
+    Event(&'a IcalEvent),
+    Todo(&'a IcalTodo),
+    Journal(&'a IcalJournal),
+}
+
+impl SearchComponent<'_> {
+    pub fn get_properties(&self) -> &[ContentLine] {
+        match self {
+            Self::Event(event) => event.get_properties(),
+            Self::Todo(todo) => todo.get_properties(),
+            Self::Journal(journal) => journal.get_properties(),
+        }
+    }
+}
+
+/// A component matching a search and the fields that matched
+#[derive(Debug, Clone)]
+pub struct SearchHit<'a> {
+    pub component: SearchComponent<'a>,
+    /// Sorted and without duplicates
+    pub fields: Vec<SearchField>,
+}
+
+/// The searchable texts of a component
+fn texts(properties: &[ContentLine]) -> Vec<(SearchField, String)> {
+    let mut texts = vec![];
+    for prop in properties {
+        match prop.name.as_str() {
+            "SUMMARY" => texts.push((SearchField::Summary, parse_text(&prop.value))),
+            "DESCRIPTION" => texts.push((SearchField::Description, parse_text(&prop.value))),
+            "LOCATION" => texts.push((SearchField::Location, parse_text(&prop.value))),
+            "CATEGORIES" => texts.extend(
+                parse_text_list(&prop.value)
+                    .into_iter()
+                    .map(|category| (SearchField::Categories, category)),
+            ),
+            "ATTENDEE" => texts.extend(
+                prop.params
+                    .get_param("CN")
+                    .map(|name| (SearchField::AttendeeName, name.to_owned())),
+            ),
+            _ => {}
+        }
+    }
+    texts
+}
+
+/// Matches a component if every word of `query` occurs in one of its fields, ignoring case
+fn search_component<'a>(component: SearchComponent<'a>, terms: &[&str]) -> Option<SearchHit<'a>> {
+    if terms.is_empty() {
+        return None;
+    }
+    let texts = texts(component.get_properties());
+    let mut fields = vec![];
+    for term in terms {
+        let matched: Vec<SearchField> = texts
+            .iter()
+            .filter(|(_, text)| Collation::UnicodeCasemap.matches(text, term, MatchType::Contains))
+            .map(|(field, _)| *field)
+            .collect();
+        if matched.is_empty() {
+            return None;
+        }
+        fields.extend(matched);
+    }
+    fields.sort();
+    fields.dedup();
+    Some(SearchHit { component, fields })
+}
+
+fn search_all<'a>(
+    components: impl IntoIterator<Item = SearchComponent<'a>>,
+    query: &str,
+) -> Vec<SearchHit<'a>> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    components
+        .into_iter()
+        .filter_map(|component| search_component(component, &terms))
+        .collect()
+}
+
+impl IcalCalendar {
+    /// The events, to-dos and journals in which every word of `query` occurs in the
+    /// SUMMARY, DESCRIPTION, LOCATION, CATEGORIES or the CN of an ATTENDEE.
+    ///
+    /// Words are compared case-insensitively with [`Collation::UnicodeCasemap`],
+    /// escaped characters in TEXT values are unescaped first.
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'_>> {
+        let components = self
+            .events
+            .iter()
+            .map(SearchComponent::Event)
+            .chain(self.todos.iter().map(SearchComponent::Todo))
+            .chain(self.journals.iter().map(SearchComponent::Journal));
+        search_all(components, query)
+    }
+}
+
+impl IcalCalendarObject {
+    /// The components matching `query`, see [`IcalCalendar::search`]
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'_>> {
+        let components: Vec<SearchComponent> = match &self.inner {
+            CalendarInnerData::Event(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .map(SearchComponent::Event)
+                .collect(),
+            CalendarInnerData::Todo(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .map(SearchComponent::Todo)
+                .collect(),
+            CalendarInnerData::Journal(main, overrides) => std::iter::once(main)
+                .chain(overrides)
+                .map(SearchComponent::Journal)
+                .collect(),
+        };
+        search_all(components, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchField;
+    use crate::IcalParser;
+    use rstest::rstest;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:planning@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+SUMMARY:Quarterly planning\r
+DESCRIPTION:Agenda:\\nBudget\\, hiring\r
+LOCATION:Room Straße 1\r
+CATEGORIES:Work,Finance\\, internal\r
+ATTENDEE;CN=Jane Doe:mailto:jane@example.com\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:report@example.com\r
+DTSTAMP:20260101T120000Z\r
+SUMMARY:Write planning report\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[rstest]
+    #[case("planning", vec![("planning@example.com", vec![SearchField::Summary]), ("report@example.com", vec![SearchField::Summary])])]
+    #[case("PLANNING jane", vec![("planning@example.com", vec![SearchField::Summary, SearchField::AttendeeName])])]
+    #[case("budget, hiring", vec![("planning@example.com", vec![SearchField::Description])])]
+    #[case("strasse", vec![("planning@example.com", vec![SearchField::Location])])]
+    #[case("finance, internal", vec![("planning@example.com", vec![SearchField::Categories])])]
+    #[case("planning mailto", vec![])]
+    #[case("  ", vec![])]
+    fn test_search(#[case] query: &str, #[case] expected: Vec<(&str, Vec<SearchField>)>) {
+        let calendar = IcalParser::from_slice(CALENDAR.as_bytes())
+            .expect_one()
+            .unwrap();
+        let hits: Vec<_> = calendar
+            .search(query)
+            .into_iter()
+            .map(|hit| {
+                let uid = hit
+                    .component
+                    .get_properties()
+                    .iter()
+                    .find(|prop| prop.name == "UID")
+                    .unwrap()
+                    .value
+                    .clone();
+                (uid, hit.fields)
+            })
+            .collect();
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(uid, fields)| (uid.to_owned(), fields))
+            .collect();
+        assert_eq!(hits, expected);
+    }
+}
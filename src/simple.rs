@@ -0,0 +1,478 @@
+//! A flat event model for web APIs
+//!
+//! [`SimpleEvent`] only carries the fields most web frontends display.
+//! Dates are formatted as RFC 3339 timestamps, all-day dates as `YYYY-MM-DD`.
+use crate::{
+    agenda::Occurrence,
+    component::{
+        CalendarInnerData, CalendarInnerDataBuilder, Component, ComponentMut, IcalCalendarObject,
+        IcalCalendarObjectBuilder, IcalEvent, IcalEventBuilder,
+    },
+    parser::{ContentLine, ParserError, ParserOptions},
+    property::{
+        IcalDTENDProperty, IcalDTSTARTProperty, IcalLOCATIONProperty, IcalRECURIDProperty,
+        IcalSUMMARYProperty, PartStat,
+    },
+    types::{CalAddress, CalDateOrDateTime, CalDateTime, CalDateTimeError, Text, Tz, escape_text},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// An ATTENDEE of a [`SimpleEvent`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleAttendee {
+    /// The email address of `mailto:` addresses, otherwise the full CAL-ADDRESS
+    pub email: String,
+    /// The CN parameter
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The PARTSTAT parameter, e.g. `ACCEPTED`
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// An event or one of its occurrences
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleEvent {
+    /// The UID
+    pub id: String,
+    /// The RECURRENCE-ID of overrides and occurrences
+    #[serde(default)]
+    pub recurrence_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub start: String,
+    /// Exclusive end, derived from DURATION if there's no DTEND
+    pub end: String,
+    pub all_day: bool,
+    /// The IANA timezone of zoned start and end values
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<SimpleAttendee>,
+    /// The RRULE value of recurring events, e.g. `FREQ=WEEKLY;BYDAY=MO`
+    #[serde(default)]
+    pub recurrence: Option<String>,
+}
+
+fn format_value(value: &CalDateOrDateTime) -> String {
+    match value {
+        CalDateOrDateTime::Date(date) => date.naive_date().format(DATE_FORMAT).to_string(),
+        CalDateOrDateTime::DateTime(datetime) => datetime.to_rfc3339(),
+    }
+}
+
+fn parse_value(
+    value: &str,
+    all_day: bool,
+    timezone: Option<chrono_tz::Tz>,
+) -> Result<CalDateOrDateTime, CalDateTimeError> {
+    if all_day {
+        return NaiveDate::parse_from_str(value, DATE_FORMAT)
+            .map(Into::into)
+            .map_err(|_| CalDateTimeError::InvalidDatetimeFormat(value.to_owned()));
+    }
+    let datetime = match timezone {
+        Some(timezone) => CalDateTime::from_rfc3339_in(value, timezone)?,
+        None => CalDateTime::from_rfc3339(value)?,
+    };
+    // There's no TZID for fixed offsets
+    if let Tz::Fixed(_) = datetime.timezone() {
+        return Ok(datetime.utc().into());
+    }
+    Ok(datetime.into())
+}
+
+fn end(event: &IcalEvent) -> CalDateOrDateTime {
+    if let Some(IcalDTENDProperty(dtend, _)) = &event.dtend {
+        return dtend.clone();
+    }
+    match &event.dtstart.0 {
+        CalDateOrDateTime::Date(date) => {
+            let duration = event.get_duration().unwrap_or(Duration::days(1));
+            (*date.naive_date() + duration).into()
+        }
+        CalDateOrDateTime::DateTime(datetime) => {
+            let duration = event.get_duration().unwrap_or_default();
+            (datetime.clone() + duration).into()
+        }
+    }
+}
+
+fn timezone(value: &CalDateOrDateTime) -> Option<chrono_tz::Tz> {
+    match value {
+        CalDateOrDateTime::DateTime(datetime) => match datetime.timezone() {
+            Tz::Olson(tz) if tz != chrono_tz::UTC => Some(tz),
+            _ => None,
+        },
+        CalDateOrDateTime::Date(_) => None,
+    }
+}
+
+fn text_line(name: &str, value: &str) -> ContentLine {
+    ContentLine {
+        name: name.to_owned(),
+        params: Default::default(),
+        value: escape_text(value),
+    }
+}
+
+/// Quotes parameter values containing delimiters
+fn quote_param(value: &str) -> String {
+    if value.contains([',', ';', ':']) {
+        format!("\"{value}\"")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Adds the TZID parameter to a date property converted from `value`
+fn zoned(mut line: ContentLine, value: &CalDateOrDateTime) -> ContentLine {
+    if let Some(tz) = timezone(value) {
+        line.params
+            .replace_param("TZID".to_owned(), tz.name().to_owned());
+    }
+    line
+}
+
+impl From<&ContentLine> for SimpleAttendee {
+    fn from(attendee: &ContentLine) -> Self {
+        let email = attendee
+            .value
+            .parse::<CalAddress>()
+            .ok()
+            .and_then(|address| address.email().map(str::to_owned))
+            .unwrap_or_else(|| attendee.value.to_owned());
+        Self {
+            email,
+            name: attendee.params.get_param("CN").map(str::to_owned),
+            status: attendee
+                .params
+                .get_param(PartStat::PARAM)
+                .map(str::to_owned),
+        }
+    }
+}
+
+impl From<&SimpleAttendee> for ContentLine {
+    fn from(attendee: &SimpleAttendee) -> Self {
+        let value = if attendee.email.parse::<CalAddress>().is_ok() {
+            attendee.email.to_owned()
+        } else {
+            format!("mailto:{}", attendee.email)
+        };
+        let mut line = Self {
+            name: "ATTENDEE".to_owned(),
+            params: Default::default(),
+            value,
+        };
+        if let Some(name) = &attendee.name {
+            line.params
+                .replace_param("CN".to_owned(), quote_param(name));
+        }
+        if let Some(status) = &attendee.status {
+            line.params
+                .replace_param(PartStat::PARAM.to_owned(), status.to_owned());
+        }
+        line
+    }
+}
+
+impl From<&IcalEvent> for SimpleEvent {
+    fn from(event: &IcalEvent) -> Self {
+        Self {
+            id: event.get_uid().to_owned(),
+            recurrence_id: event
+                .recurid
+                .as_ref()
+                .map(|IcalRECURIDProperty(recurid, ..)| format_value(recurid)),
+            title: event.get_summary().map(str::to_owned),
+            start: format_value(&event.dtstart.0),
+            end: format_value(&end(event)),
+            all_day: event.dtstart.0.is_date(),
+            timezone: timezone(&event.dtstart.0).map(|tz| tz.name().to_owned()),
+            location: event.get_location().map(Text::into_inner),
+            attendees: event
+                .get_named_properties("ATTENDEE")
+                .map(SimpleAttendee::from)
+                .collect(),
+            recurrence: event
+                .get_property("RRULE")
+                .map(|rrule| rrule.value.to_owned()),
+        }
+    }
+}
+
+impl From<&Occurrence<'_>> for SimpleEvent {
+    /// The instance with the RRULE and timezone of the event it's expanded from
+    fn from(occurrence: &Occurrence<'_>) -> Self {
+        let mut event = Self::from(&occurrence.event);
+        if let CalendarInnerData::Event(main, _) = occurrence.object.get_inner() {
+            event.recurrence = main
+                .get_property("RRULE")
+                .map(|rrule| rrule.value.to_owned());
+            if event.timezone.is_none() && !event.all_day {
+                event.timezone = timezone(&main.dtstart.0).map(|tz| tz.name().to_owned());
+            }
+        }
+        event
+    }
+}
+
+/// The main event and overrides of `object`, to-dos and journals result in no events
+pub fn events(object: &IcalCalendarObject) -> Vec<SimpleEvent> {
+    let CalendarInnerData::Event(main, overrides) = object.get_inner() else {
+        return vec![];
+    };
+    std::iter::once(main)
+        .chain(overrides)
+        .map(SimpleEvent::from)
+        .collect()
+}
+
+impl SimpleEvent {
+    /// Creates a calendar object containing the event and the VTIMEZONE it refers to.
+    /// DTSTAMP is set to the current time.
+    pub fn into_object(self, prodid: &str) -> Result<IcalCalendarObject, ParserError> {
+        let timezone = self
+            .timezone
+            .map(|name| {
+                name.parse::<chrono_tz::Tz>()
+                    .map_err(|_| CalDateTimeError::InvalidTZID(name))
+            })
+            .transpose()?;
+        let start = parse_value(&self.start, self.all_day, timezone)?;
+        let end = parse_value(&self.end, self.all_day, timezone)?;
+
+        let mut builder = IcalEventBuilder::new()
+            .with_uid(self.id)
+            .with_dtstamp(Utc::now().into());
+        builder.properties.push(zoned(
+            IcalDTSTARTProperty(start.clone(), Default::default()).into(),
+            &start,
+        ));
+        builder.properties.push(zoned(
+            IcalDTENDProperty(end.clone(), Default::default()).into(),
+            &end,
+        ));
+        if let Some(recurid) = self.recurrence_id {
+            let recurid = parse_value(&recurid, self.all_day, timezone)?;
+            builder.properties.push(zoned(
+                IcalRECURIDProperty(recurid.clone(), Default::default(), Default::default()).into(),
+                &recurid,
+            ));
+        }
+        if let Some(title) = &self.title {
+            builder
+                .properties
+                .push(IcalSUMMARYProperty::from(title.as_str()).into());
+        }
+        if let Some(location) = &self.location {
+            builder
+                .properties
+                .push(IcalLOCATIONProperty::from(location.as_str()).into());
+        }
+        if let Some(rrule) = self.recurrence {
+            builder.properties.push(ContentLine {
+                name: "RRULE".to_owned(),
+                params: Default::default(),
+                value: rrule,
+            });
+        }
+        builder
+            .properties
+            .extend(self.attendees.iter().map(ContentLine::from));
+
+        let object = IcalCalendarObjectBuilder {
+            properties: vec![
+                ContentLine {
+                    name: "VERSION".to_owned(),
+                    params: Default::default(),
+                    value: "2.0".to_owned(),
+                },
+                text_line("PRODID", prodid),
+            ],
+            inner: Some(CalendarInnerDataBuilder::Event(vec![builder])),
+            vtimezones: Default::default(),
+        };
+        object.build(
+            &ParserOptions {
+                rfc7809: true,
+                ..Default::default()
+            },
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimpleAttendee, SimpleEvent, events};
+    use crate::{
+        IcalObjectParser,
+        agenda::agenda,
+        component::{CalendarInnerData, Component},
+        generator::Emitter,
+    };
+    use chrono::NaiveDate;
+    use rstest::rstest;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:test\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Berlin\r
+BEGIN:STANDARD\r
+DTSTART:19701025T030000\r
+RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r
+TZOFFSETFROM:+0200\r
+TZOFFSETTO:+0100\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTAMP:20260101T000000Z\r
+DTSTART;TZID=Europe/Berlin:20260323T090000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO\r
+SUMMARY:Stand-up\\, daily\r
+LOCATION:Room 1\r
+ATTENDEE;CN=Jane;PARTSTAT=ACCEPTED:mailto:jane@example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTAMP:20260101T000000Z\r
+RECURRENCE-ID;TZID=Europe/Berlin:20260330T090000\r
+DTSTART;TZID=Europe/Berlin:20260330T100000\r
+DTEND;TZID=Europe/Berlin:20260330T103000\r
+SUMMARY:Moved stand-up\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_events() {
+        let object = IcalObjectParser::from_slice(ICS.as_bytes())
+            .expect_one()
+            .unwrap();
+        let events = events(&object);
+        assert_eq!(
+            events[0],
+            SimpleEvent {
+                id: "standup".to_owned(),
+                recurrence_id: None,
+                title: Some("Stand-up, daily".to_owned()),
+                start: "2026-03-23T09:00:00+01:00".to_owned(),
+                end: "2026-03-23T09:15:00+01:00".to_owned(),
+                all_day: false,
+                timezone: Some("Europe/Berlin".to_owned()),
+                location: Some("Room 1".to_owned()),
+                attendees: vec![SimpleAttendee {
+                    email: "jane@example.com".to_owned(),
+                    name: Some("Jane".to_owned()),
+                    status: Some("ACCEPTED".to_owned()),
+                }],
+                recurrence: Some("FREQ=WEEKLY;BYDAY=MO".to_owned()),
+            }
+        );
+        assert_eq!(
+            events[1].recurrence_id.as_deref(),
+            Some("2026-03-30T09:00:00+02:00")
+        );
+        assert_eq!(events[1].end, "2026-03-30T10:30:00+02:00");
+    }
+
+    #[test]
+    fn test_occurrence() {
+        let object = IcalObjectParser::from_slice(ICS.as_bytes())
+            .expect_one()
+            .unwrap();
+        let days = agenda(
+            [&object],
+            NaiveDate::from_ymd_opt(2026, 3, 23).unwrap()
+                ..=NaiveDate::from_ymd_opt(2026, 3, 23).unwrap(),
+            chrono_tz::Europe::Berlin.into(),
+        )
+        .collect::<Vec<_>>();
+        let occurrence = &days[0].1[0];
+        let event = SimpleEvent::from(occurrence);
+        assert_eq!(event.start, "2026-03-23T08:00:00Z");
+        assert_eq!(event.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(event.recurrence.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO"));
+    }
+
+    #[rstest]
+    #[case(
+        false,
+        "2026-03-23T09:00:00+01:00",
+        "2026-03-23T10:00:00+01:00",
+        Some("Europe/Berlin")
+    )]
+    #[case(false, "2026-03-23T09:00:00Z", "2026-03-23T10:00:00Z", None)]
+    #[case(false, "2026-03-23T09:00:00-00:00", "2026-03-23T10:00:00-00:00", None)]
+    #[case(true, "2026-03-23", "2026-03-25", None)]
+    fn test_roundtrip(
+        #[case] all_day: bool,
+        #[case] start: &str,
+        #[case] end: &str,
+        #[case] timezone: Option<&str>,
+    ) {
+        let event = SimpleEvent {
+            id: "roundtrip".to_owned(),
+            recurrence_id: None,
+            title: Some("Planning; Q2, draft\nsecond line".to_owned()),
+            start: start.to_owned(),
+            end: end.to_owned(),
+            all_day,
+            timezone: timezone.map(str::to_owned),
+            location: None,
+            attendees: vec![SimpleAttendee {
+                email: "jane@example.com".to_owned(),
+                name: Some("Doe, Jane".to_owned()),
+                status: None,
+            }],
+            recurrence: Some("FREQ=DAILY;COUNT=3".to_owned()),
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        let deserialized: SimpleEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, event);
+
+        let object = event.clone().into_object("-//test//EN").unwrap();
+        assert_eq!(object.vtimezones.len(), usize::from(timezone.is_some()));
+        let reparsed = IcalObjectParser::from_slice(object.generate().as_bytes())
+            .expect_one()
+            .unwrap();
+        let CalendarInnerData::Event(main, _) = reparsed.get_inner() else {
+            panic!("Expected an event");
+        };
+        assert!(main.get_property("DTSTAMP").is_some());
+        assert_eq!(events(&reparsed), vec![event]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let event = SimpleEvent {
+            id: "invalid".to_owned(),
+            recurrence_id: None,
+            title: None,
+            start: "2026-03-23".to_owned(),
+            end: "2026-03-24".to_owned(),
+            all_day: false,
+            timezone: None,
+            location: None,
+            attendees: vec![],
+            recurrence: None,
+        };
+        assert!(event.clone().into_object("-//test//EN").is_err());
+        let event = SimpleEvent {
+            timezone: Some("Mars/Olympus".to_owned()),
+            all_day: true,
+            ..event
+        };
+        assert!(event.into_object("-//test//EN").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! Reproducible synthetic calendars for load testing
+use crate::{
+    IcalParser, ParserError,
+    component::{IcalCalendar, IcalTimeZone},
+    generator::Emitter,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use itertools::Itertools;
+use std::{collections::BTreeSet, fmt::Write};
+
+const SUMMARIES: &[&str] = &[
+    "Team meeting",
+    "1:1",
+    "Sprint planning",
+    "Lunch",
+    "Dentist",
+    "Code review",
+    "Quarterly review",
+    "Customer call",
+    "Gym",
+    "Workshop",
+];
+const LOCATIONS: &[&str] = &["Room 1", "Room 2", "Cafeteria", "Online", "Main office"];
+const NAMES: &[&str] = &[
+    "Jane Doe",
+    "John Smith",
+    "Erika Mustermann",
+    "Max Müller",
+    "Zoë Martin",
+    "Li Wei",
+    "Ana García",
+    "Ola Nordmann",
+];
+const PARTSTATS: &[&str] = &["NEEDS-ACTION", "ACCEPTED", "DECLINED", "TENTATIVE"];
+/// Recurrence rules and the distance to their second instance, used for overrides
+const RRULES: &[(&str, Option<i64>)] = &[
+    ("FREQ=DAILY;COUNT=10", Some(1)),
+    ("FREQ=DAILY;INTERVAL=3;COUNT=20", Some(3)),
+    ("FREQ=WEEKLY", Some(7)),
+    ("FREQ=WEEKLY;INTERVAL=2;COUNT=26", Some(14)),
+    ("FREQ=WEEKLY;BYDAY=MO,WE,FR;COUNT=30", None),
+    ("FREQ=MONTHLY;COUNT=12", None),
+    ("FREQ=YEARLY", None),
+];
+
+/// SplitMix64, so that calendars only depend on the seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 { 0 } else { self.next() % bound }
+    }
+
+    fn chance(&mut self, percent: u8) -> bool {
+        self.below(100) < percent as u64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Options for [`calendar`] and [`ics`]
+#[derive(Debug, Clone)]
+pub struct SynthOptions {
+    seed: u64,
+    events: usize,
+    recurring: u8,
+    overrides: u8,
+    all_day: u8,
+    timezones: Vec<chrono_tz::Tz>,
+    attendees: usize,
+    start: NaiveDate,
+    days: u32,
+}
+
+impl Default for SynthOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            events: 1000,
+            recurring: 20,
+            overrides: 25,
+            all_day: 10,
+            timezones: vec![chrono_tz::Europe::Berlin],
+            attendees: 5,
+            start: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            days: 365,
+        }
+    }
+}
+
+impl SynthOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calendars generated with the same options and seed are identical, defaults to 0
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Number of events, overrides not included, defaults to 1000
+    pub fn with_events(mut self, events: usize) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Percentage of recurring events, defaults to 20
+    pub fn with_recurring(mut self, percent: u8) -> Self {
+        self.recurring = percent.min(100);
+        self
+    }
+
+    /// Percentage of recurring events with an overridden instance, defaults to 25
+    pub fn with_overrides(mut self, percent: u8) -> Self {
+        self.overrides = percent.min(100);
+        self
+    }
+
+    /// Percentage of all-day events, defaults to 10
+    pub fn with_all_day(mut self, percent: u8) -> Self {
+        self.all_day = percent.min(100);
+        self
+    }
+
+    /// Timezones of timed events, defaults to Europe/Berlin.
+    /// The remaining events are in UTC, all of them if no timezone is given.
+    pub fn with_timezones(mut self, timezones: Vec<chrono_tz::Tz>) -> Self {
+        self.timezones = timezones;
+        self
+    }
+
+    /// Maximum number of attendees per event, defaults to 5
+    pub fn with_attendees(mut self, attendees: usize) -> Self {
+        self.attendees = attendees;
+        self
+    }
+
+    /// Days the events start on, defaults to 365 days starting on 2026-01-01
+    pub fn with_range(mut self, start: NaiveDate, days: u32) -> Self {
+        self.start = start;
+        self.days = days.max(1);
+        self
+    }
+}
+
+/// A DATE-TIME value with its parameters
+fn datetime(tzid: Option<&str>, value: NaiveDateTime) -> String {
+    let value = value.format("%Y%m%dT%H%M%S");
+    match tzid {
+        Some(tzid) => format!(";TZID={tzid}:{value}"),
+        None => format!(":{value}Z"),
+    }
+}
+
+fn write_event(
+    ics: &mut String,
+    rng: &mut Rng,
+    options: &SynthOptions,
+    index: usize,
+) -> Option<String> {
+    let day = options.start + Duration::days(rng.below(options.days as u64) as i64);
+    let all_day = rng.chance(options.all_day);
+    let tzid = if options.timezones.is_empty() || rng.chance(20) {
+        None
+    } else {
+        Some(rng.pick(&options.timezones).name())
+    };
+    let start = day.and_time(NaiveTime::MIN)
+        + Duration::hours(8 + rng.below(10) as i64)
+        + Duration::minutes(15 * rng.below(4) as i64);
+    let length = Duration::minutes(15 * (1 + rng.below(12) as i64));
+    let uid = format!("synth-{index}@example.com");
+    let summary = rng.pick(SUMMARIES);
+
+    let mut event = String::new();
+    writeln!(event, "UID:{uid}\r").unwrap();
+    writeln!(event, "DTSTAMP:20260101T000000Z\r").unwrap();
+    if all_day {
+        let end = day + Duration::days(1 + rng.below(2) as i64);
+        writeln!(event, "DTSTART;VALUE=DATE:{}\r", day.format("%Y%m%d")).unwrap();
+        writeln!(event, "DTEND;VALUE=DATE:{}\r", end.format("%Y%m%d")).unwrap();
+    } else {
+        writeln!(event, "DTSTART{}\r", datetime(tzid, start)).unwrap();
+        writeln!(event, "DTEND{}\r", datetime(tzid, start + length)).unwrap();
+    }
+    if rng.chance(50) {
+        writeln!(event, "LOCATION:{}\r", rng.pick(LOCATIONS)).unwrap();
+    }
+    if rng.chance(30) {
+        writeln!(
+            event,
+            "DESCRIPTION:Agenda:\\n- Status\\, blockers\\n- Next steps\r"
+        )
+        .unwrap();
+    }
+    let attendees = rng.below(options.attendees as u64 + 1) as usize;
+    if attendees > 0 {
+        let organizer = rng.pick(NAMES);
+        writeln!(
+            event,
+            "ORGANIZER;CN={organizer}:mailto:{}\r",
+            mail(organizer)
+        )
+        .unwrap();
+        for _ in 0..attendees {
+            let name = rng.pick(NAMES);
+            writeln!(
+                event,
+                "ATTENDEE;CN={name};PARTSTAT={}:mailto:{}\r",
+                rng.pick(PARTSTATS),
+                mail(name)
+            )
+            .unwrap();
+        }
+    }
+
+    let mut overridden = None;
+    if rng.chance(options.recurring) {
+        let (rrule, second) = rng.pick(RRULES);
+        writeln!(event, "RRULE:{rrule}\r").unwrap();
+        if let Some(second) = second
+            && !all_day
+            && rng.chance(options.overrides)
+        {
+            let recurid = start + Duration::days(*second);
+            overridden = Some(format!(
+                "BEGIN:VEVENT\r\nUID:{uid}\r\nDTSTAMP:20260101T000000Z\r\nRECURRENCE-ID{}\r\nDTSTART{}\r\nDTEND{}\r\nSUMMARY:{summary} (moved)\r\nEND:VEVENT\r\n",
+                datetime(tzid, recurid),
+                datetime(tzid, recurid + Duration::hours(1)),
+                datetime(tzid, recurid + Duration::hours(1) + length),
+            ));
+        }
+    }
+    writeln!(event, "SUMMARY:{summary}\r").unwrap();
+
+    ics.push_str("BEGIN:VEVENT\r\n");
+    ics.push_str(&event);
+    ics.push_str("END:VEVENT\r\n");
+    if let Some(overridden) = &overridden {
+        ics.push_str(overridden);
+    }
+    tzid.map(ToOwned::to_owned)
+}
+
+fn mail(name: &str) -> String {
+    let local = name
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .join(".");
+    local + "@example.com"
+}
+
+/// Unfolded ICS text of the calendar
+fn raw_ics(options: &SynthOptions) -> String {
+    let mut rng = Rng(options.seed);
+    let mut events = String::new();
+    let mut tzids = BTreeSet::new();
+    for index in 0..options.events {
+        tzids.extend(write_event(&mut events, &mut rng, options, index));
+    }
+
+    let mut ics = String::from(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//caldata//synth//EN\r\nCALSCALE:GREGORIAN\r\n",
+    );
+    for tzid in tzids {
+        if let Some(vtimezone) = IcalTimeZone::from_tzid(&tzid) {
+            ics.push_str(&vtimezone.generate());
+        }
+    }
+    ics.push_str(&events);
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
+
+/// Generates a calendar with realistic events, e.g. to test against large inputs.
+///
+/// Events start during the day, last between 15 minutes and 3 hours and have a random mix
+/// of locations, descriptions and attendees. Recurring events get an RRULE and possibly an
+/// overridden instance that was moved by an hour.
+/// Fails if a timezone is missing from the bundled VTIMEZONEs.
+pub fn calendar(options: &SynthOptions) -> Result<IcalCalendar, ParserError> {
+    IcalParser::from_slice(raw_ics(options).as_bytes()).expect_one()
+}
+
+/// Generates a calendar in ICS format, see [`calendar`]
+pub fn ics(options: &SynthOptions) -> Result<Vec<u8>, ParserError> {
+    Ok(calendar(options)?.generate().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SynthOptions, calendar, ics};
+    use crate::component::Component;
+
+    #[test]
+    fn test_synth() {
+        let options = SynthOptions::new()
+            .with_seed(7)
+            .with_events(500)
+            .with_recurring(50)
+            .with_overrides(100);
+        let generated = ics(&options).unwrap();
+        assert_eq!(generated, ics(&options).unwrap());
+        assert_ne!(generated, ics(&options.clone().with_seed(8)).unwrap());
+
+        let calendar = calendar(&options).unwrap();
+        let (overrides, events): (Vec<_>, Vec<_>) = calendar
+            .events
+            .iter()
+            .partition(|event| event.get_property("RECURRENCE-ID").is_some());
+        assert_eq!(events.len(), 500);
+        assert!(!overrides.is_empty());
+        let recurring = events.iter().filter(|event| event.has_rruleset()).count();
+        assert!((150..350).contains(&recurring));
+        assert!(
+            events
+                .iter()
+                .all(|event| event.get_named_properties("ATTENDEE").count() <= 5)
+        );
+        assert!(calendar.vtimezones.contains_key("Europe/Berlin"));
+        assert_eq!(calendar.into_objects().unwrap().len(), 500);
+    }
+}
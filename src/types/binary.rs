@@ -0,0 +1,123 @@
+use crate::types::{InvalidValue, Uri, Value};
+use derive_more::{Deref, From};
+use std::str::FromStr;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A BINARY value as defined in RFC 5545 3.3.1, encoded in BASE64
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deref, From)]
+pub struct Binary(Vec<u8>);
+
+impl Binary {
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl FromStr for Binary {
+    type Err = InvalidValue;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidValue("BINARY", value.to_owned());
+        if !value.len().is_multiple_of(4) {
+            return Err(invalid());
+        }
+        let data = value.trim_end_matches('=');
+        if value.len() - data.len() > 2 {
+            return Err(invalid());
+        }
+        let mut out = Vec::with_capacity(data.len() * 3 / 4);
+        let mut buffer = 0u32;
+        let mut bits = 0;
+        for byte in data.bytes() {
+            let sextet = BASE64_ALPHABET
+                .iter()
+                .position(|&c| c == byte)
+                .ok_or_else(invalid)?;
+            buffer = (buffer << 6) | sextet as u32;
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        Ok(Self(out))
+    }
+}
+
+impl Value for Binary {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("BINARY")
+    }
+
+    fn value(&self) -> String {
+        let mut out = String::with_capacity(self.0.len().div_ceil(3) * 4);
+        for chunk in self.0.chunks(3) {
+            let buffer = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |acc, (i, &byte)| acc | (byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64_ALPHABET[(buffer >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+}
+
+/// The value of the ATTACH property, either a reference or inline data
+#[derive(Debug, Clone, PartialEq, Eq, Hash, From)]
+pub enum Attachment {
+    Uri(Uri),
+    Binary(Binary),
+}
+
+impl Value for Attachment {
+    fn value_type(&self) -> Option<&'static str> {
+        match self {
+            Self::Uri(uri) => uri.value_type(),
+            Self::Binary(binary) => binary.value_type(),
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            Self::Uri(uri) => uri.value(),
+            Self::Binary(binary) => binary.value(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Binary;
+    use crate::types::Value;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"", "")]
+    #[case(b"f", "Zg==")]
+    #[case(b"fo", "Zm8=")]
+    #[case(b"foo", "Zm9v")]
+    #[case(b"foob", "Zm9vYg==")]
+    #[case(b"fooba", "Zm9vYmE=")]
+    #[case(b"foobar", "Zm9vYmFy")]
+    fn test_base64_roundtrip(#[case] data: &[u8], #[case] encoded: &str) {
+        assert_eq!(Binary::from(data.to_vec()).value(), encoded);
+        assert_eq!(encoded.parse::<Binary>().unwrap().as_slice(), data);
+    }
+
+    #[rstest]
+    #[case("Zg=")]
+    #[case("Z===")]
+    #[case("Zm9v!A==")]
+    fn test_invalid_base64(#[case] value: &str) {
+        assert!(value.parse::<Binary>().is_err());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::types::{CalDateTimeError, Tz, Value};
-use crate::{parser::ContentLine, types::CalDateTime};
+use crate::{
+    parser::ContentLine,
+    types::{CalDateTime, datetime::parse_naive_date},
+};
 use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime};
 use std::{collections::HashMap, ops::Add};
 
@@ -38,7 +41,7 @@ impl Add<Duration> for CalDate {
 impl CalDate {
     pub fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self, CalDateTimeError> {
         let timezone = if let Some(tzid) = prop.params.get_tzid() {
             if let Some(timezone) = timezones.and_then(|timezones| timezones.get(tzid)) {
@@ -54,7 +57,7 @@ impl CalDate {
             None
         };
 
-        Self::parse(&prop.value, timezone)
+        Self::parse_in(&prop.value, timezone)
     }
 
     #[must_use]
@@ -77,8 +80,13 @@ impl CalDate {
     }
 
     pub fn parse(value: &str, timezone: Option<chrono_tz::Tz>) -> Result<Self, CalDateTimeError> {
-        let timezone = timezone.map_or(Tz::Local, Tz::Olson);
-        if let Ok(date) = NaiveDate::parse_from_str(value, LOCAL_DATE) {
+        Self::parse_in(value, timezone.map(Tz::Olson))
+    }
+
+    /// Like [`CalDate::parse`] but in any [`Tz`], e.g. a fixed offset
+    pub fn parse_in(value: &str, timezone: Option<Tz>) -> Result<Self, CalDateTimeError> {
+        let timezone = timezone.unwrap_or(Tz::Local);
+        if let Some(date) = parse_naive_date(value) {
             return Ok(Self(date, timezone));
         }
         Err(CalDateTimeError::InvalidDatetimeFormat(value.to_string()))
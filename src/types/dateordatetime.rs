@@ -1,23 +1,52 @@
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     ops::{Add, Sub},
 };
 
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use derive_more::From;
 
 use crate::{
     generator::Emitter,
     parser::{ContentLine, ParserError},
-    types::{CalDate, CalDateTime, Tz, Value},
+    types::{CalDate, CalDateTime, CalDateTimeError, LocalTimePolicy, Tz, Value},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, From)]
+/// Values are ordered by the instant they start at:
+/// DATE values start at midnight in their timezone and floating values are treated as UTC,
+/// consistent with [`CalDateTime::utc`].
+/// A DATE sorts before a DATE-TIME starting at the same instant.
+/// Use [`CalDateOrDateTime::cmp_in`] to interpret floating values in a specific timezone.
+#[derive(Debug, Clone, PartialEq, Eq, From)]
 pub enum CalDateOrDateTime {
     DateTime(CalDateTime),
     Date(CalDate),
 }
 
+impl PartialOrd for CalDateOrDateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CalDateOrDateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::DateTime(a), Self::DateTime(b)) => a.cmp(b),
+            (Self::Date(a), Self::Date(b)) => a.cmp(b),
+            (Self::Date(_), Self::DateTime(_)) => self
+                .start_in(Tz::Local)
+                .cmp(&other.start_in(Tz::Local))
+                .then(Ordering::Less),
+            (Self::DateTime(_), Self::Date(_)) => self
+                .start_in(Tz::Local)
+                .cmp(&other.start_in(Tz::Local))
+                .then(Ordering::Greater),
+        }
+    }
+}
+
 impl From<DateTime<Tz>> for CalDateOrDateTime {
     fn from(value: DateTime<Tz>) -> Self {
         Self::DateTime(value.into())
@@ -36,10 +65,57 @@ impl From<DateTime<Utc>> for CalDateOrDateTime {
     }
 }
 
+impl From<NaiveDateTime> for CalDateOrDateTime {
+    /// Creates a floating DATE-TIME value
+    fn from(value: NaiveDateTime) -> Self {
+        Self::DateTime(CalDateTime(
+            value
+                .and_local_timezone(Tz::Local)
+                .single()
+                .expect("Local timezone has constant offset"),
+        ))
+    }
+}
+
+impl From<NaiveDate> for CalDateOrDateTime {
+    /// Creates a floating DATE value
+    fn from(value: NaiveDate) -> Self {
+        Self::Date(CalDate(value, Tz::Local))
+    }
+}
+
+impl TryFrom<CalDateOrDateTime> for DateTime<Utc> {
+    type Error = CalDateTimeError;
+
+    /// Fails for DATE values, floating DATE-TIME values are treated as UTC
+    fn try_from(value: CalDateOrDateTime) -> Result<Self, Self::Error> {
+        match value {
+            CalDateOrDateTime::DateTime(datetime) => Ok(datetime.utc()),
+            CalDateOrDateTime::Date(_) => {
+                Err(CalDateTimeError::UnexpectedValueType("DATE-TIME", "DATE"))
+            }
+        }
+    }
+}
+
+impl TryFrom<CalDateOrDateTime> for NaiveDate {
+    type Error = CalDateTimeError;
+
+    /// Fails for DATE-TIME values
+    fn try_from(value: CalDateOrDateTime) -> Result<Self, Self::Error> {
+        match value {
+            CalDateOrDateTime::Date(CalDate(date, _)) => Ok(date),
+            CalDateOrDateTime::DateTime(_) => {
+                Err(CalDateTimeError::UnexpectedValueType("DATE", "DATE-TIME"))
+            }
+        }
+    }
+}
+
 impl CalDateOrDateTime {
     pub fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError> {
         Ok(match prop.params.get_value_type().unwrap_or(default_type) {
@@ -49,6 +125,31 @@ impl CalDateOrDateTime {
         })
     }
 
+    /// The instant the value starts at with floating values interpreted in `timezone`.
+    /// Local times in a gap are resolved as specified in RFC 5545.
+    fn start_in(&self, timezone: Tz) -> DateTime<Utc> {
+        let (local, tz) = match self {
+            Self::DateTime(datetime) if !datetime.timezone().is_local() => return datetime.utc(),
+            Self::DateTime(datetime) => (datetime.0.naive_local(), Tz::Local),
+            Self::Date(CalDate(date, tz)) => (date.and_time(NaiveTime::MIN), *tz),
+        };
+        let tz = if tz.is_local() { timezone } else { tz };
+        LocalTimePolicy::Rfc5545
+            .resolve(local, tz)
+            .map_or_else(|_| local.and_utc(), |datetime| datetime.to_utc())
+    }
+
+    /// Compares two values like [`Ord`] does but with floating values interpreted in `timezone`,
+    /// e.g. the timezone of the calendar they belong to
+    pub fn cmp_in(&self, other: &Self, timezone: Tz) -> Ordering {
+        let ordering = self.start_in(timezone).cmp(&other.start_in(timezone));
+        match (self, other) {
+            (Self::Date(_), Self::DateTime(_)) => ordering.then(Ordering::Less),
+            (Self::DateTime(_), Self::Date(_)) => ordering.then(Ordering::Greater),
+            _ => ordering.then_with(|| self.cmp(other)),
+        }
+    }
+
     pub fn is_date(&self) -> bool {
         matches!(self, Self::Date(_))
     }
@@ -94,6 +195,19 @@ impl CalDateOrDateTime {
             Self::Date(_) => "DATE",
         }
     }
+
+    /// Moves the wall-clock time by `duration` while keeping its value type and timezone,
+    /// see [`CalDateTime::shift`].
+    /// DATE values only move by the whole days contained in `duration`.
+    pub fn shift(self, duration: Duration) -> Result<Self, CalDateTimeError> {
+        match self {
+            Self::DateTime(datetime) => datetime.shift(duration).map(Self::DateTime),
+            Self::Date(CalDate(date, tz)) => date
+                .checked_add_signed(duration)
+                .map(|date| Self::Date(CalDate(date, tz)))
+                .ok_or(CalDateTimeError::DateOutOfRange),
+        }
+    }
 }
 
 impl Sub<&CalDateOrDateTime> for CalDateOrDateTime {
@@ -151,4 +265,105 @@ impl Value for CalDateOrDateTime {
             Self::Date(date) => Self::Date(date.utc_or_local()),
         }
     }
+
+    fn is_fixed_offset(&self) -> bool {
+        match self {
+            Self::DateTime(datetime) => datetime.is_fixed_offset(),
+            Self::Date(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{CalDate, CalDateOrDateTime, CalDateTime, CalDateTimeError, Tz};
+    use chrono::{DateTime, NaiveDate, Utc};
+    use std::cmp::Ordering;
+
+    fn value(value: &str, timezone: Option<chrono_tz::Tz>) -> CalDateOrDateTime {
+        if value.contains('T') {
+            CalDateTime::parse(value, timezone).unwrap().into()
+        } else {
+            CalDate::parse(value, timezone).unwrap().into()
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let berlin = Some(chrono_tz::Europe::Berlin);
+        let mut values = vec![
+            value("20240302", None),
+            value("20240301T090000Z", None),
+            value("20240301T000000", None),
+            value("20240301", None),
+            value("20240301T093000", berlin),
+            value("20240301", berlin),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                value("20240301", berlin),
+                value("20240301", None),
+                value("20240301T000000", None),
+                // 08:30 UTC
+                value("20240301T093000", berlin),
+                value("20240301T090000Z", None),
+                value("20240302", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cmp_in() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let floating = value("20240301T093000", None);
+        let zoned = value("20240301T090000Z", None);
+        assert_eq!(floating.cmp(&zoned), Ordering::Greater);
+        // 09:30 in Berlin is 08:30 UTC
+        assert_eq!(floating.cmp_in(&zoned, Tz::Olson(berlin)), Ordering::Less);
+        // Floating dates start at midnight in the given timezone
+        assert_eq!(
+            value("20240301", None).cmp_in(&value("20240229T233000Z", None), Tz::Olson(berlin)),
+            Ordering::Less
+        );
+        assert_eq!(
+            value("20240301", None).cmp_in(&value("20240301", None), Tz::Olson(berlin)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_chrono_conversions() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let value = CalDateOrDateTime::from(date);
+        assert_eq!(value, CalDate::parse("20240301", None).unwrap().into());
+        assert_eq!(NaiveDate::try_from(value.clone()), Ok(date));
+        assert_eq!(
+            DateTime::<Utc>::try_from(value),
+            Err(CalDateTimeError::UnexpectedValueType("DATE-TIME", "DATE"))
+        );
+
+        let datetime = date.and_hms_opt(12, 0, 0).unwrap();
+        let value = CalDateOrDateTime::from(datetime);
+        assert_eq!(value.timezone(), Tz::Local);
+        assert_eq!(
+            value,
+            CalDateTime::parse("20240301T120000", None).unwrap().into()
+        );
+        assert_eq!(
+            NaiveDate::try_from(value.clone()),
+            Err(CalDateTimeError::UnexpectedValueType("DATE", "DATE-TIME"))
+        );
+        assert_eq!(DateTime::<Utc>::try_from(value), Ok(datetime.and_utc()));
+
+        let value: CalDateOrDateTime =
+            CalDateTime::parse("20240301T120000", Some(chrono_tz::Europe::Berlin))
+                .unwrap()
+                .into();
+        assert_eq!(
+            DateTime::<Utc>::try_from(value).unwrap(),
+            date.and_hms_opt(11, 0, 0).unwrap().and_utc()
+        );
+    }
 }
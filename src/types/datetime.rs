@@ -1,11 +1,58 @@
 use crate::parser::{ContentLine, ParserError};
 use crate::types::CalDateTimeError;
-use crate::types::{Tz, Value};
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use crate::types::LOCAL_DATE;
+use crate::types::{LocalTimePolicy, Tz, Value};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc,
+};
 use std::{collections::HashMap, ops::Add};
 
 const LOCAL_DATE_TIME: &str = "%Y%m%dT%H%M%S";
 const UTC_DATE_TIME: &str = "%Y%m%dT%H%M%SZ";
+const RFC3339_LOCAL_DATE_TIME: &str = "%Y-%m-%dT%H:%M:%S%.f";
+/// RFC 3339 section 4.3 reserves this offset for an unknown local offset
+const RFC3339_UNKNOWN_OFFSET: &str = "-00:00";
+
+/// Parses a number from a fixed amount of ASCII digits
+#[inline]
+fn parse_digits(digits: &[u8]) -> Option<u32> {
+    digits.iter().try_fold(0, |acc, digit| {
+        digit
+            .is_ascii_digit()
+            .then(|| acc * 10 + u32::from(digit - b'0'))
+    })
+}
+
+/// Parses the canonical `YYYYMMDD` form without going through chrono's format strings
+#[inline]
+pub(crate) fn parse_naive_date(value: &str) -> Option<NaiveDate> {
+    match value.as_bytes() {
+        [year @ .., m1, m2, d1, d2] if year.len() == 4 => NaiveDate::from_ymd_opt(
+            parse_digits(year)? as i32,
+            parse_digits(&[*m1, *m2])?,
+            parse_digits(&[*d1, *d2])?,
+        ),
+        _ => NaiveDate::parse_from_str(value, LOCAL_DATE).ok(),
+    }
+}
+
+/// Parses the canonical `YYYYMMDDTHHMMSS` form without going through chrono's format strings
+#[inline]
+fn parse_naive_datetime(value: &str) -> Option<NaiveDateTime> {
+    let bytes = value.as_bytes();
+    if bytes.len() != 15 || bytes[8] != b'T' {
+        return NaiveDateTime::parse_from_str(value, LOCAL_DATE_TIME).ok();
+    }
+    let date = parse_naive_date(&value[..8])?;
+    let hour = parse_digits(&bytes[9..11])?;
+    let minute = parse_digits(&bytes[11..13])?;
+    let time = match parse_digits(&bytes[13..15])? {
+        // Leap seconds are represented like chrono's parser does
+        60 => NaiveTime::from_hms_nano_opt(hour, minute, 59, 1_000_000_000)?,
+        second => NaiveTime::from_hms_opt(hour, minute, second)?,
+    };
+    Some(date.and_time(time))
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 // Form 1, example: 19980118T230000 -> Local
@@ -41,9 +88,23 @@ impl Add<Duration> for CalDateTime {
 }
 
 impl CalDateTime {
+    /// Moves the wall-clock time by `duration` while keeping its timezone,
+    /// so a value moved across a DST change keeps its local time of day.
+    /// Times falling into a gap are resolved like [`LocalTimePolicy::Rfc5545`].
+    pub fn shift(self, duration: Duration) -> Result<Self, CalDateTimeError> {
+        let local = self
+            .0
+            .naive_local()
+            .checked_add_signed(duration)
+            .ok_or(CalDateTimeError::DateOutOfRange)?;
+        Ok(Self(
+            LocalTimePolicy::Rfc5545.resolve(local, self.timezone())?,
+        ))
+    }
+
     pub fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self, ParserError> {
         let timezone = if let Some(tzid) = prop.params.get_tzid() {
             if let Some(timezone) = timezones.and_then(|timezones| timezones.get(tzid)) {
@@ -59,25 +120,32 @@ impl CalDateTime {
             None
         };
 
-        Ok(Self::parse(&prop.value, timezone)?)
+        Ok(Self::parse_in(&prop.value, timezone)?)
     }
 
+    /// The iCalendar DATE-TIME value, fixed offsets have no TZID and are written in UTC
     #[must_use]
     pub fn format(&self) -> String {
         match self.timezone() {
             Tz::Olson(chrono_tz::UTC) => self.0.format(UTC_DATE_TIME).to_string(),
+            Tz::Fixed(_) => self.0.to_utc().format(UTC_DATE_TIME).to_string(),
             _ => self.0.format(LOCAL_DATE_TIME).to_string(),
         }
     }
 
     pub fn parse(value: &str, timezone: Option<chrono_tz::Tz>) -> Result<Self, CalDateTimeError> {
+        Self::parse_in(value, timezone.map(Tz::Olson))
+    }
+
+    /// Like [`CalDateTime::parse`] but in any [`Tz`], e.g. a fixed offset
+    pub fn parse_in(value: &str, timezone: Option<Tz>) -> Result<Self, CalDateTimeError> {
         let utc = value.ends_with('Z');
         // Remove Z suffix
         // Stripping the suffix manually and only running parse_from_str improves worst-case
         // performance by around 40%
         let value = value.rsplit_once('Z').map(|(v, _)| v).unwrap_or(value);
 
-        let Ok(datetime) = NaiveDateTime::parse_from_str(value, LOCAL_DATE_TIME) else {
+        let Some(datetime) = parse_naive_datetime(value) else {
             return Err(CalDateTimeError::InvalidDatetimeFormat(value.to_string()));
         };
 
@@ -87,7 +155,7 @@ impl CalDateTime {
             if let Some(timezone) = timezone {
                 return Ok(Self(
                     datetime
-                        .and_local_timezone(timezone.into())
+                        .and_local_timezone(timezone)
                         .earliest()
                         .ok_or(CalDateTimeError::LocalTimeGap)?,
                 ));
@@ -101,6 +169,52 @@ impl CalDateTime {
         }
     }
 
+    /// Parses an RFC 3339 timestamp.
+    /// Offsets other than UTC result in a fixed-offset timezone,
+    /// the unknown local offset `-00:00` results in a floating datetime.
+    pub fn from_rfc3339(value: &str) -> Result<Self, CalDateTimeError> {
+        if let Some(local) = value.strip_suffix(RFC3339_UNKNOWN_OFFSET) {
+            let datetime = NaiveDateTime::parse_from_str(local, RFC3339_LOCAL_DATE_TIME)
+                .map_err(|_| CalDateTimeError::InvalidDatetimeFormat(value.to_owned()))?;
+            return Ok(Self(
+                datetime
+                    .and_local_timezone(Tz::Local)
+                    .single()
+                    .expect("Local timezone has constant offset"),
+            ));
+        }
+        let datetime = DateTime::parse_from_rfc3339(value)
+            .map_err(|_| CalDateTimeError::InvalidDatetimeFormat(value.to_owned()))?;
+        if datetime.offset().local_minus_utc() == 0 {
+            return Ok(datetime.to_utc().into());
+        }
+        Ok(Self(datetime.with_timezone(&Tz::Fixed(*datetime.offset()))))
+    }
+
+    /// Parses an RFC 3339 timestamp and attaches `timezone` to the instant it refers to
+    pub fn from_rfc3339_in(value: &str, timezone: chrono_tz::Tz) -> Result<Self, CalDateTimeError> {
+        let datetime = DateTime::parse_from_rfc3339(value)
+            .map_err(|_| CalDateTimeError::InvalidDatetimeFormat(value.to_owned()))?;
+        Ok(Self(datetime.with_timezone(&timezone.into())))
+    }
+
+    /// Formats the value as RFC 3339 timestamp with the UTC offset in effect in its timezone.
+    /// Floating datetimes get the unknown local offset `-00:00`.
+    #[must_use]
+    pub fn to_rfc3339(&self) -> String {
+        match self.timezone() {
+            Tz::Local => {
+                self.0
+                    .naive_local()
+                    .format(RFC3339_LOCAL_DATE_TIME)
+                    .to_string()
+                    + RFC3339_UNKNOWN_OFFSET
+            }
+            Tz::Olson(chrono_tz::UTC) => self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            Tz::Olson(_) | Tz::Fixed(_) => self.0.to_rfc3339_opts(SecondsFormat::AutoSi, false),
+        }
+    }
+
     #[must_use]
     pub fn utc(&self) -> DateTime<Utc> {
         self.0.to_utc()
@@ -193,7 +307,84 @@ impl Value for CalDateTime {
     fn utc_or_local(self) -> Self {
         match self.timezone() {
             Tz::Local => self.clone(),
-            Tz::Olson(_) => Self(self.0.with_timezone(&Tz::utc())),
+            Tz::Olson(_) | Tz::Fixed(_) => Self(self.0.with_timezone(&Tz::utc())),
+        }
+    }
+
+    fn is_fixed_offset(&self) -> bool {
+        matches!(self.timezone(), Tz::Fixed(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{CalDateTime, CalDateTimeError, Tz};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("20240301T120000Z", None, "2024-03-01T12:00:00Z")]
+    #[case("20240301T120000", None, "2024-03-01T12:00:00-00:00")]
+    #[case(
+        "20240301T120000",
+        Some(chrono_tz::Europe::Berlin),
+        "2024-03-01T12:00:00+01:00"
+    )]
+    fn test_rfc3339(
+        #[case] value: &str,
+        #[case] timezone: Option<chrono_tz::Tz>,
+        #[case] rfc3339: &str,
+    ) {
+        let datetime = CalDateTime::parse(value, timezone).unwrap();
+        assert_eq!(datetime.to_rfc3339(), rfc3339);
+        let parsed = CalDateTime::from_rfc3339(rfc3339).unwrap();
+        assert_eq!(parsed.utc(), datetime.utc());
+        if let Some(timezone) = timezone {
+            assert_eq!(
+                CalDateTime::from_rfc3339_in(rfc3339, timezone).unwrap(),
+                datetime
+            );
+        } else {
+            assert_eq!(parsed, datetime);
         }
     }
+
+    #[rstest]
+    #[case("20240301T120000")]
+    #[case("19700329T020000")]
+    #[case("20161231T235960")]
+    #[case("20240230T120000")]
+    #[case("20240301T246000")]
+    #[case("2024030iT120000")]
+    #[case("20240301 120000")]
+    #[case("2024031T12000")]
+    #[case("+120240301T120000")]
+    #[case("")]
+    fn test_parse_naive_datetime(#[case] value: &str) {
+        use super::{LOCAL_DATE_TIME, parse_naive_datetime};
+        assert_eq!(
+            parse_naive_datetime(value),
+            chrono::NaiveDateTime::parse_from_str(value, LOCAL_DATE_TIME).ok()
+        );
+    }
+
+    #[test]
+    fn test_rfc3339_offset() {
+        let datetime = CalDateTime::from_rfc3339("2024-03-01T12:00:00.5+05:30").unwrap();
+        assert_eq!(datetime.timezone(), Tz::fixed(19800).unwrap());
+        assert_eq!(datetime.format(), "20240301T063000Z");
+        assert_eq!(datetime.utc().to_rfc3339(), "2024-03-01T06:30:00.500+00:00");
+        assert_eq!(datetime.to_rfc3339(), "2024-03-01T12:00:00.500+05:30");
+        assert_eq!(
+            CalDateTime::from_rfc3339("2024-03-01T12:00:00+00:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-03-01T12:00:00Z"
+        );
+        assert_eq!(
+            CalDateTime::from_rfc3339("20240301T120000Z"),
+            Err(CalDateTimeError::InvalidDatetimeFormat(
+                "20240301T120000Z".to_owned()
+            ))
+        );
+    }
 }
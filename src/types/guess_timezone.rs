@@ -306,7 +306,10 @@ static PROPRIETARY_TZIDS: phf::Map<&'static str, chrono_tz::Tz> = phf::phf_map!
 
 pub fn get_proprietary_tzid(tzid: &str) -> Option<chrono_tz::Tz> {
     // Stolen from https://github.com/stalwartlabs/calcard/blob/e056b9cdcaee69cfc43e5a5a8883e92f21977e7e/src/common/timezone.rs
-    PROPRIETARY_TZIDS.get(tzid).copied()
+    let tz = PROPRIETARY_TZIDS.get(tzid).copied();
+    #[cfg(feature = "windows-zones")]
+    let tz = tz.or_else(|| crate::types::windows_to_iana(tzid, None));
+    tz
 }
 
 #[cfg(test)]
@@ -0,0 +1,251 @@
+//! Conversions between the chrono based types and [`jiff`]
+use crate::types::{CalDate, CalDateTime, Tz};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum JiffConversionError {
+    #[error("Floating datetimes have no time zone")]
+    Floating,
+    #[error("Time zone {0} is not known")]
+    UnknownTimezone(String),
+    #[error("Time zone has no IANA name")]
+    UnnamedTimezone,
+    #[error("Value is out of range for the target type")]
+    OutOfRange,
+}
+
+impl From<jiff::Error> for JiffConversionError {
+    fn from(_: jiff::Error) -> Self {
+        Self::OutOfRange
+    }
+}
+
+fn naive_date_to_jiff(date: NaiveDate) -> Result<jiff::civil::Date, JiffConversionError> {
+    let year = i16::try_from(date.year()).map_err(|_| JiffConversionError::OutOfRange)?;
+    Ok(jiff::civil::Date::new(
+        year,
+        date.month() as i8,
+        date.day() as i8,
+    )?)
+}
+
+fn naive_date_from_jiff(date: jiff::civil::Date) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year().into(), date.month() as u32, date.day() as u32)
+        .expect("jiff's year range is contained in chrono's")
+}
+
+fn naive_datetime_to_jiff(
+    datetime: NaiveDateTime,
+) -> Result<jiff::civil::DateTime, JiffConversionError> {
+    // chrono represents leap seconds as nanoseconds beyond one second
+    let nanosecond = datetime.nanosecond().min(999_999_999);
+    let time = jiff::civil::Time::new(
+        datetime.hour() as i8,
+        datetime.minute() as i8,
+        datetime.second() as i8,
+        nanosecond as i32,
+    )?;
+    Ok(naive_date_to_jiff(datetime.date())?.to_datetime(time))
+}
+
+fn timestamp_to_utc(timestamp: jiff::Timestamp) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp.as_second(), timestamp.subsec_nanosecond() as u32)
+        .expect("jiff's timestamp range is contained in chrono's")
+}
+
+impl TryFrom<&CalDate> for jiff::civil::Date {
+    type Error = JiffConversionError;
+
+    fn try_from(value: &CalDate) -> Result<Self, Self::Error> {
+        naive_date_to_jiff(value.0)
+    }
+}
+
+impl From<jiff::civil::Date> for CalDate {
+    /// Creates a floating date
+    fn from(value: jiff::civil::Date) -> Self {
+        Self(naive_date_from_jiff(value), Tz::Local)
+    }
+}
+
+impl TryFrom<&CalDateTime> for jiff::civil::DateTime {
+    type Error = JiffConversionError;
+
+    /// The wall-clock time in the value's timezone
+    fn try_from(value: &CalDateTime) -> Result<Self, Self::Error> {
+        naive_datetime_to_jiff(value.0.naive_local())
+    }
+}
+
+impl TryFrom<&CalDateTime> for jiff::Timestamp {
+    type Error = JiffConversionError;
+
+    /// Floating times are treated as UTC, consistent with [`CalDateTime::utc`]
+    fn try_from(value: &CalDateTime) -> Result<Self, Self::Error> {
+        let utc = value.utc();
+        Ok(Self::new(
+            utc.timestamp(),
+            utc.timestamp_subsec_nanos() as i32,
+        )?)
+    }
+}
+
+impl TryFrom<&CalDateTime> for jiff::Zoned {
+    type Error = JiffConversionError;
+
+    /// Fails for floating datetimes since they aren't bound to a time zone
+    fn try_from(value: &CalDateTime) -> Result<Self, Self::Error> {
+        let time_zone = match value.timezone() {
+            Tz::Local => return Err(JiffConversionError::Floating),
+            Tz::Olson(tz) => jiff::tz::TimeZone::get(tz.name())
+                .map_err(|_| JiffConversionError::UnknownTimezone(tz.name().to_owned()))?,
+            Tz::Fixed(offset) => {
+                jiff::tz::TimeZone::fixed(jiff::tz::Offset::from_seconds(offset.local_minus_utc())?)
+            }
+        };
+        Ok(jiff::Timestamp::try_from(value)?.to_zoned(time_zone))
+    }
+}
+
+impl From<jiff::civil::DateTime> for CalDateTime {
+    /// Creates a floating datetime
+    fn from(value: jiff::civil::DateTime) -> Self {
+        let time = NaiveTime::from_hms_nano_opt(
+            value.hour() as u32,
+            value.minute() as u32,
+            value.second() as u32,
+            value.subsec_nanosecond() as u32,
+        )
+        .expect("valid time of day");
+        Self(
+            naive_date_from_jiff(value.date())
+                .and_time(time)
+                .and_local_timezone(Tz::Local)
+                .single()
+                .expect("Local timezone has constant offset"),
+        )
+    }
+}
+
+impl From<jiff::Timestamp> for CalDateTime {
+    fn from(value: jiff::Timestamp) -> Self {
+        timestamp_to_utc(value).into()
+    }
+}
+
+impl TryFrom<&jiff::Zoned> for CalDateTime {
+    type Error = JiffConversionError;
+
+    /// Requires the time zone to be an IANA time zone or a fixed offset
+    fn try_from(value: &jiff::Zoned) -> Result<Self, Self::Error> {
+        let Some(name) = value.time_zone().iana_name() else {
+            let offset = value
+                .time_zone()
+                .to_fixed_offset()
+                .map_err(|_| JiffConversionError::UnnamedTimezone)?;
+            let tz = Tz::fixed(offset.seconds()).ok_or(JiffConversionError::OutOfRange)?;
+            return Ok(Self(timestamp_to_utc(value.timestamp()).with_timezone(&tz)));
+        };
+        let tz = chrono_tz::Tz::from_str(name)
+            .map_err(|_| JiffConversionError::UnknownTimezone(name.to_owned()))?;
+        Ok(Self(
+            timestamp_to_utc(value.timestamp()).with_timezone(&Tz::Olson(tz)),
+        ))
+    }
+}
+
+impl TryFrom<jiff::Zoned> for CalDateTime {
+    type Error = JiffConversionError;
+
+    fn try_from(value: jiff::Zoned) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+/// Converts a duration as used throughout this crate to a [`jiff::SignedDuration`]
+pub fn duration_to_jiff(value: chrono::Duration) -> jiff::SignedDuration {
+    jiff::SignedDuration::new(value.num_seconds(), value.subsec_nanos())
+}
+
+/// Converts a [`jiff::SignedDuration`] to a duration as used throughout this crate
+pub fn duration_from_jiff(
+    value: jiff::SignedDuration,
+) -> Result<chrono::Duration, JiffConversionError> {
+    chrono::Duration::new(value.as_secs(), 0)
+        .and_then(|duration| {
+            duration.checked_add(&chrono::Duration::nanoseconds(value.subsec_nanos().into()))
+        })
+        .ok_or(JiffConversionError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JiffConversionError, duration_from_jiff, duration_to_jiff};
+    use crate::types::{CalDate, CalDateTime, Tz};
+    use rstest::rstest;
+
+    #[test]
+    fn test_date_roundtrip() {
+        let date = CalDate::parse("20240229", None).unwrap();
+        let converted = jiff::civil::Date::try_from(&date).unwrap();
+        assert_eq!(converted, jiff::civil::date(2024, 2, 29));
+        assert_eq!(CalDate::from(converted), date);
+    }
+
+    #[rstest]
+    #[case("20240301T120000Z", None, "2024-03-01T12:00:00+00:00[UTC]")]
+    #[case(
+        "20240301T120000",
+        Some(chrono_tz::Europe::Berlin),
+        "2024-03-01T12:00:00+01:00[Europe/Berlin]"
+    )]
+    fn test_zoned_roundtrip(
+        #[case] value: &str,
+        #[case] timezone: Option<chrono_tz::Tz>,
+        #[case] expected: &str,
+    ) {
+        let datetime = CalDateTime::parse(value, timezone).unwrap();
+        let zoned = jiff::Zoned::try_from(&datetime).unwrap();
+        assert_eq!(zoned.to_string(), expected);
+        assert_eq!(CalDateTime::try_from(zoned).unwrap(), datetime);
+    }
+
+    #[test]
+    fn test_floating() {
+        let datetime = CalDateTime::parse("20240301T120000", None).unwrap();
+        assert_eq!(
+            jiff::Zoned::try_from(&datetime),
+            Err(JiffConversionError::Floating)
+        );
+        let civil = jiff::civil::DateTime::try_from(&datetime).unwrap();
+        assert_eq!(civil, jiff::civil::date(2024, 3, 1).at(12, 0, 0, 0));
+        let roundtrip = CalDateTime::from(civil);
+        assert_eq!(roundtrip.timezone(), Tz::Local);
+        assert_eq!(roundtrip, datetime);
+    }
+
+    #[test]
+    fn test_fixed_offset_zoned() {
+        let zoned: jiff::Zoned = "2024-03-01T12:00:00+01:00[+01:00]".parse().unwrap();
+        let datetime = CalDateTime::try_from(&zoned).unwrap();
+        assert_eq!(datetime.timezone(), Tz::fixed(3600).unwrap());
+        assert_eq!(datetime.format(), "20240301T110000Z");
+        assert_eq!(jiff::Zoned::try_from(&datetime).unwrap(), zoned);
+        let datetime = CalDateTime::from(zoned.timestamp());
+        assert_eq!(datetime.format(), "20240301T110000Z");
+    }
+
+    #[rstest]
+    #[case(chrono::Duration::hours(1) + chrono::Duration::milliseconds(5))]
+    #[case(-chrono::Duration::days(7))]
+    #[case(-chrono::Duration::milliseconds(1500))]
+    fn test_duration_roundtrip(#[case] duration: chrono::Duration) {
+        let converted = duration_to_jiff(duration);
+        assert_eq!(
+            converted.as_millis(),
+            i128::from(duration.num_milliseconds())
+        );
+        assert_eq!(duration_from_jiff(converted).unwrap(), duration);
+    }
+}
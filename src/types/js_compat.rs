@@ -0,0 +1,40 @@
+//! Conversions between [`CalDateTime`] and [`js_sys::Date`] for use in the browser
+use crate::types::{CalDateTime, CalDateTimeError};
+use chrono::DateTime;
+
+impl From<&CalDateTime> for js_sys::Date {
+    /// The instant of the value, floating times are treated as UTC consistent with
+    /// [`CalDateTime::utc`]. Sub-millisecond precision is lost.
+    fn from(value: &CalDateTime) -> Self {
+        Self::new(&(value.utc().timestamp_millis() as f64).into())
+    }
+}
+
+impl From<CalDateTime> for js_sys::Date {
+    fn from(value: CalDateTime) -> Self {
+        (&value).into()
+    }
+}
+
+impl TryFrom<&js_sys::Date> for CalDateTime {
+    type Error = CalDateTimeError;
+
+    /// Creates a UTC datetime, fails for an invalid `Date`
+    fn try_from(value: &js_sys::Date) -> Result<Self, Self::Error> {
+        let millis = value.get_time();
+        if !millis.is_finite() {
+            return Err(CalDateTimeError::DateOutOfRange);
+        }
+        DateTime::from_timestamp_millis(millis as i64)
+            .map(Into::into)
+            .ok_or(CalDateTimeError::DateOutOfRange)
+    }
+}
+
+impl TryFrom<js_sys::Date> for CalDateTime {
+    type Error = CalDateTimeError;
+
+    fn try_from(value: js_sys::Date) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
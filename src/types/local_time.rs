@@ -0,0 +1,225 @@
+//! Calendar arithmetic on the wall-clock time of dates and datetimes
+use crate::types::{CalDate, CalDateOrDateTime, CalDateTime, CalDateTimeError, Tz};
+use chrono::{
+    DateTime, Days, MappedLocalTime, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+};
+
+/// How to resolve a wall-clock time that is ambiguous or doesn't exist in its timezone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LocalTimePolicy {
+    /// Behaviour mandated by RFC 5545 section 3.3.5:
+    /// Ambiguous times refer to their first occurrence and times in a gap are interpreted with
+    /// the UTC offset before the gap, moving them forward by the length of the gap.
+    #[default]
+    Rfc5545,
+    /// Like [`LocalTimePolicy::Rfc5545`] but ambiguous times refer to their second occurrence
+    Latest,
+    /// Ambiguous times and times in a gap are an error
+    Strict,
+}
+
+impl LocalTimePolicy {
+    pub fn resolve(
+        self,
+        datetime: NaiveDateTime,
+        timezone: Tz,
+    ) -> Result<DateTime<Tz>, CalDateTimeError> {
+        match (datetime.and_local_timezone(timezone), self) {
+            (MappedLocalTime::Single(datetime), _) => Ok(datetime),
+            (MappedLocalTime::Ambiguous(earliest, _), Self::Rfc5545) => Ok(earliest),
+            (MappedLocalTime::Ambiguous(_, latest), Self::Latest) => Ok(latest),
+            (MappedLocalTime::Ambiguous(..), Self::Strict) => {
+                Err(CalDateTimeError::AmbiguousLocalTime)
+            }
+            (MappedLocalTime::None, Self::Rfc5545 | Self::Latest) => {
+                // A day before is certainly before the gap unless a timezone has two transitions
+                // within a day
+                let before = datetime
+                    .checked_sub_days(Days::new(1))
+                    .ok_or(CalDateTimeError::DateOutOfRange)?;
+                let offset = timezone.offset_from_utc_datetime(&before);
+                let utc = datetime
+                    .checked_sub_offset(chrono::Offset::fix(&offset))
+                    .ok_or(CalDateTimeError::DateOutOfRange)?;
+                Ok(timezone.from_utc_datetime(&utc))
+            }
+            (MappedLocalTime::None, Self::Strict) => Err(CalDateTimeError::LocalTimeGap),
+        }
+    }
+}
+
+/// Adds months to a date, clamping the day to the end of the resulting month
+fn add_months(date: NaiveDate, months: i32) -> Result<NaiveDate, CalDateTimeError> {
+    let delta = Months::new(months.unsigned_abs());
+    if months < 0 {
+        date.checked_sub_months(delta)
+    } else {
+        date.checked_add_months(delta)
+    }
+    .ok_or(CalDateTimeError::DateOutOfRange)
+}
+
+impl CalDateTime {
+    /// Adds calendar months to the wall-clock time, keeping the timezone.
+    /// Days beyond the end of the resulting month are clamped, so January 31st plus one month
+    /// is the last day of February.
+    pub fn add_months(
+        &self,
+        months: i32,
+        policy: LocalTimePolicy,
+    ) -> Result<Self, CalDateTimeError> {
+        let local = self.0.naive_local();
+        let date = add_months(local.date(), months)?;
+        Ok(Self(
+            policy.resolve(date.and_time(local.time()), self.timezone())?,
+        ))
+    }
+
+    /// Adds calendar years to the wall-clock time, see [`CalDateTime::add_months`]
+    pub fn add_years(&self, years: i32, policy: LocalTimePolicy) -> Result<Self, CalDateTimeError> {
+        let months = years
+            .checked_mul(12)
+            .ok_or(CalDateTimeError::DateOutOfRange)?;
+        self.add_months(months, policy)
+    }
+
+    /// Replaces the wall-clock time while keeping the date and timezone
+    pub fn with_time(
+        &self,
+        time: NaiveTime,
+        policy: LocalTimePolicy,
+    ) -> Result<Self, CalDateTimeError> {
+        let date = self.0.date_naive();
+        Ok(Self(policy.resolve(date.and_time(time), self.timezone())?))
+    }
+}
+
+impl CalDateOrDateTime {
+    /// Adds calendar months while keeping the value type and timezone.
+    /// The policy only applies to DATE-TIME values.
+    pub fn add_months(
+        &self,
+        months: i32,
+        policy: LocalTimePolicy,
+    ) -> Result<Self, CalDateTimeError> {
+        Ok(match self {
+            Self::DateTime(datetime) => Self::DateTime(datetime.add_months(months, policy)?),
+            Self::Date(CalDate(date, tz)) => Self::Date(CalDate(add_months(*date, months)?, *tz)),
+        })
+    }
+
+    /// Adds calendar years while keeping the value type and timezone,
+    /// see [`CalDateOrDateTime::add_months`]
+    pub fn add_years(&self, years: i32, policy: LocalTimePolicy) -> Result<Self, CalDateTimeError> {
+        let months = years
+            .checked_mul(12)
+            .ok_or(CalDateTimeError::DateOutOfRange)?;
+        self.add_months(months, policy)
+    }
+
+    /// Replaces the wall-clock time, turning DATE values into DATE-TIME values in the same timezone
+    pub fn with_time(
+        &self,
+        time: NaiveTime,
+        policy: LocalTimePolicy,
+    ) -> Result<Self, CalDateTimeError> {
+        Ok(match self {
+            Self::DateTime(datetime) => Self::DateTime(datetime.with_time(time, policy)?),
+            Self::Date(CalDate(date, tz)) => {
+                Self::DateTime(CalDateTime(policy.resolve(date.and_time(time), *tz)?))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalTimePolicy;
+    use crate::types::{CalDate, CalDateOrDateTime, CalDateTime, CalDateTimeError};
+    use chrono::NaiveTime;
+    use rstest::rstest;
+
+    fn berlin(value: &str) -> CalDateTime {
+        CalDateTime::parse(value, Some(chrono_tz::Europe::Berlin)).unwrap()
+    }
+
+    #[rstest]
+    #[case("20240131T100000", 1, "20240229T100000")]
+    #[case("20240331T100000", -1, "20240229T100000")]
+    #[case("20240115T100000", 13, "20250215T100000")]
+    #[case("20240215T100000", -2, "20231215T100000")]
+    fn test_add_months(#[case] value: &str, #[case] months: i32, #[case] expected: &str) {
+        let datetime = berlin(value)
+            .add_months(months, LocalTimePolicy::Strict)
+            .unwrap();
+        assert_eq!(datetime, berlin(expected));
+    }
+
+    #[test]
+    fn test_add_years_keeps_wall_clock() {
+        // Winter time to summer time
+        let datetime = berlin("20240105T100000")
+            .add_months(6, LocalTimePolicy::Strict)
+            .unwrap();
+        assert_eq!(datetime.format(), "20240705T100000");
+        assert_eq!(datetime.utc().format("%H").to_string(), "08");
+
+        let datetime = berlin("20240229T100000")
+            .add_years(1, LocalTimePolicy::Strict)
+            .unwrap();
+        assert_eq!(datetime, berlin("20250228T100000"));
+    }
+
+    #[rstest]
+    // Gap on 2024-03-31 from 02:00 to 03:00
+    #[case("20240331T000000", LocalTimePolicy::Rfc5545, Ok("20240331T013000Z"))]
+    #[case("20240331T000000", LocalTimePolicy::Latest, Ok("20240331T013000Z"))]
+    #[case(
+        "20240331T000000",
+        LocalTimePolicy::Strict,
+        Err(CalDateTimeError::LocalTimeGap)
+    )]
+    // Overlap on 2024-10-27 from 02:00 to 03:00
+    #[case("20241027T000000", LocalTimePolicy::Rfc5545, Ok("20241027T003000Z"))]
+    #[case("20241027T000000", LocalTimePolicy::Latest, Ok("20241027T013000Z"))]
+    #[case(
+        "20241027T000000",
+        LocalTimePolicy::Strict,
+        Err(CalDateTimeError::AmbiguousLocalTime)
+    )]
+    fn test_dst_policy(
+        #[case] value: &str,
+        #[case] policy: LocalTimePolicy,
+        #[case] expected: Result<&str, CalDateTimeError>,
+    ) {
+        let result = berlin(value)
+            .with_time(NaiveTime::from_hms_opt(2, 30, 0).unwrap(), policy)
+            .map(|datetime| CalDateTime::from(datetime.utc()).format());
+        assert_eq!(result, expected.map(str::to_owned));
+    }
+
+    #[test]
+    fn test_date_or_datetime() {
+        let date: CalDateOrDateTime = CalDate::parse("20240131", Some(chrono_tz::Europe::Berlin))
+            .unwrap()
+            .into();
+        assert_eq!(
+            date.add_months(1, LocalTimePolicy::Strict).unwrap(),
+            CalDate::parse("20240229", Some(chrono_tz::Europe::Berlin))
+                .unwrap()
+                .into()
+        );
+        assert_eq!(
+            date.with_time(
+                NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                LocalTimePolicy::Strict
+            )
+            .unwrap(),
+            berlin("20240131T090000").into()
+        );
+        assert_eq!(
+            date.add_years(i32::MAX, LocalTimePolicy::Strict),
+            Err(CalDateTimeError::DateOutOfRange)
+        );
+    }
+}
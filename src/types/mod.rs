@@ -1,6 +1,7 @@
 mod duration;
 pub use duration::*;
 mod timezone;
+#[cfg(feature = "recurrence")]
 use crate::rrule::{RRule, Unvalidated};
 use itertools::Itertools;
 pub use timezone::*;
@@ -11,9 +12,35 @@ mod datetime;
 pub use datetime::*;
 mod dateordatetime;
 pub use dateordatetime::*;
+mod local_time;
+pub use local_time::*;
 pub use period::*;
 mod guess_timezone;
 pub use guess_timezone::*;
+mod utc_offset;
+pub use utc_offset::*;
+mod primitive;
+pub use primitive::*;
+mod uri;
+pub use uri::*;
+mod binary;
+pub use binary::*;
+#[cfg(feature = "time")]
+mod time_compat;
+#[cfg(feature = "time")]
+pub use time_compat::*;
+#[cfg(feature = "jiff")]
+mod jiff_compat;
+#[cfg(feature = "jiff")]
+pub use jiff_compat::*;
+#[cfg(feature = "js")]
+mod js_compat;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "windows-zones")]
+mod windows_zones;
+#[cfg(feature = "windows-zones")]
+pub use windows_zones::*;
 
 mod vcard;
 pub use vcard::*;
@@ -28,6 +55,10 @@ pub enum CalDateTimeError {
     InvalidTZID(String),
     #[error("Timestamp doesn't exist because of gap in local time")]
     LocalTimeGap,
+    #[error("Timestamp is ambiguous because of overlap in local time")]
+    AmbiguousLocalTime,
+    #[error("Date is out of the supported range")]
+    DateOutOfRange,
     #[error("Datetime string {0} has an invalid format")]
     InvalidDatetimeFormat(String),
     #[error("Could not parse datetime {0}")]
@@ -36,13 +67,24 @@ pub enum CalDateTimeError {
     InvalidDurationFormat(String),
     #[error("Invalid period format: {0}")]
     InvalidPeriodFormat(String),
+    #[error("Expected {0} value, got {1}")]
+    UnexpectedValueType(&'static str, &'static str),
 }
 
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("Invalid {0} value: {1}")]
+pub struct InvalidValue(pub &'static str, pub String);
+
 pub trait Value: Sized {
     fn utc_or_local(self) -> Self {
         self
     }
 
+    /// Whether the value is written in UTC since its timezone has no TZID, see [`Tz::Fixed`]
+    fn is_fixed_offset(&self) -> bool {
+        false
+    }
+
     fn value_type(&self) -> Option<&'static str>;
 
     fn value(&self) -> String;
@@ -58,6 +100,7 @@ impl Value for String {
     }
 }
 
+#[cfg(feature = "recurrence")]
 impl Value for RRule<Unvalidated> {
     fn value_type(&self) -> Option<&'static str> {
         Some("RECUR")
@@ -80,4 +123,8 @@ impl<V: Value> Value for Vec<V> {
     fn utc_or_local(self) -> Self {
         self.into_iter().map(Value::utc_or_local).collect()
     }
+
+    fn is_fixed_offset(&self) -> bool {
+        self.iter().any(Value::is_fixed_offset)
+    }
 }
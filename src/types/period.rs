@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use crate::{
     generator::Emitter,
     parser::{ContentLine, ParserError},
-    types::{CalDateOrDateTime, CalDateTime, CalDateTimeError, Value, parse_duration},
+    types::{CalDateOrDateTime, CalDateTime, CalDateTimeError, Tz, Value, parse_duration},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,7 +15,12 @@ pub enum DateTimeOrDuration {
 
 impl DateTimeOrDuration {
     pub fn parse(value: &str, timezone: Option<chrono_tz::Tz>) -> Result<Self, CalDateTimeError> {
-        if let Ok(datetime) = CalDateTime::parse(value, timezone) {
+        Self::parse_in(value, timezone.map(Tz::Olson))
+    }
+
+    /// Like [`DateTimeOrDuration::parse`] but in any [`Tz`], e.g. a fixed offset
+    pub fn parse_in(value: &str, timezone: Option<Tz>) -> Result<Self, CalDateTimeError> {
+        if let Ok(datetime) = CalDateTime::parse_in(value, timezone) {
             return Ok(Self::DateTime(datetime));
         }
         Ok(Self::Duration(parse_duration(value).unwrap()))
@@ -51,7 +56,7 @@ pub struct Period(CalDateTime, DateTimeOrDuration);
 impl Period {
     pub fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
     ) -> Result<Self, CalDateTimeError> {
         let timezone = if let Some(tzid) = prop.params.get_tzid() {
             if let Some(timezone) = timezones.and_then(|timezones| timezones.get(tzid)) {
@@ -67,22 +72,47 @@ impl Period {
             None
         };
 
-        Self::parse(&prop.value, timezone)
+        Self::parse_in(&prop.value, timezone)
     }
 
     pub fn parse(value: &str, timezone: Option<chrono_tz::Tz>) -> Result<Self, CalDateTimeError> {
+        Self::parse_in(value, timezone.map(Tz::Olson))
+    }
+
+    /// Like [`Period::parse`] but in any [`Tz`], e.g. a fixed offset
+    pub fn parse_in(value: &str, timezone: Option<Tz>) -> Result<Self, CalDateTimeError> {
         let (start, end) = value
             .split_once('/')
             .ok_or_else(|| CalDateTimeError::InvalidPeriodFormat(value.to_owned()))?;
 
-        let start = CalDateTime::parse(start, timezone)?;
-        let end = DateTimeOrDuration::parse(end, timezone)?;
+        let start = CalDateTime::parse_in(start, timezone)?;
+        let end = DateTimeOrDuration::parse_in(end, timezone)?;
         Ok(Self(start, end))
     }
 
+    pub fn start(&self) -> &CalDateTime {
+        &self.0
+    }
+
+    pub fn end(&self) -> CalDateTime {
+        match &self.1 {
+            DateTimeOrDuration::DateTime(end) => end.clone(),
+            DateTimeOrDuration::Duration(length) => self.0.clone() + *length,
+        }
+    }
+
     pub fn utc_or_local(self) -> Self {
         Self(self.0.utc_or_local(), self.1.utc_or_local())
     }
+
+    /// Moves the period by `duration`, keeping its length.
+    pub fn shift(self, duration: Duration) -> Result<Self, CalDateTimeError> {
+        let end = match self.1 {
+            DateTimeOrDuration::DateTime(end) => DateTimeOrDuration::DateTime(end.shift(duration)?),
+            DateTimeOrDuration::Duration(length) => DateTimeOrDuration::Duration(length),
+        };
+        Ok(Self(self.0.shift(duration)?, end))
+    }
 }
 
 impl Value for Period {
@@ -97,6 +127,10 @@ impl Value for Period {
             end = self.1.value()
         )
     }
+
+    fn is_fixed_offset(&self) -> bool {
+        self.0.is_fixed_offset()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -108,7 +142,7 @@ pub enum DateOrDateTimeOrPeriod {
 impl DateOrDateTimeOrPeriod {
     pub fn parse_prop(
         prop: &ContentLine,
-        timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        timezones: Option<&HashMap<String, Option<Tz>>>,
         default_type: &str,
     ) -> Result<Self, ParserError> {
         let value_type = prop.params.get_value_type().unwrap_or(default_type);
@@ -134,6 +168,13 @@ impl DateOrDateTimeOrPeriod {
             Self::Period(period) => Self::Period(period.utc_or_local()),
         }
     }
+
+    pub fn shift(self, duration: Duration) -> Result<Self, CalDateTimeError> {
+        Ok(match self {
+            Self::DateOrDateTime(dodt) => Self::DateOrDateTime(dodt.shift(duration)?),
+            Self::Period(period) => Self::Period(period.shift(duration)?),
+        })
+    }
 }
 
 impl Value for DateOrDateTimeOrPeriod {
@@ -150,4 +191,11 @@ impl Value for DateOrDateTimeOrPeriod {
             Self::Period(period) => period.value(),
         }
     }
+
+    fn is_fixed_offset(&self) -> bool {
+        match self {
+            Self::DateOrDateTime(dodt) => dodt.is_fixed_offset(),
+            Self::Period(period) => period.is_fixed_offset(),
+        }
+    }
 }
@@ -0,0 +1,286 @@
+use crate::types::{InvalidValue, Value};
+use derive_more::{Deref, Display, From};
+use std::{fmt, str::FromStr};
+
+impl Value for bool {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("BOOLEAN")
+    }
+
+    fn value(&self) -> String {
+        if *self { "TRUE" } else { "FALSE" }.to_owned()
+    }
+}
+
+pub fn parse_boolean(value: &str) -> Result<bool, InvalidValue> {
+    if value.eq_ignore_ascii_case("TRUE") {
+        Ok(true)
+    } else if value.eq_ignore_ascii_case("FALSE") {
+        Ok(false)
+    } else {
+        Err(InvalidValue("BOOLEAN", value.to_owned()))
+    }
+}
+
+impl Value for i32 {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("INTEGER")
+    }
+
+    fn value(&self) -> String {
+        self.to_string()
+    }
+}
+
+pub fn parse_integer(value: &str) -> Result<i32, InvalidValue> {
+    // i32::from_str would also accept a leading "+" which is valid here too
+    value
+        .parse()
+        .map_err(|_| InvalidValue("INTEGER", value.to_owned()))
+}
+
+/// Unescapes a TEXT value, RFC 5545 3.3.11.
+///
+/// Unknown escape sequences are kept as they are.
+pub fn parse_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            text.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped @ ('\\' | ';' | ',')) => text.push(escaped),
+            Some(other) => {
+                text.push('\\');
+                text.push(other);
+            }
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+/// Escapes a TEXT value, RFC 5545 3.3.11.
+///
+/// Newlines are escaped as `\n`, carriage returns are dropped.
+pub fn escape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    for char in value.chars() {
+        match char {
+            '\\' | ';' | ',' => {
+                text.push('\\');
+                text.push(char);
+            }
+            '\n' => text.push_str("\\n"),
+            '\r' => {}
+            char => text.push(char),
+        }
+    }
+    text
+}
+
+/// Splits a list of TEXT values at unescaped commas and unescapes them, e.g. for CATEGORIES
+pub fn parse_text_list(value: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, char) in value.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(parse_text(&value[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(parse_text(&value[start..]));
+    values
+}
+
+/// A TEXT value as defined in RFC 5545 3.3.11
+///
+/// It holds the unescaped text, escaping is applied when it's written.
+#[derive(Clone, Default, PartialEq, Eq, Hash, Deref, Display, From)]
+pub struct Text(String);
+
+impl fmt::Debug for Text {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl Text {
+    /// Unescapes the raw `value` of a content line
+    pub fn parse(value: &str) -> Self {
+        Self(parse_text(value))
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Self(text.to_owned())
+    }
+}
+
+impl Value for Text {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("TEXT")
+    }
+
+    fn value(&self) -> String {
+        escape_text(&self.0)
+    }
+}
+
+/// A FLOAT value as defined in RFC 5545 3.3.7
+///
+/// Since the format doesn't allow for NaN or infinity it's safe to compare for equality.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Float(f64);
+
+impl Eq for Float {}
+
+impl Float {
+    pub fn new(value: f64) -> Option<Self> {
+        value.is_finite().then_some(Self(value))
+    }
+
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+impl FromStr for Float {
+    type Err = InvalidValue;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidValue("FLOAT", value.to_owned());
+        let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, "0"));
+        if int.is_empty()
+            || frac.is_empty()
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        value.parse().map(Self).map_err(|_| invalid())
+    }
+}
+
+impl Value for Float {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("FLOAT")
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The value of the GEO property, latitude and longitude in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geo {
+    pub latitude: Float,
+    pub longitude: Float,
+}
+
+impl FromStr for Geo {
+    type Err = InvalidValue;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (latitude, longitude) = value
+            .split_once(';')
+            .ok_or_else(|| InvalidValue("FLOAT", value.to_owned()))?;
+        Ok(Self {
+            latitude: latitude.parse()?,
+            longitude: longitude.parse()?,
+        })
+    }
+}
+
+impl Value for Geo {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("FLOAT")
+    }
+
+    fn value(&self) -> String {
+        format!("{};{}", self.latitude.value(), self.longitude.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Float, Geo, Text, escape_text, parse_boolean, parse_integer, parse_text, parse_text_list,
+    };
+    use crate::types::Value;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("TRUE", true)]
+    #[case("false", false)]
+    fn test_boolean(#[case] value: &str, #[case] expected: bool) {
+        assert_eq!(parse_boolean(value).unwrap(), expected);
+        assert_eq!(expected.value(), value.to_uppercase());
+    }
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(parse_integer("+12").unwrap(), 12);
+        assert_eq!(parse_integer("-1").unwrap(), -1);
+        assert!(parse_integer("1.0").is_err());
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            parse_text(r"Meeting\, room 1\; bring\nnotes\\ \x"),
+            "Meeting, room 1; bring\nnotes\\ \\x"
+        );
+        assert_eq!(
+            parse_text_list(r"Work,Project\, internal,\\"),
+            vec!["Work", "Project, internal", "\\"]
+        );
+        let text = "Meeting, room 1; bring\r\nnotes\\";
+        assert_eq!(escape_text(text), r"Meeting\, room 1\; bring\nnotes\\");
+        assert_eq!(parse_text(&escape_text(text)), text.replace('\r', ""));
+
+        let text = Text::parse(r"Meeting\, room 1\nFloor 2");
+        assert_eq!(text.as_str(), "Meeting, room 1\nFloor 2");
+        assert_eq!(text.value(), r"Meeting\, room 1\nFloor 2");
+    }
+
+    #[rstest]
+    #[case("1000000.0000001", 1000000.0000001)]
+    #[case("-1.333", -1.333)]
+    #[case("+3", 3.0)]
+    fn test_float(#[case] value: &str, #[case] expected: f64) {
+        assert_eq!(value.parse::<Float>().unwrap().get(), expected);
+    }
+
+    #[rstest]
+    #[case("NaN")]
+    #[case("inf")]
+    #[case("1e5")]
+    #[case(".5")]
+    #[case("5.")]
+    fn test_invalid_float(#[case] value: &str) {
+        assert!(value.parse::<Float>().is_err());
+    }
+
+    #[test]
+    fn test_geo_roundtrip() {
+        let geo: Geo = "37.386013;-122.082932".parse().unwrap();
+        assert_eq!(geo.latitude.get(), 37.386013);
+        assert_eq!(geo.value(), "37.386013;-122.082932");
+    }
+}
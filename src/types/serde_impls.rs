@@ -0,0 +1,139 @@
+use crate::types::{CalDate, CalDateOrDateTime, CalDateTime, Tz};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+use std::str::FromStr;
+
+/// Dates and datetimes are represented by their iCalendar value and the TZID they refer to.
+/// UTC datetimes carry the `Z` suffix instead of a TZID, floating values have neither.
+/// Datetimes with a fixed offset are represented in UTC and dates with a fixed offset as floating.
+#[derive(Serialize, Deserialize)]
+struct SerdeDateOrDateTime<'a> {
+    value: String,
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    tzid: Option<std::borrow::Cow<'a, str>>,
+}
+
+impl SerdeDateOrDateTime<'static> {
+    fn new(value: String, timezone: Tz) -> Self {
+        let tzid = match timezone {
+            Tz::Local | Tz::UTC | Tz::Fixed(_) => None,
+            Tz::Olson(tz) => Some(tz.name().into()),
+        };
+        Self { value, tzid }
+    }
+}
+
+impl SerdeDateOrDateTime<'_> {
+    fn timezone<E: Error>(&self) -> Result<Option<chrono_tz::Tz>, E> {
+        self.tzid
+            .as_deref()
+            .map(|tzid| {
+                chrono_tz::Tz::from_str(tzid)
+                    .map_err(|_| E::custom(format!("Unknown timezone: {tzid}")))
+            })
+            .transpose()
+    }
+
+    fn into_value<E: Error>(self) -> Result<CalDateOrDateTime, E> {
+        let timezone = self.timezone()?;
+        if self.value.contains('T') {
+            Ok(CalDateTime::parse(&self.value, timezone)
+                .map_err(E::custom)?
+                .into())
+        } else {
+            Ok(CalDate::parse(&self.value, timezone)
+                .map_err(E::custom)?
+                .into())
+        }
+    }
+}
+
+impl Serialize for CalDateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeDateOrDateTime::new(self.format(), self.timezone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CalDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match SerdeDateOrDateTime::deserialize(deserializer)?.into_value()? {
+            CalDateOrDateTime::DateTime(datetime) => Ok(datetime),
+            CalDateOrDateTime::Date(date) => Err(D::Error::custom(format!(
+                "Expected DATE-TIME, got DATE {}",
+                date.format()
+            ))),
+        }
+    }
+}
+
+impl Serialize for CalDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerdeDateOrDateTime::new(self.format(), *self.timezone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CalDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match SerdeDateOrDateTime::deserialize(deserializer)?.into_value()? {
+            CalDateOrDateTime::Date(date) => Ok(date),
+            CalDateOrDateTime::DateTime(datetime) => Err(D::Error::custom(format!(
+                "Expected DATE, got DATE-TIME {}",
+                datetime.format()
+            ))),
+        }
+    }
+}
+
+impl Serialize for CalDateOrDateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::DateTime(datetime) => datetime.serialize(serializer),
+            Self::Date(date) => date.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CalDateOrDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SerdeDateOrDateTime::deserialize(deserializer)?.into_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{CalDate, CalDateOrDateTime, CalDateTime};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        CalDateTime::parse("20240301T120000Z", None).unwrap().into(),
+        r#"{"value":"20240301T120000Z"}"#
+    )]
+    #[case(
+        CalDateTime::parse("20240301T120000", None).unwrap().into(),
+        r#"{"value":"20240301T120000"}"#
+    )]
+    #[case(
+        CalDateTime::parse("20240301T120000", Some(chrono_tz::Europe::Berlin)).unwrap().into(),
+        r#"{"value":"20240301T120000","tzid":"Europe/Berlin"}"#
+    )]
+    #[case(
+        CalDate::parse("20240301", Some(chrono_tz::Europe::Berlin)).unwrap().into(),
+        r#"{"value":"20240301","tzid":"Europe/Berlin"}"#
+    )]
+    fn test_roundtrip(#[case] value: CalDateOrDateTime, #[case] json: &str) {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<CalDateOrDateTime>(json).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(serde_json::from_str::<CalDateTime>(r#"{"value":"20240301"}"#).is_err());
+        assert!(
+            serde_json::from_str::<CalDateTime>(r#"{"value":"20240301T120000","tzid":"Mars"}"#)
+                .is_err()
+        );
+    }
+}
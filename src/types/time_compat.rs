@@ -0,0 +1,235 @@
+//! Conversions between the chrono based types and the [`time`] crate
+use crate::types::{CalDate, CalDateTime, Tz};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Offset, Timelike, Utc};
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("Value is out of range for the target type")]
+pub struct OutOfRange;
+
+fn naive_date_to_time(date: NaiveDate) -> Result<time::Date, OutOfRange> {
+    // chrono supports a much larger year range than time
+    time::Date::from_ordinal_date(date.year(), date.ordinal() as u16).map_err(|_| OutOfRange)
+}
+
+fn naive_date_from_time(date: time::Date) -> NaiveDate {
+    NaiveDate::from_yo_opt(date.year(), date.ordinal().into())
+        .expect("time's year range is contained in chrono's")
+}
+
+fn naive_time_to_time(value: NaiveTime) -> time::Time {
+    // chrono represents leap seconds as nanoseconds beyond one second
+    let nanosecond = value.nanosecond().min(999_999_999);
+    time::Time::from_hms_nano(
+        value.hour() as u8,
+        value.minute() as u8,
+        value.second() as u8,
+        nanosecond,
+    )
+    .expect("valid time of day")
+}
+
+fn naive_time_from_time(value: time::Time) -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(
+        value.hour().into(),
+        value.minute().into(),
+        value.second().into(),
+        value.nanosecond(),
+    )
+    .expect("valid time of day")
+}
+
+impl TryFrom<&CalDate> for time::Date {
+    type Error = OutOfRange;
+
+    fn try_from(value: &CalDate) -> Result<Self, Self::Error> {
+        naive_date_to_time(value.0)
+    }
+}
+
+impl TryFrom<CalDate> for time::Date {
+    type Error = OutOfRange;
+
+    fn try_from(value: CalDate) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl From<time::Date> for CalDate {
+    /// Creates a floating date
+    fn from(value: time::Date) -> Self {
+        Self(naive_date_from_time(value), Tz::Local)
+    }
+}
+
+impl TryFrom<&CalDateTime> for time::PrimitiveDateTime {
+    type Error = OutOfRange;
+
+    /// The wall-clock time in the value's timezone
+    fn try_from(value: &CalDateTime) -> Result<Self, Self::Error> {
+        let local = value.0.naive_local();
+        Ok(Self::new(
+            naive_date_to_time(local.date())?,
+            naive_time_to_time(local.time()),
+        ))
+    }
+}
+
+impl TryFrom<CalDateTime> for time::PrimitiveDateTime {
+    type Error = OutOfRange;
+
+    fn try_from(value: CalDateTime) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl TryFrom<&CalDateTime> for time::OffsetDateTime {
+    type Error = OutOfRange;
+
+    /// Floating times are treated as UTC, consistent with [`CalDateTime::utc`]
+    fn try_from(value: &CalDateTime) -> Result<Self, Self::Error> {
+        let offset = value.0.offset().fix().local_minus_utc();
+        Ok(time::PrimitiveDateTime::try_from(value)?.assume_offset(
+            time::UtcOffset::from_whole_seconds(offset).expect("offset is less than 24 hours"),
+        ))
+    }
+}
+
+impl TryFrom<CalDateTime> for time::OffsetDateTime {
+    type Error = OutOfRange;
+
+    fn try_from(value: CalDateTime) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl From<time::PrimitiveDateTime> for CalDateTime {
+    /// Creates a floating datetime
+    fn from(value: time::PrimitiveDateTime) -> Self {
+        let datetime = NaiveDateTime::new(
+            naive_date_from_time(value.date()),
+            naive_time_from_time(value.time()),
+        );
+        Self(
+            datetime
+                .and_local_timezone(Tz::Local)
+                .single()
+                .expect("Local timezone has constant offset"),
+        )
+    }
+}
+
+impl From<time::OffsetDateTime> for CalDateTime {
+    /// Creates a UTC datetime or one with a fixed offset.
+    /// Offsets of 24 hours and more can't be represented and result in a UTC datetime.
+    fn from(value: time::OffsetDateTime) -> Self {
+        let utc = value.to_offset(time::UtcOffset::UTC);
+        let datetime = NaiveDateTime::new(
+            naive_date_from_time(utc.date()),
+            naive_time_from_time(utc.time()),
+        );
+        let datetime = DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc);
+        match Tz::fixed(value.offset().whole_seconds()) {
+            Some(tz) if !value.offset().is_utc() => Self(datetime.with_timezone(&tz)),
+            _ => datetime.into(),
+        }
+    }
+}
+
+/// Converts a duration as used throughout this crate to a [`time::Duration`]
+pub fn duration_to_time(value: chrono::Duration) -> time::Duration {
+    time::Duration::new(value.num_seconds(), value.subsec_nanos())
+}
+
+/// Converts a [`time::Duration`] to a duration as used throughout this crate
+pub fn duration_from_time(value: time::Duration) -> Result<chrono::Duration, OutOfRange> {
+    chrono::Duration::new(value.whole_seconds(), 0)
+        .and_then(|duration| {
+            duration.checked_add(&chrono::Duration::nanoseconds(
+                value.subsec_nanoseconds().into(),
+            ))
+        })
+        .ok_or(OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutOfRange, duration_from_time, duration_to_time};
+    use crate::types::{CalDate, CalDateTime, Tz};
+    use rstest::rstest;
+
+    #[test]
+    fn test_date_roundtrip() {
+        let date = CalDate::parse("20240229", None).unwrap();
+        let converted = time::Date::try_from(&date).unwrap();
+        assert_eq!(
+            converted,
+            time::Date::from_calendar_date(2024, time::Month::February, 29).unwrap()
+        );
+        assert_eq!(CalDate::from(converted), date);
+    }
+
+    #[rstest]
+    #[case("20240301T120000Z", None, "2024-03-01 12:00:00.0 +00:00:00")]
+    #[case(
+        "20240301T120000",
+        Some(chrono_tz::Europe::Berlin),
+        "2024-03-01 12:00:00.0 +01:00:00"
+    )]
+    #[case("20240301T120000", None, "2024-03-01 12:00:00.0 +00:00:00")]
+    fn test_datetime_to_time(
+        #[case] value: &str,
+        #[case] timezone: Option<chrono_tz::Tz>,
+        #[case] expected: &str,
+    ) {
+        let datetime = CalDateTime::parse(value, timezone).unwrap();
+        let converted = time::OffsetDateTime::try_from(&datetime).unwrap();
+        assert_eq!(converted.to_string(), expected);
+        assert_eq!(CalDateTime::from(converted).utc(), datetime.utc());
+    }
+
+    #[test]
+    fn test_floating_roundtrip() {
+        let datetime = CalDateTime::parse("20240301T120000", None).unwrap();
+        let converted = time::PrimitiveDateTime::try_from(&datetime).unwrap();
+        assert_eq!(converted.to_string(), "2024-03-01 12:00:00.0");
+        let roundtrip = CalDateTime::from(converted);
+        assert_eq!(roundtrip.timezone(), Tz::Local);
+        assert_eq!(roundtrip, datetime);
+    }
+
+    #[test]
+    fn test_date_out_of_range() {
+        let naive = chrono::NaiveDate::from_ymd_opt(20000, 1, 1).unwrap();
+        let date = CalDate(naive, Tz::Local);
+        assert_eq!(time::Date::try_from(&date), Err(OutOfRange));
+        let datetime = CalDateTime::from(naive.and_hms_opt(12, 0, 0).unwrap().and_utc());
+        assert_eq!(time::OffsetDateTime::try_from(&datetime), Err(OutOfRange));
+    }
+
+    #[test]
+    fn test_offset_out_of_range() {
+        let offset = time::UtcOffset::from_hms(25, 0, 0).unwrap();
+        let value = time::OffsetDateTime::UNIX_EPOCH.to_offset(offset);
+        let datetime = CalDateTime::from(value);
+        assert_eq!(datetime.timezone(), Tz::UTC);
+        assert_eq!(datetime.format(), "19700101T000000Z");
+    }
+
+    #[rstest]
+    #[case(chrono::Duration::hours(1) + chrono::Duration::milliseconds(5))]
+    #[case(-chrono::Duration::days(7))]
+    #[case(-chrono::Duration::milliseconds(1500))]
+    fn test_duration_roundtrip(#[case] duration: chrono::Duration) {
+        let converted = duration_to_time(duration);
+        assert_eq!(
+            converted.whole_milliseconds(),
+            i128::from(duration.num_milliseconds())
+        );
+        assert_eq!(duration_from_time(converted).unwrap(), duration);
+    }
+
+    #[test]
+    fn test_duration_out_of_range() {
+        assert!(duration_from_time(time::Duration::MAX).is_err());
+    }
+}
@@ -1,10 +1,13 @@
-use chrono::{MappedLocalTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use derive_more::{Display, From};
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, From, PartialEq, Eq)]
 pub enum Tz {
     Local,
     Olson(chrono_tz::Tz),
+    /// A constant UTC offset, e.g. from a VTIMEZONE without transitions
+    Fixed(FixedOffset),
 }
 
 impl Tz {
@@ -14,23 +17,41 @@ impl Tz {
         matches!(self, Self::Local)
     }
 
+    /// The IANA name, fixed offsets have none and are named `Fixed`, see [`Display`](fmt::Display)
     #[must_use]
     pub fn name(&self) -> &str {
         match self {
             Self::Local => "Local",
             Self::Olson(tz) => tz.name(),
+            Self::Fixed(_) => "Fixed",
         }
     }
 
+    /// A fixed offset of `seconds` east of UTC
+    pub fn fixed(seconds: i32) -> Option<Self> {
+        FixedOffset::east_opt(seconds).map(Self::Fixed)
+    }
+
     pub fn utc() -> Self {
         Self::Olson(chrono_tz::UTC)
     }
 }
 
+impl fmt::Display for Tz {
+    /// The name, fixed offsets are written like `+03:00`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(offset) => offset.fmt(f),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
 pub enum CalTimezoneOffset {
     Local,
     Olson(chrono_tz::TzOffset),
+    Fixed(FixedOffset),
 }
 
 impl chrono::Offset for CalTimezoneOffset {
@@ -38,6 +59,7 @@ impl chrono::Offset for CalTimezoneOffset {
         match self {
             Self::Local => Utc.fix(),
             Self::Olson(olson) => olson.fix(),
+            Self::Fixed(offset) => *offset,
         }
     }
 }
@@ -49,6 +71,7 @@ impl TimeZone for Tz {
         match offset {
             CalTimezoneOffset::Local => Self::Local,
             CalTimezoneOffset::Olson(offset) => Self::Olson(chrono_tz::Tz::from_offset(offset)),
+            CalTimezoneOffset::Fixed(offset) => Self::Fixed(*offset),
         }
     }
 
@@ -59,6 +82,7 @@ impl TimeZone for Tz {
             Self::Olson(tz) => tz
                 .offset_from_local_date(local)
                 .map(CalTimezoneOffset::Olson),
+            Self::Fixed(offset) => MappedLocalTime::Single(CalTimezoneOffset::Fixed(*offset)),
         }
     }
 
@@ -71,6 +95,7 @@ impl TimeZone for Tz {
             Self::Olson(tz) => tz
                 .offset_from_local_datetime(local)
                 .map(CalTimezoneOffset::Olson),
+            Self::Fixed(offset) => MappedLocalTime::Single(CalTimezoneOffset::Fixed(*offset)),
         }
     }
 
@@ -78,6 +103,7 @@ impl TimeZone for Tz {
         match self {
             Self::Local => CalTimezoneOffset::Local,
             Self::Olson(tz) => CalTimezoneOffset::Olson(tz.offset_from_utc_datetime(utc)),
+            Self::Fixed(offset) => CalTimezoneOffset::Fixed(*offset),
         }
     }
 
@@ -86,6 +112,27 @@ impl TimeZone for Tz {
         match self {
             Self::Local => CalTimezoneOffset::Local,
             Self::Olson(tz) => CalTimezoneOffset::Olson(tz.offset_from_utc_date(utc)),
+            Self::Fixed(offset) => CalTimezoneOffset::Fixed(*offset),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{CalDateTime, Tz};
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_fixed() {
+        let tz = Tz::fixed(-3 * 3600).unwrap();
+        assert_eq!(tz.to_string(), "-03:00");
+        let datetime = CalDateTime::from(tz.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap());
+        assert_eq!(datetime.format(), "20240301T150000Z");
+        assert_eq!(datetime.utc().to_rfc3339(), "2024-03-01T15:00:00+00:00");
+        assert_eq!(
+            (datetime + Duration::days(180)).format(),
+            "20240828T150000Z"
+        );
+        assert_eq!(Tz::fixed(24 * 3600), None);
+    }
+}
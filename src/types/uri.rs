@@ -0,0 +1,94 @@
+use crate::types::{InvalidValue, Value};
+use derive_more::{Deref, Display};
+use std::str::FromStr;
+
+fn has_scheme(value: &str) -> bool {
+    let Some((scheme, _)) = value.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// A URI value as defined in RFC 5545 3.3.13
+///
+/// Only the presence of a scheme is validated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deref, Display)]
+pub struct Uri(String);
+
+impl FromStr for Uri {
+    type Err = InvalidValue;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if !has_scheme(value) {
+            return Err(InvalidValue("URI", value.to_owned()));
+        }
+        Ok(Self(value.to_owned()))
+    }
+}
+
+impl Value for Uri {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("URI")
+    }
+
+    fn value(&self) -> String {
+        self.0.to_owned()
+    }
+}
+
+/// A CAL-ADDRESS value as defined in RFC 5545 3.3.3, usually a `mailto:` URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deref, Display)]
+pub struct CalAddress(String);
+
+impl CalAddress {
+    /// The email address of `mailto:` addresses
+    pub fn email(&self) -> Option<&str> {
+        let (scheme, address) = self.0.split_once(':')?;
+        scheme.eq_ignore_ascii_case("mailto").then_some(address)
+    }
+}
+
+impl FromStr for CalAddress {
+    type Err = InvalidValue;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if !has_scheme(value) {
+            return Err(InvalidValue("CAL-ADDRESS", value.to_owned()));
+        }
+        Ok(Self(value.to_owned()))
+    }
+}
+
+impl Value for CalAddress {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("CAL-ADDRESS")
+    }
+
+    fn value(&self) -> String {
+        self.0.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalAddress, Uri};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("http://example.com/my-report.txt", true)]
+    #[case("urn:ietf:rfc:2396", true)]
+    #[case("example.com", false)]
+    #[case("1http://example.com", false)]
+    fn test_uri(#[case] value: &str, #[case] valid: bool) {
+        assert_eq!(value.parse::<Uri>().is_ok(), valid);
+    }
+
+    #[test]
+    fn test_cal_address() {
+        let address: CalAddress = "MAILTO:jane_doe@example.com".parse().unwrap();
+        assert_eq!(address.email(), Some("jane_doe@example.com"));
+        assert!("jane_doe@example.com".parse::<CalAddress>().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::types::Value;
+use chrono::FixedOffset;
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[error("Invalid UTC offset: {0}")]
+pub struct InvalidUtcOffset(String);
+
+/// A UTC-OFFSET value as defined in RFC 5545 3.3.14, e.g. `+0100` or `-093030`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcOffset(i32);
+
+impl UtcOffset {
+    pub fn parse(value: &str) -> Result<Self, InvalidUtcOffset> {
+        let invalid = || InvalidUtcOffset(value.to_owned());
+        let (sign, digits) = match value.split_at_checked(1) {
+            Some(("+", digits)) => (1, digits),
+            Some(("-", digits)) => (-1, digits),
+            _ => return Err(invalid()),
+        };
+        if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let part = |range: std::ops::Range<usize>| {
+            digits
+                .get(range)
+                .map_or(0, |part| part.parse::<i32>().unwrap())
+        };
+        let (hours, minutes, seconds) = (part(0..2), part(2..4), part(4..6));
+        if hours > 23 || minutes > 59 || seconds > 59 {
+            return Err(invalid());
+        }
+        let offset = hours * 3600 + minutes * 60 + seconds;
+        // RFC 5545: The value MUST NOT be "-0000"
+        if sign == -1 && offset == 0 {
+            return Err(invalid());
+        }
+        Ok(Self(sign * offset))
+    }
+
+    /// Creates an offset from seconds east of UTC, `None` if it's not within ±24 hours.
+    pub fn from_seconds(seconds: i32) -> Option<Self> {
+        (seconds.abs() < 24 * 3600).then_some(Self(seconds))
+    }
+
+    /// The offset in seconds east of UTC
+    pub fn seconds(&self) -> i32 {
+        self.0
+    }
+
+    pub fn as_fixed_offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.0).expect("validated to be less than 24 hours")
+    }
+}
+
+impl From<UtcOffset> for FixedOffset {
+    fn from(value: UtcOffset) -> Self {
+        value.as_fixed_offset()
+    }
+}
+
+impl Value for UtcOffset {
+    fn value_type(&self) -> Option<&'static str> {
+        Some("UTC-OFFSET")
+    }
+
+    fn value(&self) -> String {
+        let sign = if self.0 < 0 { '-' } else { '+' };
+        let offset = self.0.abs();
+        let (hours, minutes, seconds) = (offset / 3600, offset / 60 % 60, offset % 60);
+        if seconds == 0 {
+            format!("{sign}{hours:02}{minutes:02}")
+        } else {
+            format!("{sign}{hours:02}{minutes:02}{seconds:02}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UtcOffset;
+    use crate::types::Value;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("+0100", 3600)]
+    #[case("-0500", -5 * 3600)]
+    #[case("+0000", 0)]
+    #[case("-093030", -(9 * 3600 + 30 * 60 + 30))]
+    #[case("+0545", 5 * 3600 + 45 * 60)]
+    fn test_utc_offset_roundtrip(#[case] value: &str, #[case] seconds: i32) {
+        let offset = UtcOffset::parse(value).unwrap();
+        assert_eq!(offset.seconds(), seconds);
+        assert_eq!(offset.value(), value);
+    }
+
+    #[rstest]
+    #[case("0100")]
+    #[case("-0000")]
+    #[case("+2400")]
+    #[case("+0160")]
+    #[case("+010")]
+    #[case("+01:00")]
+    #[case("+01000a")]
+    fn test_invalid_utc_offset(#[case] value: &str) {
+        assert!(UtcOffset::parse(value).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     parser::{ParseProp, ParserError},
-    types::Value,
+    types::{Tz, Value},
 };
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
 use std::collections::HashMap;
 
 mod partial_date;
@@ -28,6 +29,22 @@ impl PartialDateTime {
         let time = PartialTime::parse(time)?;
         Ok(Self { date, time })
     }
+
+    /// The local date and time if both are fully specified
+    pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+        Some(
+            self.date
+                .to_naive_date()?
+                .and_time(self.time.to_naive_time()?),
+        )
+    }
+
+    /// The date and time if fully specified including a UTC offset
+    pub fn to_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.to_naive_datetime()?
+            .and_local_timezone(self.time.offset()?)
+            .single()
+    }
 }
 
 impl PartialDateAndOrTime {
@@ -50,12 +67,42 @@ impl PartialDateAndOrTime {
         };
         Ok(Self { date, time })
     }
+
+    /// Whether a date including its year is known
+    pub fn has_year(&self) -> bool {
+        self.date.as_ref().is_some_and(PartialDate::has_year)
+    }
+
+    /// The date if year, month and day are all specified
+    pub fn to_naive_date(&self) -> Option<NaiveDate> {
+        self.date.as_ref()?.to_naive_date()
+    }
+
+    /// The local date and time if both are fully specified
+    pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+        Some(
+            self.to_naive_date()?
+                .and_time(self.time.as_ref()?.to_naive_time()?),
+        )
+    }
+
+    /// The date and time if fully specified including a UTC offset
+    pub fn to_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        self.to_naive_datetime()?
+            .and_local_timezone(self.time.as_ref()?.offset()?)
+            .single()
+    }
+
+    /// The next anniversary of the date on or after `reference`, see [`PartialDate::next_anniversary`]
+    pub fn next_anniversary(&self, reference: NaiveDate) -> Option<NaiveDate> {
+        self.date.as_ref()?.next_anniversary(reference)
+    }
 }
 
 impl ParseProp for PartialDateAndOrTime {
     fn parse_prop(
         prop: &crate::parser::ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse(&prop.value)
@@ -88,7 +135,7 @@ impl Value for PartialDateTime {
 impl ParseProp for PartialDateTime {
     fn parse_prop(
         prop: &crate::parser::ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse(&prop.value)
@@ -98,6 +145,7 @@ impl ParseProp for PartialDateTime {
 #[cfg(test)]
 mod tests {
     use crate::types::{PartialDate, PartialDateAndOrTime, PartialDateTime, PartialTime, Value};
+    use chrono::NaiveDate;
     use rstest::rstest;
 
     #[rstest]
@@ -135,4 +183,31 @@ mod tests {
         assert_eq!(parsed, value);
         assert_eq!(roundtrip, value);
     }
+
+    #[test]
+    fn test_to_datetime() {
+        let value = PartialDateAndOrTime::parse("19961022T140000-0800").unwrap();
+        assert!(value.has_year());
+        assert_eq!(
+            value.to_datetime().unwrap().to_rfc3339(),
+            "1996-10-22T14:00:00-08:00"
+        );
+
+        let value = PartialDateAndOrTime::parse("19961022T1400").unwrap();
+        assert_eq!(
+            value.to_naive_datetime(),
+            NaiveDate::from_ymd_opt(1996, 10, 22)
+                .unwrap()
+                .and_hms_opt(14, 0, 0)
+        );
+        assert_eq!(value.to_datetime(), None);
+
+        let value = PartialDateAndOrTime::parse("--1022").unwrap();
+        assert!(!value.has_year());
+        assert_eq!(value.to_naive_date(), None);
+        assert_eq!(
+            value.next_anniversary(NaiveDate::from_ymd_opt(2024, 11, 1).unwrap()),
+            NaiveDate::from_ymd_opt(2025, 10, 22)
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     parser::{ParseProp, ParserError},
-    types::Value,
+    types::{Tz, Value},
 };
 use chrono::{Datelike, NaiveDate};
 use std::{collections::HashMap, sync::OnceLock};
@@ -98,6 +98,46 @@ impl PartialDate {
     pub const fn get_day(&self) -> Option<u32> {
         self.day
     }
+
+    /// Whether the year is known, i.e. it isn't a truncated date like `--0412`
+    #[inline]
+    pub const fn has_year(&self) -> bool {
+        self.year.is_some()
+    }
+
+    /// The date if year, month and day are all specified
+    pub fn to_naive_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year?, self.month?, self.day?)
+    }
+
+    /// The month and day of this date in the given year.
+    ///
+    /// February 29 falls on February 28 in non-leap years.
+    pub fn in_year(&self, year: i32) -> Option<NaiveDate> {
+        let (month, day) = (self.month?, self.day?);
+        NaiveDate::from_ymd_opt(year, month, day).or_else(|| {
+            (month == 2 && day == 29)
+                .then(|| NaiveDate::from_ymd_opt(year, 2, 28))
+                .flatten()
+        })
+    }
+
+    /// The next anniversary on or after `reference`, e.g. the next birthday.
+    ///
+    /// Works for year-less dates as long as month and day are known.
+    /// If the year is known, anniversaries before the original date are not returned.
+    pub fn next_anniversary(&self, reference: NaiveDate) -> Option<NaiveDate> {
+        let reference = match self.to_naive_date() {
+            Some(date) => reference.max(date),
+            None => reference,
+        };
+        let this_year = self.in_year(reference.year())?;
+        if this_year >= reference {
+            Some(this_year)
+        } else {
+            self.in_year(reference.year() + 1)
+        }
+    }
 }
 
 impl Value for PartialDate {
@@ -128,7 +168,7 @@ impl Value for PartialDate {
 impl ParseProp for PartialDate {
     fn parse_prop(
         prop: &crate::parser::ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse(&prop.value)
@@ -138,6 +178,7 @@ impl ParseProp for PartialDate {
 #[cfg(test)]
 mod tests {
     use crate::types::{PartialDate, Value};
+    use chrono::NaiveDate;
     use rstest::rstest;
 
     #[rstest]
@@ -165,4 +206,36 @@ mod tests {
     fn test_parse_date_invalid(#[case] input: &str) {
         assert!(PartialDate::parse(input).is_err());
     }
+
+    #[rstest]
+    #[case("19850412", "2024-01-01", Some("2024-04-12"))]
+    #[case("19850412", "2024-04-12", Some("2024-04-12"))]
+    #[case("19850412", "2024-04-13", Some("2025-04-12"))]
+    #[case("19850412", "1970-01-01", Some("1985-04-12"))]
+    #[case("--0412", "2024-05-01", Some("2025-04-12"))]
+    #[case("--0229", "2023-03-01", Some("2024-02-29"))]
+    #[case("--0229", "2024-03-01", Some("2025-02-28"))]
+    #[case("--04", "2024-01-01", None)]
+    #[case("1985", "2024-01-01", None)]
+    fn test_next_anniversary(
+        #[case] input: &str,
+        #[case] reference: NaiveDate,
+        #[case] expected: Option<&str>,
+    ) {
+        let date = PartialDate::parse(input).unwrap();
+        assert_eq!(
+            date.next_anniversary(reference),
+            expected.map(|date| date.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_to_naive_date() {
+        let date = PartialDate::parse("19850412").unwrap();
+        assert!(date.has_year());
+        assert_eq!(date.to_naive_date(), NaiveDate::from_ymd_opt(1985, 4, 12));
+        let date = PartialDate::parse("--0412").unwrap();
+        assert!(!date.has_year());
+        assert_eq!(date.to_naive_date(), None);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{
     parser::{ParseProp, ParserError},
-    types::Value,
+    types::{Tz, Value},
 };
+use chrono::{FixedOffset, NaiveTime};
 use std::{collections::HashMap, sync::OnceLock};
 
 static RE_TIME: OnceLock<[regex::Regex; 2]> = OnceLock::new();
@@ -117,6 +118,22 @@ impl PartialTime {
 
         Err(ParserError::InvalidPropertyValue(value.to_owned()))
     }
+
+    /// The time of day if the hour is known, missing minutes and seconds default to zero
+    pub fn to_naive_time(&self) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(
+            self.hour?.into(),
+            self.minute.unwrap_or_default().into(),
+            self.second.unwrap_or_default().into(),
+        )
+    }
+
+    /// The UTC offset if specified
+    pub fn offset(&self) -> Option<FixedOffset> {
+        let offset_hour = i32::from(self.offset_hour?);
+        let offset_minute = i32::from(self.offset_minute.unwrap_or_default());
+        FixedOffset::east_opt(offset_hour * 3600 + offset_minute * 60)
+    }
 }
 
 impl Value for PartialTime {
@@ -170,7 +187,7 @@ impl Value for PartialTime {
 impl ParseProp for PartialTime {
     fn parse_prop(
         prop: &crate::parser::ContentLine,
-        _timezones: Option<&HashMap<String, Option<chrono_tz::Tz>>>,
+        _timezones: Option<&HashMap<String, Option<Tz>>>,
         _default_type: &str,
     ) -> Result<Self, ParserError> {
         Self::parse(&prop.value)
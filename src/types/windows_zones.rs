@@ -0,0 +1,400 @@
+//! Mapping between Windows timezone names and IANA timezones from the CLDR (`windowsZones.xml`)
+use std::str::FromStr;
+
+/// Rows of `(windows name, territory, space separated IANA timezones)`.
+/// The territory `001` marks the default IANA timezone for a Windows timezone.
+static WINDOWS_ZONES: &[(&str, &str, &str)] = &[
+    ("Afghanistan Standard Time", "001", "Asia/Kabul"),
+    ("Alaskan Standard Time", "001", "America/Anchorage"),
+    (
+        "Alaskan Standard Time",
+        "US",
+        "America/Anchorage America/Juneau America/Metlakatla America/Nome America/Sitka America/Yakutat",
+    ),
+    ("Aleutian Standard Time", "001", "America/Adak"),
+    ("Altai Standard Time", "001", "Asia/Barnaul"),
+    ("Arab Standard Time", "001", "Asia/Riyadh"),
+    ("Arabian Standard Time", "001", "Asia/Dubai"),
+    ("Arabic Standard Time", "001", "Asia/Baghdad"),
+    ("Argentina Standard Time", "001", "America/Buenos_Aires"),
+    ("Astrakhan Standard Time", "001", "Europe/Astrakhan"),
+    ("Atlantic Standard Time", "001", "America/Halifax"),
+    ("AUS Central Standard Time", "001", "Australia/Darwin"),
+    ("Aus Central W. Standard Time", "001", "Australia/Eucla"),
+    ("AUS Eastern Standard Time", "001", "Australia/Sydney"),
+    (
+        "AUS Eastern Standard Time",
+        "AU",
+        "Australia/Sydney Australia/Melbourne",
+    ),
+    ("Azerbaijan Standard Time", "001", "Asia/Baku"),
+    ("Azores Standard Time", "001", "Atlantic/Azores"),
+    ("Bahia Standard Time", "001", "America/Bahia"),
+    ("Bangladesh Standard Time", "001", "Asia/Dhaka"),
+    ("Belarus Standard Time", "001", "Europe/Minsk"),
+    ("Bougainville Standard Time", "001", "Pacific/Bougainville"),
+    ("Canada Central Standard Time", "001", "America/Regina"),
+    ("Cape Verde Standard Time", "001", "Atlantic/Cape_Verde"),
+    ("Caucasus Standard Time", "001", "Asia/Yerevan"),
+    ("Cen. Australia Standard Time", "001", "Australia/Adelaide"),
+    ("Central America Standard Time", "001", "America/Guatemala"),
+    ("Central Asia Standard Time", "001", "Asia/Bishkek"),
+    ("Central Brazilian Standard Time", "001", "America/Cuiaba"),
+    ("Central Europe Standard Time", "001", "Europe/Budapest"),
+    ("Central Europe Standard Time", "AL", "Europe/Tirane"),
+    ("Central Europe Standard Time", "CZ", "Europe/Prague"),
+    ("Central Europe Standard Time", "HU", "Europe/Budapest"),
+    ("Central Europe Standard Time", "ME", "Europe/Podgorica"),
+    ("Central Europe Standard Time", "RS", "Europe/Belgrade"),
+    ("Central Europe Standard Time", "SI", "Europe/Ljubljana"),
+    ("Central Europe Standard Time", "SK", "Europe/Bratislava"),
+    ("Central European Standard Time", "001", "Europe/Warsaw"),
+    ("Central European Standard Time", "BA", "Europe/Sarajevo"),
+    ("Central European Standard Time", "HR", "Europe/Zagreb"),
+    ("Central European Standard Time", "MK", "Europe/Skopje"),
+    ("Central European Standard Time", "PL", "Europe/Warsaw"),
+    (
+        "Central Pacific Standard Time",
+        "001",
+        "Pacific/Guadalcanal",
+    ),
+    ("Central Standard Time", "001", "America/Chicago"),
+    (
+        "Central Standard Time",
+        "CA",
+        "America/Winnipeg America/Rainy_River America/Rankin_Inlet America/Resolute",
+    ),
+    ("Central Standard Time", "MX", "America/Matamoros"),
+    (
+        "Central Standard Time",
+        "US",
+        "America/Chicago America/Indiana/Knox America/Indiana/Tell_City America/Menominee America/North_Dakota/Beulah America/North_Dakota/Center America/North_Dakota/New_Salem",
+    ),
+    ("Central Standard Time", "ZZ", "CST6CDT"),
+    (
+        "Central Standard Time (Mexico)",
+        "001",
+        "America/Mexico_City",
+    ),
+    ("Chatham Islands Standard Time", "001", "Pacific/Chatham"),
+    ("China Standard Time", "001", "Asia/Shanghai"),
+    ("China Standard Time", "CN", "Asia/Shanghai"),
+    ("China Standard Time", "HK", "Asia/Hong_Kong"),
+    ("China Standard Time", "MO", "Asia/Macau"),
+    ("Cuba Standard Time", "001", "America/Havana"),
+    ("Dateline Standard Time", "001", "Etc/GMT+12"),
+    ("E. Africa Standard Time", "001", "Africa/Nairobi"),
+    ("E. Australia Standard Time", "001", "Australia/Brisbane"),
+    (
+        "E. Australia Standard Time",
+        "AU",
+        "Australia/Brisbane Australia/Lindeman",
+    ),
+    ("E. Europe Standard Time", "001", "Europe/Chisinau"),
+    ("E. South America Standard Time", "001", "America/Sao_Paulo"),
+    ("Easter Island Standard Time", "001", "Pacific/Easter"),
+    ("Eastern Standard Time", "001", "America/New_York"),
+    ("Eastern Standard Time", "BS", "America/Nassau"),
+    (
+        "Eastern Standard Time",
+        "CA",
+        "America/Toronto America/Iqaluit America/Montreal America/Nipigon America/Pangnirtung America/Thunder_Bay",
+    ),
+    (
+        "Eastern Standard Time",
+        "US",
+        "America/New_York America/Detroit America/Indiana/Petersburg America/Indiana/Vincennes America/Indiana/Winamac America/Kentucky/Monticello America/Louisville",
+    ),
+    ("Eastern Standard Time", "ZZ", "EST5EDT"),
+    ("Eastern Standard Time (Mexico)", "001", "America/Cancun"),
+    ("Egypt Standard Time", "001", "Africa/Cairo"),
+    ("Ekaterinburg Standard Time", "001", "Asia/Yekaterinburg"),
+    ("Fiji Standard Time", "001", "Pacific/Fiji"),
+    ("FLE Standard Time", "001", "Europe/Kiev"),
+    ("FLE Standard Time", "AX", "Europe/Mariehamn"),
+    ("FLE Standard Time", "BG", "Europe/Sofia"),
+    ("FLE Standard Time", "EE", "Europe/Tallinn"),
+    ("FLE Standard Time", "FI", "Europe/Helsinki"),
+    ("FLE Standard Time", "LT", "Europe/Vilnius"),
+    ("FLE Standard Time", "LV", "Europe/Riga"),
+    (
+        "FLE Standard Time",
+        "UA",
+        "Europe/Kiev Europe/Uzhgorod Europe/Zaporozhye",
+    ),
+    ("Georgian Standard Time", "001", "Asia/Tbilisi"),
+    ("GMT Standard Time", "001", "Europe/London"),
+    ("GMT Standard Time", "ES", "Atlantic/Canary"),
+    ("GMT Standard Time", "FO", "Atlantic/Faeroe"),
+    ("GMT Standard Time", "GB", "Europe/London"),
+    ("GMT Standard Time", "GG", "Europe/Guernsey"),
+    ("GMT Standard Time", "IE", "Europe/Dublin"),
+    ("GMT Standard Time", "IM", "Europe/Isle_of_Man"),
+    ("GMT Standard Time", "JE", "Europe/Jersey"),
+    ("GMT Standard Time", "PT", "Europe/Lisbon Atlantic/Madeira"),
+    ("Greenland Standard Time", "001", "America/Godthab"),
+    ("Greenwich Standard Time", "001", "Atlantic/Reykjavik"),
+    ("GTB Standard Time", "001", "Europe/Bucharest"),
+    ("GTB Standard Time", "CY", "Asia/Nicosia Asia/Famagusta"),
+    ("GTB Standard Time", "GR", "Europe/Athens"),
+    ("GTB Standard Time", "RO", "Europe/Bucharest"),
+    ("Haiti Standard Time", "001", "America/Port-au-Prince"),
+    ("Hawaiian Standard Time", "001", "Pacific/Honolulu"),
+    ("Hawaiian Standard Time", "US", "Pacific/Honolulu"),
+    ("Hawaiian Standard Time", "ZZ", "Etc/GMT+10"),
+    ("India Standard Time", "001", "Asia/Calcutta"),
+    ("India Standard Time", "IN", "Asia/Calcutta"),
+    ("Iran Standard Time", "001", "Asia/Tehran"),
+    ("Israel Standard Time", "001", "Asia/Jerusalem"),
+    ("Jordan Standard Time", "001", "Asia/Amman"),
+    ("Kaliningrad Standard Time", "001", "Europe/Kaliningrad"),
+    ("Korea Standard Time", "001", "Asia/Seoul"),
+    ("Libya Standard Time", "001", "Africa/Tripoli"),
+    ("Line Islands Standard Time", "001", "Pacific/Kiritimati"),
+    ("Lord Howe Standard Time", "001", "Australia/Lord_Howe"),
+    ("Magadan Standard Time", "001", "Asia/Magadan"),
+    ("Magallanes Standard Time", "001", "America/Punta_Arenas"),
+    ("Marquesas Standard Time", "001", "Pacific/Marquesas"),
+    ("Mauritius Standard Time", "001", "Indian/Mauritius"),
+    ("Middle East Standard Time", "001", "Asia/Beirut"),
+    ("Montevideo Standard Time", "001", "America/Montevideo"),
+    ("Morocco Standard Time", "001", "Africa/Casablanca"),
+    ("Mountain Standard Time", "001", "America/Denver"),
+    (
+        "Mountain Standard Time",
+        "CA",
+        "America/Edmonton America/Cambridge_Bay America/Inuvik America/Yellowknife",
+    ),
+    ("Mountain Standard Time", "MX", "America/Ojinaga"),
+    (
+        "Mountain Standard Time",
+        "US",
+        "America/Denver America/Boise",
+    ),
+    ("Mountain Standard Time", "ZZ", "MST7MDT"),
+    ("Mountain Standard Time (Mexico)", "001", "America/Mazatlan"),
+    ("Myanmar Standard Time", "001", "Asia/Rangoon"),
+    ("N. Central Asia Standard Time", "001", "Asia/Novosibirsk"),
+    ("Namibia Standard Time", "001", "Africa/Windhoek"),
+    ("Nepal Standard Time", "001", "Asia/Katmandu"),
+    ("New Zealand Standard Time", "001", "Pacific/Auckland"),
+    ("Newfoundland Standard Time", "001", "America/St_Johns"),
+    ("Norfolk Standard Time", "001", "Pacific/Norfolk"),
+    ("North Asia East Standard Time", "001", "Asia/Irkutsk"),
+    ("North Asia Standard Time", "001", "Asia/Krasnoyarsk"),
+    ("North Korea Standard Time", "001", "Asia/Pyongyang"),
+    ("Omsk Standard Time", "001", "Asia/Omsk"),
+    ("Pacific SA Standard Time", "001", "America/Santiago"),
+    ("Pacific Standard Time", "001", "America/Los_Angeles"),
+    ("Pacific Standard Time", "CA", "America/Vancouver"),
+    ("Pacific Standard Time", "US", "America/Los_Angeles"),
+    ("Pacific Standard Time", "ZZ", "PST8PDT"),
+    ("Pacific Standard Time (Mexico)", "001", "America/Tijuana"),
+    ("Pakistan Standard Time", "001", "Asia/Karachi"),
+    ("Paraguay Standard Time", "001", "America/Asuncion"),
+    ("Qyzylorda Standard Time", "001", "Asia/Qyzylorda"),
+    ("Romance Standard Time", "001", "Europe/Paris"),
+    ("Romance Standard Time", "BE", "Europe/Brussels"),
+    ("Romance Standard Time", "DK", "Europe/Copenhagen"),
+    ("Romance Standard Time", "ES", "Europe/Madrid Africa/Ceuta"),
+    ("Romance Standard Time", "FR", "Europe/Paris"),
+    ("Russia Time Zone 10", "001", "Asia/Srednekolymsk"),
+    ("Russia Time Zone 11", "001", "Asia/Kamchatka"),
+    ("Russia Time Zone 3", "001", "Europe/Samara"),
+    ("Russian Standard Time", "001", "Europe/Moscow"),
+    ("Russian Standard Time", "RU", "Europe/Moscow Europe/Kirov"),
+    ("Russian Standard Time", "UA", "Europe/Simferopol"),
+    ("SA Eastern Standard Time", "001", "America/Cayenne"),
+    ("SA Pacific Standard Time", "001", "America/Bogota"),
+    ("SA Western Standard Time", "001", "America/La_Paz"),
+    ("Saint Pierre Standard Time", "001", "America/Miquelon"),
+    ("Sakhalin Standard Time", "001", "Asia/Sakhalin"),
+    ("Samoa Standard Time", "001", "Pacific/Apia"),
+    ("Sao Tome Standard Time", "001", "Africa/Sao_Tome"),
+    ("Saratov Standard Time", "001", "Europe/Saratov"),
+    ("SE Asia Standard Time", "001", "Asia/Bangkok"),
+    ("Singapore Standard Time", "001", "Asia/Singapore"),
+    ("South Africa Standard Time", "001", "Africa/Johannesburg"),
+    ("South Sudan Standard Time", "001", "Africa/Juba"),
+    ("Sri Lanka Standard Time", "001", "Asia/Colombo"),
+    ("Sudan Standard Time", "001", "Africa/Khartoum"),
+    ("Syria Standard Time", "001", "Asia/Damascus"),
+    ("Taipei Standard Time", "001", "Asia/Taipei"),
+    ("Tasmania Standard Time", "001", "Australia/Hobart"),
+    ("Tocantins Standard Time", "001", "America/Araguaina"),
+    ("Tokyo Standard Time", "001", "Asia/Tokyo"),
+    ("Tokyo Standard Time", "ID", "Asia/Jayapura"),
+    ("Tokyo Standard Time", "JP", "Asia/Tokyo"),
+    ("Tokyo Standard Time", "PW", "Pacific/Palau"),
+    ("Tokyo Standard Time", "TL", "Asia/Dili"),
+    ("Tokyo Standard Time", "ZZ", "Etc/GMT-9"),
+    ("Tomsk Standard Time", "001", "Asia/Tomsk"),
+    ("Tonga Standard Time", "001", "Pacific/Tongatapu"),
+    ("Transbaikal Standard Time", "001", "Asia/Chita"),
+    ("Turkey Standard Time", "001", "Europe/Istanbul"),
+    (
+        "Turks And Caicos Standard Time",
+        "001",
+        "America/Grand_Turk",
+    ),
+    ("Ulaanbaatar Standard Time", "001", "Asia/Ulaanbaatar"),
+    ("US Eastern Standard Time", "001", "America/Indianapolis"),
+    ("US Mountain Standard Time", "001", "America/Phoenix"),
+    (
+        "US Mountain Standard Time",
+        "CA",
+        "America/Creston America/Dawson_Creek America/Fort_Nelson",
+    ),
+    ("US Mountain Standard Time", "MX", "America/Hermosillo"),
+    ("US Mountain Standard Time", "US", "America/Phoenix"),
+    ("US Mountain Standard Time", "ZZ", "Etc/GMT+7"),
+    ("UTC", "001", "Etc/UTC"),
+    ("UTC", "ZZ", "Etc/UTC Etc/GMT"),
+    ("UTC+12", "001", "Etc/GMT-12"),
+    ("UTC+13", "001", "Etc/GMT-13"),
+    ("UTC-02", "001", "Etc/GMT+2"),
+    ("UTC-08", "001", "Etc/GMT+8"),
+    ("UTC-09", "001", "Etc/GMT+9"),
+    ("UTC-11", "001", "Etc/GMT+11"),
+    ("Venezuela Standard Time", "001", "America/Caracas"),
+    ("Vladivostok Standard Time", "001", "Asia/Vladivostok"),
+    ("Volgograd Standard Time", "001", "Europe/Volgograd"),
+    ("W. Australia Standard Time", "001", "Australia/Perth"),
+    ("W. Central Africa Standard Time", "001", "Africa/Lagos"),
+    ("W. Europe Standard Time", "001", "Europe/Berlin"),
+    ("W. Europe Standard Time", "AD", "Europe/Andorra"),
+    ("W. Europe Standard Time", "AT", "Europe/Vienna"),
+    ("W. Europe Standard Time", "CH", "Europe/Zurich"),
+    (
+        "W. Europe Standard Time",
+        "DE",
+        "Europe/Berlin Europe/Busingen",
+    ),
+    ("W. Europe Standard Time", "GI", "Europe/Gibraltar"),
+    ("W. Europe Standard Time", "IT", "Europe/Rome"),
+    ("W. Europe Standard Time", "LI", "Europe/Vaduz"),
+    ("W. Europe Standard Time", "LU", "Europe/Luxembourg"),
+    ("W. Europe Standard Time", "MC", "Europe/Monaco"),
+    ("W. Europe Standard Time", "MT", "Europe/Malta"),
+    ("W. Europe Standard Time", "NL", "Europe/Amsterdam"),
+    ("W. Europe Standard Time", "NO", "Europe/Oslo"),
+    ("W. Europe Standard Time", "SE", "Europe/Stockholm"),
+    ("W. Europe Standard Time", "SJ", "Arctic/Longyearbyen"),
+    ("W. Europe Standard Time", "SM", "Europe/San_Marino"),
+    ("W. Europe Standard Time", "VA", "Europe/Vatican"),
+    ("W. Mongolia Standard Time", "001", "Asia/Hovd"),
+    ("West Asia Standard Time", "001", "Asia/Tashkent"),
+    ("West Bank Standard Time", "001", "Asia/Hebron"),
+    ("West Pacific Standard Time", "001", "Pacific/Port_Moresby"),
+    ("Yakutsk Standard Time", "001", "Asia/Yakutsk"),
+    ("Yukon Standard Time", "001", "America/Whitehorse"),
+];
+
+/// Timezones renamed in the IANA database that CLDR still lists by their old name,
+/// as `(CLDR name, IANA name)`
+static RENAMED: &[(&str, &str)] = &[
+    ("America/Buenos_Aires", "America/Argentina/Buenos_Aires"),
+    ("America/Godthab", "America/Nuuk"),
+    ("America/Indianapolis", "America/Indiana/Indianapolis"),
+    ("America/Louisville", "America/Kentucky/Louisville"),
+    ("Asia/Calcutta", "Asia/Kolkata"),
+    ("Asia/Katmandu", "Asia/Kathmandu"),
+    ("Asia/Rangoon", "Asia/Yangon"),
+    ("Atlantic/Faeroe", "Atlantic/Faroe"),
+    ("Europe/Kiev", "Europe/Kyiv"),
+];
+
+/// The current IANA name of a timezone listed by CLDR
+fn canonical(name: &str) -> &str {
+    RENAMED
+        .iter()
+        .find(|(cldr, _)| *cldr == name)
+        .map_or(name, |(_, iana)| iana)
+}
+
+fn iana_names(names: &str) -> impl Iterator<Item = chrono_tz::Tz> + '_ {
+    names
+        .split(' ')
+        .filter_map(|name| chrono_tz::Tz::from_str(canonical(name)).ok())
+}
+
+/// Finds the IANA timezone for a Windows timezone name like `W. Europe Standard Time`.
+/// If `territory` (an ISO 3166 region code) is given its specific mapping is preferred
+/// over the default one.
+pub fn windows_to_iana(windows: &str, territory: Option<&str>) -> Option<chrono_tz::Tz> {
+    let mut rows = WINDOWS_ZONES.iter().filter(|(name, ..)| *name == windows);
+    let find = |territory: &str| {
+        rows.clone()
+            .find(|(_, row_territory, _)| *row_territory == territory)
+            .and_then(|(.., names)| iana_names(names).next())
+    };
+    territory
+        .and_then(find)
+        .or_else(|| find("001"))
+        .or_else(|| rows.find_map(|(.., names)| iana_names(names).next()))
+}
+
+/// Finds the Windows timezone name for an IANA timezone, e.g. for Exchange interop.
+/// Both the current and the old name of renamed timezones are found.
+pub fn iana_to_windows(tz: chrono_tz::Tz) -> Option<&'static str> {
+    let tz = canonical(tz.name());
+    WINDOWS_ZONES
+        .iter()
+        .find(|(.., names)| names.split(' ').any(|name| canonical(name) == tz))
+        .map(|(windows, ..)| *windows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RENAMED, WINDOWS_ZONES, iana_to_windows, windows_to_iana};
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_data_is_valid() {
+        for (windows, _, names) in WINDOWS_ZONES {
+            for name in names.split(' ') {
+                assert!(
+                    chrono_tz::Tz::from_str(name).is_ok(),
+                    "{windows}: {name} is unknown"
+                );
+            }
+        }
+        for (cldr, iana) in RENAMED {
+            assert!(chrono_tz::Tz::from_str(cldr).is_ok(), "{cldr} is unknown");
+            assert!(chrono_tz::Tz::from_str(iana).is_ok(), "{iana} is unknown");
+        }
+    }
+
+    #[rstest]
+    #[case("W. Europe Standard Time", None, Some(chrono_tz::Europe::Berlin))]
+    #[case("W. Europe Standard Time", Some("AT"), Some(chrono_tz::Europe::Vienna))]
+    #[case("W. Europe Standard Time", Some("US"), Some(chrono_tz::Europe::Berlin))]
+    #[case("Eastern Standard Time", Some("CA"), Some(chrono_tz::America::Toronto))]
+    #[case("India Standard Time", None, Some(chrono_tz::Asia::Kolkata))]
+    #[case("Mars Standard Time", None, None)]
+    fn test_windows_to_iana(
+        #[case] windows: &str,
+        #[case] territory: Option<&str>,
+        #[case] expected: Option<chrono_tz::Tz>,
+    ) {
+        assert_eq!(windows_to_iana(windows, territory), expected);
+    }
+
+    #[rstest]
+    #[case(chrono_tz::Europe::Berlin, Some("W. Europe Standard Time"))]
+    #[case(chrono_tz::Europe::Vienna, Some("W. Europe Standard Time"))]
+    #[case(chrono_tz::Atlantic::Madeira, Some("GMT Standard Time"))]
+    #[case(chrono_tz::America::Detroit, Some("Eastern Standard Time"))]
+    #[case(chrono_tz::Asia::Kolkata, Some("India Standard Time"))]
+    #[case(chrono_tz::Asia::Calcutta, Some("India Standard Time"))]
+    #[case(chrono_tz::Europe::Kyiv, Some("FLE Standard Time"))]
+    #[case(chrono_tz::Asia::Kathmandu, Some("Nepal Standard Time"))]
+    #[case(
+        chrono_tz::America::Argentina::Buenos_Aires,
+        Some("Argentina Standard Time")
+    )]
+    fn test_iana_to_windows(#[case] tz: chrono_tz::Tz, #[case] expected: Option<&str>) {
+        assert_eq!(iana_to_windows(tz), expected);
+    }
+}
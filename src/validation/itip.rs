@@ -0,0 +1,216 @@
+//! Rules for iTIP messages from RFC 5546
+use super::{Node, Severity, ValidationReport};
+
+/// How often a property must occur in the components of an iTIP message
+#[derive(Clone, Copy)]
+enum Occurrence {
+    /// Exactly once
+    One,
+    /// At least once
+    OneOrMore,
+    /// Not at all
+    Zero,
+}
+
+use Occurrence::{One, OneOrMore, Zero};
+
+const METHODS: &[&str] = &[
+    "PUBLISH",
+    "REQUEST",
+    "REPLY",
+    "ADD",
+    "CANCEL",
+    "REFRESH",
+    "COUNTER",
+    "DECLINECOUNTER",
+];
+
+/// Components that may be sent with a method
+fn allowed_components(method: &str) -> &'static [&'static str] {
+    match method {
+        "PUBLISH" => &["VEVENT", "VTODO", "VJOURNAL", "VFREEBUSY"],
+        "REQUEST" | "REPLY" => &["VEVENT", "VTODO", "VFREEBUSY"],
+        "ADD" | "CANCEL" => &["VEVENT", "VTODO", "VJOURNAL"],
+        _ => &["VEVENT", "VTODO"],
+    }
+}
+
+/// Properties whose occurrence is restricted by a method
+fn restricted_properties(method: &str, component: &str) -> &'static [(&'static str, Occurrence)] {
+    match (method, component) {
+        ("PUBLISH", "VEVENT") => &[
+            ("DTSTART", One),
+            ("ORGANIZER", One),
+            ("SUMMARY", One),
+            ("ATTENDEE", Zero),
+        ],
+        ("PUBLISH", _) => &[("ORGANIZER", One), ("ATTENDEE", Zero)],
+        ("REQUEST", "VEVENT") => &[
+            ("DTSTART", One),
+            ("ORGANIZER", One),
+            ("SUMMARY", One),
+            ("ATTENDEE", OneOrMore),
+        ],
+        ("REQUEST", _) => &[("ORGANIZER", One), ("ATTENDEE", OneOrMore)],
+        ("REPLY", _) | ("REFRESH", _) => &[("ORGANIZER", One), ("ATTENDEE", One)],
+        ("ADD", "VEVENT") => &[
+            ("DTSTART", One),
+            ("ORGANIZER", One),
+            ("SUMMARY", One),
+            ("SEQUENCE", One),
+        ],
+        ("ADD", _) => &[("ORGANIZER", One), ("SEQUENCE", One)],
+        ("CANCEL", _) => &[("ORGANIZER", One), ("SEQUENCE", One)],
+        ("COUNTER", "VEVENT") => &[("DTSTART", One), ("ORGANIZER", One), ("SUMMARY", One)],
+        ("COUNTER", _) => &[("ORGANIZER", One)],
+        ("DECLINECOUNTER", _) => &[("ORGANIZER", One), ("ATTENDEE", OneOrMore)],
+        _ => &[],
+    }
+}
+
+/// Values STATUS may have with a method, [`None`] if it isn't restricted
+fn allowed_status(method: &str, component: &str) -> Option<&'static [&'static str]> {
+    match (method, component) {
+        ("CANCEL", _) => Some(&["CANCELLED"]),
+        ("REQUEST", "VEVENT") => Some(&["TENTATIVE", "CONFIRMED"]),
+        ("REQUEST", "VTODO") => Some(&["NEEDS-ACTION", "IN-PROCESS", "COMPLETED"]),
+        _ => None,
+    }
+}
+
+fn occurrences(method: &str, node: &Node, report: &mut ValidationReport) {
+    for (name, occurrence) in restricted_properties(method, node.name) {
+        let count = node.get_named_properties(name).count();
+        let message = match occurrence {
+            One if count != 1 => "exactly once",
+            OneOrMore if count == 0 => "at least once",
+            Zero if count != 0 => "not at all",
+            _ => continue,
+        };
+        report.push(
+            Severity::Error,
+            "itip-property-count",
+            &node.path,
+            Some(name),
+            format!("{name} must occur {message} in {method} messages"),
+        );
+    }
+}
+
+fn status(method: &str, node: &Node, report: &mut ValidationReport) {
+    let (Some(allowed), Some(status)) = (
+        allowed_status(method, node.name),
+        node.get_property("STATUS"),
+    ) else {
+        return;
+    };
+    if !allowed.contains(&status.value.as_str()) {
+        report.push(
+            Severity::Error,
+            "itip-status",
+            &node.path,
+            Some("STATUS"),
+            format!(
+                "STATUS {} is not allowed in {method} messages, expected one of {}",
+                status.value,
+                allowed.join(", ")
+            ),
+        );
+    }
+}
+
+fn sequence(node: &Node, report: &mut ValidationReport) {
+    for prop in node.get_named_properties("SEQUENCE") {
+        if prop.value.parse::<u32>().is_err() {
+            report.push(
+                Severity::Error,
+                "itip-sequence",
+                &node.path,
+                Some("SEQUENCE"),
+                format!(
+                    "SEQUENCE must be a non-negative integer, got {}",
+                    prop.value
+                ),
+            );
+        }
+    }
+}
+
+fn reply_attendee(method: &str, node: &Node, report: &mut ValidationReport) {
+    if method != "REPLY" {
+        return;
+    }
+    for attendee in node.get_named_properties("ATTENDEE") {
+        if attendee.params.get_param("PARTSTAT").is_none() {
+            report.push(
+                Severity::Warning,
+                "itip-partstat",
+                &node.path,
+                Some("ATTENDEE"),
+                "The replying ATTENDEE should state its PARTSTAT",
+            );
+        }
+    }
+}
+
+/// Runs the rules for the METHOD of the calendar, does nothing if there's none
+pub(super) fn check_method(root: &Node, report: &mut ValidationReport) {
+    let Some(method) = root.get_property("METHOD") else {
+        return;
+    };
+    let method = method.value.as_str();
+    if !METHODS.contains(&method) {
+        report.push(
+            Severity::Error,
+            "itip-method",
+            &root.path,
+            Some("METHOD"),
+            format!("Unknown METHOD {method}"),
+        );
+        return;
+    }
+
+    let allowed = allowed_components(method);
+    let mut uid = None;
+    for node in &root.children {
+        if !matches!(node.name, "VEVENT" | "VTODO" | "VJOURNAL" | "VFREEBUSY") {
+            continue;
+        }
+        if !allowed.contains(&node.name) {
+            report.push(
+                Severity::Error,
+                "itip-component",
+                &node.path,
+                None,
+                format!("{} must not be sent with METHOD {method}", node.name),
+            );
+            continue;
+        }
+        // All components of a scheduling message refer to the same calendar object
+        if method != "PUBLISH"
+            && let Some(prop) = node.get_property("UID")
+        {
+            match uid {
+                None => uid = Some(prop.value.as_str()),
+                Some(uid) if uid != prop.value => report.push(
+                    Severity::Error,
+                    "itip-uid",
+                    &node.path,
+                    Some("UID"),
+                    format!(
+                        "UID {} doesn't match the UID {uid} of the message",
+                        prop.value
+                    ),
+                ),
+                Some(_) => {}
+            }
+        }
+        if node.name == "VFREEBUSY" {
+            continue;
+        }
+        occurrences(method, node, report);
+        status(method, node, report);
+        sequence(node, report);
+        reply_attendee(method, node, report);
+    }
+}
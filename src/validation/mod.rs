@@ -0,0 +1,935 @@
+//! Validation of calendars beyond what is needed to parse them.
+//!
+//! Parsing only rejects data we can't make sense of. The validation report lists everything
+//! else that's wrong or questionable about a calendar, so it can be shown to users
+//! without rejecting their data.
+//! Additional checks can be registered as [`ValidationRule`]s on a [`Validator`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! let input = std::fs::read_to_string("./tests/resources/ical_example_1.ics").unwrap();
+//! let report = caldata::validation::validate_slice(input.as_bytes());
+//! for finding in report.iter() {
+//!     println!("{finding}");
+//! }
+//! ```
+use crate::{
+    ContentLineParser, ParserError,
+    component::{Component, ComponentMut, IcalCalendar, IcalCalendarBuilder},
+    parser::{ContentLine, ParserOptions},
+    repair::Vendor,
+};
+use std::fmt;
+
+mod itip;
+mod output;
+pub use output::{WireViolation, WireViolationKind, check_output};
+mod profiles;
+mod rules;
+pub(crate) use rules::required_properties;
+mod text;
+pub use text::sanitize;
+
+/// Maximum length of a content line in octets, excluding the line break
+const MAX_LINE_LENGTH: usize = 75;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Valid but noteworthy, e.g. floating times
+    Info,
+    /// Discouraged by the specification or known to cause trouble with clients
+    Warning,
+    /// Violates the specification
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Identifier of the rule that produced this finding, e.g. `required-property`
+    pub rule: &'static str,
+    /// Path to the component, e.g. `VCALENDAR/VEVENT[0]/VALARM[1]`,
+    /// or the line for findings about the raw input
+    pub path: String,
+    /// Name of the property the finding refers to
+    pub property: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] {}", self.severity, self.rule, self.path)?;
+        if let Some(property) = &self.property {
+            write!(f, " {property}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    pub fn push(
+        &mut self,
+        severity: Severity,
+        rule: &'static str,
+        path: impl Into<String>,
+        property: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.findings.push(Finding {
+            severity,
+            rule,
+            path: path.into(),
+            property: property.map(ToOwned::to_owned),
+            message: message.into(),
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter()
+    }
+
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+        self.iter()
+            .filter(move |finding| finding.severity == severity)
+    }
+
+    /// The highest severity of all findings
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.iter().map(|finding| finding.severity).max()
+    }
+
+    /// Whether there are no findings with [`Severity::Error`]
+    pub fn is_valid(&self) -> bool {
+        self.max_severity() < Some(Severity::Error)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a ValidationReport {
+    type Item = &'a Finding;
+    type IntoIter = std::slice::Iter<'a, Finding>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.findings.iter()
+    }
+}
+
+/// A component to run the rules on, independent of whether it's verified
+#[derive(Debug)]
+pub struct Node<'a> {
+    /// Path to the component, e.g. `VCALENDAR/VEVENT[0]/VALARM[1]`
+    pub path: String,
+    pub name: &'static str,
+    pub properties: &'a [ContentLine],
+    pub children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    fn new<C: Component>(component: &'a C, parent: &str, index: usize) -> Self {
+        let name = component.get_comp_name();
+        Self {
+            path: format!("{parent}/{name}[{index}]"),
+            name,
+            properties: component.get_properties(),
+            children: vec![],
+        }
+    }
+
+    fn with_children<C: Component>(mut self, children: &'a [C]) -> Self {
+        self.children = children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| Node::new(child, &self.path, index))
+            .collect();
+        self
+    }
+
+    pub fn get_property(&self, name: &str) -> Option<&'a ContentLine> {
+        self.properties.iter().find(|prop| prop.name == name)
+    }
+
+    pub fn get_named_properties<'s>(
+        &'s self,
+        name: &'s str,
+    ) -> impl Iterator<Item = &'a ContentLine> + 's {
+        self.properties.iter().filter(move |prop| prop.name == name)
+    }
+
+    /// This node and all of its descendants
+    pub fn walk(&self) -> Box<dyn Iterator<Item = &Node<'a>> + '_> {
+        Box::new(std::iter::once(self).chain(self.children.iter().flat_map(Node::walk)))
+    }
+}
+
+fn timezone_nodes<'a, const VERIFIED: bool, A, E, F, J, T>(
+    calendar: &'a IcalCalendar<VERIFIED, A, E, F, J, T>,
+    root: &str,
+) -> impl Iterator<Item = Node<'a>> {
+    calendar
+        .vtimezones
+        .values()
+        .enumerate()
+        .map(move |(index, timezone)| {
+            Node::new(timezone, root, index).with_children(&timezone.transitions)
+        })
+}
+
+impl<'a> Node<'a> {
+    fn from_calendar(calendar: &'a IcalCalendar) -> Self {
+        let root = "VCALENDAR";
+        let mut children: Vec<_> = timezone_nodes(calendar, root).collect();
+        children.extend(
+            (calendar.events.iter().enumerate())
+                .map(|(index, event)| Node::new(event, root, index).with_children(&event.alarms)),
+        );
+        children.extend(
+            (calendar.todos.iter().enumerate())
+                .map(|(index, todo)| Node::new(todo, root, index).with_children(&todo.alarms)),
+        );
+        children.extend(Self::leaves(&calendar.journals, root));
+        children.extend(Self::leaves(&calendar.free_busys, root));
+        children.extend(Self::leaves(&calendar.alarms, root));
+        Self {
+            path: root.to_owned(),
+            name: root,
+            properties: &calendar.properties,
+            children,
+        }
+    }
+
+    fn from_builder(calendar: &'a IcalCalendarBuilder) -> Self {
+        let root = "VCALENDAR";
+        let mut children: Vec<_> = timezone_nodes(calendar, root).collect();
+        children.extend(
+            (calendar.events.iter().enumerate())
+                .map(|(index, event)| Node::new(event, root, index).with_children(&event.alarms)),
+        );
+        children.extend(
+            (calendar.todos.iter().enumerate())
+                .map(|(index, todo)| Node::new(todo, root, index).with_children(&todo.alarms)),
+        );
+        children.extend(Self::leaves(&calendar.journals, root));
+        children.extend(Self::leaves(&calendar.free_busys, root));
+        children.extend(Self::leaves(&calendar.alarms, root));
+        Self {
+            path: root.to_owned(),
+            name: root,
+            properties: &calendar.properties,
+            children,
+        }
+    }
+
+    fn leaves<C: Component>(components: &'a [C], root: &str) -> impl Iterator<Item = Node<'a>> {
+        components
+            .iter()
+            .enumerate()
+            .map(move |(index, component)| Node::new(component, root, index))
+    }
+}
+
+/// A check that runs alongside the built-in rules, e.g. for organization-specific conventions
+///
+/// # Examples
+///
+/// ```rust
+/// use caldata::validation::{Node, Severity, ValidationReport, Validator};
+///
+/// let validator = Validator::default().with_rule(|node: &Node, report: &mut ValidationReport| {
+///     if node.name == "VEVENT" && node.get_property("CATEGORIES").is_none() {
+///         report.push(
+///             Severity::Error,
+///             "mandatory-categories",
+///             &node.path,
+///             Some("CATEGORIES"),
+///             "Events must be categorized",
+///         );
+///     }
+/// });
+/// let input = std::fs::read_to_string("./tests/resources/ical_example_1.ics").unwrap();
+/// let report = validator.validate_slice(input.as_bytes());
+/// assert!(report.iter().any(|finding| finding.rule == "mandatory-categories"));
+/// ```
+pub trait ValidationRule: Send + Sync {
+    /// Checks a single component, called for every component of the calendar
+    fn check(&self, node: &Node, report: &mut ValidationReport);
+
+    /// Checks the calendar as a whole, called once with the VCALENDAR component
+    fn check_calendar(&self, _root: &Node, _report: &mut ValidationReport) {}
+}
+
+impl<F: Fn(&Node, &mut ValidationReport) + Send + Sync> ValidationRule for F {
+    fn check(&self, node: &Node, report: &mut ValidationReport) {
+        self(node, report)
+    }
+}
+
+/// Runs the built-in rules and the registered custom rules
+#[derive(Default)]
+pub struct Validator {
+    rules: Vec<Box<dyn ValidationRule>>,
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validator")
+            .field("rules", &self.rules.len())
+            .finish()
+    }
+}
+
+impl Validator {
+    pub fn with_rule(mut self, rule: impl ValidationRule + 'static) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    pub fn add_rule(&mut self, rule: impl ValidationRule + 'static) {
+        self.rules.push(Box::new(rule));
+    }
+
+    fn run_rules(&self, root: &Node) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        rules::check_calendar(root, &mut report);
+        itip::check_method(root, &mut report);
+        for rule in &self.rules {
+            rule.check_calendar(root, &mut report);
+        }
+        for node in root.walk() {
+            rules::check(node, &mut report);
+            text::check(node, &mut report);
+            for rule in &self.rules {
+                rule.check(node, &mut report);
+            }
+        }
+        report
+    }
+
+    /// Validates a parsed calendar
+    pub fn validate(&self, calendar: &IcalCalendar) -> ValidationReport {
+        self.run_rules(&Node::from_calendar(calendar))
+    }
+
+    /// Validates a calendar that hasn't been verified (yet)
+    pub fn validate_builder(&self, calendar: &IcalCalendarBuilder) -> ValidationReport {
+        self.run_rules(&Node::from_builder(calendar))
+    }
+
+    /// Validates the raw input of a single calendar, see [`validate_slice`]
+    pub fn validate_slice(&self, input: &[u8]) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for (index, line) in input.split(|&byte| byte == b'\n').enumerate() {
+            let length = line.strip_suffix(b"\r").unwrap_or(line).len();
+            if length > MAX_LINE_LENGTH {
+                report.push(
+                    Severity::Warning,
+                    "line-too-long",
+                    format!("line {}", index + 1),
+                    None,
+                    format!("Line is {length} octets long, lines SHOULD be folded after {MAX_LINE_LENGTH} octets"),
+                );
+            }
+        }
+
+        let options = ParserOptions::default();
+        let mut line_parser = ContentLineParser::from_slice(input);
+        let calendar = match line_parser.next() {
+            Some(Ok(line))
+                if line.name == "BEGIN"
+                    && line.value.eq_ignore_ascii_case("VCALENDAR")
+                    && line.params.is_empty() =>
+            {
+                IcalCalendarBuilder::from_parser(&mut line_parser, &options)
+            }
+            Some(Err(err)) => Err(ParserError::ContentLineError(err)),
+            Some(Ok(_)) => Err(ParserError::MissingHeader),
+            None => Err(ParserError::EmptyInput),
+        };
+        let calendar = match calendar {
+            Ok(calendar) => calendar,
+            Err(err) => {
+                report.push(
+                    Severity::Error,
+                    "syntax",
+                    "VCALENDAR",
+                    None,
+                    err.to_string(),
+                );
+                return report;
+            }
+        };
+
+        report
+            .findings
+            .extend(self.validate_builder(&calendar).findings);
+        if let Err(err) = calendar.build(&options, None) {
+            report.push(
+                Severity::Error,
+                "verification",
+                "VCALENDAR",
+                None,
+                err.to_string(),
+            );
+        }
+        report
+    }
+}
+
+/// Validates a parsed calendar
+pub fn validate(calendar: &IcalCalendar) -> ValidationReport {
+    Validator::default().validate(calendar)
+}
+
+/// Checks a calendar for constructs that the client of `vendor` mishandles,
+/// e.g. RRULE parts it ignores or attachments exceeding its size limit.
+///
+/// Only the findings of the client profile are reported, see [`validate`] for the other rules.
+/// There are no rules for [`Vendor::Thunderbird`] yet.
+pub fn lint(calendar: &IcalCalendar, vendor: Vendor) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    profiles::check(&Node::from_calendar(calendar), vendor, &mut report);
+    report
+}
+
+/// Validates a calendar that hasn't been verified (yet)
+pub fn validate_builder(calendar: &IcalCalendarBuilder) -> ValidationReport {
+    Validator::default().validate_builder(calendar)
+}
+
+/// Validates the raw input of a single calendar.
+///
+/// In addition to the rules of [`validate`] this checks the length of the folded lines
+/// and reports errors that would reject the calendar when parsing it.
+pub fn validate_slice(input: &[u8]) -> ValidationReport {
+    Validator::default().validate_slice(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Node, Severity, ValidationReport, ValidationRule, Validator, sanitize, validate,
+        validate_slice,
+    };
+    use crate::{IcalParser, component::Component};
+
+    #[cfg(feature = "recurrence")]
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:20010712T182145Z-123401@example.com\r
+DTSTART;TZID=Europe/Berlin:20060102T120000\r
+DURATION:PT1H\r
+DTEND;TZID=Europe/Berlin:20060102T130000\r
+RRULE:FREQ=DAILY;UNTIL=20060110T120000\r
+SUMMARY:Event with a summary that is way too long to fit into a single line of content\r
+BEGIN:VALARM\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:todo@example.com\r
+DTSTAMP:20060206T001121Z\r
+DUE:20060110T120000\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_validate_slice() {
+        let report = validate_slice(CALENDAR.as_bytes());
+        let findings: Vec<_> = report
+            .iter()
+            .map(|finding| {
+                (
+                    finding.severity,
+                    finding.rule,
+                    finding.path.as_str(),
+                    finding.property.as_deref(),
+                )
+            })
+            .collect();
+        similar_asserts::assert_eq!(
+            findings,
+            vec![
+                (Severity::Warning, "line-too-long", "line 10", None),
+                (
+                    Severity::Warning,
+                    "missing-vtimezone",
+                    "VCALENDAR/VEVENT[0]",
+                    Some("DTSTART")
+                ),
+                (
+                    Severity::Warning,
+                    "missing-vtimezone",
+                    "VCALENDAR/VEVENT[0]",
+                    Some("DTEND")
+                ),
+                (
+                    Severity::Error,
+                    "required-property",
+                    "VCALENDAR/VEVENT[0]",
+                    Some("DTSTAMP")
+                ),
+                (
+                    Severity::Error,
+                    "property-conflict",
+                    "VCALENDAR/VEVENT[0]",
+                    Some("DURATION")
+                ),
+                (
+                    Severity::Error,
+                    "until-not-utc",
+                    "VCALENDAR/VEVENT[0]",
+                    Some("RRULE")
+                ),
+                (
+                    Severity::Error,
+                    "required-property",
+                    "VCALENDAR/VEVENT[0]/VALARM[0]",
+                    Some("ACTION")
+                ),
+                (
+                    Severity::Info,
+                    "floating-time",
+                    "VCALENDAR/VTODO[0]",
+                    Some("DUE")
+                ),
+                (Severity::Error, "verification", "VCALENDAR", None),
+            ]
+        );
+        assert!(!report.is_valid());
+        assert_eq!(report.max_severity(), Some(Severity::Error));
+    }
+
+    #[test]
+    fn test_validate_slice_syntax_error() {
+        let report = validate_slice(b"BEGIN:VCALENDAR\r\nVERSION:2.0\r\n");
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule, "syntax");
+    }
+
+    #[test]
+    fn test_localized_properties() {
+        let input = |summaries: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Example Corp.//CalDAV Client//EN\r\nBEGIN:VEVENT\r\nUID:meeting@example.com\r\nDTSTAMP:20260101T120000Z\r\nDTSTART:20260323T090000Z\r\n{summaries}END:VEVENT\r\nEND:VCALENDAR\r\n"
+            )
+        };
+        let translated =
+            input("SUMMARY;LANGUAGE=en:Meeting\r\nSUMMARY;LANGUAGE=de:Besprechung\r\n");
+        assert!(validate_slice(translated.as_bytes()).findings.is_empty());
+        let calendar = IcalParser::from_slice(translated.as_bytes())
+            .expect_one()
+            .unwrap();
+        let event = &calendar.events[0];
+        assert_eq!(
+            event
+                .get_localized_summary(&["de-DE", "en"])
+                .unwrap()
+                .as_str(),
+            "Besprechung"
+        );
+        assert_eq!(
+            event.get_localized_summary(&["fr"]).unwrap().as_str(),
+            "Meeting"
+        );
+        assert!(event.get_localized_description(&["en"]).is_none());
+
+        let report = validate_slice(
+            input("SUMMARY;LANGUAGE=de:Besprechung\r\nSUMMARY;LANGUAGE=DE:Sitzung\r\n").as_bytes(),
+        );
+        let findings: Vec<_> = report
+            .iter()
+            .map(|finding| (finding.rule, finding.property.as_deref()))
+            .collect();
+        assert_eq!(findings, [("duplicate-property", Some("SUMMARY"))]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = std::fs::read_to_string("tests/resources/ical_everything.ics").unwrap();
+        let calendar = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let report = validate(&calendar);
+        assert!(report.is_valid(), "{:#?}", report.findings);
+    }
+
+    #[test]
+    fn test_itip() {
+        let message = |method: &str, extra: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+METHOD:{method}\r
+BEGIN:VEVENT\r
+UID:meeting@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+SUMMARY:Meeting\r
+ORGANIZER:mailto:boss@example.com\r
+{extra}END:VEVENT\r
+END:VCALENDAR\r
+"
+            )
+        };
+        let rules = |input: String| -> Vec<_> {
+            validate_slice(input.as_bytes())
+                .iter()
+                .map(|finding| (finding.rule, finding.property.clone()))
+                .collect()
+        };
+        let attendee =
+            |name: &str| format!("ATTENDEE;PARTSTAT=ACCEPTED:mailto:{name}@example.com\r\n");
+
+        assert_eq!(rules(message("REQUEST", &attendee("jane"))), vec![]);
+        assert_eq!(
+            rules(message("REQUEST", "STATUS:CANCELLED\r\n")),
+            vec![
+                ("itip-property-count", Some("ATTENDEE".to_owned())),
+                ("itip-status", Some("STATUS".to_owned())),
+            ]
+        );
+        assert_eq!(
+            rules(message(
+                "REPLY",
+                &format!("{}{}", attendee("jane"), attendee("john"))
+            )),
+            vec![("itip-property-count", Some("ATTENDEE".to_owned()))]
+        );
+        assert_eq!(
+            rules(message("CANCEL", "SEQUENCE:-1\r\n")),
+            vec![("itip-sequence", Some("SEQUENCE".to_owned()))]
+        );
+        assert_eq!(
+            rules(message("PUBLISH", "").replace("ORGANIZER:mailto:boss@example.com\r\n", "")),
+            vec![("itip-property-count", Some("ORGANIZER".to_owned()))]
+        );
+        assert_eq!(
+            rules(message("INVITE", "")),
+            vec![("itip-method", Some("METHOD".to_owned()))]
+        );
+
+        let cancel = message("CANCEL", "SEQUENCE:1\r\n").replace(
+            "END:VCALENDAR",
+            "BEGIN:VEVENT\r
+UID:other@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+ORGANIZER:mailto:boss@example.com\r
+SEQUENCE:1\r
+END:VEVENT\r
+END:VCALENDAR",
+        );
+        assert_eq!(rules(cancel), vec![("itip-uid", Some("UID".to_owned()))]);
+    }
+
+    #[test]
+    fn test_alarms() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:alarms@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER;RELATED=END:-PT5M\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER;VALUE=DATE-TIME:20000101T000000Z\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT5M\r
+REPEAT:-1\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:EMAIL\r
+TRIGGER:-PT5M\r
+END:VALARM\r
+BEGIN:VALARM\r
+ACTION:EMAIL\r
+TRIGGER;RELATED=START:-PT5M\r
+ATTENDEE:mailto:jane@example.com\r
+REPEAT:2\r
+DURATION:PT5M\r
+END:VALARM\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let report = validate_slice(input.as_bytes());
+        let findings: Vec<_> = report
+            .iter()
+            .filter(|finding| finding.rule != "verification")
+            .map(|finding| (finding.rule, finding.path.as_str()))
+            .collect();
+        similar_asserts::assert_eq!(
+            findings,
+            vec![
+                ("trigger-without-end", "VCALENDAR/VEVENT[0]/VALARM[0]"),
+                ("stale-trigger", "VCALENDAR/VEVENT[0]/VALARM[1]"),
+                ("invalid-repeat", "VCALENDAR/VEVENT[0]/VALARM[2]"),
+                ("repeat-duration", "VCALENDAR/VEVENT[0]/VALARM[2]"),
+                ("email-without-attendee", "VCALENDAR/VEVENT[0]/VALARM[3]"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "recurrence")]
+    #[test]
+    fn test_lint() {
+        use super::lint;
+        use crate::repair::Vendor;
+
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:lint@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;VALUE=DATE:20260323\r
+RRULE:FREQ=YEARLY;COUNT=800;BYYEARDAY=1,100\r
+ATTACH;ENCODING=BASE64;VALUE=BINARY:SGVsbG8gV29ybGQh\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:hourly@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000\r
+RRULE:FREQ=HOURLY;BYMINUTE=0,30\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let calendar = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let rules = |vendor| -> Vec<_> {
+            lint(&calendar, vendor)
+                .iter()
+                .map(|finding| (finding.rule, finding.path.clone()))
+                .collect()
+        };
+        let first = "VCALENDAR/VEVENT[0]".to_owned();
+        let second = "VCALENDAR/VEVENT[1]".to_owned();
+        similar_asserts::assert_eq!(
+            rules(Vendor::Google),
+            vec![
+                ("client-rrule-count", first.clone()),
+                ("client-attachment-size", first.clone()),
+                ("client-rrule-frequency", second.clone()),
+                ("client-rrule-part", second.clone()),
+                ("client-x-property", "VCALENDAR".to_owned()),
+            ]
+        );
+        similar_asserts::assert_eq!(
+            rules(Vendor::Outlook),
+            vec![
+                ("client-rrule-part", first.clone()),
+                ("client-rrule-frequency", second.clone()),
+                ("client-rrule-part", second),
+                ("client-x-property", first),
+            ]
+        );
+        similar_asserts::assert_eq!(
+            rules(Vendor::Apple),
+            vec![("client-x-property", "VCALENDAR".to_owned())]
+        );
+        assert!(lint(&calendar, Vendor::Thunderbird).is_empty());
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        struct RequiredCost;
+
+        impl ValidationRule for RequiredCost {
+            fn check(&self, node: &Node, report: &mut ValidationReport) {
+                if node.name == "VEVENT" && node.get_property("X-COST").is_none() {
+                    report.push(
+                        Severity::Warning,
+                        "required-cost",
+                        &node.path,
+                        Some("X-COST"),
+                        "Events need a cost center",
+                    );
+                }
+            }
+
+            fn check_calendar(&self, root: &Node, report: &mut ValidationReport) {
+                if root.get_property("X-WR-CALNAME").is_none() {
+                    report.push(
+                        Severity::Error,
+                        "calendar-name",
+                        &root.path,
+                        Some("X-WR-CALNAME"),
+                        "Calendars must be named",
+                    );
+                }
+            }
+        }
+
+        let input = std::fs::read_to_string("tests/resources/ical_everything.ics").unwrap();
+        let calendar = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let report = Validator::default()
+            .with_rule(RequiredCost)
+            .validate(&calendar);
+        let rules: Vec<_> = report
+            .iter()
+            .filter(|finding| finding.severity > Severity::Info)
+            .map(|finding| finding.rule)
+            .collect();
+        assert_eq!(rules, vec!["calendar-name", "required-cost"]);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_parameter_values() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:params@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+ATTENDEE;PARTSTAT=MAYBE;ROLE=X-OBSERVER;CUTYPE=room:mailto:room@example.com\r
+ATTENDEE;PARTSTAT=IN-PROCESS:mailto:jane@example.com\r
+ATTENDEE;ROLE=CHAIR;ROLE=CHAIR;ROLE=OPT-PARTICIPANT:mailto:max@example.com\r
+CONFERENCE;VALUE=URI;FEATURE=AUDIO,HOLOGRAM:https://chat.example.com/audio\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let findings: Vec<_> = validate_slice(input.as_bytes())
+            .iter()
+            .map(|finding| (finding.rule, finding.message.clone()))
+            .collect();
+        similar_asserts::assert_eq!(
+            findings,
+            vec![
+                (
+                    "unknown-param-value",
+                    "MAYBE is not a registered value of PARTSTAT".to_owned()
+                ),
+                (
+                    "partstat-context",
+                    "PARTSTAT IN-PROCESS is not allowed in VEVENT".to_owned()
+                ),
+                (
+                    "conflicting-parameter",
+                    "ROLE occurs more than once with differing values, the first is used"
+                        .to_owned()
+                ),
+                (
+                    "unknown-param-value",
+                    "HOLOGRAM is not a registered value of FEATURE".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delegation() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:delegation@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+ATTENDEE;PARTSTAT=DELEGATED;DELEGATED-TO=\"mailto:john@example.com\":mailto:jane@example.com\r
+ATTENDEE;DELEGATED-FROM=\"mailto:jane@example.com\":mailto:john@example.com\r
+ATTENDEE;DELEGATED-TO=\"mailto:john@example.com\",\"mailto:max@example.com\":mailto:eve@example.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let findings: Vec<_> = validate_slice(input.as_bytes())
+            .iter()
+            .filter(|finding| finding.rule == "delegation")
+            .map(|finding| (finding.severity, finding.message.clone()))
+            .collect();
+        similar_asserts::assert_eq!(
+            findings,
+            vec![
+                (
+                    Severity::Error,
+                    "DELEGATED-TO of mailto:eve@example.com refers to mailto:john@example.com, whose DELEGATED-FROM doesn't refer back".to_owned()
+                ),
+                (
+                    Severity::Warning,
+                    "DELEGATED-TO of mailto:eve@example.com refers to mailto:max@example.com, who isn't an attendee".to_owned()
+                ),
+                (
+                    Severity::Warning,
+                    "mailto:eve@example.com delegated the attendance but its PARTSTAT isn't DELEGATED".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:text@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART:20260323T080000Z\r
+SUMMARY:Lunch, then coffee\r
+LOCATION:Room 1\\; Building A\r
+CATEGORIES:WORK,FOOD\r
+DESCRIPTION:Bring\u{7} snacks\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let calendar = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let findings: Vec<_> = validate(&calendar)
+            .iter()
+            .map(|finding| (finding.rule, finding.property.clone().unwrap()))
+            .collect();
+        similar_asserts::assert_eq!(
+            findings,
+            vec![
+                ("text-escaping", "SUMMARY".to_owned()),
+                ("control-character", "DESCRIPTION".to_owned()),
+            ]
+        );
+
+        let calendar = sanitize(calendar).unwrap();
+        assert!(validate(&calendar).is_empty());
+        let event = &calendar.events[0];
+        assert_eq!(
+            event.get_property("SUMMARY").unwrap().value,
+            "Lunch\\, then coffee"
+        );
+        assert_eq!(
+            event.get_property("DESCRIPTION").unwrap().value,
+            "Bring snacks"
+        );
+    }
+}
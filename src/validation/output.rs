@@ -0,0 +1,158 @@
+//! Compliance of emitted calendar data with the wire format of RFC 5545
+use super::MAX_LINE_LENGTH;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireViolationKind {
+    /// The line is longer than 75 octets, excluding the line break
+    LineTooLong(usize),
+    /// LF without a preceding CR
+    BareLf,
+    /// CR that isn't followed by LF
+    BareCr,
+    /// The data doesn't end with a line break
+    MissingFinalLineBreak,
+    /// Octets that aren't valid UTF-8, e.g. because a line was folded within a character
+    InvalidUtf8,
+    /// A control character other than HTAB
+    ControlCharacter(u8),
+}
+
+impl fmt::Display for WireViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LineTooLong(length) => write!(
+                f,
+                "line is {length} octets long, lines must be folded after {MAX_LINE_LENGTH} octets"
+            ),
+            Self::BareLf => f.write_str("line break without CR"),
+            Self::BareCr => f.write_str("CR without LF"),
+            Self::MissingFinalLineBreak => f.write_str("missing line break at the end"),
+            Self::InvalidUtf8 => f.write_str("invalid UTF-8"),
+            Self::ControlCharacter(char) => write!(f, "control character {char:#04x}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireViolation {
+    pub kind: WireViolationKind,
+    /// Offset of the first offending octet, for long lines the start of the line
+    pub offset: usize,
+    /// Number of the physical line, starting at 1
+    pub line: usize,
+}
+
+impl fmt::Display for WireViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} (offset {}): {}",
+            self.line, self.offset, self.kind
+        )
+    }
+}
+
+/// Checks emitted calendar data against the rules of the wire format:
+/// lines folded after 75 octets, CRLF line breaks, valid UTF-8 and no control characters.
+///
+/// Returns all violations ordered by their offset.
+///
+/// # Examples
+///
+/// ```rust
+/// use caldata::{IcalParser, generator::Emitter, validation::check_output};
+///
+/// let input = std::fs::read_to_string("./tests/resources/ical_everything.ics").unwrap();
+/// let calendar = IcalParser::from_slice(input.as_bytes()).expect_one().unwrap();
+/// assert_eq!(check_output(calendar.generate().as_bytes()), vec![]);
+/// ```
+pub fn check_output(output: &[u8]) -> Vec<WireViolation> {
+    let mut violations = vec![];
+    let mut push = |kind, offset, line| {
+        violations.push(WireViolation { kind, offset, line });
+    };
+
+    let mut line = 1;
+    let mut line_start = 0;
+    for (offset, &byte) in output.iter().enumerate() {
+        match byte {
+            b'\n' => {
+                let crlf = offset > 0 && output[offset - 1] == b'\r';
+                if !crlf {
+                    push(WireViolationKind::BareLf, offset, line);
+                }
+                let length = offset - line_start - usize::from(crlf);
+                if length > MAX_LINE_LENGTH {
+                    push(WireViolationKind::LineTooLong(length), line_start, line);
+                }
+                line += 1;
+                line_start = offset + 1;
+            }
+            b'\r' if output.get(offset + 1) != Some(&b'\n') => {
+                push(WireViolationKind::BareCr, offset, line);
+            }
+            b'\r' | b'\t' => {}
+            byte if byte < 0x20 || byte == 0x7f => {
+                push(WireViolationKind::ControlCharacter(byte), offset, line);
+            }
+            _ => {}
+        }
+    }
+    if line_start < output.len() {
+        let length = output.len() - line_start;
+        if length > MAX_LINE_LENGTH {
+            push(WireViolationKind::LineTooLong(length), line_start, line);
+        }
+        push(WireViolationKind::MissingFinalLineBreak, output.len(), line);
+    }
+
+    let mut offset = 0;
+    while offset < output.len() {
+        match std::str::from_utf8(&output[offset..]) {
+            Ok(_) => break,
+            Err(err) => {
+                let invalid = offset + err.valid_up_to();
+                let line = 1 + output[..invalid].iter().filter(|&&b| b == b'\n').count();
+                push(WireViolationKind::InvalidUtf8, invalid, line);
+                offset = invalid + err.error_len().unwrap_or(output.len() - invalid);
+            }
+        }
+    }
+
+    violations.sort_by_key(|violation| violation.offset);
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WireViolation, WireViolationKind, check_output};
+
+    #[test]
+    fn test_check_output() {
+        let long = format!("DESCRIPTION:{}\r\n", "a".repeat(70));
+        let output = [
+            b"BEGIN:VCALENDAR\r\n".as_slice(),
+            b"VERSION:2.0\n",
+            long.as_bytes(),
+            b"SUMMARY:\xc3\r\n \xa4\x07\r\n",
+            b"END:VCALENDAR",
+        ]
+        .concat();
+        let violations: Vec<_> = check_output(&output)
+            .into_iter()
+            .map(|WireViolation { kind, offset, line }| (kind, offset, line))
+            .collect();
+        similar_asserts::assert_eq!(
+            violations,
+            vec![
+                (WireViolationKind::BareLf, 28, 2),
+                (WireViolationKind::LineTooLong(82), 29, 3),
+                (WireViolationKind::InvalidUtf8, 121, 4),
+                (WireViolationKind::InvalidUtf8, 125, 5),
+                (WireViolationKind::ControlCharacter(0x07), 126, 5),
+                (WireViolationKind::MissingFinalLineBreak, 142, 6),
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Lint profiles for constructs that calendar clients mishandle
+use super::{Node, Severity, ValidationReport, rules};
+use crate::repair::Vendor;
+#[cfg(feature = "recurrence")]
+use crate::rrule::{Frequency, RRule};
+
+/// Maximum number of instances a client creates for a recurrence with COUNT
+#[cfg(feature = "recurrence")]
+fn max_count(vendor: Vendor) -> Option<u32> {
+    match vendor {
+        Vendor::Google => Some(730),
+        Vendor::Outlook => Some(999),
+        Vendor::Apple | Vendor::Thunderbird => None,
+    }
+}
+
+/// Maximum size of inline attachments in octets
+fn max_inline_attachment(vendor: Vendor) -> Option<usize> {
+    match vendor {
+        // Only supports links to attachments
+        Vendor::Google => Some(0),
+        Vendor::Outlook => Some(25 * 1024 * 1024),
+        Vendor::Apple | Vendor::Thunderbird => None,
+    }
+}
+
+/// RRULE parts a client ignores
+#[cfg(feature = "recurrence")]
+fn unsupported_rrule_parts<S>(vendor: Vendor, rrule: &RRule<S>) -> Vec<&'static str> {
+    let mut parts = vec![];
+    if matches!(vendor, Vendor::Google | Vendor::Outlook) {
+        if !rrule.get_by_hour().is_empty() {
+            parts.push("BYHOUR");
+        }
+        if !rrule.get_by_minute().is_empty() {
+            parts.push("BYMINUTE");
+        }
+        if !rrule.get_by_second().is_empty() {
+            parts.push("BYSECOND");
+        }
+    }
+    if vendor == Vendor::Outlook {
+        if !rrule.get_by_year_day().is_empty() {
+            parts.push("BYYEARDAY");
+        }
+        if !rrule.get_by_week_no().is_empty() {
+            parts.push("BYWEEKNO");
+        }
+        if rrule.get_by_month().len() > 1 {
+            parts.push("BYMONTH");
+        }
+        if rrule.get_by_set_pos().len() > 1 {
+            parts.push("BYSETPOS");
+        }
+    }
+    parts
+}
+
+fn rrules(node: &Node, vendor: Vendor, report: &mut ValidationReport) {
+    if vendor == Vendor::Thunderbird {
+        return;
+    }
+    if node.get_named_properties("RRULE").count() > 1 {
+        report.push(
+            Severity::Warning,
+            "client-multiple-rrule",
+            &node.path,
+            Some("RRULE"),
+            format!("{vendor:?} only uses the first RRULE"),
+        );
+    }
+    if node.get_property("EXRULE").is_some() {
+        report.push(
+            Severity::Warning,
+            "client-exrule",
+            &node.path,
+            Some("EXRULE"),
+            format!("{vendor:?} ignores EXRULE, use EXDATE instead"),
+        );
+    }
+
+    #[cfg(feature = "recurrence")]
+    for prop in node.get_named_properties("RRULE") {
+        // Invalid RRULEs are reported by the built-in rules
+        let Ok(rrule) = RRule::from_str_lenient(&prop.value) else {
+            continue;
+        };
+        if matches!(vendor, Vendor::Google | Vendor::Outlook) && rrule.get_freq() > Frequency::Daily
+        {
+            report.push(
+                Severity::Warning,
+                "client-rrule-frequency",
+                &node.path,
+                Some("RRULE"),
+                format!("{vendor:?} doesn't support FREQ={}", rrule.get_freq()),
+            );
+        }
+        for part in unsupported_rrule_parts(vendor, &rrule) {
+            report.push(
+                Severity::Warning,
+                "client-rrule-part",
+                &node.path,
+                Some("RRULE"),
+                format!("{vendor:?} doesn't support this usage of {part}"),
+            );
+        }
+        if let (Some(count), Some(max)) = (rrule.get_count(), max_count(vendor))
+            && count > max
+        {
+            report.push(
+                Severity::Warning,
+                "client-rrule-count",
+                &node.path,
+                Some("RRULE"),
+                format!("{vendor:?} only creates up to {max} instances, COUNT is {count}"),
+            );
+        }
+    }
+}
+
+fn attachments(node: &Node, vendor: Vendor, report: &mut ValidationReport) {
+    let Some(max) = max_inline_attachment(vendor) else {
+        return;
+    };
+    for prop in node.get_named_properties("ATTACH") {
+        if prop.params.get_value_type() != Some("BINARY") {
+            continue;
+        }
+        // Size of the decoded base64 value
+        let size = prop.value.len() / 4 * 3;
+        if size > max {
+            let message = if max == 0 {
+                format!("{vendor:?} doesn't support inline attachments")
+            } else {
+                format!(
+                    "{vendor:?} only supports inline attachments of up to {max} octets, this one has {size}"
+                )
+            };
+            report.push(
+                Severity::Warning,
+                "client-attachment-size",
+                &node.path,
+                Some("ATTACH"),
+                message,
+            );
+        }
+    }
+}
+
+fn is_all_day(node: &Node) -> bool {
+    node.get_property("DTSTART")
+        .is_some_and(|dtstart| dtstart.params.get_value_type() == Some("DATE"))
+}
+
+fn is_floating(node: &Node) -> bool {
+    node.get_property("DTSTART")
+        .is_some_and(|dtstart| rules::is_floating(&dtstart.value, dtstart.params.get_tzid()))
+}
+
+fn x_properties(root: &Node, vendor: Vendor, report: &mut ValidationReport) {
+    match vendor {
+        Vendor::Outlook => {
+            for node in root.walk() {
+                if node.name == "VEVENT"
+                    && is_all_day(node)
+                    && node.get_property("X-MICROSOFT-CDO-ALLDAYEVENT").is_none()
+                {
+                    report.push(
+                        Severity::Info,
+                        "client-x-property",
+                        &node.path,
+                        Some("X-MICROSOFT-CDO-ALLDAYEVENT"),
+                        "Outlook expects X-MICROSOFT-CDO-ALLDAYEVENT:TRUE for all-day events",
+                    );
+                }
+            }
+        }
+        Vendor::Google => {
+            if root.get_property("X-WR-TIMEZONE").is_none()
+                && root.walk().any(|node| {
+                    matches!(node.name, "VEVENT" | "VTODO" | "VJOURNAL") && is_floating(node)
+                })
+            {
+                report.push(
+                    Severity::Info,
+                    "client-x-property",
+                    &root.path,
+                    Some("X-WR-TIMEZONE"),
+                    "Google interprets floating times in the timezone of X-WR-TIMEZONE",
+                );
+            }
+        }
+        Vendor::Apple => {
+            if root.get_property("X-WR-CALNAME").is_none() {
+                report.push(
+                    Severity::Info,
+                    "client-x-property",
+                    &root.path,
+                    Some("X-WR-CALNAME"),
+                    "Apple Calendar names subscribed calendars after X-WR-CALNAME",
+                );
+            }
+        }
+        Vendor::Thunderbird => {}
+    }
+}
+
+/// Runs the rules of the profile for `vendor`
+pub(super) fn check(root: &Node, vendor: Vendor, report: &mut ValidationReport) {
+    for node in root.walk() {
+        rrules(node, vendor, report);
+        attachments(node, vendor, report);
+    }
+    x_properties(root, vendor, report);
+}
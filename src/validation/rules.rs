@@ -0,0 +1,487 @@
+//! Built-in rules from RFC 5545
+use super::{Node, Severity, ValidationReport};
+#[cfg(feature = "recurrence")]
+use crate::rrule::RRule;
+use crate::{
+    itip::same_address,
+    parser::ContentLine,
+    property::{CuType, FbType, Feature, ImageDisplay, PartStat, RelType, Role},
+    types::CalDateTime,
+};
+use chrono::{Duration, Utc};
+use std::{collections::HashSet, str::FromStr};
+
+/// Properties that MUST be present in a component
+pub(crate) fn required_properties(component: &str) -> &'static [(&'static str, Severity)] {
+    match component {
+        // Apple Calendar omits PRODID
+        "VCALENDAR" => &[("VERSION", Severity::Error), ("PRODID", Severity::Warning)],
+        "VEVENT" => &[
+            ("UID", Severity::Error),
+            ("DTSTAMP", Severity::Error),
+            ("DTSTART", Severity::Error),
+        ],
+        "VTODO" | "VJOURNAL" | "VFREEBUSY" => {
+            &[("UID", Severity::Error), ("DTSTAMP", Severity::Error)]
+        }
+        "VALARM" => &[("ACTION", Severity::Error), ("TRIGGER", Severity::Error)],
+        "VTIMEZONE" => &[("TZID", Severity::Error)],
+        "STANDARD" | "DAYLIGHT" => &[
+            ("DTSTART", Severity::Error),
+            ("TZOFFSETFROM", Severity::Error),
+            ("TZOFFSETTO", Severity::Error),
+        ],
+        _ => &[],
+    }
+}
+
+/// Properties that MUST NOT occur more than once in a component
+const SINGLE_PROPERTIES: &[&str] = &[
+    "VERSION",
+    "PRODID",
+    "CALSCALE",
+    "METHOD",
+    "UID",
+    "DTSTAMP",
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "DURATION",
+    "RECURRENCE-ID",
+    "SUMMARY",
+    "DESCRIPTION",
+    "LOCATION",
+    "CLASS",
+    "STATUS",
+    "SEQUENCE",
+    "PRIORITY",
+    "CREATED",
+    "LAST-MODIFIED",
+    "COMPLETED",
+    "PERCENT-COMPLETE",
+    "ORGANIZER",
+    "TRANSP",
+    "GEO",
+    "URL",
+    "TZID",
+    "ACTION",
+    "TRIGGER",
+    "REPEAT",
+];
+
+/// Single properties that may occur once per LANGUAGE to offer translations
+const LOCALIZED_PROPERTIES: &[&str] = &["SUMMARY", "DESCRIPTION", "LOCATION"];
+
+/// Properties that MUST NOT occur together, the latter is reported
+const CONFLICTING_PROPERTIES: &[(&str, &str)] = &[("DTEND", "DURATION"), ("DUE", "DURATION")];
+
+/// Properties whose DATE-TIME values may be floating
+const FLOATING_PROPERTIES: &[&str] = &["DTSTART", "DTEND", "DUE", "RECURRENCE-ID"];
+
+/// Parameters whose values are taken from an IANA registry
+const REGISTRIES: &[(&str, &[&str])] = &[
+    (PartStat::PARAM, PartStat::VALUES),
+    (Role::PARAM, Role::VALUES),
+    (CuType::PARAM, CuType::VALUES),
+    (RelType::PARAM, RelType::VALUES),
+    (FbType::PARAM, FbType::VALUES),
+    (ImageDisplay::PARAM, ImageDisplay::VALUES),
+    (Feature::PARAM, Feature::VALUES),
+];
+
+/// Absolute alarm triggers older than this most likely are leftovers
+const STALE_TRIGGER_AGE: Duration = Duration::days(365);
+
+pub(super) fn is_floating(value: &str, tzid: Option<&str>) -> bool {
+    tzid.is_none() && value.contains('T') && !value.ends_with('Z')
+}
+
+fn required(node: &Node, report: &mut ValidationReport) {
+    for (name, severity) in required_properties(node.name) {
+        if node.get_property(name).is_none() {
+            report.push(
+                *severity,
+                "required-property",
+                &node.path,
+                Some(name),
+                format!("{} requires the {name} property", node.name),
+            );
+        }
+    }
+}
+
+fn single(node: &Node, report: &mut ValidationReport) {
+    let mut seen = HashSet::new();
+    for prop in node.properties {
+        let localized = LOCALIZED_PROPERTIES.contains(&prop.name.as_str());
+        let language = localized
+            .then(|| prop.params.get_param("LANGUAGE"))
+            .flatten()
+            .map(str::to_ascii_lowercase);
+        if SINGLE_PROPERTIES.contains(&prop.name.as_str())
+            && !seen.insert((prop.name.as_str(), language))
+        {
+            report.push(
+                Severity::Error,
+                "duplicate-property",
+                &node.path,
+                Some(&prop.name),
+                if localized {
+                    format!("{} must not occur more than once per LANGUAGE", prop.name)
+                } else {
+                    format!("{} must not occur more than once", prop.name)
+                },
+            );
+        }
+    }
+    if node.get_named_properties("RRULE").count() > 1 {
+        report.push(
+            Severity::Warning,
+            "multiple-rrule",
+            &node.path,
+            Some("RRULE"),
+            "RRULE should not occur more than once",
+        );
+    }
+}
+
+fn conflicting(node: &Node, report: &mut ValidationReport) {
+    for (first, second) in CONFLICTING_PROPERTIES {
+        if node.get_property(first).is_some() && node.get_property(second).is_some() {
+            report.push(
+                Severity::Error,
+                "property-conflict",
+                &node.path,
+                Some(second),
+                format!("{first} and {second} must not occur together"),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "recurrence")]
+fn rrules(node: &Node, report: &mut ValidationReport) {
+    let dtstart = node.get_property("DTSTART");
+    let floating_start =
+        dtstart.is_none_or(|dtstart| is_floating(&dtstart.value, dtstart.params.get_tzid()));
+    for prop in node
+        .get_named_properties("RRULE")
+        .chain(node.get_named_properties("EXRULE"))
+    {
+        let rrule = match RRule::from_str_lenient(&prop.value) {
+            Ok(rrule) => rrule,
+            Err(err) => {
+                report.push(
+                    Severity::Error,
+                    "invalid-rrule",
+                    &node.path,
+                    Some(&prop.name),
+                    err.to_string(),
+                );
+                continue;
+            }
+        };
+        // Inside of VTIMEZONE components DTSTART always is in local time
+        if node.name == "STANDARD" || node.name == "DAYLIGHT" || floating_start {
+            continue;
+        }
+        if rrule
+            .get_until()
+            .is_some_and(|until| until.timezone().is_local())
+        {
+            report.push(
+                Severity::Error,
+                "until-not-utc",
+                &node.path,
+                Some(&prop.name),
+                "UNTIL must be specified in UTC if DTSTART has a timezone",
+            );
+        }
+    }
+}
+
+fn floating(node: &Node, report: &mut ValidationReport) {
+    for prop in node.properties {
+        if FLOATING_PROPERTIES.contains(&prop.name.as_str())
+            && is_floating(&prop.value, prop.params.get_tzid())
+        {
+            report.push(
+                Severity::Info,
+                "floating-time",
+                &node.path,
+                Some(&prop.name),
+                "Floating time is interpreted in the local timezone of each user",
+            );
+        }
+    }
+}
+
+fn parameters(node: &Node, report: &mut ValidationReport) {
+    for prop in node.properties {
+        if let Some(param) = prop.params.conflicting_param() {
+            report.push(
+                Severity::Warning,
+                "conflicting-parameter",
+                &node.path,
+                Some(&prop.name),
+                format!("{param} occurs more than once with differing values, the first is used"),
+            );
+        }
+        for (param, values) in prop.params.iter() {
+            let Some((_, registered)) = REGISTRIES.iter().find(|(name, _)| *name == param) else {
+                continue;
+            };
+            for value in values {
+                if registered
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(value))
+                    || value.to_uppercase().starts_with("X-")
+                {
+                    continue;
+                }
+                report.push(
+                    Severity::Warning,
+                    "unknown-param-value",
+                    &node.path,
+                    Some(&prop.name),
+                    format!("{value} is not a registered value of {param}"),
+                );
+            }
+        }
+        if let Some(partstat) = prop.params.get_param(PartStat::PARAM)
+            && let Ok(partstat) = partstat.parse::<PartStat>()
+            && !partstat.is_valid_for(node.name)
+        {
+            report.push(
+                Severity::Warning,
+                "partstat-context",
+                &node.path,
+                Some(&prop.name),
+                format!("PARTSTAT {partstat} is not allowed in {}", node.name),
+            );
+        }
+    }
+}
+
+/// Checks that DELEGATED-TO and DELEGATED-FROM of the attendees refer to each other
+fn delegation(node: &Node, report: &mut ValidationReport) {
+    let attendees: Vec<&ContentLine> = node.get_named_properties("ATTENDEE").collect();
+    let find = |address: &str| {
+        attendees
+            .iter()
+            .find(|attendee| same_address(&attendee.value, address))
+    };
+    let refers_to = |attendee: &ContentLine, param: &str, address: &str| {
+        attendee
+            .params
+            .get_param_values(param)
+            .iter()
+            .any(|value| same_address(value, address))
+    };
+    for attendee in &attendees {
+        for (param, back_param) in [
+            ("DELEGATED-TO", "DELEGATED-FROM"),
+            ("DELEGATED-FROM", "DELEGATED-TO"),
+        ] {
+            for address in attendee.params.get_param_values(param) {
+                if same_address(address, &attendee.value) {
+                    report.push(
+                        Severity::Error,
+                        "delegation",
+                        &node.path,
+                        Some("ATTENDEE"),
+                        format!("{address} has itself in {param}"),
+                    );
+                    continue;
+                }
+                match find(address) {
+                    // Replies only contain the replying attendee
+                    None => report.push(
+                        Severity::Warning,
+                        "delegation",
+                        &node.path,
+                        Some("ATTENDEE"),
+                        format!(
+                            "{param} of {} refers to {address}, who isn't an attendee",
+                            attendee.value
+                        ),
+                    ),
+                    Some(other) if !refers_to(other, back_param, &attendee.value) => report.push(
+                        Severity::Error,
+                        "delegation",
+                        &node.path,
+                        Some("ATTENDEE"),
+                        format!(
+                            "{param} of {} refers to {address}, whose {back_param} doesn't refer back",
+                            attendee.value
+                        ),
+                    ),
+                    Some(_) => {}
+                }
+            }
+        }
+        if !attendee.params.get_param_values("DELEGATED-TO").is_empty()
+            && !attendee
+                .params
+                .get_param(PartStat::PARAM)
+                .is_some_and(|partstat| partstat.eq_ignore_ascii_case(PartStat::Delegated.as_str()))
+        {
+            report.push(
+                Severity::Warning,
+                "delegation",
+                &node.path,
+                Some("ATTENDEE"),
+                format!(
+                    "{} delegated the attendance but its PARTSTAT isn't DELEGATED",
+                    attendee.value
+                ),
+            );
+        }
+    }
+}
+
+fn alarm(parent: &Node, alarm: &Node, report: &mut ValidationReport) {
+    if let Some(trigger) = alarm.get_property("TRIGGER") {
+        if trigger.params.get_value_type() == Some("DATE-TIME") {
+            if let Ok(datetime) = CalDateTime::parse(&trigger.value, None)
+                && datetime.utc() < Utc::now() - STALE_TRIGGER_AGE
+            {
+                report.push(
+                    Severity::Warning,
+                    "stale-trigger",
+                    &alarm.path,
+                    Some("TRIGGER"),
+                    format!(
+                        "Absolute TRIGGER {} lies in the distant past",
+                        trigger.value
+                    ),
+                );
+            }
+        } else if trigger.params.get_param("RELATED") == Some("END") {
+            let end = if parent.name == "VTODO" {
+                "DUE"
+            } else {
+                "DTEND"
+            };
+            let has_end = parent.get_property(end).is_some()
+                || (parent.get_property("DTSTART").is_some()
+                    && parent.get_property("DURATION").is_some());
+            if !has_end {
+                report.push(
+                    Severity::Error,
+                    "trigger-without-end",
+                    &alarm.path,
+                    Some("TRIGGER"),
+                    format!(
+                        "TRIGGER is related to the end but {} has neither {end} nor DTSTART and DURATION",
+                        parent.name
+                    ),
+                );
+            }
+        }
+    }
+
+    let repeat = alarm.get_property("REPEAT");
+    if let Some(repeat) = repeat
+        && repeat.value.parse::<u32>().is_err()
+    {
+        report.push(
+            Severity::Error,
+            "invalid-repeat",
+            &alarm.path,
+            Some("REPEAT"),
+            format!(
+                "REPEAT must be a non-negative integer, got {}",
+                repeat.value
+            ),
+        );
+    }
+    let duration = alarm.get_property("DURATION");
+    if repeat.is_some() != duration.is_some() {
+        let (present, missing) = if repeat.is_some() {
+            ("REPEAT", "DURATION")
+        } else {
+            ("DURATION", "REPEAT")
+        };
+        report.push(
+            Severity::Error,
+            "repeat-duration",
+            &alarm.path,
+            Some(present),
+            format!("{present} requires {missing} to be present as well"),
+        );
+    }
+
+    if alarm
+        .get_property("ACTION")
+        .is_some_and(|action| action.value == "EMAIL")
+        && alarm.get_property("ATTENDEE").is_none()
+    {
+        report.push(
+            Severity::Error,
+            "email-without-attendee",
+            &alarm.path,
+            Some("ATTENDEE"),
+            "EMAIL alarms require at least one ATTENDEE to send the email to",
+        );
+    }
+}
+
+fn alarms(node: &Node, report: &mut ValidationReport) {
+    for child in &node.children {
+        if child.name == "VALARM" {
+            alarm(node, child, report);
+        }
+    }
+}
+
+/// Runs all rules concerning a single component
+pub(super) fn check(node: &Node, report: &mut ValidationReport) {
+    required(node, report);
+    single(node, report);
+    conflicting(node, report);
+    #[cfg(feature = "recurrence")]
+    rrules(node, report);
+    floating(node, report);
+    parameters(node, report);
+    delegation(node, report);
+    alarms(node, report);
+}
+
+/// Runs all rules concerning the calendar as a whole
+pub(super) fn check_calendar(root: &Node, report: &mut ValidationReport) {
+    let tzids: HashSet<&str> = root
+        .children
+        .iter()
+        .filter(|child| child.name == "VTIMEZONE")
+        .filter_map(|timezone| timezone.get_property("TZID"))
+        .map(|tzid| tzid.value.as_str())
+        .collect();
+    for node in root.walk() {
+        for prop in node.properties {
+            let Some(tzid) = prop.params.get_tzid() else {
+                continue;
+            };
+            if tzids.contains(tzid) {
+                continue;
+            }
+            if chrono_tz::Tz::from_str(tzid).is_ok() {
+                report.push(
+                    Severity::Warning,
+                    "missing-vtimezone",
+                    &node.path,
+                    Some(&prop.name),
+                    format!("No VTIMEZONE for {tzid}, omitting it is only allowed by RFC 7809"),
+                );
+            } else {
+                report.push(
+                    Severity::Error,
+                    "unknown-tzid",
+                    &node.path,
+                    Some(&prop.name),
+                    format!("No VTIMEZONE with TZID {tzid}"),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Rules and fixes for the escaping of TEXT values
+use super::{Node, Severity, ValidationReport};
+use crate::{
+    component::{Component, ComponentMut, IcalCalendar},
+    parser::{ContentLine, ParserError, ParserOptions},
+};
+use std::borrow::Cow;
+
+/// Properties with a single TEXT value
+const TEXT_PROPERTIES: &[&str] = &[
+    "SUMMARY",
+    "DESCRIPTION",
+    "LOCATION",
+    "COMMENT",
+    "CONTACT",
+    "TZNAME",
+    "NAME",
+    "X-WR-CALNAME",
+    "X-WR-CALDESC",
+];
+
+/// Properties with a comma separated list of TEXT values
+const TEXT_LIST_PROPERTIES: &[&str] = &["CATEGORIES", "RESOURCES"];
+
+fn is_control(char: char) -> bool {
+    char.is_control() && char != '\t'
+}
+
+/// Escapes the characters of a TEXT value that aren't escaped yet
+/// and removes control characters, newlines are escaped as `\n`.
+fn sanitize_text(value: &str, list: bool) -> Cow<'_, str> {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.peek() {
+                Some(next @ ('\\' | ';' | ',' | 'n' | 'N')) => {
+                    out.push('\\');
+                    out.push(*next);
+                    chars.next();
+                }
+                _ => out.push_str("\\\\"),
+            },
+            ';' => out.push_str("\\;"),
+            ',' if !list => out.push_str("\\,"),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => out.push_str("\\n"),
+            char if is_control(char) => {}
+            char => out.push(char),
+        }
+    }
+    if out == value {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+/// Whether the property has a TEXT value and if it's a list
+fn text_kind(name: &str) -> Option<bool> {
+    if TEXT_PROPERTIES.contains(&name) {
+        Some(false)
+    } else if TEXT_LIST_PROPERTIES.contains(&name) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+pub(super) fn check(node: &Node, report: &mut ValidationReport) {
+    for prop in node.properties {
+        if prop.value.chars().any(is_control) {
+            report.push(
+                Severity::Error,
+                "control-character",
+                &node.path,
+                Some(&prop.name),
+                "Values must not contain control characters",
+            );
+        }
+        let Some(list) = text_kind(&prop.name) else {
+            continue;
+        };
+        let without_controls: String = prop.value.chars().filter(|c| !is_control(*c)).collect();
+        if sanitize_text(&without_controls, list) != without_controls {
+            let characters = if list {
+                "Backslashes and semicolons"
+            } else {
+                "Backslashes, semicolons and commas"
+            };
+            report.push(
+                Severity::Warning,
+                "text-escaping",
+                &node.path,
+                Some(&prop.name),
+                format!("{characters} in TEXT values must be escaped with a backslash"),
+            );
+        }
+    }
+}
+
+fn sanitize_properties(properties: &mut [ContentLine]) {
+    for prop in properties {
+        let value = match text_kind(&prop.name) {
+            Some(list) => sanitize_text(&prop.value, list),
+            None if prop.value.chars().any(is_control) => {
+                Cow::Owned(prop.value.chars().filter(|c| !is_control(*c)).collect())
+            }
+            None => continue,
+        };
+        if let Cow::Owned(value) = value {
+            prop.value = value;
+        }
+    }
+}
+
+/// Fixes the violations reported by the `control-character` and `text-escaping` rules.
+///
+/// Unescaped characters of TEXT values are escaped, newlines are replaced with `\n`
+/// and other control characters are removed.
+pub fn sanitize(calendar: IcalCalendar) -> Result<IcalCalendar, ParserError> {
+    let mut builder = calendar.mutable();
+    sanitize_properties(&mut builder.properties);
+    for event in &mut builder.events {
+        sanitize_properties(&mut event.properties);
+        for alarm in &mut event.alarms {
+            sanitize_properties(&mut alarm.properties);
+        }
+    }
+    for todo in &mut builder.todos {
+        sanitize_properties(&mut todo.properties);
+        for alarm in &mut todo.alarms {
+            sanitize_properties(&mut alarm.properties);
+        }
+    }
+    for journal in &mut builder.journals {
+        sanitize_properties(&mut journal.properties);
+    }
+    for free_busy in &mut builder.free_busys {
+        sanitize_properties(&mut free_busy.properties);
+    }
+    for alarm in &mut builder.alarms {
+        sanitize_properties(&mut alarm.properties);
+    }
+    let options = ParserOptions::reverify(&builder.timezones);
+    builder.build(&options, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_text;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("Meeting", false, "Meeting")]
+    #[case("Lunch, then coffee", false, "Lunch\\, then coffee")]
+    #[case("Lunch\\, then coffee", false, "Lunch\\, then coffee")]
+    #[case("a;b", false, "a\\;b")]
+    #[case("C:\\Users", false, "C:\\\\Users")]
+    #[case("trailing\\", false, "trailing\\\\")]
+    #[case("line\r\nbreak", false, "line\\nbreak")]
+    #[case("bell\u{7}\tand tab", false, "bell\tand tab")]
+    #[case("WORK,PERSONAL;X", true, "WORK,PERSONAL\\;X")]
+    fn test_sanitize_text(#[case] value: &str, #[case] list: bool, #[case] expected: &str) {
+        assert_eq!(sanitize_text(value, list), expected);
+    }
+}
@@ -190,11 +190,18 @@ pub mod line {
     }
 }
 
+#[cfg(feature = "recurrence")]
 pub mod calendar_object {
     use caldata::{
-        IcalObjectParser, IcalParser, component::CalendarInnerData, generator::Emitter, types::Tz,
+        IcalObjectParser, IcalParser,
+        component::{
+            CalendarInnerData, Component, ExpansionRange, IcalCalendarObject, RecurrenceIssue,
+        },
+        generator::Emitter,
+        parser::{ParserError, ParserOptions},
+        types::{CalDate, CalDateOrDateTime, CalDateTimeError, Tz},
     };
-    use chrono::{DateTime, Timelike};
+    use chrono::{DateTime, NaiveDate, TimeZone, Timelike, Utc};
     use itertools::Itertools;
 
     #[rstest::rstest]
@@ -259,6 +266,7 @@ pub mod calendar_object {
     #[case(3, include_str!("./resources/ical_recurrence_date_2.ics"))]
     // Has no RRULE
     #[case(4, include_str!("./resources/ical_example_1.ics"))]
+    #[case(5, include_str!("./resources/ical_recurring_todo.ics"))]
     fn rrule_expansion(#[case] case: usize, #[case] input: &str) {
         set_snapshot_suffix!("{case}");
         let reader = IcalObjectParser::from_slice(input.as_bytes());
@@ -270,6 +278,534 @@ pub mod calendar_object {
             insta::assert_debug_snapshot!(format!("{i}_data"), recurrence.get_inner());
         }
     }
+
+    #[test]
+    fn expand_empty_range() {
+        use chrono::{TimeZone, Utc};
+
+        let input = include_str!("./resources/ical_recurring_todo.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let start = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let range = ExpansionRange::new().with_start(start);
+        assert!(cal.expand(&range).unwrap().object.is_none());
+        // Returned unexpanded
+        assert!(matches!(
+            cal.expand_recurrence(Some(start), None),
+            std::borrow::Cow::Borrowed(_)
+        ));
+        let recurrence = cal.expand_recurrence(None, Some(start));
+        let CalendarInnerData::Todo(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        assert_eq!(overrides.len() + 1, 4);
+    }
+
+    #[rstest::rstest]
+    #[case(0, include_str!("./resources/Recurring at 9am, third at 10am.ics"), chrono::Duration::hours(26))]
+    #[case(1, include_str!("./resources/recurring_wholeday.ics"), chrono::Duration::days(3))]
+    #[case(2, include_str!("./resources/ical_shift.ics"), chrono::Duration::minutes(-90))]
+    // Across the change to daylight saving time
+    #[case(3, include_str!("./resources/ical_shift_dst.ics"), chrono::Duration::days(7))]
+    // Only whole days move DATE values
+    #[case(4, include_str!("./resources/ical_shift_wholeday.ics"), chrono::Duration::hours(36))]
+    fn shift(#[case] case: usize, #[case] input: &str, #[case] duration: chrono::Duration) {
+        set_snapshot_suffix!("{case}");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let shifted = cal.clone().shift(duration).unwrap();
+        insta::assert_snapshot!(shifted.generate());
+
+        // Every instance of the shifted series must be an original instance moved by duration
+        // in its timezone
+        let CalendarInnerData::Event(main, overrides) = cal.get_inner() else {
+            panic!()
+        };
+        let CalendarInnerData::Event(shifted_main, shifted_overrides) = shifted.get_inner() else {
+            panic!()
+        };
+        let original = main.expand_recurrence(None, None, overrides);
+        let shifted = shifted_main.expand_recurrence(None, None, shifted_overrides);
+        assert_eq!(original.len(), shifted.len());
+        for (original, shifted) in original.iter().zip(shifted.iter()) {
+            assert_eq!(
+                shifted.dtstart.0,
+                original.dtstart.0.clone().shift(duration).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn shift_errors() {
+        let input = include_str!("./resources/recurring_wholeday.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(matches!(
+            cal.shift(chrono::Duration::days(100_000_000)),
+            Err(ParserError::DateTime(CalDateTimeError::DateOutOfRange))
+        ));
+
+        // Objects only accepted with non-default options can still be shifted
+        let input = include_str!("./resources/ical_rrule_extension.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .with_options(ParserOptions {
+                lenient_rrule: true,
+                ..Default::default()
+            })
+            .expect_one()
+            .unwrap();
+        let shifted = cal.shift(chrono::Duration::days(1)).unwrap();
+        assert!(shifted.generate().contains("DTSTART:20260106T090000Z"));
+    }
+
+    #[test]
+    fn expand_range() {
+        let input = include_str!("./resources/Recurring at 9am, third at 10am.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let date = |y, m, d| CalDate(NaiveDate::from_ymd_opt(y, m, d).unwrap(), Tz::Local);
+
+        let range = ExpansionRange::new()
+            .with_start(date(2025, 12, 31))
+            .with_end(date(2026, 1, 2));
+        let expansion = cal.expand(&range).unwrap();
+        assert_eq!(expansion.truncated, Some(0));
+        let CalendarInnerData::Event(_, overrides) = expansion.object.unwrap().get_inner().clone()
+        else {
+            panic!()
+        };
+        // Includes the moved instance on 2026-01-02
+        assert_eq!(overrides.len() + 1, 3);
+
+        let expansion = cal.expand(&ExpansionRange::new().with_limit(1)).unwrap();
+        assert_eq!(expansion.truncated, Some(3));
+
+        let range = ExpansionRange::new()
+            .with_start(date(2026, 1, 1))
+            .with_timezone(Tz::Olson(chrono_tz::Europe::Berlin));
+        let expansion = cal.expand(&range).unwrap();
+        insta::assert_snapshot!(expansion.object.unwrap().generate());
+
+        let range = ExpansionRange::new().with_start(date(2027, 1, 1));
+        assert!(cal.expand(&range).unwrap().object.is_none());
+    }
+
+    /// Overrides moved into the range are included even if their RECURRENCE-ID isn't
+    #[test]
+    fn expand_range_moved_override() {
+        let input = include_str!("./resources/ical_moved_override.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let day = CalDate(NaiveDate::from_ymd_opt(2026, 1, 6).unwrap(), Tz::Local);
+        let range = ExpansionRange::new().with_start(day.clone()).with_end(day);
+        let expansion = cal.expand(&range).unwrap();
+        assert_eq!(expansion.truncated, Some(0));
+        let CalendarInnerData::Event(first, rest) = expansion.object.unwrap().get_inner().clone()
+        else {
+            panic!()
+        };
+        let starts: Vec<_> = std::iter::once(first)
+            .chain(rest)
+            .map(|event| event.get_property("DTSTART").unwrap().value.clone())
+            .collect();
+        assert_eq!(starts, ["20260106T090000Z", "20260106T140000Z"]);
+
+        // The RECURRENCE-ID of the moved override is inside but its start is not
+        let day = CalDate(NaiveDate::from_ymd_opt(2026, 1, 9).unwrap(), Tz::Local);
+        let range = ExpansionRange::new().with_start(day.clone()).with_end(day);
+        let expansion = cal.expand(&range).unwrap();
+        let CalendarInnerData::Event(first, rest) = expansion.object.unwrap().get_inner().clone()
+        else {
+            panic!()
+        };
+        assert!(rest.is_empty());
+        assert_eq!(first.get_summary(), Some("Daily (moved forward)"));
+    }
+
+    /// Fixed offsets only select instances, the output stays in UTC
+    #[test]
+    fn expand_range_fixed_offset() {
+        let input = include_str!("./resources/ical_moved_override.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        // 2026-01-05 in UTC-10 lasts from 10:00 UTC until 10:00 UTC on the next day
+        let day = CalDate(NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(), Tz::Local);
+        let range = ExpansionRange::new()
+            .with_start(day.clone())
+            .with_end(day)
+            .with_timezone(Tz::fixed(-10 * 3600).unwrap());
+        let object = cal.expand(&range).unwrap().object.unwrap();
+        let CalendarInnerData::Event(first, rest) = object.get_inner() else {
+            panic!()
+        };
+        assert!(rest.is_empty());
+        assert_eq!(
+            first.get_property("DTSTART").unwrap().value,
+            "20260106T090000Z"
+        );
+        assert!(first.get_property("DTSTART").unwrap().params.is_empty());
+        assert!(object.get_timezones().is_empty());
+    }
+
+    /// All-day events are clipped by their date in the output timezone
+    #[test]
+    fn expand_range_wholeday() {
+        let input = include_str!("./resources/recurring_wholeday.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let count =
+            |range: ExpansionRange| match cal.expand(&range).unwrap().object.unwrap().get_inner() {
+                CalendarInnerData::Event(_, overrides) => overrides.len() + 1,
+                _ => panic!(),
+            };
+        let end = Utc.with_ymd_and_hms(2025, 12, 31, 3, 0, 0).unwrap();
+        assert_eq!(count(ExpansionRange::new().with_end(end)), 2);
+        let range = ExpansionRange::new()
+            .with_end(end)
+            .with_timezone(Tz::Olson(chrono_tz::America::New_York));
+        assert_eq!(count(range), 1);
+    }
+
+    #[test]
+    fn normalize_overrides() {
+        let input = include_str!("./resources/ical_redundant_overrides.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let normalized = cal.clone().normalize_overrides().unwrap();
+        insta::assert_snapshot!(normalized.generate());
+
+        let CalendarInnerData::Event(_, overrides) = normalized.get_inner() else {
+            panic!()
+        };
+        assert_eq!(overrides.len(), 1);
+
+        // Both objects must still describe the same instances
+        let starts = |cal: &IcalCalendarObject| {
+            let CalendarInnerData::Event(main, overrides) = cal.get_inner() else {
+                panic!()
+            };
+            main.expand_recurrence(None, None, overrides)
+                .iter()
+                .map(|ev| (ev.dtstart.0.utc(), ev.get_duration()))
+                .sorted()
+                .collect_vec()
+        };
+        assert_eq!(starts(&cal), starts(&normalized));
+    }
+
+    #[test]
+    fn check_recurrence() {
+        let input = include_str!("./resources/ical_corrupt_overrides.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        let datetime =
+            |d, h| CalDateOrDateTime::from(Utc.with_ymd_and_hms(2026, 1, d, h, 0, 0).unwrap());
+        assert_eq!(
+            cal.check_recurrence(),
+            vec![
+                RecurrenceIssue::ExcludedOverride(datetime(12, 14)),
+                RecurrenceIssue::OrphanOverride(datetime(20, 14)),
+                RecurrenceIssue::DuplicateOverride(datetime(19, 14)),
+                RecurrenceIssue::UnusedExdate(datetime(13, 14)),
+            ]
+        );
+
+        let input = include_str!("./resources/Recurring at 9am, third at 10am.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert!(cal.check_recurrence().is_empty());
+    }
+
+    #[test]
+    fn lenient_rrule() {
+        let input = include_str!("./resources/ical_rrule_extension.ics");
+        let reader = IcalObjectParser::from_slice(input.as_bytes());
+        assert!(reader.expect_one().is_err());
+
+        let reader = IcalObjectParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            lenient_rrule: true,
+            ..Default::default()
+        });
+        let cal = reader.expect_one().unwrap();
+        similar_asserts::assert_eq!(cal.generate(), input.replace('\n', "\r\n"));
+        let recurrence = cal.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        assert_eq!(overrides.len() + 1, 3);
+    }
+
+    /// Thunderbird specifies UNTIL in local time
+    #[test]
+    fn repair_local_until() {
+        let input = include_str!("./resources/ical_local_until.ics");
+        let reader = IcalObjectParser::from_slice(input.as_bytes());
+        assert!(reader.expect_one().is_err());
+
+        let reader = IcalObjectParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            repair_local_until: true,
+            ..Default::default()
+        });
+        let cal = reader.expect_one().unwrap();
+        insta::assert_snapshot!(cal.generate());
+        let recurrence = cal.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        // The last occurrence on 2026-04-06 is in summer time and would be missing
+        // if UNTIL was reinterpreted as UTC
+        assert_eq!(overrides.len() + 1, 6);
+
+        // A DATE UNTIL includes the occurrence at 09:00 on that day
+        let input = include_str!("./resources/ical_local_until_date.ics");
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .with_options(ParserOptions {
+                repair_local_until: true,
+                ..Default::default()
+            })
+            .expect_one()
+            .unwrap();
+        assert!(
+            cal.generate()
+                .contains("RRULE:FREQ=WEEKLY;UNTIL=20260406T215959Z")
+        );
+        let recurrence = cal.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        assert_eq!(overrides.len() + 1, 6);
+    }
+
+    #[test]
+    fn clamp_leap_seconds() {
+        let input = include_str!("./resources/ical_leap_second.ics");
+        let reader = IcalObjectParser::from_slice(input.as_bytes());
+        assert!(reader.expect_one().is_err());
+
+        let reader = IcalObjectParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            clamp_leap_seconds: true,
+            ..Default::default()
+        });
+        let cal = reader.expect_one().unwrap();
+        similar_asserts::assert_eq!(
+            cal.generate(),
+            input.replace("235960Z", "235959Z").replace('\n', "\r\n")
+        );
+        let recurrence = cal.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        assert_eq!(overrides.len() + 1, 3);
+    }
+
+    #[test]
+    fn duplicate_params() {
+        use caldata::parser::{DuplicateParams, ParserErrorKind};
+
+        let input = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example Corp.//CalDAV Client//EN\r
+BEGIN:VEVENT\r
+UID:duplicate@example.com\r
+DTSTAMP:20260101T120000Z\r
+DTSTART;VALUE=DATE-TIME;TZID=Etc/UTC;TZID=Etc/GMT+2:20260323T080000\r
+SUMMARY;LANGUAGE=en;LANGUAGE=en:Meeting\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let parse = |duplicate_params| {
+            IcalObjectParser::from_slice(input.as_bytes())
+                .with_options(ParserOptions {
+                    rfc7809: true,
+                    duplicate_params,
+                    ..Default::default()
+                })
+                .expect_one()
+        };
+        for duplicate_params in [DuplicateParams::Ignore, DuplicateParams::Warn] {
+            let object = parse(duplicate_params).unwrap();
+            let CalendarInnerData::Event(event, _) = object.get_inner() else {
+                panic!()
+            };
+            assert_eq!(
+                event.dtstart.0.utc().to_rfc3339(),
+                "2026-03-23T08:00:00+00:00"
+            );
+        }
+        let err = parse(DuplicateParams::Reject).unwrap_err();
+        assert_eq!(err.kind(), ParserErrorKind::Syntax);
+        assert_eq!(err.property(), Some("DTSTART"));
+        assert_eq!(
+            err.to_string(),
+            "conflicting values for the parameter TZID of DTSTART"
+        );
+    }
+
+    #[test]
+    fn tzid_aliases() {
+        let input = include_str!("./resources/ical_lotus_notes.ics");
+        let tzid = "Z=-1$DST=1$DSTSTART=0-3-1-1$DSTEND=0-10-1-1$ZN=W. Europe";
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(cal.get_timezones().get(tzid), Some(&None));
+
+        let reader = IcalObjectParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            tzid_aliases: [(tzid.to_owned(), chrono_tz::Europe::Berlin)].into(),
+            ..Default::default()
+        });
+        let cal = reader.expect_one().unwrap();
+        assert_eq!(
+            cal.get_timezones().get(tzid),
+            Some(&Some(chrono_tz::Europe::Berlin.into()))
+        );
+        let CalendarInnerData::Event(main, _) = cal.get_inner() else {
+            panic!()
+        };
+        assert_eq!(
+            main.dtstart.0.timezone(),
+            Tz::Olson(chrono_tz::Europe::Berlin)
+        );
+    }
+
+    #[test]
+    fn default_timezone() {
+        let input = include_str!("./resources/ical_floating.ics");
+        let options = ParserOptions {
+            default_timezone: Some(chrono_tz::Europe::Berlin),
+            ..Default::default()
+        };
+        let berlin = Tz::Olson(chrono_tz::Europe::Berlin);
+
+        let cal = IcalObjectParser::from_slice(input.as_bytes())
+            .with_options(options.clone())
+            .expect_one()
+            .unwrap();
+        let CalendarInnerData::Event(main, overrides) = cal.get_inner() else {
+            panic!()
+        };
+        assert_eq!(main.dtstart.0.timezone(), berlin);
+        assert_eq!(overrides[0].dtstart.0.timezone(), berlin);
+        let output = cal.generate();
+        assert!(output.contains("BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\n"));
+        assert!(output.contains("DTSTART;TZID=Europe/Berlin:20260323T090000\r\n"));
+        assert!(output.contains("EXDATE;TZID=Europe/Berlin:20260330T090000\r\n"));
+        // UNTIL is converted to UTC since DTSTART isn't floating anymore
+        assert!(output.contains("UNTIL=20260406T070000Z"));
+        // DTSTAMP already is in UTC
+        assert!(output.contains("DTSTAMP:20260101T120000Z\r\n"));
+
+        let recurrence = cal.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        // 2026-03-30 is excluded
+        assert_eq!(overrides.len() + 1, 2);
+
+        let cal = IcalParser::from_slice(input.as_bytes())
+            .with_options(options)
+            .expect_one()
+            .unwrap();
+        assert_eq!(cal.events[0].dtstart.0.timezone(), berlin);
+        assert!(cal.vtimezones.contains_key("Europe/Berlin"));
+
+        let cal = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap();
+        assert_eq!(cal.events[0].dtstart.0.timezone(), Tz::Local);
+    }
+
+    #[test]
+    fn normalize_to_utc() {
+        let input = include_str!("./resources/ical_normalize_utc.ics");
+        let cal = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+            .normalize_to_utc()
+            .unwrap();
+        // The recurring event keeps its timezone
+        assert_eq!(
+            cal.vtimezones.keys().collect::<Vec<_>>(),
+            vec!["Europe/Berlin"]
+        );
+        assert_eq!(cal.events[1].dtstart.0.timezone(), Tz::UTC);
+        let output = cal.generate();
+        assert!(output.contains("DTSTART;TZID=Europe/Berlin:20260323T090000\r\n"));
+        assert!(output.contains("EXDATE;TZID=Europe/Berlin:20260330T090000\r\n"));
+        // 09:00 in Berlin is 07:00 UTC in summer
+        assert!(output.contains("DTSTART:20260330T070000Z\r\n"));
+        assert!(output.contains("DTEND:20260330T080000Z\r\n"));
+        assert!(output.contains("DTSTART;VALUE=DATE:20260323\r\n"));
+        assert!(output.contains("DTSTART:20260323T090000\r\n"));
+
+        let objects = cal.into_objects().unwrap();
+        let zoned = objects
+            .iter()
+            .find(|object| object.get_uid() == "normalize-zoned")
+            .unwrap();
+        let recurrence = zoned.expand_recurrence(None, None);
+        let CalendarInnerData::Event(_, overrides) = recurrence.get_inner() else {
+            panic!()
+        };
+        // 2026-03-30 is still excluded
+        assert_eq!(overrides.len() + 1, 2);
+    }
+
+    #[test]
+    fn in_timezone() {
+        let input = include_str!("./resources/ical_normalize_utc.ics");
+        let objects = IcalParser::from_slice(input.as_bytes())
+            .expect_one()
+            .unwrap()
+            .normalize_to_utc()
+            .unwrap()
+            .into_objects()
+            .unwrap();
+        let find = |uid: &str| {
+            objects
+                .iter()
+                .find(|object| object.get_uid() == uid)
+                .unwrap()
+                .clone()
+                .in_timezone(chrono_tz::Europe::Bratislava)
+                .unwrap()
+        };
+
+        let single = find("normalize-single");
+        assert_eq!(
+            single.get_vtimezones().keys().collect::<Vec<_>>(),
+            vec!["Europe/Bratislava"]
+        );
+        let output = single.generate();
+        assert!(output.contains("DTSTART;TZID=Europe/Bratislava:20260330T090000\r\n"));
+        assert!(output.contains("DTEND;TZID=Europe/Bratislava:20260330T100000\r\n"));
+        assert!(output.contains("DTSTAMP:20260101T120000Z\r\n"));
+
+        // Recurring events keep their timezone
+        let zoned = find("normalize-zoned");
+        assert_eq!(
+            zoned.get_vtimezones().keys().collect::<Vec<_>>(),
+            vec!["Europe/Berlin"]
+        );
+        let output = zoned.generate();
+        assert!(output.contains("DTSTART;TZID=Europe/Berlin:20260323T090000\r\n"));
+        assert!(output.contains("EXDATE;TZID=Europe/Berlin:20260330T090000\r\n"));
+
+        let output = find("normalize-wholeday").generate();
+        assert!(output.contains("DTSTART;VALUE=DATE:20260323\r\n"));
+        let output = find("normalize-floating").generate();
+        assert!(output.contains("DTSTART:20260323T090000\r\n"));
+    }
 }
 
 pub mod rfc7809 {
@@ -283,16 +819,20 @@ pub mod rfc7809 {
         set_snapshot_suffix!("{case}");
         let reader = IcalObjectParser::from_slice(input.as_bytes());
         assert!(reader.expect_one().is_err());
-        let reader = IcalObjectParser::from_slice(input.as_bytes())
-            .with_options(ParserOptions { rfc7809: true });
+        let reader = IcalObjectParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            rfc7809: true,
+            ..Default::default()
+        });
 
         let cal = reader.expect_one().unwrap();
         insta::assert_snapshot!(cal.generate());
 
         let reader = IcalParser::from_slice(input.as_bytes());
         assert!(reader.expect_one().is_err());
-        let reader =
-            IcalParser::from_slice(input.as_bytes()).with_options(ParserOptions { rfc7809: true });
+        let reader = IcalParser::from_slice(input.as_bytes()).with_options(ParserOptions {
+            rfc7809: true,
+            ..Default::default()
+        });
 
         let cal2 = reader.expect_one().unwrap();
         insta::assert_snapshot!("fullcal", cal2.generate());
@@ -511,6 +1051,8 @@ pub mod generator {
         for res in reader {
             let cal = res.unwrap();
             similar_asserts::assert_eq!(cal.generate(), input);
+            // The parsed RRULE is only part of the snapshot with the recurrence feature
+            #[cfg(feature = "recurrence")]
             insta::assert_debug_snapshot!(cal);
         }
     }